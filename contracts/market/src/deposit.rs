@@ -6,7 +6,7 @@
 use crate::error::ContractError;
 use crate::events::emit_collateral_deposited;
 use crate::storage;
-use crate::types::{MarketStatus, Position};
+use crate::types::{Market, MarketStatus, Position};
 use crate::validation;
 
 use soroban_sdk::token::Client as TokenClient;
@@ -16,11 +16,19 @@ use soroban_sdk::{Address, Env};
 ///
 /// # Detailed Flow
 /// 1. **Authorization**: Verify user signed this transaction
-/// 2. **Validation**: Check amount, market existence, market status
-/// 3. **Token Transfer**: Move USDC from user to contract (ATOMIC POINT)
-/// 4. **State Update**: Persist position and collateral data
+/// 2. **Validation**: Check amount, market existence, market status, and
+///    that the new total fits
+/// 3. **Token Transfer**: Re-check the market, then move USDC from user to
+///    contract (ATOMIC POINT)
+/// 4. **State Update**: Persist position and collateral data, extend TTLs
 /// 5. **Event Emission**: Publish CollateralDeposited event
 ///
+/// # Re-entrancy
+/// The token transfer is the only external call. Soroban refuses to
+/// re-enter a contract that is already on the call stack, so a token that
+/// calls back into `deposit_collateral` from `transfer` fails that inner
+/// call and the deposit is recorded once.
+///
 /// # Arguments
 /// * `env` - Soroban environment (provides ledger, storage, events access)
 /// * `user` - User's Stellar address (must authorize this call)
@@ -34,7 +42,11 @@ use soroban_sdk::{Address, Env};
 /// - `MarketNotFound`: market_id doesn't exist in storage
 /// - `MarketNotActive`: Market is resolved or cancelled
 /// - `InvalidQuantity`: amount <= 0 or exceeds max
-/// - `TokenTransferFailed`: USDC transfer failed (insufficient balance, etc.)
+/// - `BelowMinDeposit`: amount is below the market's `min_deposit`
+/// - `MarketClosedToDeposits`: the user has no position and the market
+///   already has `max_participants` participants
+/// - `TokenTransferFailed`: the token rejected the transfer (insufficient
+///   balance, missing authorization, etc.)
 /// - `ArithmeticOverflow`: Collateral amount would exceed i128 max
 ///
 /// # Events
@@ -52,31 +64,27 @@ pub fn deposit_collateral(
     // Authorization
     user.require_auth();
 
+    execute_deposit(env, user, market_id, amount)
+}
+
+/// Steps 2–5 of [`deposit_collateral`] for callers that have already
+/// obtained the user's authorization (e.g. `deposit_and_buy`).
+pub(crate) fn execute_deposit(
+    env: Env,
+    user: Address,
+    market_id: u32,
+    amount: i128,
+) -> Result<(), ContractError> {
     // Validation: reject zero or negative deposits explicitly
     if amount <= 0 {
         return Err(ContractError::InvalidQuantity);
     }
     validation::validate_collateral_amount(amount)?;
-
-    let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
-
-    if market.status != MarketStatus::Active {
-        return Err(ContractError::MarketNotActive);
-    }
-
-    if market.closed_to_deposits {
-        return Err(ContractError::MarketClosedToDeposits);
-    }
-
-    if env.ledger().timestamp() > market.end_time {
-        return Err(ContractError::MarketExpired);
+    let market = load_depositable_market(&env, market_id)?;
+    if amount < market.min_deposit {
+        return Err(ContractError::BelowMinDeposit);
     }
 
-    // Transfer USDC from user to contract
-    let contract_address = env.current_contract_address();
-    let token_client = TokenClient::new(&env, &market.collateral_token);
-    token_client.transfer(&user, &contract_address, &amount);
-
     // TODO: Refactor collateral management
     // Current design requires separate deposits per market. Users cannot use
     // Market A collateral for Market B trades. refactor will introduce:
@@ -87,15 +95,13 @@ pub fn deposit_collateral(
     // 1. User deposits USDC into specific market
     // 2. Collateral locked to this market only
     // 3. User must deposit separately for each market they want to trade
-    let mut position = storage::get_position(&env, market_id, &user)?.unwrap_or_else(|| Position {
-        market_id,
-        user: user.clone(),
-        yes_shares: 0,
-        no_shares: 0,
-        locked_collateral: 0,
-        total_deposited: 0,
-        is_settled: false,
-    });
+    let mut position = match storage::get_position(&env, market_id, &user)? {
+        Some(position) => position,
+        None => {
+            require_participant_slot(&env, &market, market_id)?;
+            Position::new_empty(market_id, user.clone(), env.ledger().timestamp())
+        }
+    };
 
     // Add to total_deposited (total collateral user has in this market).
     //
@@ -111,8 +117,24 @@ pub fn deposit_collateral(
         .checked_add(amount)
         .ok_or(ContractError::ArithmeticOverflow)?;
 
+    // Transfer USDC from user to contract. The market is re-read and
+    // re-checked immediately before the transfer so the funds move against
+    // the same state the deposit is recorded in. A rejected transfer
+    // (insufficient balance, missing authorization, a failing token) is
+    // reported as a typed error rather than trapping the host.
+    let market = load_depositable_market(&env, market_id)?;
+    let contract_address = env.current_contract_address();
+    let token_client = TokenClient::new(&env, &market.collateral_token);
+    if !matches!(
+        token_client.try_transfer(&user, &contract_address, &amount),
+        Ok(Ok(()))
+    ) {
+        return Err(ContractError::TokenTransferFailed);
+    }
+
     // Persist updated position
     storage::set_position(&env, market_id, &user, &position)?;
+    storage::extend_ttl(&env);
 
     // Record deposit timestamp for cooldown enforcement on withdrawals (issue #413).
     storage::set_last_deposit_time(&env, market_id, &user, env.ledger().timestamp());
@@ -124,12 +146,58 @@ pub fn deposit_collateral(
     Ok(())
 }
 
+/// Reject a user new to `market` once it holds `max_participants` distinct
+/// users; users already in the market may keep depositing.
+///
+/// # Errors
+/// - `MarketClosedToDeposits`: The market is full
+fn require_participant_slot(
+    env: &Env,
+    market: &Market,
+    market_id: u32,
+) -> Result<(), ContractError> {
+    if market.max_participants > 0
+        && storage::get_market_participants(env, market_id).len() >= market.max_participants
+    {
+        return Err(ContractError::MarketClosedToDeposits);
+    }
+    Ok(())
+}
+
+/// Load `market_id` and check that it accepts deposits now.
+///
+/// # Errors
+/// - `MarketNotFound`: market_id doesn't exist in storage
+/// - `MarketNotActive`: Market is resolved or cancelled
+/// - `MarketClosedToDeposits`: The admin closed the market to deposits
+/// - `MarketExpired`: The market's `end_time` has passed
+fn load_depositable_market(env: &Env, market_id: u32) -> Result<Market, ContractError> {
+    let market = storage::get_market(env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+
+    if market.status != MarketStatus::Active {
+        return Err(ContractError::MarketNotActive);
+    }
+
+    if market.closed_to_deposits {
+        return Err(ContractError::MarketClosedToDeposits);
+    }
+
+    if env.ledger().timestamp() > market.end_time {
+        return Err(ContractError::MarketExpired);
+    }
+
+    Ok(market)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::Market;
+    use crate::types::AdapterType;
     use soroban_sdk::token::StellarAssetClient;
-    use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, BytesN, Env, String,
+    };
 
     fn setup_env() -> Env {
         Env::default()
@@ -151,6 +219,25 @@ mod tests {
             resolved_at: None,
             adapter_type: AdapterType::Ed25519,
             outcome_count: 2,
+            closed_to_deposits: false,
+            source_hash: BytesN::from_array(env, &[0u8; 32]),
+            resolution_grace: None,
+            fee_rate_bps: None,
+            withdraw_cooldown: None,
+            challenge_deadline: None,
+            trading_fee_bps: 0,
+            collateral_decimals: 7,
+            total_yes_shares: 0,
+            total_no_shares: 0,
+            yes_reserve: 0,
+            no_reserve: 0,
+            category: soroban_sdk::Symbol::new(env, "general"),
+            resolution_source: String::from_str(env, "test source"),
+            kind: crate::types::MarketKind::Binary,
+            settled_value: None,
+            min_deposit: 0,
+            max_position: 0,
+            max_participants: 0,
         }
     }
 
@@ -484,8 +571,8 @@ mod tests {
         let contract_id = env.register(crate::MarketContract, ());
 
         // Create market with end_time in the past
-        let mut market = create_test_market(&env, market_id, &collateral_token);
-        market.end_time = 0; // expired
+        let market = create_test_market(&env, market_id, &collateral_token);
+        env.ledger().set_timestamp(market.end_time + 1); // expired
         env.as_contract(&contract_id, || {
             storage::set_version(&env);
             storage::set_market(&env, market_id, &market).unwrap();
@@ -539,4 +626,106 @@ mod tests {
         // Final total must equal sum of all deposits
         assert_eq!(running, deposits.iter().sum::<i128>());
     }
+
+    /// Collateral token that rejects every transfer.
+    #[soroban_sdk::contract]
+    struct RejectingToken;
+
+    #[soroban_sdk::contractimpl]
+    impl RejectingToken {
+        pub fn transfer(_env: Env, _from: Address, _to: Address, _amount: i128) {
+            panic!("transfer rejected");
+        }
+    }
+
+    #[test]
+    fn test_rejected_transfer_returns_token_transfer_failed() {
+        let env = setup_env();
+        let user = Address::generate(&env);
+        let market_id = 1;
+        let token_id = env.register(RejectingToken, ());
+        let contract_id = env.register(crate::MarketContract, ());
+        let client = crate::MarketContractClient::new(&env, &contract_id);
+
+        let market = create_test_market(&env, market_id, &token_id);
+        env.as_contract(&contract_id, || {
+            storage::set_version(&env);
+            storage::set_market(&env, market_id, &market).unwrap();
+        });
+        env.mock_all_auths();
+
+        assert_eq!(
+            client.try_deposit_collateral(&user, &market_id, &5_000, &None),
+            Err(Ok(ContractError::TokenTransferFailed))
+        );
+        let position = env.as_contract(&contract_id, || {
+            storage::get_position(&env, market_id, &user).unwrap()
+        });
+        assert!(position.is_none());
+    }
+
+    /// Collateral token whose `transfer` tries to deposit into the market
+    /// again before returning, recording whether that inner call succeeded.
+    #[soroban_sdk::contract]
+    struct ReentrantToken;
+
+    #[soroban_sdk::contractimpl]
+    impl ReentrantToken {
+        pub fn set_market(env: Env, market: Address) {
+            env.storage()
+                .instance()
+                .set(&soroban_sdk::symbol_short!("market"), &market);
+        }
+
+        pub fn reentered(env: Env) -> Option<bool> {
+            env.storage()
+                .instance()
+                .get(&soroban_sdk::symbol_short!("reentered"))
+        }
+
+        pub fn transfer(env: Env, from: Address, _to: Address, amount: i128) {
+            let market: Address = env
+                .storage()
+                .instance()
+                .get(&soroban_sdk::symbol_short!("market"))
+                .unwrap();
+            let reentered = crate::MarketContractClient::new(&env, &market)
+                .try_deposit_collateral(&from, &1, &amount, &None)
+                .is_ok();
+            env.storage()
+                .instance()
+                .set(&soroban_sdk::symbol_short!("reentered"), &reentered);
+        }
+    }
+
+    #[test]
+    fn test_reentrant_token_cannot_double_count_deposit() {
+        let env = setup_env();
+        let user = Address::generate(&env);
+        let market_id = 1;
+        let token_id = env.register(ReentrantToken, ());
+        let token = ReentrantTokenClient::new(&env, &token_id);
+        let contract_id = env.register(crate::MarketContract, ());
+        let client = crate::MarketContractClient::new(&env, &contract_id);
+        token.set_market(&contract_id);
+
+        let market = create_test_market(&env, market_id, &token_id);
+        env.as_contract(&contract_id, || {
+            storage::set_version(&env);
+            storage::set_market(&env, market_id, &market).unwrap();
+        });
+        env.mock_all_auths();
+
+        client.deposit_collateral(&user, &market_id, &5_000, &None);
+
+        // The token's nested deposit was refused, so the one transfer is
+        // recorded exactly once.
+        assert_eq!(token.reentered(), Some(false));
+        let position = client.get_position(&market_id, &user);
+        assert_eq!(position.total_deposited, 5_000);
+        let collateral = env.as_contract(&contract_id, || {
+            storage::get_market_collateral(&env, market_id)
+        });
+        assert_eq!(collateral, 5_000);
+    }
 }
@@ -52,8 +52,25 @@ pub enum ContractError {
     /// Only Active markets accept new trades and collateral deposits.
     MarketNotActive = 5,
 
+    /// Market has been closed to new deposits by the admin.
+    ///
+    /// Withdrawals and trading remain available; only new collateral is rejected.
+    /// Also returned to a user new to a market that has reached its
+    /// `max_participants`, while existing participants may still deposit.
+    MarketClosedToDeposits = 6,
+
+    /// Market metadata is invalid: the URI is empty or longer than 2048
+    /// characters, or the resolution source is empty or 200 characters or
+    /// longer.
+    InvalidMetadataUri = 7,
+
     /// Withdraw attempted before the cooldown period since the last deposit has elapsed.
-    WithdrawCooldownActive = 6,
+    WithdrawCooldownActive = 8,
+
+    /// Resolution was attempted before the market's end_time.
+    ///
+    /// The outcome can only be submitted once trading has closed.
+    MarketNotEnded = 9,
 
     // ========== Position Errors (10-19) ==========
     /// User does not have enough collateral locked to perform this operation.
@@ -74,6 +91,8 @@ pub enum ContractError {
     /// Share amount is invalid (e.g., negative or zero when positive required).
     ///
     /// Share amounts must be non-negative, and at least one side must be positive.
+    /// Returned for bad share counts in trades; collateral amounts use
+    /// [`ContractError::InvalidQuantity`].
     InvalidShareAmount = 13,
 
     // ========== Oracle Errors (20-29) ==========
@@ -87,9 +106,11 @@ pub enum ContractError {
     /// Only the designated oracle can submit resolutions for this market.
     UnauthorizedOracle = 21,
 
-    /// Resolution outcome value is invalid (must be true or false).
+    /// The market's outcome is invalid or does not fit the market.
     ///
-    /// Outcome must be a valid boolean value.
+    /// Raised for a scalar band whose lower bound is not below its upper
+    /// bound, and when a binary market is resolved with
+    /// `resolve_scalar_market` or a scalar one with `resolve_market`.
     InvalidOutcome = 22,
 
     /// Reflector oracle returned no price for the requested asset.
@@ -99,6 +120,16 @@ pub enum ContractError {
     /// node network may be temporarily disconnected.
     OraclePriceUnavailable = 23,
 
+    /// The same oracle key appears more than once.
+    ///
+    /// Each oracle may sign or be listed only once, so a single key cannot
+    /// make up a multi-oracle threshold on its own.
+    DuplicateOracleSigner = 24,
+
+    /// A market oracle set is empty, too large, or has a threshold of zero
+    /// or above its size.
+    InvalidOracleSet = 25,
+
     // ========== Validation Errors (30-39) ==========
     /// Price is out of valid range (must be between 0 and 1).
     ///
@@ -107,17 +138,23 @@ pub enum ContractError {
 
     /// Quantity is invalid (must be positive).
     ///
-    /// Quantities, amounts, and counts must be greater than zero.
+    /// Collateral amounts and other counts must be greater than zero. Share
+    /// counts in trades use [`ContractError::InvalidShareAmount`] instead.
+    /// Also returned by `deposit_collateral` for an idempotency key the user
+    /// has already deposited with, since that deposit has nothing left to
+    /// apply.
     InvalidQuantity = 31,
 
     /// Timestamp is invalid (e.g., end_time in the past or too far in future).
     ///
-    /// Market end_time must be in the future and within one year.
+    /// Market end_time must be in the future and within the max market
+    /// duration (one year by default).
     InvalidTimestamp = 32,
 
     /// Market question is invalid (e.g., empty string or exceeds 500 characters).
     ///
-    /// Questions must be non-empty and reasonably sized (1-499 characters).
+    /// Questions must be non-empty and reasonably sized (1-499 characters),
+    /// and must not exactly match the question of another active market.
     InvalidQuestion = 33,
 
     /// Outcome count is not exactly 2.
@@ -132,6 +169,18 @@ pub enum ContractError {
     /// or any special/reserved address.
     InvalidAdmin = 35,
 
+    /// Deposit amount is below the protocol minimum (`MIN_DEPOSIT_AMOUNT`) or
+    /// the market's `min_deposit`.
+    BelowMinDeposit = 36,
+
+    /// Requested fee rate exceeds the admin-configured fee cap.
+    FeeCapExceeded = 37,
+    /// Execution price is worse than the caller's `max_price` slippage bound.
+    SlippageExceeded = 38,
+    /// Per-market resolution grace is outside
+    /// `MIN_RESOLUTION_GRACE_SECONDS..=MAX_RESOLUTION_GRACE_SECONDS`.
+    InvalidResolutionGrace = 39,
+
     // ========== Authorization Errors (40-49) ==========
     /// Caller is not authorized to perform this action.
     ///
@@ -167,6 +216,12 @@ pub enum ContractError {
     /// Ensure the user has sufficient balance and has approved the contract.
     TokenTransferFailed = 50,
 
+    /// Collateral token address does not behave like a token.
+    ///
+    /// Rejected at market creation when the address is the market contract
+    /// itself or does not answer a read-only `decimals()` call.
+    UnsupportedToken = 51,
+
     // ========== Arithmetic Errors (60-69) ==========
     /// Arithmetic operation overflowed.
     ///
@@ -187,6 +242,46 @@ pub enum ContractError {
     /// Call `ResolutionContract::finalize` first, then retry `resolve_market`.
     ResolutionNotFinalized = 80,
 
+    /// The oracle resolution window for this market has closed.
+    ///
+    /// Resolution is accepted until `end_time` plus the market's grace
+    /// (or the global default); afterwards the admin must cancel the market.
+    ResolutionWindowClosed = 81,
+
+    /// A resolution commitment is pending for this market.
+    ///
+    /// Trading is frozen and no further commitment is accepted until the
+    /// oracle reveals with `reveal_resolution`.
+    ResolutionCommitted = 82,
+
+    /// `reveal_resolution` was called for a market with no commitment, or
+    /// `admin_execute_resolution` for one with no admin proposal.
+    NoResolutionCommitment = 83,
+
+    /// The revealed outcome, salt and signature do not hash to the commitment.
+    CommitmentMismatch = 84,
+
+    /// The reveal was submitted in the same ledger as the commitment.
+    ///
+    /// Wait for at least one more ledger before calling `reveal_resolution`.
+    RevealTooEarly = 85,
+
+    /// `finalize_resolution` was called before the challenge deadline, or
+    /// `admin_execute_resolution` before the admin proposal's timelock.
+    ///
+    /// The proposed outcome can still be challenged; retry once the ledger
+    /// timestamp reaches the market's `challenge_deadline` (or the
+    /// proposal's `executable_at`).
+    ChallengeWindowOpen = 86,
+
+    /// `dispute_resolution` was called at or after the challenge deadline.
+    ///
+    /// Once the window has passed the proposed outcome can only be finalized.
+    ChallengeWindowClosed = 87,
+
+    /// `resolve_dispute` was called for a market that is not disputed.
+    MarketNotDisputed = 88,
+
     // ========== Pause / Initialization Errors (90-99) ==========
     /// The contract has not been initialized yet.
     ///
@@ -212,6 +307,9 @@ mod tests {
         assert_eq!(ContractError::MarketExpired as u32, 4);
         assert_eq!(ContractError::MarketNotActive as u32, 5);
         assert_eq!(ContractError::MarketClosedToDeposits as u32, 6);
+        assert_eq!(ContractError::InvalidMetadataUri as u32, 7);
+        assert_eq!(ContractError::WithdrawCooldownActive as u32, 8);
+        assert_eq!(ContractError::MarketNotEnded as u32, 9);
         assert_eq!(ContractError::InsufficientCollateral as u32, 10);
         assert_eq!(ContractError::PositionAlreadySettled as u32, 11);
         assert_eq!(ContractError::NoPositionFound as u32, 12);
@@ -220,20 +318,26 @@ mod tests {
         assert_eq!(ContractError::UnauthorizedOracle as u32, 21);
         assert_eq!(ContractError::InvalidOutcome as u32, 22);
         assert_eq!(ContractError::OraclePriceUnavailable as u32, 23);
+        assert_eq!(ContractError::DuplicateOracleSigner as u32, 24);
+        assert_eq!(ContractError::InvalidOracleSet as u32, 25);
         assert_eq!(ContractError::InvalidPrice as u32, 30);
         assert_eq!(ContractError::InvalidQuantity as u32, 31);
         assert_eq!(ContractError::InvalidTimestamp as u32, 32);
         assert_eq!(ContractError::InvalidQuestion as u32, 33);
-        assert_eq!(ContractError::InvalidFeeRate as u32, 34);
+        assert_eq!(ContractError::InvalidOutcomeCount as u32, 34);
+        assert_eq!(ContractError::InvalidAdmin as u32, 35);
+        assert_eq!(ContractError::BelowMinDeposit as u32, 36);
+        assert_eq!(ContractError::FeeCapExceeded as u32, 37);
+        assert_eq!(ContractError::SlippageExceeded as u32, 38);
         assert_eq!(ContractError::Unauthorized as u32, 40);
         assert_eq!(ContractError::NotAdmin as u32, 41);
         assert_eq!(ContractError::AlreadyInitialized as u32, 42);
         assert_eq!(ContractError::NoPendingAdmin as u32, 43);
         assert_eq!(ContractError::TokenTransferFailed as u32, 50);
+        assert_eq!(ContractError::UnsupportedToken as u32, 51);
         assert_eq!(ContractError::ArithmeticOverflow as u32, 60);
         assert_eq!(ContractError::NotInitialized as u32, 90);
         assert_eq!(ContractError::ContractPaused as u32, 91);
-
     }
 
     #[test]
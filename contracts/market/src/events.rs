@@ -9,22 +9,45 @@
 //! | Struct                   | Topic symbol                        |
 //! |--------------------------|-------------------------------------|
 //! | `ContractInitialized`    | `contract_initialized`              |
+//! | `ContractUpgradedEvent`  | `contract_upgraded_event`           |
 //! | `MarketCreated`          | `market_created`                    |
 //! | `CollateralDeposited`    | `collateral_deposited`              |
 //! | `CollateralWithdrawn`    | `collateral_withdrawn`              |
 //! | `WithdrawEdgeCase`       | `withdraw_edge_case`                |
+//! | `ResolutionCommitted`    | `resolution_committed`              |
+//! | `ResolutionProposed`     | `resolution_proposed`               |
+//! | `ResolutionDisputed`     | `resolution_disputed`               |
+//! | `DisputeResolved`        | `dispute_resolved`                  |
+//! | `AdminResolutionProposed`| `admin_resolution_proposed`         |
+//! | `AdminResolutionExecuted`| `admin_resolution_executed`         |
 //! | `MarketResolved`         | `market_resolved`                   |
+//! | `MarketInvalidated`      | `market_invalidated`                |
+//! | `ScalarMarketResolved`   | `scalar_market_resolved`            |
 //! | `MarketCanceled`         | `market_canceled`                   |
+//! | `MarketStatusChangedEvent` | `market_status_changed_event`     |
+//! | `MarketReopened`         | `market_reopened`                   |
+//! | `MarketCategorized`      | `market_categorized`                |
+//! | `OracleRotated`          | `oracle_rotated`                    |
 //! | `PositionSettled`        | `position_settled`                  |
+//! | `Claimed`                | `claimed`                           |
 //! | `PositionUpdated`        | `position_updated`                  |
 //! | `PositionLimitExceeded`  | `position_limit_exceeded`           |
 //! | `OracleSignatureVerified`| `oracle_signature_verified`         |
 //! | `FeeCalculated`          | `fee_calculated`                    |
+//! | `FeesWithdrawn`          | `fees_withdrawn`                    |
 //! | `TreasurySet`            | `treasury_set`                      |
 //! | `AdminTransferProposed`  | `admin_transfer_proposed`           |
 //! | `AdminTransferAccepted`  | `admin_transfer_accepted`           |
+//!
+//! # Event ordering
+//!
+//! Every event carries an `event_seq` data field taken from a contract-wide
+//! counter ([`crate::storage::next_event_seq`]) that starts at 1 and grows by
+//! one per emission. Indexers can use it to order events and to detect any
+//! they missed.
 
-use soroban_sdk::{contractevent, Address, BytesN, Env, String};
+use crate::types::MarketStatus;
+use soroban_sdk::{contractevent, Address, BytesN, Env, String, Symbol};
 
 #[contractevent]
 #[derive(Clone, Debug)]
@@ -33,6 +56,7 @@ pub struct ContractInitialized {
     pub admin: Address,
     /// Ledger timestamp when the contract was bootstrapped.
     pub initialized_at: u64,
+    pub event_seq: u64,
 }
 
 /// Emit an event when the contract is initialized with an admin.
@@ -48,6 +72,31 @@ pub fn emit_contract_initialized(env: &Env, admin: &Address) {
     ContractInitialized {
         admin: admin.clone(),
         initialized_at: env.ledger().timestamp(),
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+/// Event emitted when the admin replaces the contract's code.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ContractUpgradedEvent {
+    #[topic]
+    pub new_wasm_hash: BytesN<32>,
+    /// Contract version after the upgrade.
+    pub version: u32,
+    /// Ledger timestamp when the upgrade was applied.
+    pub upgraded_at: u64,
+    pub event_seq: u64,
+}
+
+/// Emit event when `upgrade` installs new contract code.
+pub fn emit_contract_upgraded(env: &Env, new_wasm_hash: &BytesN<32>, version: u32) {
+    ContractUpgradedEvent {
+        new_wasm_hash: new_wasm_hash.clone(),
+        version,
+        upgraded_at: env.ledger().timestamp(),
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -60,6 +109,7 @@ pub struct EmergencyPauseToggledEvent {
     pub paused: bool,
     /// Ledger timestamp when the pause state was changed.
     pub timestamp: u64,
+    pub event_seq: u64,
 }
 
 /// Emit event when the emergency pause flag is toggled.
@@ -67,6 +117,7 @@ pub fn emit_emergency_pause_toggled(env: &Env, paused: bool) {
     EmergencyPauseToggledEvent {
         paused,
         timestamp: env.ledger().timestamp(),
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -77,10 +128,14 @@ pub fn emit_emergency_pause_toggled(env: &Env, paused: bool) {
 pub struct MarketCreated {
     #[topic]
     pub market_id: u32,
+    #[topic]
     pub creator: Address,
+    #[topic]
+    pub category: Symbol,
     pub question: String,
     pub end_time: u64,
     pub metadata_uri: Option<String>,
+    pub event_seq: u64,
 }
 
 #[contractevent]
@@ -92,6 +147,7 @@ pub struct CollateralDeposited {
     pub market_id: u32,
     pub amount: i128,
     pub new_total: i128,
+    pub event_seq: u64,
 }
 
 #[contractevent]
@@ -103,6 +159,7 @@ pub struct CollateralWithdrawn {
     pub market_id: u32,
     pub amount: i128,
     pub new_total: i128,
+    pub event_seq: u64,
 }
 
 #[contractevent]
@@ -113,6 +170,7 @@ pub struct WithdrawEdgeCase {
     #[topic]
     pub market_id: u32,
     pub amount: i128,
+    pub event_seq: u64,
 }
 
 /// Emit event when collateral is deposited
@@ -144,6 +202,7 @@ pub fn emit_collateral_deposited(
         market_id,
         amount,
         new_total,
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -177,6 +236,7 @@ pub fn emit_collateral_withdrawn(
         market_id,
         amount,
         new_total,
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -184,24 +244,28 @@ pub fn emit_collateral_withdrawn(
 /// Emit a MarketCreated event
 ///
 /// Publishes a [`MarketCreated`] to the Soroban event stream when a new
-/// prediction market is initialized. The event is indexed by `market_id` as
-/// a topic for efficient lookup by off-chain indexers and frontends.
+/// prediction market is initialized. The event is indexed by `market_id`,
+/// `creator` and `category` as topics, so off-chain indexers and frontends can
+/// look markets up by ID or filter them by author or category.
 ///
 /// # Arguments
 /// * env - Contract environment
 /// * market_id - Unique identifier of the created market
 /// * creator - Address that created the market
+/// * category - Category assigned to the market at creation
 /// * question - The market question
 /// * end_time - Unix timestamp when market closes for trading
+/// * metadata_uri - Optional off-chain metadata URI
 ///
 /// # Example
 /// ```ignore
-/// emit_market_created(&env, 1, &creator, &String::from_str(&env, "Will BTC hit $100k?"), 1735689600);
+/// emit_market_created(&env, 1, &creator, &Symbol::new(&env, "crypto"), &String::from_str(&env, "Will BTC hit $100k?"), 1735689600, &None);
 /// ```
 pub fn emit_market_created(
     env: &Env,
     market_id: u32,
     creator: &Address,
+    category: &Symbol,
     question: &String,
     end_time: u64,
     metadata_uri: &Option<String>,
@@ -210,9 +274,11 @@ pub fn emit_market_created(
     MarketCreated {
         market_id,
         creator: creator.clone(),
+        category: category.clone(),
         question: question.clone(),
         end_time,
         metadata_uri: metadata_uri.clone(),
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -224,6 +290,7 @@ pub struct MarketClosedToDepositsEvent {
     pub market_id: u32,
     pub admin: Address,
     pub closed_at: u64,
+    pub event_seq: u64,
 }
 
 /// Emit event when a market is closed to new deposits
@@ -252,6 +319,7 @@ pub fn emit_market_closed_to_deposits(
         market_id,
         admin: admin.clone(),
         closed_at,
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -277,6 +345,210 @@ pub fn emit_withdraw_edge_case(env: &Env, user: &Address, market_id: u32, amount
         user: user.clone(),
         market_id,
         amount,
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ResolutionProposed {
+    #[topic]
+    pub market_id: u32,
+    pub resolver: Address,
+    pub outcome: bool,
+    /// Timestamp after which `finalize_resolution` may resolve the market.
+    pub challenge_deadline: u64,
+    pub event_seq: u64,
+}
+
+/// Emit a ResolutionProposed event
+///
+/// Publishes a [`ResolutionProposed`] when a verified outcome enters its
+/// challenge window. `MarketResolved` follows once the market is finalized.
+///
+/// # Arguments
+/// * `env` - Contract environment
+/// * `market_id` - Unique identifier of the market
+/// * `resolver` - Address that submitted the resolution
+/// * `outcome` - Proposed outcome (true = YES, false = NO)
+/// * `challenge_deadline` - End of the challenge window
+pub fn emit_resolution_proposed(
+    env: &Env,
+    market_id: u32,
+    resolver: &Address,
+    outcome: bool,
+    challenge_deadline: u64,
+) {
+    ResolutionProposed {
+        market_id,
+        resolver: resolver.clone(),
+        outcome,
+        challenge_deadline,
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ResolutionDisputed {
+    #[topic]
+    pub market_id: u32,
+    #[topic]
+    pub disputer: Address,
+    /// Outcome proposed by the oracle that is being disputed.
+    pub proposed_outcome: bool,
+    pub bond: i128,
+    pub event_seq: u64,
+}
+
+/// Emit a ResolutionDisputed event
+///
+/// Publishes a [`ResolutionDisputed`] when a bonded dispute is raised against
+/// a market's proposed outcome during its challenge window.
+///
+/// # Arguments
+/// * `env` - Contract environment
+/// * `market_id` - Unique identifier of the market
+/// * `disputer` - Address that posted the bond
+/// * `proposed_outcome` - Outcome under dispute
+/// * `bond` - Bond amount in the market's collateral token
+pub fn emit_resolution_disputed(
+    env: &Env,
+    market_id: u32,
+    disputer: &Address,
+    proposed_outcome: bool,
+    bond: i128,
+) {
+    ResolutionDisputed {
+        market_id,
+        disputer: disputer.clone(),
+        proposed_outcome,
+        bond,
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct DisputeResolved {
+    #[topic]
+    pub market_id: u32,
+    #[topic]
+    pub admin: Address,
+    pub disputer: Address,
+    pub final_outcome: bool,
+    /// Whether the bond was returned to the disputer; otherwise it was
+    /// slashed into the market's accrued fees.
+    pub bond_refunded: bool,
+    pub bond: i128,
+    pub event_seq: u64,
+}
+
+/// Emit a DisputeResolved event
+///
+/// Publishes a [`DisputeResolved`] when the admin settles a dispute, fixing
+/// the market's final outcome. `MarketResolved` is emitted alongside it.
+///
+/// # Arguments
+/// * `env` - Contract environment
+/// * `market_id` - Unique identifier of the market
+/// * `admin` - Admin that settled the dispute
+/// * `disputer` - Address that raised the dispute
+/// * `final_outcome` - Outcome the market resolves to
+/// * `bond_refunded` - Whether the bond went back to the disputer
+/// * `bond` - Bond amount in the market's collateral token
+pub fn emit_dispute_resolved(
+    env: &Env,
+    market_id: u32,
+    admin: &Address,
+    disputer: &Address,
+    final_outcome: bool,
+    bond_refunded: bool,
+    bond: i128,
+) {
+    DisputeResolved {
+        market_id,
+        admin: admin.clone(),
+        disputer: disputer.clone(),
+        final_outcome,
+        bond_refunded,
+        bond,
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AdminResolutionProposed {
+    #[topic]
+    pub market_id: u32,
+    #[topic]
+    pub admin: Address,
+    pub outcome: bool,
+    /// Earliest ledger timestamp at which the proposal can be executed.
+    pub executable_at: u64,
+    pub event_seq: u64,
+}
+
+/// Emit an AdminResolutionProposed event
+///
+/// Publishes an [`AdminResolutionProposed`] when the admin proposes a manual
+/// outcome for a market, starting its timelock.
+///
+/// # Arguments
+/// * `env` - Contract environment
+/// * `market_id` - Unique identifier of the market
+/// * `admin` - Admin that proposed the outcome
+/// * `outcome` - Proposed outcome
+/// * `executable_at` - When the proposal becomes executable
+pub fn emit_admin_resolution_proposed(
+    env: &Env,
+    market_id: u32,
+    admin: &Address,
+    outcome: bool,
+    executable_at: u64,
+) {
+    AdminResolutionProposed {
+        market_id,
+        admin: admin.clone(),
+        outcome,
+        executable_at,
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct AdminResolutionExecuted {
+    #[topic]
+    pub market_id: u32,
+    #[topic]
+    pub admin: Address,
+    pub outcome: bool,
+    pub event_seq: u64,
+}
+
+/// Emit an AdminResolutionExecuted event
+///
+/// Publishes an [`AdminResolutionExecuted`] when a timelocked admin
+/// proposal resolves its market. `MarketResolved` is emitted alongside it.
+///
+/// # Arguments
+/// * `env` - Contract environment
+/// * `market_id` - Unique identifier of the market
+/// * `admin` - Admin that proposed the outcome
+/// * `outcome` - Outcome the market resolves to
+pub fn emit_admin_resolution_executed(env: &Env, market_id: u32, admin: &Address, outcome: bool) {
+    AdminResolutionExecuted {
+        market_id,
+        admin: admin.clone(),
+        outcome,
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -286,17 +558,22 @@ pub fn emit_withdraw_edge_case(env: &Env, user: &Address, market_id: u32, amount
 pub struct MarketResolved {
     #[topic]
     pub market_id: u32,
+    #[topic]
     pub oracle_pubkey: BytesN<32>,
     pub resolver: Address,
     pub outcome: bool,
+    /// Collateral deposited in the market's unsettled positions at resolution.
+    pub total_collateral: i128,
     pub resolved_at: u64,
+    pub event_seq: u64,
 }
 
 /// Emit a MarketResolved event
 ///
 /// Publishes a [`MarketResolved`] to the Soroban event stream when a
-/// market is resolved by an oracle. The event is indexed by `market_id` as
-/// a topic, allowing efficient queries for resolution events.
+/// market is resolved by an oracle. The event is indexed by `market_id` and
+/// `oracle_pubkey` as topics, so indexers can filter resolutions by market or
+/// by oracle.
 ///
 /// # Arguments
 /// * env - Contract environment
@@ -304,11 +581,12 @@ pub struct MarketResolved {
 /// * oracle_pubkey - Oracle public key used to verify the resolution signature
 /// * resolver - Address of the resolver who submitted the resolution
 /// * outcome - Market outcome (true = YES won, false = NO won)
+/// * total_collateral - Collateral at stake in the market's unsettled positions
 /// * resolved_at - Unix timestamp when market was resolved
 ///
 /// # Example
 /// ```ignore
-/// emit_market_resolved(&env, 1, &oracle_pubkey, &resolver_address, true, env.ledger().timestamp());
+/// emit_market_resolved(&env, 1, &oracle_pubkey, &resolver, true, 1_000, env.ledger().timestamp());
 /// ```
 pub fn emit_market_resolved(
     env: &Env,
@@ -316,6 +594,7 @@ pub fn emit_market_resolved(
     oracle_pubkey: &BytesN<32>,
     resolver: &Address,
     outcome: bool,
+    total_collateral: i128,
     resolved_at: u64,
 ) {
     MarketResolved {
@@ -323,7 +602,97 @@ pub fn emit_market_resolved(
         oracle_pubkey: oracle_pubkey.clone(),
         resolver: resolver.clone(),
         outcome,
+        total_collateral,
+        resolved_at,
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct MarketInvalidated {
+    #[topic]
+    pub market_id: u32,
+    #[topic]
+    pub oracle_pubkey: BytesN<32>,
+    pub resolver: Address,
+    /// Collateral deposited in the market's unsettled positions, all of
+    /// which is refunded.
+    pub total_collateral: i128,
+    pub resolved_at: u64,
+    pub event_seq: u64,
+}
+
+/// Emit a MarketInvalidated event
+///
+/// Publishes a [`MarketInvalidated`] when the oracle resolves a market to
+/// `Outcome::Invalid`. It takes the place of `MarketResolved`, which carries
+/// a winning side.
+///
+/// # Arguments
+/// * env - Contract environment
+/// * market_id - Unique identifier of the resolved market
+/// * oracle_pubkey - Oracle public key used to verify the resolution signature
+/// * resolver - Address of the resolver who submitted the resolution
+/// * total_collateral - Collateral at stake in the market's unsettled positions
+/// * resolved_at - Unix timestamp when market was resolved
+pub fn emit_market_invalidated(
+    env: &Env,
+    market_id: u32,
+    oracle_pubkey: &BytesN<32>,
+    resolver: &Address,
+    total_collateral: i128,
+    resolved_at: u64,
+) {
+    MarketInvalidated {
+        market_id,
+        oracle_pubkey: oracle_pubkey.clone(),
+        resolver: resolver.clone(),
+        total_collateral,
+        resolved_at,
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ScalarMarketResolved {
+    #[topic]
+    pub market_id: u32,
+    #[topic]
+    pub oracle_pubkey: BytesN<32>,
+    pub resolver: Address,
+    /// Oracle-signed value; payouts clamp it into the market's bounds.
+    pub value: i128,
+    /// Collateral deposited in the market's unsettled positions at resolution.
+    pub total_collateral: i128,
+    pub resolved_at: u64,
+    pub event_seq: u64,
+}
+
+/// Emit a ScalarMarketResolved event
+///
+/// The scalar counterpart of [`emit_market_resolved`], published when a
+/// scalar market resolves to an oracle-signed value.
+pub fn emit_scalar_market_resolved(
+    env: &Env,
+    market_id: u32,
+    oracle_pubkey: &BytesN<32>,
+    resolver: &Address,
+    value: i128,
+    total_collateral: i128,
+    resolved_at: u64,
+) {
+    ScalarMarketResolved {
+        market_id,
+        oracle_pubkey: oracle_pubkey.clone(),
+        resolver: resolver.clone(),
+        value,
+        total_collateral,
         resolved_at,
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -335,6 +704,41 @@ pub struct MarketCanceled {
     pub market_id: u32,
     pub canceler: Address,
     pub canceled_at: u64,
+    pub event_seq: u64,
+}
+
+/// Event emitted whenever a market's status changes, whatever caused it.
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct MarketStatusChangedEvent {
+    #[topic]
+    pub market_id: u32,
+    pub old_status: MarketStatus,
+    pub new_status: MarketStatus,
+    /// Ledger timestamp when the status changed.
+    pub changed_at: u64,
+    pub event_seq: u64,
+}
+
+/// Emit event when a stored market moves from `old_status` to `new_status`.
+///
+/// Published by `storage::set_market`, which every status change goes
+/// through, so indexers can follow a market's lifecycle from this one
+/// stream alongside the operation-specific events.
+pub fn emit_market_status_changed(
+    env: &Env,
+    market_id: u32,
+    old_status: &MarketStatus,
+    new_status: &MarketStatus,
+) {
+    MarketStatusChangedEvent {
+        market_id,
+        old_status: old_status.clone(),
+        new_status: new_status.clone(),
+        changed_at: env.ledger().timestamp(),
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
 }
 
 /// Emit a MarketCanceled event
@@ -359,6 +763,152 @@ pub fn emit_market_canceled(env: &Env, market_id: u32, canceler: &Address, cance
         market_id,
         canceler: canceler.clone(),
         canceled_at,
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct MarketReopened {
+    #[topic]
+    pub market_id: u32,
+    pub reopener: Address,
+    pub reopened_at: u64,
+    pub event_seq: u64,
+}
+
+/// Emit a MarketReopened event
+///
+/// Publishes a [`MarketReopened`] when an admin reverts an accidental
+/// cancellation, so indexers can hide the reclaim flow again and resume
+/// showing the market as tradeable.
+///
+/// # Arguments
+/// * `env` - Contract environment
+/// * `market_id` - Unique identifier of the reopened market
+/// * `reopener` - Admin address that reopened the market
+/// * `reopened_at` - Unix timestamp (ledger time) when the market was reopened
+pub fn emit_market_reopened(env: &Env, market_id: u32, reopener: &Address, reopened_at: u64) {
+    MarketReopened {
+        market_id,
+        reopener: reopener.clone(),
+        reopened_at,
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct ResolutionCommitted {
+    #[topic]
+    pub market_id: u32,
+    #[topic]
+    pub resolver: Address,
+    pub commitment: BytesN<32>,
+    /// Ledger sequence of the commitment; the reveal must land after it.
+    pub ledger: u32,
+    pub event_seq: u64,
+}
+
+/// Emit a ResolutionCommitted event
+///
+/// Publishes a [`ResolutionCommitted`] when an oracle seals its resolution,
+/// signalling that trading in the market is frozen until the reveal.
+///
+/// # Arguments
+/// * `env` - Contract environment
+/// * `market_id` - Unique identifier of the market
+/// * `resolver` - Address that committed and must reveal
+/// * `commitment` - `keccak256(outcome_byte || salt || signature)`
+/// * `ledger` - Ledger sequence in which the commitment was stored
+pub fn emit_resolution_committed(
+    env: &Env,
+    market_id: u32,
+    resolver: &Address,
+    commitment: &BytesN<32>,
+    ledger: u32,
+) {
+    ResolutionCommitted {
+        market_id,
+        resolver: resolver.clone(),
+        commitment: commitment.clone(),
+        ledger,
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct MarketCategorized {
+    #[topic]
+    pub market_id: u32,
+    pub category: Symbol,
+    pub previous: Option<Symbol>,
+    pub event_seq: u64,
+}
+
+/// Emit a MarketCategorized event
+///
+/// Publishes a [`MarketCategorized`] when an admin assigns or changes a
+/// market's category, so indexers can move it between filtered listings.
+///
+/// # Arguments
+/// * `env` - Contract environment
+/// * `market_id` - Unique identifier of the market
+/// * `category` - The market's new category
+/// * `previous` - The category it was removed from, if any
+pub fn emit_market_categorized(
+    env: &Env,
+    market_id: u32,
+    category: &Symbol,
+    previous: &Option<Symbol>,
+) {
+    MarketCategorized {
+        market_id,
+        category: category.clone(),
+        previous: previous.clone(),
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct OracleRotated {
+    #[topic]
+    pub market_id: u32,
+    pub old_pubkey: BytesN<32>,
+    pub new_pubkey: BytesN<32>,
+    pub rotated_at: u64,
+    pub event_seq: u64,
+}
+
+/// Emit an OracleRotated event
+///
+/// Publishes an [`OracleRotated`] when an admin replaces the oracle key of an
+/// unresolved market, so participants can audit which key will sign its
+/// outcome.
+///
+/// # Arguments
+/// * `env` - Contract environment
+/// * `market_id` - Unique identifier of the market
+/// * `old_pubkey` - The key that was replaced
+/// * `new_pubkey` - The key now trusted to resolve the market
+pub fn emit_oracle_rotated(
+    env: &Env,
+    market_id: u32,
+    old_pubkey: &BytesN<32>,
+    new_pubkey: &BytesN<32>,
+) {
+    OracleRotated {
+        market_id,
+        old_pubkey: old_pubkey.clone(),
+        new_pubkey: new_pubkey.clone(),
+        rotated_at: env.ledger().timestamp(),
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -372,6 +922,7 @@ pub struct PositionLimitExceeded {
     pub user: Address,
     /// The share side that would go negative: true = YES, false = NO
     pub side_yes: bool,
+    pub event_seq: u64,
 }
 
 /// Emit an event when a position change is rejected because it would push a
@@ -393,6 +944,7 @@ pub fn emit_position_limit_exceeded(env: &Env, market_id: u32, user: &Address, s
         market_id,
         user: user.clone(),
         side_yes,
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -408,6 +960,7 @@ pub struct PositionUpdated {
     pub yes_shares: i128,
     pub no_shares: i128,
     pub locked_collateral: i128,
+    pub event_seq: u64,
 }
 
 /// Emit an event whenever a user's position is modified (shares bought or sold).
@@ -440,6 +993,7 @@ pub fn emit_position_updated(
         yes_shares,
         no_shares,
         locked_collateral,
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -455,6 +1009,7 @@ pub struct TradeExecuted {
     pub price_bps: i128,
     pub side_yes: bool,
     pub executed_at: u64,
+    pub event_seq: u64,
 }
 
 /// Emit an event when a trade is executed (shares bought or sold).
@@ -492,6 +1047,7 @@ pub fn emit_trade_executed(
         price_bps,
         side_yes,
         executed_at,
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -503,6 +1059,7 @@ pub struct ValidationFailed {
     #[topic]
     pub context: soroban_sdk::Symbol,
     pub error_code: u32,
+    pub event_seq: u64,
 }
 
 /// Emit an event when a validation step fails, recording which context triggered
@@ -528,6 +1085,7 @@ pub fn emit_validation_failed(env: &Env, context: soroban_sdk::Symbol, error_cod
     ValidationFailed {
         context,
         error_code,
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -540,8 +1098,12 @@ pub struct PositionSettled {
     pub market_id: u32,
     #[topic]
     pub user: Address,
+    /// Amount credited to the user, after any settlement fee.
     pub payout: i128,
+    /// Payout before the settlement fee was deducted.
+    pub gross_payout: i128,
     pub settled_at: u64,
+    pub event_seq: u64,
 }
 
 /// Emit an event when a user's position is settled and payout is transferred.
@@ -551,11 +1113,12 @@ pub struct PositionSettled {
 /// * `market_id` - Market identifier
 /// * `user` - Address of the user receiving the payout
 /// * `payout` - Amount transferred to the user in stroops
+/// * `gross_payout` - Payout before the settlement fee, in stroops
 /// * `settled_at` - Unix timestamp (ledger time) when settlement occurred
 ///
 /// # Example
 /// ```ignore
-/// emit_position_settled(&env, market_id, &user, 500_000, env.ledger().timestamp());
+/// emit_position_settled(&env, market_id, &user, 495_000, 500_000, env.ledger().timestamp());
 /// ```
 #[allow(dead_code)]
 pub fn emit_position_settled(
@@ -563,13 +1126,49 @@ pub fn emit_position_settled(
     market_id: u32,
     user: &Address,
     payout: i128,
+    gross_payout: i128,
     settled_at: u64,
 ) {
     PositionSettled {
         market_id,
         user: user.clone(),
         payout,
+        gross_payout,
         settled_at,
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct Claimed {
+    #[topic]
+    pub market_id: u32,
+    #[topic]
+    pub user: Address,
+    pub amount: i128,
+    pub claimed_at: u64,
+    pub event_seq: u64,
+}
+
+/// Emit a Claimed event
+///
+/// Publishes a [`Claimed`] when a user pulls the settlement payout credited
+/// to them in a market.
+///
+/// # Arguments
+/// * `env` - Contract environment
+/// * `market_id` - Market the payout was credited in
+/// * `user` - User receiving the transfer
+/// * `amount` - Amount transferred, in stroops
+pub fn emit_claimed(env: &Env, market_id: u32, user: &Address, amount: i128) {
+    Claimed {
+        market_id,
+        user: user.clone(),
+        amount,
+        claimed_at: env.ledger().timestamp(),
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -581,6 +1180,7 @@ pub struct OracleSignatureVerified {
     pub market_id: u32,
     pub outcome: bool,
     pub verified_at: u64,
+    pub event_seq: u64,
 }
 
 /// Emit event when oracle signature is verified
@@ -605,6 +1205,7 @@ pub fn emit_oracle_signature_verified(env: &Env, market_id: u32, outcome: bool,
         market_id,
         outcome,
         verified_at,
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -618,6 +1219,7 @@ pub struct FeeCalculated {
     pub user: Address,
     pub fee_amount: i128,
     pub available_after_fee: i128,
+    pub event_seq: u64,
 }
 
 /// Emit event when a fee is calculated during a withdrawal action.
@@ -640,6 +1242,35 @@ pub fn emit_fee_calculated(
         user: user.clone(),
         fee_amount,
         available_after_fee,
+        event_seq: crate::storage::next_event_seq(env),
+    }
+    .publish(env);
+}
+
+#[contractevent]
+#[derive(Clone, Debug)]
+pub struct FeesWithdrawn {
+    #[topic]
+    pub market_id: u32,
+    #[topic]
+    pub admin: Address,
+    pub amount: i128,
+    pub event_seq: u64,
+}
+
+/// Emit event when the admin withdraws a market's accrued trading fees.
+///
+/// # Arguments
+/// * `env` - Soroban environment
+/// * `market_id` - Market the fees were collected in
+/// * `admin` - Admin address receiving the fees
+/// * `amount` - Fees transferred in stroops
+pub fn emit_fees_withdrawn(env: &Env, market_id: u32, admin: &Address, amount: i128) {
+    FeesWithdrawn {
+        market_id,
+        admin: admin.clone(),
+        amount,
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -652,6 +1283,7 @@ pub struct AdminTransferProposed {
     #[topic]
     pub pending_admin: Address,
     pub proposed_at: u64,
+    pub event_seq: u64,
 }
 
 pub fn emit_admin_transfer_proposed(env: &Env, current_admin: &Address, pending_admin: &Address) {
@@ -659,6 +1291,7 @@ pub fn emit_admin_transfer_proposed(env: &Env, current_admin: &Address, pending_
         current_admin: current_admin.clone(),
         pending_admin: pending_admin.clone(),
         proposed_at: env.ledger().timestamp(),
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -671,6 +1304,7 @@ pub struct AdminTransferAccepted {
     #[topic]
     pub new_admin: Address,
     pub accepted_at: u64,
+    pub event_seq: u64,
 }
 
 pub fn emit_admin_transfer_accepted(env: &Env, old_admin: &Address, new_admin: &Address) {
@@ -678,6 +1312,7 @@ pub fn emit_admin_transfer_accepted(env: &Env, old_admin: &Address, new_admin: &
         old_admin: old_admin.clone(),
         new_admin: new_admin.clone(),
         accepted_at: env.ledger().timestamp(),
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -688,12 +1323,14 @@ pub struct TreasurySet {
     #[topic]
     pub treasury: Address,
     pub set_at: u64,
+    pub event_seq: u64,
 }
 
 pub fn emit_treasury_set(env: &Env, treasury: &Address) {
     TreasurySet {
         treasury: treasury.clone(),
         set_at: env.ledger().timestamp(),
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -704,12 +1341,14 @@ pub struct AdminRenounceProposedEvent {
     #[topic]
     pub admin: Address,
     pub proposed_at: u64,
+    pub event_seq: u64,
 }
 
 pub fn emit_admin_renounce_proposed(env: &Env, admin: &Address) {
     AdminRenounceProposedEvent {
         admin: admin.clone(),
         proposed_at: env.ledger().timestamp(),
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -720,12 +1359,14 @@ pub struct AdminRenouncedEvent {
     #[topic]
     pub former_admin: Address,
     pub renounced_at: u64,
+    pub event_seq: u64,
 }
 
 pub fn emit_admin_renounced(env: &Env, admin: &Address) {
     AdminRenouncedEvent {
         former_admin: admin.clone(),
         renounced_at: env.ledger().timestamp(),
+        event_seq: crate::storage::next_event_seq(env),
     }
     .publish(env);
 }
@@ -767,6 +1408,48 @@ mod tests {
             .unwrap()
             .into_val(&env);
         assert_eq!(initialized_at_val, env.ledger().timestamp());
+        let event_seq: u64 = data
+            .get(Symbol::new(&env, "event_seq"))
+            .unwrap()
+            .into_val(&env);
+        assert_eq!(event_seq, 1);
+    }
+
+    #[test]
+    fn test_event_seq_increments_across_emissions() {
+        let env = Env::default();
+        let contract_id = env.register(MarketContract, ());
+        let admin = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        env.as_contract(&contract_id, || {
+            emit_contract_initialized(&env, &admin);
+            emit_treasury_set(&env, &admin);
+            emit_fee_calculated(&env, 1, &user, 10, 90);
+        });
+
+        let events = env.events().all();
+        assert_eq!(events.len(), 3);
+        for (i, event) in events.iter().enumerate() {
+            let data: Map<Symbol, Val> = event.2.try_into_val(&env).unwrap();
+            let event_seq: u64 = data
+                .get(Symbol::new(&env, "event_seq"))
+                .unwrap()
+                .into_val(&env);
+            assert_eq!(event_seq, i as u64 + 1);
+        }
+
+        // The counter is contract-wide and survives across invocations.
+        env.as_contract(&contract_id, || {
+            emit_emergency_pause_toggled(&env, true);
+        });
+        let event = env.events().all().last().unwrap();
+        let data: Map<Symbol, Val> = event.2.try_into_val(&env).unwrap();
+        let event_seq: u64 = data
+            .get(Symbol::new(&env, "event_seq"))
+            .unwrap()
+            .into_val(&env);
+        assert_eq!(event_seq, 4);
     }
 
     #[test]
@@ -778,9 +1461,12 @@ mod tests {
         let creator = Address::generate(&env);
         let question = String::from_str(&env, "Will BTC hit $100k?");
         let end_time = 1234567890u64;
+        let category = Symbol::new(&env, "crypto");
 
         env.as_contract(&contract_id, || {
-            emit_market_created(&env, market_id, &creator, &question, end_time);
+            emit_market_created(
+                &env, market_id, &creator, &category, &question, end_time, &None,
+            );
         });
 
         // Verify event was published
@@ -793,7 +1479,7 @@ mod tests {
 
         // Topics
         let topics = &event.1;
-        assert_eq!(topics.len(), 2);
+        assert_eq!(topics.len(), 4);
 
         let topic0: Symbol = topics.get(0).unwrap().into_val(&env);
         assert_eq!(topic0, Symbol::new(&env, "market_created"));
@@ -801,13 +1487,15 @@ mod tests {
         let topic1: u32 = topics.get(1).unwrap().into_val(&env);
         assert_eq!(topic1, market_id);
 
-        // Data
+        let topic2: Address = topics.get(2).unwrap().into_val(&env);
+        assert_eq!(topic2, creator);
+
+        let topic3: Symbol = topics.get(3).unwrap().into_val(&env);
+        assert_eq!(topic3, category);
+
         // Data
         let data: Map<Symbol, Val> = event.2.try_into_val(&env).unwrap();
-        let creator_val: Address = data
-            .get(Symbol::new(&env, "creator"))
-            .unwrap()
-            .into_val(&env);
+        assert!(data.get(Symbol::new(&env, "creator")).is_none());
         let question_val: String = data
             .get(Symbol::new(&env, "question"))
             .unwrap()
@@ -816,7 +1504,6 @@ mod tests {
             .get(Symbol::new(&env, "end_time"))
             .unwrap()
             .into_val(&env);
-        assert_eq!(creator_val, creator);
         assert_eq!(question_val, question);
         assert_eq!(end_time_val, end_time);
     }
@@ -830,10 +1517,19 @@ mod tests {
         let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
         let resolver = Address::generate(&env);
         let outcome = true;
+        let total_collateral = 5_000i128;
         let resolved_at = 1234567890u64;
 
         env.as_contract(&contract_id, || {
-            emit_market_resolved(&env, market_id, &oracle_pubkey, &resolver, outcome, resolved_at);
+            emit_market_resolved(
+                &env,
+                market_id,
+                &oracle_pubkey,
+                &resolver,
+                outcome,
+                total_collateral,
+                resolved_at,
+            );
         });
 
         let events = env.events().all();
@@ -847,9 +1543,11 @@ mod tests {
 
         let topic1: u32 = topics.get(1).unwrap().into_val(&env);
         assert_eq!(topic1, market_id);
+        let topic2: BytesN<32> = topics.get(2).unwrap().into_val(&env);
+        assert_eq!(topic2, oracle_pubkey);
 
         let data: Map<Symbol, Val> = event.2.try_into_val(&env).unwrap();
-        let resolver_val: BytesN<32> = data
+        let resolver_val: Address = data
             .get(Symbol::new(&env, "resolver"))
             .unwrap()
             .into_val(&env);
@@ -862,7 +1560,12 @@ mod tests {
             .unwrap()
             .into_val(&env);
         assert_eq!(resolver_val, resolver);
+        let total_collateral_val: i128 = data
+            .get(Symbol::new(&env, "total_collateral"))
+            .unwrap()
+            .into_val(&env);
         assert_eq!(outcome_val, outcome);
+        assert_eq!(total_collateral_val, total_collateral);
         assert_eq!(resolved_at_val, resolved_at);
     }
 
@@ -967,11 +1670,12 @@ mod tests {
 
         let market_id = 1u32;
         let user = Address::generate(&env);
-        let payout = 100i128;
+        let payout = 99i128;
+        let gross_payout = 100i128;
         let settled_at = 1234567890u64;
 
         env.as_contract(&contract_id, || {
-            emit_position_settled(&env, market_id, &user, payout, settled_at);
+            emit_position_settled(&env, market_id, &user, payout, gross_payout, settled_at);
         });
 
         let events = env.events().all();
@@ -995,6 +1699,11 @@ mod tests {
             .unwrap()
             .into_val(&env);
         assert_eq!(payout_val, payout);
+        let gross_payout_val: i128 = data
+            .get(Symbol::new(&env, "gross_payout"))
+            .unwrap()
+            .into_val(&env);
+        assert_eq!(gross_payout_val, gross_payout);
     }
 
     #[test]
@@ -0,0 +1,305 @@
+//! Accounting and lifecycle invariant checks (feature `invariant-checks`).
+//!
+//! Compiled only with the `invariant-checks` feature, which the test build
+//! enables; release and WASM builds contain none of this module. State-changing
+//! entry points call [`assert_invariants`] through the `check_invariants!`
+//! macro once they succeed, so an accounting regression panics at the
+//! operation that introduced it instead of surfacing later as a bad payout.
+//!
+//! Checked for a market:
+//! - every position has non-negative shares and `0 <= locked <= deposited`;
+//! - `total_collateral == sum(positions) + accrued_fees`, in every status. A
+//!   market holds its tracked collateral plus its accrued fees; fees come out
+//!   of `total_deposited` as they accrue, so this is the tracked collateral
+//!   matching the sum of unsettled deposits recomputed over the market's
+//!   participants, with the fees never negative;
+//! - the status only moves along `Active -> Resolved`, `Active -> Canceled`,
+//!   `Canceled -> Active`, or through `PendingResolution` from `Active` to
+//!   `Resolved` or `Canceled`; a disputed proposal moves
+//!   `PendingResolution -> Disputed -> Resolved`.
+
+use crate::storage;
+use crate::types::MarketStatus;
+use soroban_sdk::{contracttype, Env};
+
+/// Bookkeeping kept only for the checks; never read by contract logic.
+#[derive(Clone)]
+#[contracttype]
+enum InvariantKey {
+    /// Status observed by the previous check.
+    LastStatus(u32),
+}
+
+fn is_legal_transition(from: &MarketStatus, to: &MarketStatus) -> bool {
+    from == to
+        || matches!(
+            (from, to),
+            (MarketStatus::Active, MarketStatus::Resolved)
+                | (MarketStatus::Active, MarketStatus::Canceled)
+                | (MarketStatus::Canceled, MarketStatus::Active)
+                | (MarketStatus::Active, MarketStatus::PendingResolution)
+                | (MarketStatus::PendingResolution, MarketStatus::Resolved)
+                | (MarketStatus::PendingResolution, MarketStatus::Canceled)
+                | (MarketStatus::PendingResolution, MarketStatus::Disputed)
+                | (MarketStatus::Disputed, MarketStatus::Resolved)
+        )
+}
+
+/// Panic if `market_id` violates any accounting or lifecycle invariant.
+pub(crate) fn assert_invariants(env: &Env, market_id: u32) {
+    let Some(market) = storage::get_market(env, market_id).unwrap() else {
+        return;
+    };
+
+    let status_key = InvariantKey::LastStatus(market_id);
+    if let Some(previous) = env
+        .storage()
+        .persistent()
+        .get::<_, MarketStatus>(&status_key)
+    {
+        if !is_legal_transition(&previous, &market.status) {
+            panic!("invariant violated: illegal market status transition");
+        }
+    }
+    env.storage().persistent().set(&status_key, &market.status);
+
+    let mut owed: i128 = 0;
+    for user in storage::get_market_participants(env, market_id).iter() {
+        let Some(position) = storage::get_position(env, market_id, &user).unwrap() else {
+            continue;
+        };
+        if position.yes_shares < 0 || position.no_shares < 0 {
+            panic!("invariant violated: negative share balance");
+        }
+        if position.locked_collateral < 0 || position.locked_collateral > position.total_deposited {
+            panic!("invariant violated: locked collateral outside deposited range");
+        }
+        if !position.is_settled {
+            owed = owed.saturating_add(position.total_deposited);
+        }
+    }
+
+    let accrued_fees = storage::get_accrued_fees(env, market_id);
+    if accrued_fees < 0 {
+        panic!("invariant violated: negative accrued fees");
+    }
+    let total_collateral = storage::get_market_collateral(env, market_id) + accrued_fees;
+    if total_collateral != owed + accrued_fees {
+        panic!("invariant violated: tracked collateral differs from unsettled deposits");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AdapterType, Market, Position};
+    use soroban_sdk::testutils::Address as _;
+    use soroban_sdk::{Address, BytesN};
+
+    /// Register the contract with one empty market.
+    fn setup(env: &Env) -> (Address, u32) {
+        let contract_id = env.register(crate::MarketContract, ());
+
+        let market = Market {
+            id: 1,
+            question: soroban_sdk::String::from_str(env, "Invariant market"),
+            end_time: 1_000,
+            oracle_pubkey: BytesN::from_array(env, &[1u8; 32]),
+            status: MarketStatus::Active,
+            result: None,
+            creator: Address::generate(env),
+            created_at: 0,
+            collateral_token: Address::generate(env),
+            price_bps: 5_000,
+            resolver: None,
+            resolved_at: None,
+            adapter_type: AdapterType::Ed25519,
+            outcome_count: 2,
+            closed_to_deposits: false,
+            source_hash: BytesN::from_array(env, &[0u8; 32]),
+            resolution_grace: None,
+            fee_rate_bps: None,
+            withdraw_cooldown: None,
+            challenge_deadline: None,
+            trading_fee_bps: 0,
+            collateral_decimals: 7,
+            total_yes_shares: 0,
+            total_no_shares: 0,
+            yes_reserve: 0,
+            no_reserve: 0,
+            category: soroban_sdk::Symbol::new(env, "general"),
+            resolution_source: soroban_sdk::String::from_str(env, "test source"),
+            kind: crate::types::MarketKind::Binary,
+            settled_value: None,
+            min_deposit: 0,
+            max_position: 0,
+            max_participants: 0,
+        };
+        env.as_contract(&contract_id, || {
+            storage::set_version(env);
+            storage::set_market(env, 1, &market).unwrap();
+        });
+        (contract_id, 1)
+    }
+
+    fn write_position(env: &Env, market_id: u32, user: &Address, locked: i128, deposited: i128) {
+        let position = Position {
+            locked_collateral: locked,
+            total_deposited: deposited,
+            ..Position::new_empty(market_id, user.clone(), 0)
+        };
+        storage::set_position(env, market_id, user, &position).unwrap();
+    }
+
+    #[test]
+    fn test_consistent_state_passes() {
+        let env = Env::default();
+        let (contract_id, market_id) = setup(&env);
+        let user = Address::generate(&env);
+        env.as_contract(&contract_id, || {
+            write_position(&env, market_id, &user, 40, 100);
+            assert_invariants(&env, market_id);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "locked collateral outside deposited range")]
+    fn test_catches_locked_above_deposited() {
+        let env = Env::default();
+        let (contract_id, market_id) = setup(&env);
+        let user = Address::generate(&env);
+        env.as_contract(&contract_id, || {
+            write_position(&env, market_id, &user, 150, 100);
+            assert_invariants(&env, market_id);
+        });
+    }
+
+    /// Overwrite the market's tracked collateral without touching positions.
+    fn corrupt_collateral(env: &Env, market_id: u32, amount: i128) {
+        env.storage()
+            .persistent()
+            .set(&storage::StorageKey::MarketCollateral(market_id), &amount);
+    }
+
+    #[test]
+    #[should_panic(expected = "tracked collateral differs from unsettled deposits")]
+    fn test_catches_collateral_drift() {
+        let env = Env::default();
+        let (contract_id, market_id) = setup(&env);
+        env.as_contract(&contract_id, || {
+            write_position(&env, market_id, &Address::generate(&env), 0, 60);
+            write_position(&env, market_id, &Address::generate(&env), 0, 60);
+            storage::set_accrued_fees(&env, market_id, 5);
+            assert_invariants(&env, market_id);
+
+            corrupt_collateral(&env, market_id, 100);
+            assert_invariants(&env, market_id);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "tracked collateral differs from unsettled deposits")]
+    fn test_checks_collateral_of_resolved_market() {
+        let env = Env::default();
+        let (contract_id, market_id) = setup(&env);
+        env.as_contract(&contract_id, || {
+            write_position(&env, market_id, &Address::generate(&env), 0, 60);
+            let mut market = storage::get_market(&env, market_id).unwrap().unwrap();
+            market.status = MarketStatus::Resolved;
+            market.result = Some(true);
+            storage::set_market(&env, market_id, &market).unwrap();
+            assert_invariants(&env, market_id);
+
+            corrupt_collateral(&env, market_id, 80);
+            assert_invariants(&env, market_id);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "negative accrued fees")]
+    fn test_catches_negative_fees() {
+        let env = Env::default();
+        let (contract_id, market_id) = setup(&env);
+        env.as_contract(&contract_id, || {
+            storage::set_accrued_fees(&env, market_id, -1);
+            assert_invariants(&env, market_id);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "illegal market status transition")]
+    fn test_catches_resolved_market_reverting_to_active() {
+        let env = Env::default();
+        let (contract_id, market_id) = setup(&env);
+        env.as_contract(&contract_id, || {
+            let mut market = storage::get_market(&env, market_id).unwrap().unwrap();
+            market.status = MarketStatus::Resolved;
+            storage::set_market(&env, market_id, &market).unwrap();
+            assert_invariants(&env, market_id);
+
+            market.status = MarketStatus::Active;
+            storage::set_market(&env, market_id, &market).unwrap();
+            assert_invariants(&env, market_id);
+        });
+    }
+
+    #[test]
+    fn test_legal_transitions() {
+        assert!(is_legal_transition(
+            &MarketStatus::Active,
+            &MarketStatus::Resolved
+        ));
+        assert!(is_legal_transition(
+            &MarketStatus::Active,
+            &MarketStatus::Canceled
+        ));
+        assert!(is_legal_transition(
+            &MarketStatus::Canceled,
+            &MarketStatus::Active
+        ));
+        assert!(is_legal_transition(
+            &MarketStatus::Active,
+            &MarketStatus::PendingResolution
+        ));
+        assert!(is_legal_transition(
+            &MarketStatus::PendingResolution,
+            &MarketStatus::Resolved
+        ));
+        assert!(is_legal_transition(
+            &MarketStatus::PendingResolution,
+            &MarketStatus::Canceled
+        ));
+        assert!(!is_legal_transition(
+            &MarketStatus::Resolved,
+            &MarketStatus::Active
+        ));
+        assert!(!is_legal_transition(
+            &MarketStatus::Resolved,
+            &MarketStatus::Canceled
+        ));
+        assert!(!is_legal_transition(
+            &MarketStatus::Canceled,
+            &MarketStatus::Resolved
+        ));
+        assert!(!is_legal_transition(
+            &MarketStatus::PendingResolution,
+            &MarketStatus::Active
+        ));
+        assert!(is_legal_transition(
+            &MarketStatus::PendingResolution,
+            &MarketStatus::Disputed
+        ));
+        assert!(is_legal_transition(
+            &MarketStatus::Disputed,
+            &MarketStatus::Resolved
+        ));
+        assert!(!is_legal_transition(
+            &MarketStatus::Active,
+            &MarketStatus::Disputed
+        ));
+        assert!(!is_legal_transition(
+            &MarketStatus::Disputed,
+            &MarketStatus::Canceled
+        ));
+    }
+}
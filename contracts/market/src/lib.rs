@@ -1,5 +1,9 @@
 #![no_std]
 #![deny(clippy::all)]
+// Entry points like `initialize_market` take more than seven arguments; the
+// argument helpers generated by `#[contractimpl]` do not inherit per-function
+// allows, so the lint is relaxed crate-wide.
+#![allow(clippy::too_many_arguments)]
 
 //! # Market Contract
 //!
@@ -12,6 +16,7 @@
 //! ```text
 //!  User withdrawal
 //!      │  fee_amount = amount * fee_rate_bps / 10_000
+//!      │  (market's fee_rate_bps override, else the global rate)
 //!      ▼
 //!  MarketContract
 //!      │  token.transfer(market → treasury, fee_amount)  (if treasury registered)
@@ -26,6 +31,7 @@
 //! |------------------------------------|---------------------------------|
 //! | `initialize`                       | anyone (once)                   |
 //! | `initialize_market` / set_*        | admin                           |
+//! | `upgrade` / `migrate`              | admin                           |
 //! | `deposit_collateral`               | any user                        |
 //! | `update_position`                  | any user (active market)        |
 //! | `withdraw_unused_collateral`       | any user                        |
@@ -45,19 +51,25 @@
 //! | `Position(u32, Address)`            | `Position`      | Per-user, per-market position (shares, collateral) |
 //! | `Treasury`                          | `Address`       | Optional treasury contract for fee collection      |
 //! | `FeeRateBps`                        | `i128`          | Withdrawal fee rate in basis points (0–10_000)     |
+//! | `SettlementFeeBps`                  | `u32`           | Fee on winning payouts in basis points (≤ 1_000)   |
 //! | `OutcomeTokenContract`              | `Address`       | Optional outcome-token contract for mint/burn      |
 //! | `ResolutionContract`                | `Address`       | Optional resolution contract that gates resolution |
 //! | `ThresholdSigners`                  | `Vec<BytesN<32>>` | Multi-signer quorum public keys (#378)           |
 //! | `ThresholdQuorum`                   | `u32`           | Min valid signatures required for resolution (#378)|
+//! | `ContractVersion`                   | `u32`           | Code version, bumped by `upgrade`                  |
 
 mod deposit;
-mod error;
+pub mod error;
+#[allow(dead_code)]
 mod events;
+#[cfg(feature = "invariant-checks")]
+mod invariants;
 pub mod oracle;
 #[cfg(feature = "oracle-adapter")]
 pub mod oracle_adapter;
 #[allow(dead_code)]
 mod positions;
+pub mod pricing;
 #[allow(dead_code)]
 pub mod settlement;
 mod withdraw;
@@ -68,14 +80,29 @@ mod test;
 #[cfg(test)]
 mod withdraw_fuzz;
 pub mod types;
+pub mod units;
 #[allow(dead_code)]
 mod validation;
 
 use crate::error::ContractError;
-use crate::types::{AdapterType, Market, MarketStatus, Position};
-use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, String};
+use crate::types::{
+    AdminResolutionProposal, ContractHealth, Dispute, Market, MarketKind, MarketMetadata,
+    MarketStats, MarketStatus, OracleConfig, OracleScheme, OracleSet, Outcome, Position,
+    Resolution, ResolutionCommitment,
+};
+use soroban_sdk::{contract, contractimpl, Address, BytesN, Env, String, Symbol};
 use vatix_outcome_token_contract::{OutcomeTokenContractClient, types::TokenKind};
 use vatix_resolution_contract::types::CandidateStatus as ResolutionCandidateStatus;
+use vatix_resolution_contract::ResolutionContractClient;
+
+/// Assert the `invariants` module's checks for a market after a successful
+/// state change. Expands to nothing unless `invariant-checks` is enabled.
+macro_rules! check_invariants {
+    ($env:expr, $market_id:expr) => {
+        #[cfg(feature = "invariant-checks")]
+        crate::invariants::assert_invariants($env, $market_id);
+    };
+}
 
 #[contract]
 pub struct MarketContract;
@@ -92,24 +119,57 @@ impl MarketContract {
     /// * `creator` - Admin address that authorizes market creation
     /// * `question` - Human-readable market question (1–499 characters)
     /// * `end_time` - Unix timestamp after which trading closes (must be
-    ///   within one year of the current ledger time)
+    ///   within the max market duration of the current ledger time; one year
+    ///   unless changed with `set_max_market_duration`)
     /// * `oracle_pubkey` - Ed25519 public key of the oracle that will sign
     ///   the resolution outcome
     /// * `collateral_token` - Address of the SAC token used as collateral
     ///   (e.g. USDC)
+    /// * `metadata` - Optional off-chain metadata URI, the market's category
+    ///   (also indexed for `list_markets_by_category`), a description of its
+    ///   resolution source, whether it is binary or scalar, its deposit
+    ///   minimum, net position cap and participant cap (zero disables
+    ///   each), and whether
+    ///   its ID is derived from `creator`, `question` and `end_time` rather
+    ///   than taken from the counter
+    /// * `source_hash` - Hash of the agreed resolution source (criteria/URL).
+    ///   Stored immutably and bound into every oracle signature for the market
+    /// * `resolution_grace` - Optional seconds after `end_time` the oracle has
+    ///   to resolve; `None` sets no resolution window
+    /// * `fee_rate_bps` - Optional fee rate for this market in basis points;
+    ///   `None` uses the global rate set by `set_fee_rate`
+    /// * `trading_fee_bps` - Fee in basis points charged on shares bought
+    ///   (at most `MAX_TRADING_FEE_BPS`); zero disables it
     ///
     /// # Returns
-    /// The `u32` market ID assigned to the new market (auto-incremented).
+    /// The `u32` market ID assigned to the new market: the next counter
+    /// value, or the content-derived ID when `metadata.derive_id` is set.
     ///
     /// # Errors
     /// - [`ContractError::Unauthorized`] – `creator` is not the admin
-    /// - [`ContractError::InvalidQuestion`] – question is empty or ≥ 500 chars
+    /// - [`ContractError::InvalidQuestion`] – question is empty or ≥ 500 chars,
+    ///   or exactly matches the question of another `Active` market
+    /// - [`ContractError::InvalidMetadataUri`] – the metadata URI is empty or
+    ///   too long, or the resolution source is empty or ≥ 200 chars
     /// - [`ContractError::InvalidTimestamp`] – `end_time` is in the past or
-    ///   more than one year in the future
+    ///   further ahead than the max market duration
+    /// - [`ContractError::InvalidOutcome`] – a scalar market's lower
+    ///   bound is not below its upper bound
+    /// - [`ContractError::InvalidQuantity`] – `min_deposit` or
+    ///   `max_position` is negative
+    /// - [`ContractError::UnsupportedToken`] – `collateral_token` is this
+    ///   contract or does not answer `decimals()` (best-effort check)
+    /// - [`ContractError::InvalidResolutionGrace`] – `resolution_grace` is
+    ///   outside the accepted bounds
+    /// - [`ContractError::InvalidPrice`] – `fee_rate_bps` outside 0–10_000
+    /// - [`ContractError::FeeCapExceeded`] – `fee_rate_bps` exceeds the fee
+    ///   cap, or `trading_fee_bps` exceeds `MAX_TRADING_FEE_BPS`
+    /// - [`ContractError::AlreadyInitialized`] – the derived ID is already
+    ///   taken by another market
     ///
     /// # Events
-    /// Emits [`MarketCreated`] with `market_id`, `creator`, `question`,
-    /// and `end_time` as payload.
+    /// Emits [`MarketCreated`] with `market_id`, `creator` and `category` as
+    /// topics and `question`, `end_time` and `metadata_uri` as payload.
     ///
     /// # Example
     /// ```ignore
@@ -119,6 +179,20 @@ impl MarketContract {
     ///     &(env.ledger().timestamp() + 86_400),
     ///     &oracle_pubkey,
     ///     &usdc_token,
+    ///     &MarketMetadata {
+    ///         uri: None,
+    ///         category: Symbol::new(&env, "crypto"),
+    ///         resolution_source: String::from_str(&env, "https://www.coingecko.com"),
+    ///         kind: MarketKind::Binary,
+    ///         min_deposit: 0,
+    ///         max_position: 0,
+    ///         max_participants: 0,
+    ///         derive_id: false,
+    ///     },
+    ///     &source_hash,
+    ///     &None,
+    ///     &None,
+    ///     &0,
     /// );
     /// assert_eq!(market_id, 1);
     /// ```
@@ -188,19 +262,26 @@ impl MarketContract {
         current_admin: Address,
         new_admin: Address,
     ) -> Result<(), ContractError> {
-        validation::require_initialized(&env)?;
+        // 1. Contract must have an admin
         if !storage::has_admin(&env) {
             return Err(ContractError::NotAdmin);
         }
-        
+
+        // 2. Require current admin authorization
+        current_admin.require_auth();
+
         // 3. Verify current admin
         let stored_admin = storage::get_admin(&env)?;
         if current_admin != stored_admin {
             return Err(ContractError::NotAdmin);
         }
+
+        // 4. Nominee must be a user account
+        validation::validate_admin_address(&new_admin)?;
+
         storage::set_pending_admin(&env, &new_admin);
         events::emit_admin_transfer_proposed(&env, &current_admin, &new_admin);
-        
+
         Ok(())
     }
 
@@ -227,6 +308,110 @@ impl MarketContract {
         Ok(())
     }
 
+    /// Pause or unpause the contract for incident response.
+    ///
+    /// While paused, market creation, deposits, withdrawals and trading are
+    /// rejected with [`ContractError::ContractPaused`]. Resolution and
+    /// settlement stay open so users can always exit their positions.
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
+    ///
+    /// # Events
+    /// Emits `EmergencyPauseToggledEvent` with the new flag.
+    pub fn set_paused(env: Env, admin: Address, paused: bool) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+        storage::set_paused(&env, paused);
+        events::emit_emergency_pause_toggled(&env, paused);
+        Ok(())
+    }
+
+    /// Whether the contract is currently paused; `false` until
+    /// [`set_paused`] is first called.
+    pub fn is_paused(env: Env) -> bool {
+        storage::is_paused(&env)
+    }
+
+    /// The current admin address.
+    ///
+    /// A pending nomination from [`propose_admin`] is not reflected until it
+    /// is accepted.
+    ///
+    /// # Errors
+    /// - [`ContractError::NotInitialized`] – `initialize` has not been called
+    pub fn get_admin(env: Env) -> Result<Address, ContractError> {
+        validation::require_initialized(&env)?;
+        storage::get_admin(&env)
+    }
+
+    /// Replace the contract's code with the uploaded WASM `new_wasm_hash`,
+    /// keeping its storage, and bump the contract version.
+    ///
+    /// Only the stored admin may call this. The new code takes effect once
+    /// this invocation returns.
+    ///
+    /// # Errors
+    /// - [`ContractError::NotInitialized`] – `initialize` has not been called
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
+    /// - [`ContractError::ArithmeticOverflow`] – the version cannot be bumped
+    ///
+    /// # Events
+    /// Emits `ContractUpgradedEvent` with the new hash and version.
+    pub fn upgrade(
+        env: Env,
+        admin: Address,
+        new_wasm_hash: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+        let version = storage::get_contract_version(&env)
+            .checked_add(1)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        storage::set_contract_version(&env, version);
+        events::emit_contract_upgraded(&env, &new_wasm_hash, version);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    /// The contract code version: 1 for a fresh deployment, bumped by each
+    /// [`upgrade`].
+    pub fn version(env: Env) -> u32 {
+        storage::get_contract_version(&env)
+    }
+
+    /// Convert stored data to the storage schema this code expects.
+    ///
+    /// Call after an [`upgrade`] that bumps `STORAGE_VERSION`; until then
+    /// every storage accessor fails with [`ContractError::UpgradeRequired`].
+    /// Calling it when the schema is already current changes nothing.
+    ///
+    /// # Returns
+    /// The schema version the stored data was at before the call.
+    ///
+    /// # Errors
+    /// - [`ContractError::NotInitialized`] – `initialize` has not been called
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
+    /// - [`ContractError::UpgradeRequired`] – the stored schema cannot be
+    ///   converted in place
+    pub fn migrate(env: Env, admin: Address) -> Result<u32, ContractError> {
+        validation::require_initialized(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin_unversioned(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+        storage::migrate(&env)
+    }
+
     pub fn initialize_market(
         env: Env,
         creator: Address,
@@ -234,7 +419,11 @@ impl MarketContract {
         end_time: u64,
         oracle_pubkey: BytesN<32>,
         collateral_token: Address,
-        metadata_uri: Option<String>,
+        metadata: MarketMetadata,
+        source_hash: BytesN<32>,
+        resolution_grace: Option<u64>,
+        fee_rate_bps: Option<i128>,
+        trading_fee_bps: u32,
     ) -> Result<u32, ContractError> {
         validation::require_initialized(&env)?;
         validation::require_not_paused(&env)?;
@@ -245,24 +434,138 @@ impl MarketContract {
             return Err(ContractError::NotAdmin);
         }
 
-        // 2. Validate inputs
-        let current_time = env.ledger().timestamp();
-        validation::validate_market_creation(&question, end_time, current_time)?;
-        validation::validate_metadata_uri(&metadata_uri)?;
+        Self::create_market(
+            &env,
+            &creator,
+            question,
+            end_time,
+            oracle_pubkey,
+            collateral_token,
+            metadata,
+            source_hash,
+            resolution_grace,
+            fee_rate_bps,
+            trading_fee_bps,
+        )
+    }
+
+    /// Create several markets sharing a collateral token and metadata in one
+    /// call.
+    ///
+    /// `questions[i]`, `end_times[i]`, `oracle_pubkeys[i]` and
+    /// `source_hashes[i]` describe market `i`. Each market is validated and
+    /// created exactly as by [`initialize_market`], with no resolution
+    /// grace, the default fee rate and no trading fee. Any failure reverts
+    /// the whole batch, so markets are never partially created.
+    ///
+    /// # Returns
+    /// The assigned market IDs, in the order of `questions`.
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] – `creator` is not the admin
+    /// - [`ContractError::InvalidQuantity`] – the vectors differ in length,
+    ///   are empty, or hold more than [`validation::MAX_MARKET_BATCH_SIZE`]
+    ///   markets
+    /// - Any error of [`initialize_market`] for an individual market
+    ///
+    /// # Events
+    /// Emits [`MarketCreated`] for each market.
+    pub fn initialize_markets_batch(
+        env: Env,
+        creator: Address,
+        questions: soroban_sdk::Vec<String>,
+        end_times: soroban_sdk::Vec<u64>,
+        oracle_pubkeys: soroban_sdk::Vec<BytesN<32>>,
+        source_hashes: soroban_sdk::Vec<BytesN<32>>,
+        collateral_token: Address,
+        metadata: MarketMetadata,
+    ) -> Result<soroban_sdk::Vec<u32>, ContractError> {
+        validation::require_initialized(&env)?;
+        validation::require_not_paused(&env)?;
+        creator.require_auth();
+        let admin = storage::get_admin(&env)?;
+        if creator != admin {
+            return Err(ContractError::NotAdmin);
+        }
+
+        let count = questions.len();
+        if count == 0
+            || count > validation::MAX_MARKET_BATCH_SIZE
+            || end_times.len() != count
+            || oracle_pubkeys.len() != count
+            || source_hashes.len() != count
+        {
+            return Err(ContractError::InvalidQuantity);
+        }
 
-        // Guard: an all-zero pubkey can never produce a valid Ed25519 signature,
-        // making the market permanently unresolvable.
-        if oracle_pubkey == BytesN::from_array(&env, &[0u8; 32]) {
-            return Err(ContractError::InvalidSignature);
+        let mut market_ids = soroban_sdk::Vec::new(&env);
+        for i in 0..count {
+            let market_id = Self::create_market(
+                &env,
+                &creator,
+                questions.get_unchecked(i),
+                end_times.get_unchecked(i),
+                oracle_pubkeys.get_unchecked(i),
+                collateral_token.clone(),
+                metadata.clone(),
+                source_hashes.get_unchecked(i),
+                None,
+                None,
+                0,
+            )?;
+            market_ids.push_back(market_id);
         }
+        Ok(market_ids)
+    }
+
+    /// Validate and store a new market for an already-authorized admin;
+    /// shared by [`initialize_market`] and [`initialize_markets_batch`].
+    fn create_market(
+        env: &Env,
+        creator: &Address,
+        question: String,
+        end_time: u64,
+        oracle_pubkey: BytesN<32>,
+        collateral_token: Address,
+        metadata: MarketMetadata,
+        source_hash: BytesN<32>,
+        resolution_grace: Option<u64>,
+        fee_rate_bps: Option<i128>,
+        trading_fee_bps: u32,
+    ) -> Result<u32, ContractError> {
+        // 2. Validate inputs
+        let current_time = env.ledger().timestamp();
+        validation::validate_market_creation(
+            &question,
+            end_time,
+            current_time,
+            storage::get_max_market_duration(env),
+        )?;
+        validation::validate_metadata_uri(&metadata.uri)?;
+        validation::validate_resolution_source(&metadata.resolution_source)?;
+        validation::validate_market_kind(&metadata.kind)?;
+        validation::validate_market_limits(metadata.min_deposit, metadata.max_position)?;
+        let collateral_decimals = validation::validate_collateral_token(env, &collateral_token)?;
+        validation::validate_resolution_grace(resolution_grace)?;
+        validation::validate_market_fee_rate(env, fee_rate_bps)?;
+        validation::validate_trading_fee_bps(trading_fee_bps)?;
+        validation::require_unique_question(env, &question)?;
+        validation::validate_oracle_pubkey(env, &oracle_pubkey)?;
 
         // 3. Generate market ID
-        let market_id = storage::increment_market_id(&env)?;
+        let market_id = if metadata.derive_id {
+            storage::derive_market_id(env, creator, &question, end_time)
+        } else {
+            storage::increment_market_id(env)?
+        };
+        storage::extend_ttl(env);
 
         // Guard: the generated ID must not already be in storage.
-        // Under normal operation this cannot happen (the counter is monotonic),
-        // but we reject explicitly to prevent any accidental overwrite.
-        if storage::has_market(&env, market_id)? {
+        // Counter IDs cannot repeat (the counter is monotonic) and derived IDs
+        // live in their own range, so this only trips when a derived ID
+        // repeats an earlier market's creator, question and end time; we
+        // reject explicitly to prevent any overwrite.
+        if storage::has_market(env, market_id)? {
             return Err(ContractError::AlreadyInitialized);
         }
 
@@ -283,16 +586,45 @@ impl MarketContract {
             adapter_type: crate::types::AdapterType::Ed25519,
             outcome_count: 2,
             closed_to_deposits: false,
+            source_hash,
+            resolution_grace,
+            fee_rate_bps,
+            withdraw_cooldown: None,
+            challenge_deadline: None,
+            trading_fee_bps,
+            collateral_decimals,
+            total_yes_shares: 0,
+            total_no_shares: 0,
+            yes_reserve: pricing::INITIAL_RESERVE,
+            no_reserve: pricing::INITIAL_RESERVE,
+            category: metadata.category.clone(),
+            resolution_source: metadata.resolution_source,
+            kind: metadata.kind,
+            settled_value: None,
+            min_deposit: metadata.min_deposit,
+            max_position: metadata.max_position,
+            max_participants: metadata.max_participants,
         };
 
         // 5. Store market
-        storage::set_market(&env, market_id, &market)?;
-        storage::append_market_id(&env, market_id);
+        storage::set_market(env, market_id, &market)?;
+        storage::append_market_id(env, market_id);
+        storage::set_market_category(env, market_id, &metadata.category);
+        storage::set_question_market(env, &question, market_id);
 
         // 6. Emit event
-        events::emit_market_created(&env, market_id, &creator, &question, end_time, &metadata_uri);
+        events::emit_market_created(
+            env,
+            market_id,
+            creator,
+            &metadata.category,
+            &question,
+            end_time,
+            &metadata.uri,
+        );
 
         // 7. Return market ID
+        check_invariants!(env, market_id);
         Ok(market_id)
     }
 
@@ -302,6 +634,10 @@ impl MarketContract {
     /// * `user` - User's Stellar address (must authorize this call)
     /// * `market_id` - Market identifier
     /// * `amount` - Amount in stroops (1 USDC = 10^7 stroops)
+    /// * `idempotency_key` - Optional client-chosen key for this deposit.
+    ///   Each key is accepted once per user for
+    ///   [`storage::DEPOSIT_KEY_TTL`] ledgers, so a relayer can resubmit a
+    ///   deposit whose outcome it did not see without transferring twice
     ///
     /// # Errors
     /// - `MarketNotFound`: market_id doesn't exist
@@ -309,14 +645,30 @@ impl MarketContract {
     /// - `InvalidQuantity`: amount <= 0 or exceeds max
     /// - `TokenTransferFailed`: USDC transfer failed
     /// - `ArithmeticOverflow`: Amount would cause overflow
+    /// - `InvalidQuantity`: also returned when `user` already deposited with
+    ///   `idempotency_key`, as the amount left to apply is zero; nothing is
+    ///   transferred
+    /// - `MarketClosedToDeposits`: closed by the admin, or `user` is new to
+    ///   a market that has reached its `max_participants`
     pub fn deposit_collateral(
         env: Env,
         user: Address,
         market_id: u32,
         amount: i128,
+        idempotency_key: Option<BytesN<32>>,
     ) -> Result<(), ContractError> {
         validation::require_not_paused(&env)?;
-        deposit::deposit_collateral(env, user, market_id, amount)
+        if let Some(key) = &idempotency_key {
+            if storage::has_deposit_key(&env, &user, key) {
+                return Err(ContractError::InvalidQuantity);
+            }
+        }
+        deposit::deposit_collateral(env.clone(), user.clone(), market_id, amount)?;
+        if let Some(key) = &idempotency_key {
+            storage::set_deposit_key(&env, &user, key);
+        }
+        check_invariants!(&env, market_id);
+        Ok(())
     }
 
     /// Withdraw unused collateral from a market
@@ -332,6 +684,7 @@ impl MarketContract {
     ///
     /// # Errors
     /// - MarketNotFound
+    /// - NoPositionFound: User never deposited into the market
     /// - InsufficientCollateral: Trying to withdraw locked collateral
     /// - InvalidQuantity: Amount <= 0
     ///
@@ -344,585 +697,2938 @@ impl MarketContract {
         amount: i128,
     ) -> Result<(), ContractError> {
         validation::require_not_paused(&env)?;
-        withdraw::withdraw_unused_collateral(env, user, market_id, amount)
+        withdraw::withdraw_unused_collateral(env.clone(), user, market_id, amount)?;
+        check_invariants!(&env, market_id);
+        Ok(())
     }
 
     /// Resolve a market with oracle-signed outcome
     ///
+    /// With a non-zero `challenge_window` the market enters
+    /// `PendingResolution` instead: settlement stays closed until
+    /// [`finalize_resolution`] is called after the deadline, and the admin may
+    /// cancel the market in the meantime to reject the outcome.
+    ///
     /// # Arguments
     /// * `env` - Contract environment
-    /// * `market_id` - Market to resolve (decimal string, e.g. "1")
-    /// * `outcome` - Outcome (true = YES won, false = NO won)
+    /// * `market_id` - Market to resolve
+    /// * `outcome` - Outcome the oracle signed: YES or NO won, or `Invalid`
+    ///   when the question cannot be answered, which refunds every position
+    /// * `oracle_pubkey` - Public key of the oracle that signed the outcome;
+    ///   `None` checks the signature against the market's oracle directly
     /// * `signature` - Oracle's Ed25519 signature (64 bytes)
+    /// * `challenge_window` - Seconds the outcome stays open to challenge;
+    ///   zero resolves immediately. Must be zero for `Invalid`
+    ///
+    /// A market holding no collateral has nothing to settle: once the
+    /// signature checks out it moves straight to `Canceled` instead, whatever
+    /// the outcome or challenge window.
     ///
     /// # Returns
     /// Unit (success)
     ///
     /// # Errors
     /// - MarketNotFound
-    /// - MarketAlreadyResolved
+    /// - MarketAlreadyResolved: Resolved, or pending resolution
+    /// - MarketNotActive: The market was canceled or expired
+    /// - MarketNotEnded: The ledger time is before the market's `end_time`
+    /// - InvalidOutcome: The market is scalar; see [`resolve_scalar_market`]
+    /// - UnauthorizedOracle: `oracle_pubkey` is given and is not the
+    ///   market's oracle, checked before the signature
     /// - InvalidSignature: Signature verification failed
-    /// - UnauthorizedOracle: Wrong oracle pubkey
+    /// - ResolutionWindowClosed: More than the market's resolution grace has
+    ///   passed since `end_time`
+    /// - ResolutionCommitted: A sealed resolution is awaiting its reveal; see
+    ///   [`reveal_resolution`]
+    /// - InvalidTimestamp: `challenge_window` exceeds
+    ///   `MAX_CHALLENGE_WINDOW_SECONDS`, or is non-zero for an `Invalid`
+    ///   outcome
     ///
     /// # Events
-    /// Emits MarketResolved event with the authorized oracle public key as resolver.
+    /// Emits MarketResolved event with the authorized oracle public key as
+    /// resolver, MarketInvalidated instead for an `Invalid` outcome, or
+    /// ResolutionProposed when a challenge window is set. An empty market
+    /// emits MarketCanceled with the resolver as `canceler`.
     pub fn resolve_market(
         env: Env,
         resolver: Address,
-        market_id: String,
-        outcome: bool,
+        market_id: u32,
+        outcome: Outcome,
+        oracle_pubkey: Option<BytesN<32>>,
         signature: BytesN<64>,
+        challenge_window: u64,
     ) -> Result<(), ContractError> {
-        validation::require_not_paused(&env)?;
         resolver.require_auth();
-        let market_id = validation::parse_market_id(&market_id)?;
-        // Step 1: Load and validate market
+        validation::validate_challenge_window(challenge_window)?;
+        // A sealed resolution can only be completed by its reveal.
+        validation::require_no_pending_commitment(&env, market_id)?;
+        Self::execute_resolution(
+            env,
+            resolver,
+            market_id,
+            outcome,
+            oracle_pubkey,
+            signature,
+            challenge_window,
+        )
+    }
+
+    /// Resolve a scalar market to an oracle-signed value.
+    ///
+    /// The oracle signs `construct_scalar_oracle_message` for the market and
+    /// `value`. Positions then settle with
+    /// `settlement::calculate_scalar_payout`: YES shares pay in proportion to
+    /// how far the value sits above the market's lower bound and NO shares
+    /// to how far it sits below the upper bound. Values outside the bounds
+    /// are stored as signed and clamped at payout.
+    ///
+    /// Scalar markets resolve immediately, without a challenge window. As in
+    /// [`resolve_market`], a registered Resolution contract must hold a
+    /// finalized candidate for the market, and a market holding no
+    /// collateral is canceled instead.
+    ///
+    /// # Arguments
+    /// * `resolver` - Address submitting the resolution
+    /// * `market_id` - Market to resolve
+    /// * `value` - Settled value signed by the oracle
+    /// * `oracle_pubkey` - As in [`resolve_market`]
+    /// * `signature` - Oracle's Ed25519 signature (64 bytes)
+    ///
+    /// # Errors
+    /// - MarketNotFound
+    /// - MarketAlreadyResolved: Resolved, pending resolution or disputed
+    /// - MarketNotActive: The market was canceled or expired
+    /// - InvalidOutcome: The market is binary; see [`resolve_market`]
+    /// - MarketNotEnded: The ledger time is before the market's `end_time`
+    /// - ResolutionWindowClosed: More than the market's resolution grace has
+    ///   passed since `end_time`
+    /// - ResolutionCommitted: A sealed resolution is awaiting its reveal
+    /// - ResolutionNotFinalized: A resolution contract is registered and has
+    ///   no finalized candidate for the market
+    /// - UnauthorizedOracle: `oracle_pubkey` is given and is not the
+    ///   market's oracle
+    /// - InvalidSignature: Signature verification failed
+    ///
+    /// # Events
+    /// Emits ScalarMarketResolved, or MarketCanceled for an empty market.
+    pub fn resolve_scalar_market(
+        env: Env,
+        resolver: Address,
+        market_id: u32,
+        value: i128,
+        oracle_pubkey: Option<BytesN<32>>,
+        signature: BytesN<64>,
+    ) -> Result<(), ContractError> {
+        resolver.require_auth();
+
         let mut market =
             storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
-        if market.status == MarketStatus::Resolved {
-            return Err(ContractError::MarketAlreadyResolved);
+        validation::validate_resolvable(&market.status)?;
+        if market.kind == MarketKind::Binary {
+            return Err(ContractError::InvalidOutcome);
+        }
+        validation::require_market_ended(&env, &market)?;
+        validation::require_resolution_window_open(&env, &market)?;
+        validation::require_no_pending_commitment(&env, market_id)?;
+        Self::require_finalized_candidate(&env, market_id)?;
+        if let Some(oracle_pubkey) = &oracle_pubkey {
+            oracle::validate_oracle_authorization(&market, oracle_pubkey)?;
+        }
+        oracle::verify_scalar_outcome(&env, market_id, &market, value, &signature)?;
+        if Self::cancel_if_empty(&env, market_id, &mut market, &resolver)? {
+            return Ok(());
         }
 
-        // Step 2: Verify outcome using the configured adapter for this market.
-        oracle::verify_market_outcome(
-            &env,
-            market_id,
-            &market,
-            market.adapter_type.clone(),
-            outcome,
-            &signature,
-        )?;
-        events::emit_oracle_signature_verified(&env, market_id, outcome, env.ledger().timestamp());
-
-        // Step 3: Update market (status, outcome, resolver, persist)
+        let now = env.ledger().timestamp();
         market.status = MarketStatus::Resolved;
-        market.result = Some(outcome);
+        market.settled_value = Some(value);
         market.resolver = Some(resolver.clone());
-        let resolved_at = env.ledger().timestamp();
-        market.resolved_at = Some(resolved_at);
+        market.resolved_at = Some(now);
         storage::set_market(&env, market_id, &market)?;
+        storage::remove_resolution_commitment(&env, market_id);
 
-        // Step 4: Emit event
-        events::emit_market_resolved(
+        events::emit_scalar_market_resolved(
             &env,
             market_id,
             &market.oracle_pubkey,
             &resolver,
-            outcome,
-            resolved_at,
+            value,
+            storage::get_market_collateral(&env, market_id),
+            now,
         );
-
+        check_invariants!(&env, market_id);
         Ok(())
     }
 
-    /// Cancel a market before it is resolved, halting all further trading.
+    /// Seal an oracle resolution ahead of revealing it.
     ///
-    /// Only the stored admin may call this. The market must still be
-    /// [`MarketStatus::Active`]; a resolved market has a final outcome and an
-    /// already-canceled market is rejected to surface the redundant call.
-    /// Once canceled, deposits and position updates are rejected (both already
-    /// require an `Active` status), and affected users may reclaim their
-    /// collateral via [`withdraw_canceled_collateral`].
+    /// Submitting a signed outcome straight to [`resolve_market`] lets anyone
+    /// watching pending transactions, including the oracle itself, trade on
+    /// it before it lands. Committing first freezes trading in the market;
+    /// the outcome is then published with [`reveal_resolution`] in a later
+    /// ledger. A commitment that is not revealed within
+    /// `RESOLUTION_REVEAL_WINDOW_LEDGERS` lapses and trading resumes.
+    ///
+    /// Only the market's oracle, authorizing as the account of its key (see
+    /// `oracle::oracle_account`), or the admin may commit, and only once the
+    /// market has ended, so a stranger cannot keep a market frozen by
+    /// committing again each time a commitment lapses.
     ///
     /// # Arguments
-    /// * `env` - Contract environment
-    /// * `admin` - Must be the stored admin address (authorizes the call)
-    /// * `market_id` - Identifier of the market to cancel
+    /// * `resolver` - The oracle's account or the admin; only it may reveal
+    /// * `market_id` - Market to resolve
+    /// * `commitment` - `keccak256(outcome_byte || salt || signature)`, see
+    ///   `oracle::construct_resolution_commitment`
     ///
     /// # Errors
-    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
-    /// - [`ContractError::MarketNotFound`] – the market does not exist
-    /// - [`ContractError::MarketAlreadyResolved`] – the market is already resolved
-    /// - [`ContractError::MarketNotActive`] – the market is already canceled
+    /// - MarketNotFound
+    /// - MarketAlreadyResolved
+    /// - MarketNotActive: The market was canceled or expired
+    /// - MarketNotEnded: The ledger time is before the market's `end_time`
+    /// - ResolutionWindowClosed: More than the market's resolution grace has
+    ///   passed since `end_time`
+    /// - Unauthorized: `resolver` is neither the market's oracle nor the admin
+    /// - ResolutionCommitted: A live commitment already exists
     ///
     /// # Events
-    /// Emits [`MarketCanceled`] with `market_id`, `canceler`, and
-    /// `canceled_at` on success.
-    pub fn cancel_market(
+    /// Emits ResolutionCommitted.
+    pub fn commit_resolution(
         env: Env,
-        admin: Address,
+        resolver: Address,
         market_id: u32,
+        commitment: BytesN<32>,
     ) -> Result<(), ContractError> {
-        validation::require_initialized(&env)?;
-        validation::require_not_paused(&env)?;
-        // 1. Authorization: only the stored admin may cancel a market.
-        admin.require_auth();
-        let stored_admin = storage::get_admin(&env)?;
-        if admin != stored_admin {
-            return Err(ContractError::NotAdmin);
-        }
-
-        // 2. Load the market and enforce the cancel policy (Active only).
-        let mut market =
-            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
-        validation::validate_cancelable(&market.status)?;
-
-        // 3. Transition to Canceled and persist.
-        market.status = MarketStatus::Canceled;
-        storage::set_market(&env, market_id, &market)?;
+        resolver.require_auth();
 
-        // 4. Emit the cancellation event for off-chain indexers.
-        events::emit_market_canceled(&env, market_id, &admin, env.ledger().timestamp());
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        validation::validate_resolvable(&market.status)?;
+        validation::require_market_ended(&env, &market)?;
+        validation::require_resolution_window_open(&env, &market)?;
+        if resolver != oracle::oracle_account(&env, &market.oracle_pubkey)
+            && resolver != storage::get_admin(&env)?
+        {
+            return Err(ContractError::Unauthorized);
+        }
+        validation::require_no_pending_commitment(&env, market_id)?;
 
+        let ledger = env.ledger().sequence();
+        storage::set_resolution_commitment(
+            &env,
+            market_id,
+            &ResolutionCommitment {
+                resolver: resolver.clone(),
+                commitment: commitment.clone(),
+                ledger,
+            },
+        );
+        events::emit_resolution_committed(&env, market_id, &resolver, &commitment, ledger);
         Ok(())
     }
 
-    /// Reclaim deposited collateral from a canceled market.
-    ///
-    /// When a market is canceled before resolution there is no winning outcome,
-    /// so each user is made whole by returning the full collateral they have
-    /// deposited in that market. The user's position balances are zeroed and the
-    /// collateral (SAC) tokens are transferred from the contract back to them.
+    /// Reveal a committed resolution and resolve the market with it.
     ///
-    /// # Arguments
-    /// * `env` - Contract environment
-    /// * `user` - User reclaiming their collateral (must authorize the call)
-    /// * `market_id` - Identifier of the canceled market
-    ///
-    /// # Returns
-    /// The amount of collateral refunded to the user, in stroops.
+    /// The outcome, salt and signature must hash to the commitment stored by
+    /// [`commit_resolution`], and the reveal must land in a later ledger than
+    /// the commitment. The signature is then verified exactly as in
+    /// [`resolve_market`].
     ///
     /// # Errors
-    /// - [`ContractError::MarketNotFound`] – the market does not exist
-    /// - [`ContractError::MarketNotActive`] – the market is not canceled, so the
-    ///   reclaim path does not apply
-    /// - [`ContractError::NoPositionFound`] – the user has no position in the market
-    /// - [`ContractError::InsufficientCollateral`] – the user has no collateral to reclaim
+    /// - NoResolutionCommitment: No live commitment for the market
+    /// - Unauthorized: `resolver` is not the address that committed
+    /// - RevealTooEarly: Same ledger as the commitment
+    /// - CommitmentMismatch: The revealed values do not match the commitment
+    /// - Any error of [`resolve_market`]
     ///
     /// # Events
-    /// Emits `CollateralWithdrawn` with the refunded amount and the user's new
-    /// (zero) total.
-    pub fn withdraw_canceled_collateral(
+    /// Emits MarketResolved, as [`resolve_market`] does.
+    pub fn reveal_resolution(
         env: Env,
-        user: Address,
+        resolver: Address,
         market_id: u32,
-    ) -> Result<i128, ContractError> {
-        // 1. Authorization: only the position owner may reclaim their collateral.
-        user.require_auth();
+        outcome: bool,
+        salt: BytesN<32>,
+        signature: BytesN<64>,
+    ) -> Result<(), ContractError> {
+        resolver.require_auth();
 
-        // 2. The reclaim path is exclusive to canceled markets.
-        let market =
-            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
-        if market.status != MarketStatus::Canceled {
-            return Err(ContractError::MarketNotActive);
+        let pending = validation::pending_resolution_commitment(&env, market_id)
+            .ok_or(ContractError::NoResolutionCommitment)?;
+        if pending.resolver != resolver {
+            return Err(ContractError::Unauthorized);
         }
-
-        // 3. Load the user's position and the full deposited balance.
-        let mut position = storage::get_position(&env, market_id, &user)?
-            .ok_or(ContractError::NoPositionFound)?;
-        let refund = position.total_deposited;
-        if refund <= 0 {
-            return Err(ContractError::InsufficientCollateral);
+        if env.ledger().sequence() <= pending.ledger {
+            return Err(ContractError::RevealTooEarly);
+        }
+        let revealed = oracle::construct_resolution_commitment(&env, outcome, &salt, &signature);
+        if revealed != pending.commitment {
+            return Err(ContractError::CommitmentMismatch);
         }
 
-        // 4. Refund the collateral from the contract back to the user.
-        let contract_address = env.current_contract_address();
+        Self::execute_resolution(env, resolver, market_id, outcome.into(), None, signature, 0)
+    }
+
+    /// Resolution steps shared by [`resolve_market`] and
+    /// [`reveal_resolution`], for callers that have already obtained the
+    /// resolver's authorization.
+    ///
+    /// A submitted `oracle_pubkey` must be the market's oracle; without one
+    /// the signature is checked against the market's oracle directly.
+    fn execute_resolution(
+        env: Env,
+        resolver: Address,
+        market_id: u32,
+        outcome: Outcome,
+        oracle_pubkey: Option<BytesN<32>>,
+        signature: BytesN<64>,
+        challenge_window: u64,
+    ) -> Result<(), ContractError> {
+        // Step 1: Load and validate market
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        validation::validate_resolvable(&market.status)?;
+        validation::validate_outcome(&market, outcome)?;
+        validation::require_market_ended(&env, &market)?;
+        validation::require_resolution_window_open(&env, &market)?;
+        // A proposed outcome is stored as its winning side, which an invalid
+        // resolution does not have.
+        if outcome == Outcome::Invalid && challenge_window > 0 {
+            return Err(ContractError::InvalidTimestamp);
+        }
+
+        // Step 1a: When a resolution contract is registered, only a finalized
+        // candidate for this market may resolve it.
+        Self::require_finalized_candidate(&env, market_id)?;
+
+        // Step 2: Verify outcome using the configured adapter for this market.
+        // A key other than the market's oracle is rejected before the
+        // signature is looked at, so it is not reported as a bad signature.
+        if let Some(oracle_pubkey) = &oracle_pubkey {
+            oracle::validate_oracle_authorization(&market, oracle_pubkey)?;
+        }
+        oracle::verify_market_outcome(
+            &env,
+            market_id,
+            &market,
+            market.adapter_type.clone(),
+            outcome,
+            &signature,
+        )?;
+        // Step 3 (empty): with no collateral there is nothing to settle.
+        if Self::cancel_if_empty(&env, market_id, &mut market, &resolver)? {
+            return Ok(());
+        }
+        let now = env.ledger().timestamp();
+        let Some(outcome) = outcome.winner() else {
+            // Step 3 (invalid): resolve with no winning side; settlement
+            // refunds every position its deposit.
+            market.status = MarketStatus::Resolved;
+            market.resolver = Some(resolver.clone());
+            market.resolved_at = Some(now);
+            storage::set_market(&env, market_id, &market)?;
+            storage::remove_resolution_commitment(&env, market_id);
+            events::emit_market_invalidated(
+                &env,
+                market_id,
+                &market.oracle_pubkey,
+                &resolver,
+                storage::get_market_collateral(&env, market_id),
+                now,
+            );
+            check_invariants!(&env, market_id);
+            return Ok(());
+        };
+        events::emit_oracle_signature_verified(&env, market_id, outcome, now);
+
+        // Step 3: Update market (status, outcome, resolver, persist). Any
+        // commitment is spent once the market resolves, by either path.
+        market.result = Some(outcome);
+        market.resolver = Some(resolver.clone());
+        if challenge_window > 0 {
+            let challenge_deadline = now.saturating_add(challenge_window);
+            market.status = MarketStatus::PendingResolution;
+            market.challenge_deadline = Some(challenge_deadline);
+            storage::set_market(&env, market_id, &market)?;
+            storage::remove_resolution_commitment(&env, market_id);
+            events::emit_resolution_proposed(
+                &env,
+                market_id,
+                &resolver,
+                outcome,
+                challenge_deadline,
+            );
+            check_invariants!(&env, market_id);
+            return Ok(());
+        }
+        market.status = MarketStatus::Resolved;
+        market.resolved_at = Some(now);
+        storage::set_market(&env, market_id, &market)?;
+        storage::remove_resolution_commitment(&env, market_id);
+
+        // Step 4: Emit event
+        events::emit_market_resolved(
+            &env,
+            market_id,
+            &market.oracle_pubkey,
+            &resolver,
+            outcome,
+            storage::get_market_collateral(&env, market_id),
+            now,
+        );
+
+        check_invariants!(&env, market_id);
+        Ok(())
+    }
+
+    /// When a resolution contract is registered, require it to hold a
+    /// finalized candidate for the market; oracle resolution paths call this
+    /// before verifying signatures.
+    ///
+    /// # Errors
+    /// - [`ContractError::ResolutionNotFinalized`] – no candidate for the
+    ///   market, or its candidate is not finalized
+    fn require_finalized_candidate(env: &Env, market_id: u32) -> Result<(), ContractError> {
+        let Some(resolution_contract) = storage::get_resolution_contract(env) else {
+            return Ok(());
+        };
+        let resolution_client = ResolutionContractClient::new(env, &resolution_contract);
+        let finalized = resolution_client
+            .get_candidate_id_for_market(&market_id)
+            .and_then(|candidate_id| resolution_client.get_candidate(&candidate_id))
+            .is_some_and(|candidate| candidate.status == ResolutionCandidateStatus::Finalized);
+        if !finalized {
+            return Err(ContractError::ResolutionNotFinalized);
+        }
+        Ok(())
+    }
+
+    /// Cancel `market` instead of resolving it when it holds no collateral.
+    ///
+    /// Every resolution path calls this once the outcome is authorized: an
+    /// empty market has nothing to settle. Returns whether it was canceled.
+    fn cancel_if_empty(
+        env: &Env,
+        market_id: u32,
+        market: &mut Market,
+        canceler: &Address,
+    ) -> Result<bool, ContractError> {
+        if storage::get_market_collateral(env, market_id) != 0 {
+            return Ok(false);
+        }
+        market.status = MarketStatus::Canceled;
+        storage::set_market(env, market_id, market)?;
+        storage::remove_resolution_commitment(env, market_id);
+        events::emit_market_canceled(env, market_id, canceler, env.ledger().timestamp());
+        check_invariants!(env, market_id);
+        Ok(true)
+    }
+
+    /// Resolve a `PendingResolution` market once its challenge window has
+    /// passed, making the proposed outcome final and opening settlement.
+    ///
+    /// Anyone may call this; the outcome was verified by [`resolve_market`].
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::MarketAlreadyResolved`] – the market is already resolved
+    /// - [`ContractError::MarketNotActive`] – the market is not pending
+    ///   resolution, including while a dispute is open
+    /// - [`ContractError::ChallengeWindowOpen`] – the challenge deadline has not
+    ///   been reached
+    ///
+    /// # Events
+    /// Emits `MarketResolved` with the proposing resolver.
+    pub fn finalize_resolution(env: Env, market_id: u32) -> Result<(), ContractError> {
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        match market.status {
+            MarketStatus::PendingResolution => {}
+            MarketStatus::Resolved => return Err(ContractError::MarketAlreadyResolved),
+            _ => return Err(ContractError::MarketNotActive),
+        }
+        let now = env.ledger().timestamp();
+        if now < market.challenge_deadline.unwrap_or(0) {
+            return Err(ContractError::ChallengeWindowOpen);
+        }
+        let (Some(outcome), Some(resolver)) = (market.result, market.resolver.clone()) else {
+            return Err(ContractError::MarketNotActive);
+        };
+
+        market.status = MarketStatus::Resolved;
+        market.resolved_at = Some(now);
+        storage::set_market(&env, market_id, &market)?;
+
+        events::emit_market_resolved(
+            &env,
+            market_id,
+            &market.oracle_pubkey,
+            &resolver,
+            outcome,
+            storage::get_market_collateral(&env, market_id),
+            now,
+        );
+
+        check_invariants!(&env, market_id);
+        Ok(())
+    }
+
+    /// Dispute a market's proposed outcome by posting a bond.
+    ///
+    /// Callable by anyone while the market is `PendingResolution` and its
+    /// challenge window is open. The bond is transferred from `disputer` to
+    /// the contract in the market's collateral token, and the market moves to
+    /// [`MarketStatus::Disputed`], blocking [`finalize_resolution`] until the
+    /// admin calls [`resolve_dispute`]. Disputes stay open while the contract
+    /// is paused, since the challenge deadline keeps running and
+    /// [`finalize_resolution`] is not paused either.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `disputer` - Address posting the bond (must authorize the call)
+    /// * `market_id` - Market identifier
+    /// * `bond_amount` - Bond in the collateral token's base units (must be
+    ///   positive)
+    ///
+    /// # Errors
+    /// - [`ContractError::InvalidQuantity`] – `bond_amount` is not positive
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::MarketAlreadyResolved`] – the market is already resolved
+    /// - [`ContractError::MarketNotActive`] – the market is not pending
+    ///   resolution (including one that is already disputed)
+    /// - [`ContractError::ChallengeWindowClosed`] – the challenge deadline has
+    ///   been reached
+    ///
+    /// # Events
+    /// Emits `ResolutionDisputed` with the disputed outcome and bond.
+    pub fn dispute_resolution(
+        env: Env,
+        disputer: Address,
+        market_id: u32,
+        bond_amount: i128,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        disputer.require_auth();
+        if bond_amount <= 0 {
+            return Err(ContractError::InvalidQuantity);
+        }
+
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        match market.status {
+            MarketStatus::PendingResolution => {}
+            MarketStatus::Resolved => return Err(ContractError::MarketAlreadyResolved),
+            _ => return Err(ContractError::MarketNotActive),
+        }
+        let now = env.ledger().timestamp();
+        if now >= market.challenge_deadline.unwrap_or(0) {
+            return Err(ContractError::ChallengeWindowClosed);
+        }
+        let proposed_outcome = market.result.ok_or(ContractError::MarketNotActive)?;
+
+        soroban_sdk::token::Client::new(&env, &market.collateral_token).transfer(
+            &disputer,
+            env.current_contract_address(),
+            &bond_amount,
+        );
+
+        market.status = MarketStatus::Disputed;
+        storage::set_market(&env, market_id, &market)?;
+        storage::set_dispute(
+            &env,
+            market_id,
+            &Dispute {
+                disputer: disputer.clone(),
+                bond: bond_amount,
+                disputed_at: now,
+            },
+        );
+
+        events::emit_resolution_disputed(&env, market_id, &disputer, proposed_outcome, bond_amount);
+
+        check_invariants!(&env, market_id);
+        Ok(())
+    }
+
+    /// Settle a dispute by fixing the market's final outcome.
+    ///
+    /// Only the stored admin may call this. The market resolves to
+    /// `final_outcome` with the admin recorded as resolver, opening
+    /// settlement. The disputer's bond is either returned to them or slashed
+    /// into the market's accrued fees, withdrawable with [`withdraw_fees`].
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `admin` - Must be the stored admin address (authorizes the call)
+    /// * `market_id` - Identifier of the disputed market
+    /// * `final_outcome` - The true outcome (true = YES, false = NO)
+    /// * `refund_bond` - `true` to return the bond to the disputer, `false`
+    ///   to slash it
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::MarketNotDisputed`] – the market is not disputed
+    ///
+    /// # Events
+    /// Emits `DisputeResolved`, then `MarketResolved` with the admin as
+    /// resolver.
+    pub fn resolve_dispute(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+        final_outcome: bool,
+        refund_bond: bool,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        validation::require_not_paused(&env)?;
+        admin.require_auth();
+        if admin != storage::get_admin(&env)? {
+            return Err(ContractError::NotAdmin);
+        }
+
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        if market.status != MarketStatus::Disputed {
+            return Err(ContractError::MarketNotDisputed);
+        }
+        let dispute =
+            storage::get_dispute(&env, market_id).ok_or(ContractError::MarketNotDisputed)?;
+
+        if refund_bond {
+            soroban_sdk::token::Client::new(&env, &market.collateral_token).transfer(
+                &env.current_contract_address(),
+                &dispute.disputer,
+                &dispute.bond,
+            );
+        } else {
+            let accrued = storage::get_accrued_fees(&env, market_id)
+                .checked_add(dispute.bond)
+                .ok_or(ContractError::ArithmeticOverflow)?;
+            storage::set_accrued_fees(&env, market_id, accrued);
+        }
+        storage::remove_dispute(&env, market_id);
+
+        let now = env.ledger().timestamp();
+        market.status = MarketStatus::Resolved;
+        market.result = Some(final_outcome);
+        market.resolver = Some(admin.clone());
+        market.resolved_at = Some(now);
+        storage::set_market(&env, market_id, &market)?;
+
+        events::emit_dispute_resolved(
+            &env,
+            market_id,
+            &admin,
+            &dispute.disputer,
+            final_outcome,
+            refund_bond,
+            dispute.bond,
+        );
+        events::emit_market_resolved(
+            &env,
+            market_id,
+            &market.oracle_pubkey,
+            &admin,
+            final_outcome,
+            storage::get_market_collateral(&env, market_id),
+            now,
+        );
+
+        check_invariants!(&env, market_id);
+        Ok(())
+    }
+
+    /// Return the open dispute against a market's proposed outcome, if any.
+    pub fn get_dispute(env: Env, market_id: u32) -> Option<Dispute> {
+        storage::get_dispute(&env, market_id)
+    }
+
+    /// Propose a manual outcome for a market whose oracle has failed.
+    ///
+    /// Only the stored admin may call this, once the market has ended. The
+    /// outcome only takes effect when [`admin_execute_resolution`] is called
+    /// at least [`validation::ADMIN_RESOLUTION_DELAY_SECONDS`] later; until
+    /// then the market stays `Active`, so the oracle can still resolve it or
+    /// the admin can cancel it. Proposing again replaces the previous
+    /// proposal and restarts the delay.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `admin` - Must be the stored admin address (authorizes the call)
+    /// * `market_id` - Identifier of the market
+    /// * `outcome` - Proposed outcome (true = YES, false = NO)
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::MarketAlreadyResolved`] – the market is already resolved
+    /// - [`ContractError::MarketNotActive`] – the market is not `Active`
+    /// - [`ContractError::InvalidOutcome`] – the market is scalar
+    /// - [`ContractError::MarketNotEnded`] – the market's `end_time` has not
+    ///   been reached
+    ///
+    /// # Events
+    /// Emits `AdminResolutionProposed` with the time the proposal becomes
+    /// executable.
+    pub fn admin_propose_resolution(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+        outcome: bool,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        validation::require_not_paused(&env)?;
+        admin.require_auth();
+        if admin != storage::get_admin(&env)? {
+            return Err(ContractError::NotAdmin);
+        }
+
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        match market.status {
+            MarketStatus::Active => {}
+            MarketStatus::Resolved => return Err(ContractError::MarketAlreadyResolved),
+            _ => return Err(ContractError::MarketNotActive),
+        }
+        validation::require_binary_market(&market)?;
+        validation::require_market_ended(&env, &market)?;
+
+        let now = env.ledger().timestamp();
+        let executable_at = now
+            .checked_add(validation::ADMIN_RESOLUTION_DELAY_SECONDS)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        storage::set_admin_resolution(
+            &env,
+            market_id,
+            &AdminResolutionProposal {
+                admin: admin.clone(),
+                outcome,
+                proposed_at: now,
+                executable_at,
+            },
+        );
+
+        events::emit_admin_resolution_proposed(&env, market_id, &admin, outcome, executable_at);
+        Ok(())
+    }
+
+    /// Resolve a market to the admin's proposed outcome once its timelock
+    /// has elapsed.
+    ///
+    /// Anyone may call this; the outcome was authorized by the admin in
+    /// [`admin_propose_resolution`]. The market resolves with the proposing
+    /// admin recorded as resolver, opening settlement. A market holding no
+    /// collateral is canceled instead, as in [`resolve_market`].
+    ///
+    /// Like the oracle paths, this keeps working while the contract is
+    /// paused. Unlike them it does not consult a registered Resolution
+    /// contract: it is the override for when the oracle has failed, and with
+    /// it any candidate that contract would need to finalize.
+    ///
+    /// # Errors
+    /// - [`ContractError::NoResolutionCommitment`] – no admin proposal is
+    ///   pending for the market
+    /// - [`ContractError::ChallengeWindowOpen`] – the ledger timestamp has not
+    ///   reached the proposal's `executable_at`
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::MarketAlreadyResolved`] – the market was resolved
+    ///   in the meantime
+    /// - [`ContractError::MarketNotActive`] – the market is no longer `Active`
+    /// - [`ContractError::ResolutionCommitted`] – an oracle commitment is
+    ///   pending for the market
+    ///
+    /// # Events
+    /// Emits `AdminResolutionExecuted`, then `MarketResolved` with the admin
+    /// as resolver; an empty market emits `MarketCanceled` instead.
+    pub fn admin_execute_resolution(env: Env, market_id: u32) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        let proposal = storage::get_admin_resolution(&env, market_id)
+            .ok_or(ContractError::NoResolutionCommitment)?;
+        let now = env.ledger().timestamp();
+        if now < proposal.executable_at {
+            return Err(ContractError::ChallengeWindowOpen);
+        }
+
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        match market.status {
+            MarketStatus::Active => {}
+            MarketStatus::Resolved => return Err(ContractError::MarketAlreadyResolved),
+            _ => return Err(ContractError::MarketNotActive),
+        }
+        validation::require_no_pending_commitment(&env, market_id)?;
+        if Self::cancel_if_empty(&env, market_id, &mut market, &proposal.admin)? {
+            storage::remove_admin_resolution(&env, market_id);
+            return Ok(());
+        }
+
+        market.status = MarketStatus::Resolved;
+        market.result = Some(proposal.outcome);
+        market.resolver = Some(proposal.admin.clone());
+        market.resolved_at = Some(now);
+        storage::set_market(&env, market_id, &market)?;
+        storage::remove_admin_resolution(&env, market_id);
+
+        events::emit_admin_resolution_executed(&env, market_id, &proposal.admin, proposal.outcome);
+        events::emit_market_resolved(
+            &env,
+            market_id,
+            &market.oracle_pubkey,
+            &proposal.admin,
+            proposal.outcome,
+            storage::get_market_collateral(&env, market_id),
+            now,
+        );
+
+        check_invariants!(&env, market_id);
+        Ok(())
+    }
+
+    /// Return the admin outcome awaiting its timelock for a market, if any.
+    pub fn get_admin_resolution(env: Env, market_id: u32) -> Option<AdminResolutionProposal> {
+        storage::get_admin_resolution(&env, market_id)
+    }
+
+    /// Verify an oracle signature for a market outcome without resolving it.
+    ///
+    /// Used by the resolution contract to reject proposals early when the
+    /// signed payload would not be accepted by [`resolve_market`].
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::InvalidSignature`] – the signature does not verify
+    /// - [`ContractError::UnauthorizedOracle`] – the market's adapter cannot
+    ///   verify signatures
+    pub fn verify_signature(
+        env: Env,
+        market_id: u32,
+        outcome: bool,
+        signature: BytesN<64>,
+    ) -> Result<(), ContractError> {
+        let market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        oracle::verify_market_outcome(
+            &env,
+            market_id,
+            &market,
+            market.adapter_type.clone(),
+            outcome.into(),
+            &signature,
+        )
+    }
+
+    /// Cancel a market before it is resolved, halting all further trading.
+    ///
+    /// Only the stored admin may call this. The market must still be
+    /// [`MarketStatus::Active`] or [`MarketStatus::PendingResolution`] (which
+    /// challenges the proposed outcome); a resolved market has a final outcome
+    /// and an already-canceled market is rejected to surface the redundant call.
+    /// Once canceled, deposits and position updates are rejected (both already
+    /// require an `Active` status), and affected users may reclaim their
+    /// collateral via [`withdraw_canceled_collateral`].
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `admin` - Must be the stored admin address (authorizes the call)
+    /// * `market_id` - Identifier of the market to cancel
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::MarketAlreadyResolved`] – the market is already resolved
+    /// - [`ContractError::MarketNotActive`] – the market is already canceled
+    ///
+    /// # Events
+    /// Emits [`MarketCanceled`] with `market_id`, `canceler`, and
+    /// `canceled_at` on success.
+    pub fn cancel_market(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        validation::require_not_paused(&env)?;
+        // 1. Authorization: only the stored admin may cancel a market.
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+
+        // 2. Load the market and enforce the cancel policy (Active or
+        //    pending resolution).
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        validation::validate_cancelable(&market.status)?;
+
+        // 3. Transition to Canceled and persist. Canceling a pending market
+        //    rejects its proposed outcome.
+        if market.status == MarketStatus::PendingResolution {
+            market.result = None;
+            market.resolver = None;
+            market.challenge_deadline = None;
+        }
+        market.status = MarketStatus::Canceled;
+        storage::set_market(&env, market_id, &market)?;
+
+        // 4. Emit the cancellation event for off-chain indexers.
+        events::emit_market_canceled(&env, market_id, &admin, env.ledger().timestamp());
+
+        check_invariants!(&env, market_id);
+        Ok(())
+    }
+
+    /// Cancel a market whose oracle never resolved it.
+    ///
+    /// Callable by anyone once the market's resolution window has closed, or
+    /// for a market without a resolution grace once the ledger time is past
+    /// `end_time` plus [`validation::RESOLUTION_TIMEOUT_SECONDS`], so
+    /// collateral cannot be stuck forever behind an absent oracle. The market
+    /// must still be [`MarketStatus::Active`]; it moves to
+    /// [`MarketStatus::Canceled`] and participants reclaim their principal via
+    /// [`withdraw_canceled_collateral`]. Works while the contract is paused,
+    /// like every other exit.
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::MarketNotActive`] – the market is no longer `Active`
+    /// - [`ContractError::MarketNotEnded`] – the resolution timeout has not
+    ///   elapsed yet
+    ///
+    /// # Events
+    /// Emits [`MarketCanceled`] with the contract's own address as `canceler`.
+    pub fn expire_market(env: Env, market_id: u32) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        if market.status != MarketStatus::Active {
+            return Err(ContractError::MarketNotActive);
+        }
+        let now = env.ledger().timestamp();
+        if now <= validation::expiry_threshold(&market) {
+            return Err(ContractError::MarketNotEnded);
+        }
+
+        market.status = MarketStatus::Canceled;
+        storage::set_market(&env, market_id, &market)?;
+        events::emit_market_canceled(&env, market_id, &env.current_contract_address(), now);
+
+        check_invariants!(&env, market_id);
+        Ok(())
+    }
+
+    /// Reopen a market that was canceled in error, restoring it to
+    /// [`MarketStatus::Active`].
+    ///
+    /// Only the stored admin may call this, and only while no user has
+    /// claimed a refund via [`withdraw_canceled_collateral`]. Once any refund
+    /// has been paid the cancellation is final, since reopening would leave
+    /// refunded positions holding shares without collateral.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `admin` - Must be the stored admin address (authorizes the call)
+    /// * `market_id` - Identifier of the canceled market to reopen
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::MarketNotActive`] – the market is not canceled, or a
+    ///   refund has already been claimed
+    ///
+    /// # Events
+    /// Emits [`MarketReopened`] with `market_id`, `reopener`, and
+    /// `reopened_at` on success.
+    pub fn reopen_market(env: Env, admin: Address, market_id: u32) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        validation::require_not_paused(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        if market.status != MarketStatus::Canceled || storage::has_refunds_started(&env, market_id)
+        {
+            return Err(ContractError::MarketNotActive);
+        }
+
+        market.status = MarketStatus::Active;
+        storage::set_market(&env, market_id, &market)?;
+
+        events::emit_market_reopened(&env, market_id, &admin, env.ledger().timestamp());
+
+        check_invariants!(&env, market_id);
+        Ok(())
+    }
+
+    /// Assign a market to a category for filtered listing.
+    ///
+    /// Only the stored admin may call this. Recategorizing moves the market
+    /// out of its previous category's index; assigning the current category
+    /// again is a no-op that succeeds.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `admin` - Must be the stored admin address (authorizes the call)
+    /// * `market_id` - Identifier of the market to categorize
+    /// * `category` - Short category name, e.g. `sports`
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    ///
+    /// # Events
+    /// Emits [`MarketCategorized`] with the new and previous category when
+    /// the category changes.
+    pub fn set_market_category(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+        category: Symbol,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        validation::require_not_paused(&env)?;
+        admin.require_auth();
+        if admin != storage::get_admin(&env)? {
+            return Err(ContractError::NotAdmin);
+        }
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+
+        let previous = storage::set_market_category(&env, market_id, &category);
+        if previous.as_ref() != Some(&category) {
+            market.category = category.clone();
+            storage::set_market(&env, market_id, &market)?;
+            events::emit_market_categorized(&env, market_id, &category, &previous);
+        }
+        Ok(())
+    }
+
+    /// Replace the oracle key trusted to resolve a market.
+    ///
+    /// Only the stored admin may call this, and only while the market is
+    /// [`MarketStatus::Active`], so a compromised key can be swapped out
+    /// before it signs an outcome. Rotating to the current key is a no-op
+    /// that succeeds.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `admin` - Must be the stored admin address (authorizes the call)
+    /// * `market_id` - Identifier of the market
+    /// * `new_pubkey` - Ed25519 public key that will sign the outcome
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
+    /// - [`ContractError::InvalidSignature`] – `new_pubkey` is all zeros
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::MarketAlreadyResolved`] – the market is no longer
+    ///   `Active`
+    ///
+    /// # Events
+    /// Emits [`OracleRotated`] with the old and new keys when the key changes.
+    pub fn rotate_oracle(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+        new_pubkey: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        validation::require_not_paused(&env)?;
+        admin.require_auth();
+        if admin != storage::get_admin(&env)? {
+            return Err(ContractError::NotAdmin);
+        }
+        validation::validate_oracle_pubkey(&env, &new_pubkey)?;
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        if market.status != MarketStatus::Active {
+            return Err(ContractError::MarketAlreadyResolved);
+        }
+
+        if market.oracle_pubkey != new_pubkey {
+            let old_pubkey = market.oracle_pubkey.clone();
+            market.oracle_pubkey = new_pubkey.clone();
+            storage::set_market(&env, market_id, &market)?;
+            events::emit_oracle_rotated(&env, market_id, &old_pubkey, &new_pubkey);
+        }
+        Ok(())
+    }
+
+    /// Close a market to new collateral deposits.
+    ///
+    /// Only the stored admin may call this. The market stays
+    /// [`MarketStatus::Active`]: trading, withdrawals, resolution and
+    /// settlement are unaffected, only [`deposit_collateral`] is rejected with
+    /// [`ContractError::MarketClosedToDeposits`]. Closing an already-closed
+    /// market is a no-op that succeeds.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `admin` - Must be the stored admin address (authorizes the call)
+    /// * `market_id` - Identifier of the market to close
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    ///
+    /// # Events
+    /// Emits [`MarketClosedToDepositsEvent`] with `market_id`, `admin`, and
+    /// `closed_at` on success.
+    pub fn close_market_to_deposits(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        market.closed_to_deposits = true;
+        storage::set_market(&env, market_id, &market)?;
+
+        events::emit_market_closed_to_deposits(&env, market_id, &admin, env.ledger().timestamp());
+
+        check_invariants!(&env, market_id);
+        Ok(())
+    }
+
+    /// Set or clear a market's withdraw cooldown.
+    ///
+    /// Users may not withdraw from the market until `cooldown` seconds have
+    /// passed since their last deposit there. `Some(0)` disables the cooldown
+    /// and `None` restores `DEFAULT_WITHDRAW_COOLDOWN_SECONDS`.
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::InvalidTimestamp`] – `cooldown` exceeds
+    ///   `MAX_WITHDRAW_COOLDOWN_SECONDS`
+    pub fn set_withdraw_cooldown(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+        cooldown: Option<u64>,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        validation::require_not_paused(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+        validation::validate_withdraw_cooldown(cooldown)?;
+
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        market.withdraw_cooldown = cooldown;
+        storage::set_market(&env, market_id, &market)?;
+
+        check_invariants!(&env, market_id);
+        Ok(())
+    }
+
+    /// Reclaim deposited collateral from a canceled market.
+    ///
+    /// When a market is canceled before resolution there is no winning outcome,
+    /// so each user is made whole by returning the full collateral they have
+    /// deposited in that market. The user's position balances are zeroed and the
+    /// collateral (SAC) tokens are transferred from the contract back to them.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `user` - User reclaiming their collateral (must authorize the call)
+    /// * `market_id` - Identifier of the canceled market
+    ///
+    /// # Returns
+    /// The amount of collateral refunded to the user, in stroops.
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::MarketNotActive`] – the market is not canceled, so the
+    ///   reclaim path does not apply
+    /// - [`ContractError::NoPositionFound`] – the user has no position in the market
+    /// - [`ContractError::PositionAlreadySettled`] – the deposit was already
+    ///   refunded through [`settle_position`]
+    /// - [`ContractError::InsufficientCollateral`] – the user has no collateral to reclaim
+    ///
+    /// # Events
+    /// Emits `CollateralWithdrawn` with the refunded amount and the user's new
+    /// (zero) total.
+    pub fn withdraw_canceled_collateral(
+        env: Env,
+        user: Address,
+        market_id: u32,
+    ) -> Result<i128, ContractError> {
+        // 1. Authorization: only the position owner may reclaim their collateral.
+        user.require_auth();
+        Self::execute_canceled_refund(env, user, market_id)
+    }
+
+    /// Refund body of [`withdraw_canceled_collateral`]; callers must have
+    /// already authorized `user`.
+    fn execute_canceled_refund(
+        env: Env,
+        user: Address,
+        market_id: u32,
+    ) -> Result<i128, ContractError> {
+        // 2. The reclaim path is exclusive to canceled markets.
+        let market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        if market.status != MarketStatus::Canceled {
+            return Err(ContractError::MarketNotActive);
+        }
+
+        // 3. Load the user's position and the full deposited balance.
+        let mut position = storage::get_position(&env, market_id, &user)?
+            .ok_or(ContractError::NoPositionFound)?;
+        if position.is_settled {
+            return Err(ContractError::PositionAlreadySettled);
+        }
+        let refund = position.total_deposited;
+        if refund <= 0 {
+            return Err(ContractError::InsufficientCollateral);
+        }
+
+        // 4. Refund the collateral from the contract back to the user.
+        let contract_address = env.current_contract_address();
         let token_client = soroban_sdk::token::Client::new(&env, &market.collateral_token);
         token_client.transfer(&contract_address, &user, &refund);
+        storage::set_refunds_started(&env, market_id);
+
+        // 5. Zero out the position balances now that the collateral has left.
+        position.total_deposited = 0;
+        position.locked_collateral = 0;
+        storage::set_position(&env, market_id, &user, &position)?;
+
+        // 6. Emit position_updated so indexers see the zeroed balances.
+        events::emit_position_updated(
+            &env,
+            market_id,
+            &user,
+            position.yes_shares,
+            position.no_shares,
+            position.locked_collateral,
+        );
+
+        // 7. Reuse the collateral-withdrawn event so indexers track the refund.
+        events::emit_collateral_withdrawn(&env, &user, market_id, refund, position.total_deposited);
+
+        check_invariants!(&env, market_id);
+        Ok(refund)
+    }
+
+    /// Quote a trade of `quantity` `yes_side` shares on the market's curve
+    /// and move the curve by its value; `toward_yes` is the direction the
+    /// trade pushes the price.
+    ///
+    /// The trade's value at the pre-trade price moves the reserves through
+    /// [`pricing::apply_trade`], and the fill is priced at the midpoint of the
+    /// curve's YES price before and after. A large trade therefore pays for
+    /// the slippage it causes instead of filling entirely at the spot price.
+    ///
+    /// Returns the YES price the trade fills at.
+    fn trade_on_curve(
+        env: &Env,
+        market_id: u32,
+        yes_side: bool,
+        toward_yes: bool,
+        quantity: i128,
+    ) -> Result<i128, ContractError> {
+        let mut market =
+            storage::get_market(env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        let market_price = pricing::current_price(market.yes_reserve, market.no_reserve);
+        let side_price = if yes_side {
+            market_price
+        } else {
+            positions::BASIS_POINTS - market_price
+        };
+        let value = quantity
+            .checked_mul(side_price)
+            .ok_or(ContractError::ArithmeticOverflow)?
+            / positions::BASIS_POINTS;
+        let (yes_reserve, no_reserve) =
+            pricing::apply_trade(market.yes_reserve, market.no_reserve, toward_yes, value)?;
+        market.yes_reserve = yes_reserve;
+        market.no_reserve = no_reserve;
+        storage::set_market(env, market_id, &market)?;
+        Ok((market_price + pricing::current_price(yes_reserve, no_reserve)) / 2)
+    }
+
+    /// Steps 2–6 of [`update_position`] for callers that have already
+    /// obtained the user's authorization and priced the trade (e.g.
+    /// [`buy_shares`]).
+    fn execute_position_update(
+        env: Env,
+        user: Address,
+        market_id: u32,
+        yes_delta: i128,
+        no_delta: i128,
+        market_price: i128,
+    ) -> Result<Position, ContractError> {
+        // 2. Validate market state: must exist, be Active, and not be expired
+        let mut market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        if market.status != MarketStatus::Active {
+            return Err(ContractError::MarketNotActive);
+        }
+        if env.ledger().timestamp() > market.end_time {
+            return Err(ContractError::MarketExpired);
+        }
+        validation::require_no_pending_commitment(&env, market_id)?;
+
+        // 3. Validate the market price up front for a clear ContractError
+        validation::validate_market_price(market_price)?;
+
+        // 4. Enforce that deposited collateral covers any increase in the lock.
+        //    Negative-share deltas are left for positions::update_position to
+        //    reject (it also emits a PositionLimitExceeded event).
+        //    Selling from a position that was never opened is reported as such
+        //    rather than as a negative share balance.
+        let position = match storage::get_position(&env, market_id, &user)? {
+            Some(position) => position,
+            None if yes_delta < 0 || no_delta < 0 => return Err(ContractError::NoPositionFound),
+            None => Position::new_empty(market_id, user.clone(), env.ledger().timestamp()),
+        };
+        let new_yes = position.yes_shares + yes_delta;
+        let new_no = position.no_shares + no_delta;
+        validation::validate_position_cap(&market, &position, new_yes, new_no)?;
+        if new_yes >= 0 && new_no >= 0 {
+            let prospective_locked =
+                positions::calculate_locked_collateral(new_yes, new_no, market_price)?;
+            let lock_increased = prospective_locked > position.locked_collateral;
+            if lock_increased && prospective_locked > position.total_deposited {
+                return Err(ContractError::InsufficientCollateral);
+            }
+        }
+
+        // 5. Apply the share deltas (persists the position and emits an event)
+        let mut result =
+            positions::update_position(&env, market_id, &user, yes_delta, no_delta, market_price)
+                .map_err(|e| match e {
+                positions::PositionError::ShareBalanceBelowZero => {
+                    ContractError::InvalidShareAmount
+                }
+                positions::PositionError::InvalidMarketPrice => ContractError::InvalidPrice,
+                positions::PositionError::ArithmeticOverflow => ContractError::ArithmeticOverflow,
+            })?;
+
+        // 5a. Charge the market's trading fee on shares bought. It comes out
+        //     of the user's free collateral and accrues to the market.
+        let bought = yes_delta
+            .max(0)
+            .checked_add(no_delta.max(0))
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        if market.trading_fee_bps > 0 && bought > 0 {
+            let fee = validation::calculate_fee(bought, i128::from(market.trading_fee_bps))?;
+            if fee > 0 {
+                let mut position = result.clone();
+                position.total_deposited = position
+                    .total_deposited
+                    .checked_sub(fee)
+                    .ok_or(ContractError::ArithmeticOverflow)?;
+                if position.total_deposited < position.locked_collateral {
+                    return Err(ContractError::InsufficientCollateral);
+                }
+                storage::set_position(&env, market_id, &user, &position)?;
+                let accrued = storage::get_accrued_fees(&env, market_id)
+                    .checked_add(fee)
+                    .ok_or(ContractError::ArithmeticOverflow)?;
+                storage::set_accrued_fees(&env, market_id, accrued);
+                result = position;
+            }
+        }
+
+        // 5b. Mint or burn outcome tokens for the updated position.
+        if let Some(outcome_token_address) = storage::get_outcome_token_contract(&env) {
+            let token_client = OutcomeTokenContractClient::new(&env, &outcome_token_address);
+            if yes_delta > 0 {
+                token_client.mint(&market_id, &user, &TokenKind::Yes, &yes_delta);
+            } else if yes_delta < 0 {
+                token_client.burn(&market_id, &user, &TokenKind::Yes, &(-yes_delta));
+            }
+
+            if no_delta > 0 {
+                token_client.mint(&market_id, &user, &TokenKind::No, &no_delta);
+            } else if no_delta < 0 {
+                token_client.burn(&market_id, &user, &TokenKind::No, &(-no_delta));
+            }
+        }
+
+        // 6. Persist the updated price so withdraw and other callers see it,
+        //    along with the market-wide share totals.
+        market.price_bps = market_price;
+        market.total_yes_shares = market
+            .total_yes_shares
+            .checked_add(yes_delta)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        market.total_no_shares = market
+            .total_no_shares
+            .checked_add(no_delta)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        storage::set_market(&env, market_id, &market)?;
+
+        check_invariants!(&env, market_id);
+        Ok(result)
+    }
+
+    /// Settle a user's position in a resolved market and pay out their winnings.
+    ///
+    /// Completes the deposit -> resolve -> settle -> receive-funds loop: it
+    /// calculates the payout for the resolved outcome, marks the position
+    /// settled, and transfers the payout in collateral (SAC) tokens from the
+    /// contract to the user. In a canceled market there is no outcome, and the
+    /// user's full deposit is refunded instead.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `user` - User settling their position (must authorize the call)
+    /// * `market_id` - Market identifier
+    ///
+    /// # Returns
+    /// The payout amount transferred to the user, in stroops.
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] - the market does not exist
+    /// - [`ContractError::NoPositionFound`] - the user has no position
+    /// - [`ContractError::MarketNotResolved`] - the market is neither resolved
+    ///   nor canceled
+    /// - [`ContractError::PositionAlreadySettled`] - already settled
+    ///
+    /// # Events
+    /// Emits `PositionSettled` with the payout amount.
+    pub fn settle_position(env: Env, user: Address, market_id: u32) -> Result<i128, ContractError> {
+        let payout = settlement::settle_position(&env, &user, market_id)?;
+        check_invariants!(&env, market_id);
+        Ok(payout)
+    }
+
+    /// Transfer the settlement payout credited to `user` in a market.
+    ///
+    /// [`settle_positions`] and [`batch_settle_positions`] only credit
+    /// payouts, so one recipient whose transfer fails cannot hold up a whole
+    /// batch; each user pulls their balance here. [`settle_position`] and
+    /// [`settle_all`] claim on the user's behalf.
+    ///
+    /// # Returns
+    /// The amount transferred, in stroops; 0 when nothing is claimable.
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] - the market does not exist
+    ///
+    /// # Events
+    /// Emits `Claimed` when a non-zero balance is paid out.
+    pub fn claim(env: Env, user: Address, market_id: u32) -> Result<i128, ContractError> {
+        let amount = settlement::claim(&env, &user, market_id)?;
+        check_invariants!(&env, market_id);
+        Ok(amount)
+    }
+
+    /// Return the settlement payout credited to `user` in a market and not
+    /// yet claimed.
+    pub fn get_claimable(env: Env, market_id: u32, user: Address) -> i128 {
+        storage::get_claimable(&env, market_id, &user)
+    }
+
+    /// Close a user's position in whatever way the market's state allows.
+    ///
+    /// - `Active`: sells all YES and NO shares back at the market's current
+    ///   price, unlocking their collateral for withdrawal (as
+    ///   [`update_position`]; rejected while paused or after `end_time`).
+    /// - `Resolved`: settles the position and pays out (as
+    ///   [`settle_position`]).
+    /// - `Canceled`: refunds the full deposit (as
+    ///   [`withdraw_canceled_collateral`]).
+    ///
+    /// # Returns
+    /// Collateral unlocked (`Active`), payout (`Resolved`) or refund
+    /// (`Canceled`), in stroops.
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::NoPositionFound`] – the user has no position
+    /// - Any error of the path taken for the market's state
+    ///
+    /// # Events
+    /// Emits the events of the path taken: `PositionUpdated`,
+    /// `PositionSettled`, or `PositionUpdated` + `CollateralWithdrawn`.
+    pub fn close_position(env: Env, user: Address, market_id: u32) -> Result<i128, ContractError> {
+        user.require_auth();
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        let position =
+            storage::get_position(&env, market_id, &user)?.ok_or(ContractError::NoPositionFound)?;
+
+        let released = match market.status {
+            MarketStatus::Active => {
+                validation::require_not_paused(&env)?;
+                let updated = Self::execute_position_update(
+                    env.clone(),
+                    user,
+                    market_id,
+                    -position.yes_shares,
+                    -position.no_shares,
+                    market.price_bps,
+                )?;
+                position.locked_collateral - updated.locked_collateral
+            }
+            MarketStatus::Resolved => settlement::execute_settle_position(&env, &user, market_id)?,
+            MarketStatus::Canceled => Self::execute_canceled_refund(env.clone(), user, market_id)?,
+            MarketStatus::PendingResolution | MarketStatus::Disputed => {
+                return Err(ContractError::MarketNotResolved)
+            }
+        };
+
+        check_invariants!(&env, market_id);
+        Ok(released)
+    }
+
+    /// Settle all of a user's resolved positions across markets in one call.
+    ///
+    /// Each resolved, unsettled position is settled and paid out exactly as
+    /// [`settle_position`] would; unresolved markets are skipped. At most
+    /// [`validation::MAX_PAGE_SIZE`] of the user's markets are examined per
+    /// call, so users in more markets may need to call again.
+    ///
+    /// # Returns
+    /// `(market_id, payout)` for every position settled by this call.
+    ///
+    /// # Events
+    /// Emits `PositionSettled` for each settled position.
+    pub fn settle_all(
+        env: Env,
+        user: Address,
+    ) -> Result<soroban_sdk::Vec<(u32, i128)>, ContractError> {
+        let settled = settlement::settle_all(&env, &user)?;
+        #[cfg(feature = "invariant-checks")]
+        for (market_id, _) in settled.iter() {
+            invariants::assert_invariants(&env, market_id);
+        }
+        Ok(settled)
+    }
+
+    /// Settle a batch of users' positions in a resolved market, returning
+    /// each user's payout.
+    ///
+    /// Intended for market operators settling many users at once. Each user
+    /// is settled as [`settle_position`] would, but the payout is only
+    /// credited; users collect it with [`claim`]. Already-settled or missing
+    /// positions yield 0 and are skipped rather than aborting the batch.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `market_id` - Market identifier (must be resolved)
+    /// * `users` - Addresses to settle (at most
+    ///   [`validation::MAX_SETTLE_BATCH_SIZE`])
+    ///
+    /// # Returns
+    /// The payout (in stroops) credited to each user, in the order of
+    /// `users`.
+    ///
+    /// # Errors
+    /// - [`ContractError::InvalidQuantity`] - `users` is longer than
+    ///   [`validation::MAX_SETTLE_BATCH_SIZE`]
+    /// - [`ContractError::MarketNotFound`] - the market does not exist
+    /// - [`ContractError::MarketNotResolved`] - the market is not resolved
+    ///
+    /// # Events
+    /// Emits `PositionSettled` for each successfully settled position.
+    pub fn settle_positions(
+        env: Env,
+        market_id: u32,
+        users: soroban_sdk::Vec<Address>,
+    ) -> Result<soroban_sdk::Vec<i128>, ContractError> {
+        let payouts = settlement::settle_positions(&env, market_id, users)?;
+        check_invariants!(&env, market_id);
+        Ok(payouts)
+    }
 
-        // 5. Zero out the position balances now that the collateral has left.
-        position.total_deposited = 0;
-        position.locked_collateral = 0;
-        storage::set_position(&env, market_id, &user, &position)?;
+    /// Settle multiple users' positions in a resolved market in one call.
+    ///
+    /// This is a batched variant of [`settle_position`] intended for operators
+    /// settling many users at once (e.g. a cron job after resolution). Each
+    /// user is settled independently and their payout credited for [`claim`];
+    /// already-settled or missing positions are silently skipped so a single
+    /// bad entry does not abort the whole batch.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `market_id` - Market identifier (must be resolved)
+    /// * `users` - Addresses to settle
+    ///
+    /// # Returns
+    /// Total collateral (in stroops) credited across all settled positions.
+    ///
+    /// # Errors
+    /// - [`ContractError::InvalidQuantity`] - `users` is longer than
+    ///   [`validation::MAX_SETTLE_BATCH_SIZE`]
+    /// - [`ContractError::MarketNotFound`] - the market does not exist
+    /// - [`ContractError::MarketNotResolved`] - the market is not resolved
+    ///
+    /// # Events
+    /// Emits `PositionSettled` for each successfully settled position.
+    pub fn batch_settle_positions(
+        env: Env,
+        market_id: u32,
+        users: soroban_sdk::Vec<Address>,
+    ) -> Result<i128, ContractError> {
+        let total_payout = settlement::batch_settle_positions(&env, market_id, users)?;
+        check_invariants!(&env, market_id);
+        Ok(total_payout)
+    }
+
+    /// Settle the next slice of a resolved market's participants, returning
+    /// where to resume.
+    ///
+    /// Lets an off-chain driver settle a market too large for one
+    /// transaction: call with `start_index = 0`, then with each returned
+    /// cursor until it is `None`. Payouts are credited for [`claim`];
+    /// already-settled or missing positions are skipped.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `market_id` - Market identifier (must be resolved)
+    /// * `start_index` - Position in the participant index to start from
+    /// * `count` - Participants to visit (at most
+    ///   [`validation::MAX_SETTLE_BATCH_SIZE`])
+    ///
+    /// # Returns
+    /// `(next_index, total_payout)`, with `next_index` `None` once every
+    /// participant has been visited.
+    ///
+    /// # Errors
+    /// - [`ContractError::InvalidQuantity`] - `count` is 0 or above
+    ///   [`validation::MAX_SETTLE_BATCH_SIZE`]
+    /// - [`ContractError::MarketNotFound`] - the market does not exist
+    /// - [`ContractError::MarketNotResolved`] - the market is not resolved
+    ///
+    /// # Events
+    /// Emits `PositionSettled` for each successfully settled position.
+    pub fn settle_participants(
+        env: Env,
+        market_id: u32,
+        start_index: u32,
+        count: u32,
+    ) -> Result<(Option<u32>, i128), ContractError> {
+        let result = settlement::settle_participants(&env, market_id, start_index, count)?;
+        check_invariants!(&env, market_id);
+        Ok(result)
+    }
+
+    /// Register the treasury contract address for protocol fee routing.
+    ///
+    /// Once set, any non-zero withdrawal fee computed during
+    /// [`withdraw_unused_collateral`] will be transferred to this address and
+    /// recorded via the treasury's `collect_fee` entry point.
+    ///
+    /// Only the stored admin may call this.
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin.
+    pub fn set_treasury_contract(
+        env: Env,
+        admin: Address,
+        treasury: Address,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+        storage::set_treasury(&env, &treasury);
+        events::emit_treasury_set(&env, &treasury);
+        Ok(())
+    }
+
+    /// Set the withdrawal fee rate in basis points (0–10_000).
+    ///
+    /// Only the stored admin may call this. A rate of 0 disables fees.
+    /// The rate must not exceed the configured fee cap (see `set_fee_cap`).
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] — `admin` is not the stored admin.
+    /// - [`ContractError::InvalidPrice`] — `fee_rate_bps` outside 0–10_000.
+    /// - [`ContractError::FeeCapExceeded`] — `fee_rate_bps` exceeds the fee cap.
+    pub fn set_fee_rate(
+        env: Env,
+        admin: Address,
+        fee_rate_bps: i128,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+        validation::validate_fee_rate_bps(fee_rate_bps)?;
+        let cap = storage::get_fee_cap_bps(&env);
+        if fee_rate_bps > cap {
+            return Err(ContractError::FeeCapExceeded);
+        }
+        storage::set_fee_rate_bps(&env, fee_rate_bps);
+        Ok(())
+    }
+
+    /// Set the fee, in basis points, taken from winning settlement payouts.
+    ///
+    /// Only the stored admin may call this. The fee accrues to the market
+    /// alongside its trading fees and is collected with `withdraw_fees`;
+    /// refunds from canceled markets are never charged. A fee of 0 disables
+    /// it.
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] — `admin` is not the stored admin.
+    /// - [`ContractError::FeeCapExceeded`] — `settlement_fee_bps` exceeds
+    ///   `MAX_SETTLEMENT_FEE_BPS`.
+    pub fn set_settlement_fee(
+        env: Env,
+        admin: Address,
+        settlement_fee_bps: u32,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+        validation::validate_settlement_fee_bps(settlement_fee_bps)?;
+        storage::set_settlement_fee_bps(&env, settlement_fee_bps);
+        Ok(())
+    }
+
+    /// Set how far past creation, in seconds, a new market's `end_time` may
+    /// lie (one year by default).
+    ///
+    /// Only the stored admin may call this. Existing markets are unaffected.
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] — `admin` is not the stored admin.
+    /// - [`ContractError::InvalidTimestamp`] — `seconds` is 0.
+    pub fn set_max_market_duration(
+        env: Env,
+        admin: Address,
+        seconds: u64,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+        if seconds == 0 {
+            return Err(ContractError::InvalidTimestamp);
+        }
+        storage::set_max_market_duration(&env, seconds);
+        Ok(())
+    }
+
+    /// Configure the multi-signer quorum for threshold-based resolution (#378).
+    ///
+    /// `signers` is the ordered set of oracle public keys. `quorum` is the
+    /// minimum number of valid signatures required by `resolve_market_threshold`.
+    /// Setting `quorum` to 0 or passing an empty `signers` list effectively
+    /// disables threshold resolution.
+    ///
+    /// Only the stored admin may call this.
+    pub fn set_threshold_signers(
+        env: Env,
+        admin: Address,
+        signers: soroban_sdk::Vec<BytesN<32>>,
+        quorum: u32,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+        storage::set_threshold_signers(&env, &signers);
+        storage::set_threshold_quorum(&env, quorum);
+        Ok(())
+    }
+
+    /// Return the current threshold signer set.
+    pub fn get_threshold_signers(env: Env) -> soroban_sdk::Vec<BytesN<32>> {
+        storage::get_threshold_signers(&env)
+    }
+
+    /// Return the current quorum requirement.
+    pub fn get_threshold_quorum(env: Env) -> u32 {
+        storage::get_threshold_quorum(&env)
+    }
+
+    /// Return the oracle keys and signature threshold that resolve a market.
+    ///
+    /// A market with its own oracle set resolves through
+    /// `resolve_market_multi`, and this returns that set. Otherwise, when a
+    /// threshold signer set with a non-zero quorum is configured, the market
+    /// resolves through `resolve_market_threshold` and this returns that set
+    /// and quorum. Failing both it returns the market's single oracle key
+    /// with a threshold of 1.
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    pub fn get_oracle_config(env: Env, market_id: u32) -> Result<OracleConfig, ContractError> {
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        if let Some(set) = storage::get_market_oracle_set(&env, market_id) {
+            return Ok(OracleConfig {
+                keys: set.keys,
+                threshold: set.threshold,
+                scheme: OracleScheme::MarketSet,
+            });
+        }
+        let signers = storage::get_threshold_signers(&env);
+        let quorum = storage::get_threshold_quorum(&env);
+        if quorum > 0 && !signers.is_empty() {
+            return Ok(OracleConfig {
+                keys: signers,
+                threshold: quorum,
+                scheme: OracleScheme::Threshold,
+            });
+        }
+        Ok(OracleConfig {
+            keys: soroban_sdk::vec![&env, market.oracle_pubkey],
+            threshold: 1,
+            scheme: OracleScheme::Single,
+        })
+    }
+
+    /// Resolve a market using a quorum of oracle signatures (#378).
+    ///
+    /// Callers provide one signature per registered signer (use 64 zero bytes
+    /// for signers whose signature is unavailable). The market resolves once
+    /// the valid-signature count reaches the stored quorum. A market holding
+    /// no collateral is canceled instead, as in [`resolve_market`].
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] — market does not exist.
+    /// - [`ContractError::MarketAlreadyResolved`] — already resolved.
+    /// - [`ContractError::MarketNotActive`] — the market was canceled or expired.
+    /// - [`ContractError::MarketNotEnded`] — the market's `end_time` has not passed.
+    /// - [`ContractError::InvalidOutcome`] — the market is scalar.
+    /// - [`ContractError::ResolutionWindowClosed`] — the resolution grace has passed.
+    /// - [`ContractError::ResolutionCommitted`] — a sealed resolution is
+    ///   awaiting its reveal.
+    /// - [`ContractError::ResolutionNotFinalized`] — a resolution contract is
+    ///   registered and has no finalized candidate for the market.
+    /// - [`ContractError::UnauthorizedOracle`] — no signers/quorum configured.
+    /// - [`ContractError::InvalidSignature`] — fewer than quorum valid sigs.
+    pub fn resolve_market_threshold(
+        env: Env,
+        resolver: Address,
+        market_id: u32,
+        outcome: bool,
+        signatures: soroban_sdk::Vec<BytesN<64>>,
+    ) -> Result<(), ContractError> {
+        resolver.require_auth();
+
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        validation::validate_resolvable(&market.status)?;
+        validation::validate_outcome(&market, outcome.into())?;
+        validation::require_market_ended(&env, &market)?;
+        validation::require_resolution_window_open(&env, &market)?;
+        validation::require_no_pending_commitment(&env, market_id)?;
+        Self::require_finalized_candidate(&env, market_id)?;
+
+        let signers = storage::get_threshold_signers(&env);
+        let quorum = storage::get_threshold_quorum(&env);
+
+        oracle::verify_threshold_signatures(
+            &env,
+            market_id,
+            market.created_at,
+            &market.source_hash,
+            outcome,
+            &signers,
+            &signatures,
+            quorum,
+        )?;
+        events::emit_oracle_signature_verified(&env, market_id, outcome, env.ledger().timestamp());
+
+        Self::record_resolution(&env, market_id, &mut market, &resolver, outcome)
+    }
+
+    /// Assign or clear a market's own oracle set for `resolve_market_multi`.
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::MarketNotActive`] – the market is no longer active
+    /// - [`ContractError::InvalidOracleSet`] – empty or oversized set, or a
+    ///   threshold outside `1..=keys.len()`
+    /// - [`ContractError::DuplicateOracleSigner`] – a key is listed twice
+    pub fn set_market_oracles(
+        env: Env,
+        admin: Address,
+        market_id: u32,
+        oracle_set: Option<OracleSet>,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        validation::require_not_paused(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+        if let Some(set) = &oracle_set {
+            validation::validate_oracle_set(set)?;
+        }
+
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        if market.status != MarketStatus::Active {
+            return Err(ContractError::MarketNotActive);
+        }
+        storage::set_market_oracle_set(&env, market_id, &oracle_set);
+        Ok(())
+    }
+
+    /// Resolve a market once enough of its own oracles sign the outcome.
+    ///
+    /// `signatures[i]` is `signers[i]`'s signature over the same message
+    /// [`resolve_market`] verifies. At least the set's threshold of distinct
+    /// listed signers must have signed `outcome`. A market holding no
+    /// collateral is canceled instead, as in [`resolve_market`].
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] — market does not exist.
+    /// - [`ContractError::MarketAlreadyResolved`] — resolved or pending resolution.
+    /// - [`ContractError::MarketNotActive`] — the market was canceled or expired.
+    /// - [`ContractError::MarketNotEnded`] — the market's `end_time` has not passed.
+    /// - [`ContractError::InvalidOutcome`] — the market is scalar.
+    /// - [`ContractError::ResolutionWindowClosed`] — the resolution grace has passed.
+    /// - [`ContractError::ResolutionCommitted`] — a sealed resolution is
+    ///   awaiting its reveal.
+    /// - [`ContractError::ResolutionNotFinalized`] — a resolution contract is
+    ///   registered and has no finalized candidate for the market.
+    /// - [`ContractError::UnauthorizedOracle`] — the market has no oracle set,
+    ///   or a signer is not in it.
+    /// - [`ContractError::DuplicateOracleSigner`] — a signer appears twice.
+    /// - [`ContractError::InvalidSignature`] — fewer than the threshold of
+    ///   valid signatures.
+    pub fn resolve_market_multi(
+        env: Env,
+        resolver: Address,
+        market_id: u32,
+        outcome: bool,
+        signatures: soroban_sdk::Vec<BytesN<64>>,
+        signers: soroban_sdk::Vec<BytesN<32>>,
+    ) -> Result<(), ContractError> {
+        resolver.require_auth();
 
-        // 6. Emit position_updated so indexers see the zeroed balances.
-        events::emit_position_updated(
+        let mut market =
+            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        validation::validate_resolvable(&market.status)?;
+        validation::validate_outcome(&market, outcome.into())?;
+        validation::require_market_ended(&env, &market)?;
+        validation::require_resolution_window_open(&env, &market)?;
+        validation::require_no_pending_commitment(&env, market_id)?;
+        Self::require_finalized_candidate(&env, market_id)?;
+        let oracle_set = storage::get_market_oracle_set(&env, market_id)
+            .ok_or(ContractError::UnauthorizedOracle)?;
+
+        oracle::verify_oracle_set_signatures(
             &env,
             market_id,
-            &user,
-            position.yes_shares,
-            position.no_shares,
-            position.locked_collateral,
+            market.created_at,
+            &market.source_hash,
+            outcome,
+            &oracle_set,
+            &signers,
+            &signatures,
+        )?;
+        events::emit_oracle_signature_verified(&env, market_id, outcome, env.ledger().timestamp());
+
+        Self::record_resolution(&env, market_id, &mut market, &resolver, outcome)
+    }
+
+    /// Mark a verified market resolved and emit `MarketResolved`, or cancel
+    /// it when it holds no collateral; shared by the quorum-based resolution
+    /// paths.
+    fn record_resolution(
+        env: &Env,
+        market_id: u32,
+        market: &mut Market,
+        resolver: &Address,
+        outcome: bool,
+    ) -> Result<(), ContractError> {
+        if Self::cancel_if_empty(env, market_id, market, resolver)? {
+            return Ok(());
+        }
+        market.status = MarketStatus::Resolved;
+        market.result = Some(outcome);
+        market.resolver = Some(resolver.clone());
+        let resolved_at = env.ledger().timestamp();
+        market.resolved_at = Some(resolved_at);
+        storage::set_market(env, market_id, market)?;
+        storage::remove_resolution_commitment(env, market_id);
+
+        events::emit_market_resolved(
+            env,
+            market_id,
+            &market.oracle_pubkey,
+            resolver,
+            outcome,
+            storage::get_market_collateral(env, market_id),
+            resolved_at,
         );
 
-        // 7. Reuse the collateral-withdrawn event so indexers track the refund.
-        events::emit_collateral_withdrawn(&env, &user, market_id, refund, position.total_deposited);
+        check_invariants!(env, market_id);
+        Ok(())
+    }
 
-        Ok(refund)
+    /// Transfer a market's accrued trading and settlement fees to the admin.
+    ///
+    /// Fees are charged on shares bought at the market's `trading_fee_bps`
+    /// and on winning payouts at the settlement fee, and held by the contract
+    /// until withdrawn. The counter is zeroed.
+    ///
+    /// # Returns
+    /// The amount transferred in stroops; zero when nothing has accrued.
+    ///
+    /// # Errors
+    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    ///
+    /// # Events
+    /// Emits `FeesWithdrawn` when a non-zero amount is transferred.
+    pub fn withdraw_fees(env: Env, admin: Address, market_id: u32) -> Result<i128, ContractError> {
+        validation::require_initialized(&env)?;
+        validation::require_not_paused(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        let amount = storage::get_accrued_fees(&env, market_id);
+        if amount == 0 {
+            return Ok(0);
+        }
+        storage::set_accrued_fees(&env, market_id, 0);
+        soroban_sdk::token::Client::new(&env, &market.collateral_token).transfer(
+            &env.current_contract_address(),
+            &admin,
+            &amount,
+        );
+        events::emit_fees_withdrawn(&env, market_id, &admin, amount);
+
+        check_invariants!(&env, market_id);
+        Ok(amount)
     }
 
-    /// Buy or sell YES/NO shares by applying signed deltas to a user's position.
+    /// Return the trading and settlement fees accrued in a market and not yet
+    /// withdrawn.
+    pub fn get_accrued_fees(env: Env, market_id: u32) -> i128 {
+        storage::get_accrued_fees(&env, market_id)
+    }
+
+    /// Return the settlement fee in basis points; 0 until
+    /// [`set_settlement_fee`] is called.
+    pub fn get_settlement_fee(env: Env) -> u32 {
+        storage::get_settlement_fee_bps(&env)
+    }
+
+    /// Return the current withdrawal fee rate in basis points.
+    ///
+    /// Returns 0 if no fee rate has been configured.
+    pub fn get_fee_rate(env: Env) -> i128 {
+        storage::get_fee_rate_bps(&env)
+    }
+
+    /// Register the deployed outcome-token contract address used by this
+    /// market contract to mint and burn outcome tokens for position updates.
     ///
-    /// **This is the primary trading entry point for the Vatix prediction market protocol.**
+    /// Only the stored admin may call this.
+    pub fn set_outcome_token_contract(
+        env: Env,
+        admin: Address,
+        outcome_token_contract: Address,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+        storage::set_outcome_token_contract(&env, &outcome_token_contract);
+        Ok(())
+    }
+
+    /// Return the registered outcome-token contract address, if any.
+    pub fn get_outcome_token_contract(env: Env) -> Option<Address> {
+        storage::get_outcome_token_contract(&env)
+    }
+
+    /// Register the resolution contract that gates `resolve_market`.
     ///
-    /// This function provides the on-chain interface for share trading, implementing
-    /// the core logic from [`positions::update_position`] with comprehensive market-level
-    /// and authorization validations. It supports both buying (positive delta) and 
-    /// selling (negative delta) of YES and NO shares in a single atomic operation.
+    /// When set, `resolve_market` will call into this contract to verify that
+    /// a finalized candidate exists for the market before accepting a resolution.
+    /// Pass `None` (by omitting the storage entry) to remove the gate.
     ///
-    /// # Trading Flow
-    /// 1. User deposits collateral via [`deposit_collateral`]
-    /// 2. User calls `update_position` to buy/sell shares
-    /// 3. Contract validates market state, user authorization, and collateral requirements
-    /// 4. Position is updated and locked collateral is recalculated
-    /// 5. Outcome tokens are minted/burned (if outcome-token contract is registered)
-    /// 6. Events are emitted for off-chain indexing
+    /// Only the stored admin may call this.
+    pub fn set_resolution_contract(
+        env: Env,
+        admin: Address,
+        resolution_contract: Address,
+    ) -> Result<(), ContractError> {
+        validation::require_initialized(&env)?;
+        admin.require_auth();
+        let stored_admin = storage::get_admin(&env)?;
+        if admin != stored_admin {
+            return Err(ContractError::NotAdmin);
+        }
+        storage::set_resolution_contract(&env, &resolution_contract);
+        Ok(())
+    }
+
+    /// Return the registered resolution contract address, if any.
+    pub fn get_resolution_contract(env: Env) -> Option<Address> {
+        storage::get_resolution_contract(&env)
+    }
+
+    // ========== Trading Convenience Functions ==========
+
+    /// Buy YES or NO shares in a market at the price set by its curve.
+    ///
+    /// The caller does not supply a price: the trade fills at the YES price
+    /// implied by the market's constant-product reserves (see
+    /// [`pricing::current_price`]) across the move its cost causes toward the
+    /// side bought.
     ///
     /// # Arguments
     /// * `env` - Contract environment
-    /// * `user` - User whose position is updated (must authorize the call)
+    /// * `user` - User buying shares (must authorize the call)
     /// * `market_id` - Market identifier
-    /// * `yes_delta` - Change in YES shares (positive to buy, negative to sell)
-    /// * `no_delta` - Change in NO shares (positive to buy, negative to sell)
-    /// * `market_price` - Current market price in basis points (0–10_000) used
-    ///   to calculate locked collateral for the resulting net position
+    /// * `buy_yes` - `true` to buy YES shares, `false` to buy NO shares
+    /// * `quantity` - Number of shares to buy (must be positive)
     ///
     /// # Returns
-    /// The updated [`Position`] structure containing the new share balances,
-    /// locked collateral, and total deposited amount.
+    /// The updated [`Position`] after the purchase.
     ///
     /// # Errors
-    /// - [`ContractError::MarketNotFound`] – market does not exist
-    /// - [`ContractError::MarketNotActive`] – market is resolved or canceled
-    /// - [`ContractError::MarketExpired`] – current time exceeds market `end_time`
-    /// - [`ContractError::InvalidPrice`] – `market_price` is outside valid range (0–10_000)
-    /// - [`ContractError::InsufficientCollateral`] – deposited collateral insufficient
-    ///   to cover the increased locked amount
-    /// - [`ContractError::InvalidShareAmount`] – deltas would result in negative share balance
+    /// Same as [`update_position`], plus:
+    /// - [`ContractError::InvalidShareAmount`] – quantity is zero or negative
+    pub fn buy_shares(
+        env: Env,
+        user: Address,
+        market_id: u32,
+        buy_yes: bool,
+        quantity: i128,
+    ) -> Result<Position, ContractError> {
+        validation::require_not_paused(&env)?;
+        validation::validate_share_quantity(quantity)?;
+        user.require_auth();
+
+        let market_price = Self::trade_on_curve(&env, market_id, buy_yes, buy_yes, quantity)?;
+        let (yes_delta, no_delta) = if buy_yes {
+            (quantity, 0)
+        } else {
+            (0, quantity)
+        };
+        Self::execute_position_update(env, user, market_id, yes_delta, no_delta, market_price)
+    }
+
+    /// Deposit collateral and buy YES or NO shares in a single call.
+    ///
+    /// Combines [`deposit_collateral`] with [`buy_shares`] under one user
+    /// authorization so a first-time trader needs only one transaction. Both
+    /// steps succeed together or the whole call reverts, including the
+    /// collateral transfer.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `user` - User depositing and buying (must authorize the call)
+    /// * `market_id` - Market identifier
+    /// * `amount` - Collateral to deposit in stroops
+    /// * `buy_yes` - `true` to buy YES shares, `false` to buy NO shares
+    /// * `quantity` - Number of shares to buy (must be positive)
+    /// * `max_price` - Worst acceptable per-share price for the chosen side in
+    ///   basis points, checked against the price the curve fills the trade at
+    ///
+    /// # Returns
+    /// The updated [`Position`] after the deposit and purchase.
+    ///
+    /// # Errors
+    /// Same as [`deposit_collateral`] and [`buy_shares`], plus:
+    /// - [`ContractError::SlippageExceeded`] – the filled side price exceeds
+    ///   `max_price`
     ///
     /// # Events
-    /// - `PositionUpdated` – emitted on successful position change with new balances
-    /// - `TradeExecuted` – emitted for each non-zero delta (YES and/or NO)
-    /// - `PositionLimitExceeded` – emitted when delta would drive share balance negative
+    /// Emits `CollateralDeposited` followed by the trade events of
+    /// [`update_position`].
+    pub fn deposit_and_buy(
+        env: Env,
+        user: Address,
+        market_id: u32,
+        amount: i128,
+        buy_yes: bool,
+        quantity: i128,
+        max_price: i128,
+    ) -> Result<Position, ContractError> {
+        validation::require_not_paused(&env)?;
+        validation::validate_share_quantity(quantity)?;
+
+        // One authorization covers both the deposit and the purchase.
+        user.require_auth();
+        deposit::execute_deposit(env.clone(), user.clone(), market_id, amount)?;
+
+        // Slippage guard on the price the curve actually fills the chosen
+        // side at.
+        let market_price = Self::trade_on_curve(&env, market_id, buy_yes, buy_yes, quantity)?;
+        let side_price = if buy_yes {
+            market_price
+        } else {
+            positions::BASIS_POINTS - market_price
+        };
+        if side_price > max_price {
+            return Err(ContractError::SlippageExceeded);
+        }
+
+        let (yes_delta, no_delta) = if buy_yes {
+            (quantity, 0)
+        } else {
+            (0, quantity)
+        };
+        Self::execute_position_update(env, user, market_id, yes_delta, no_delta, market_price)
+    }
+
+    /// Sell YES or NO shares in a market at the price set by its curve.
     ///
-    /// # Examples
-    /// ```ignore
-    /// // Buy 100 YES shares at 60% market price
-    /// let position = client.update_position(
-    ///     &user,
-    ///     &market_id,
-    ///     &(100 * STROOPS_PER_USDC),  // yes_delta: buy 100
-    ///     &0i128,                       // no_delta: no change
-    ///     &6_000i128,                   // market_price: 60%
-    /// );
-    /// // Result: 60 USDC locked (100 shares * 60% price)
+    /// The counterpart of [`buy_shares`]: the sale fills at the curve's YES
+    /// price across the move its proceeds cause away from the side sold.
+    /// Locked collateral is recomputed for the smaller position, so collateral
+    /// freed by the sale becomes withdrawable.
     ///
-    /// // Sell 50 YES shares
-    /// let position = client.update_position(
-    ///     &user,
-    ///     &market_id,
-    ///     &(-50 * STROOPS_PER_USDC),  // yes_delta: sell 50
-    ///     &0i128,                       // no_delta: no change
-    ///     &6_000i128,                   // market_price: 60%
-    /// );
-    /// ```
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `user` - User selling shares (must authorize the call)
+    /// * `market_id` - Market identifier
+    /// * `sell_yes` - `true` to sell YES shares, `false` to sell NO shares
+    /// * `quantity` - Number of shares to sell (must be positive)
     ///
-    /// # Security
-    /// - Requires user authorization via `user.require_auth()`
-    /// - Validates market is Active and not expired
-    /// - Enforces collateral requirements before state changes
-    /// - Prevents negative share balances
-    /// - All state changes are atomic (succeed or revert together)
-    pub fn update_position(
+    /// # Returns
+    /// The updated [`Position`] after the sale.
+    ///
+    /// # Errors
+    /// Same as [`update_position`]; a non-positive quantity, or selling more
+    /// shares than held, returns [`ContractError::InvalidShareAmount`].
+    pub fn sell_shares(
         env: Env,
         user: Address,
         market_id: u32,
-        yes_delta: i128,
-        no_delta: i128,
-        market_price: i128,
+        sell_yes: bool,
+        quantity: i128,
     ) -> Result<Position, ContractError> {
         validation::require_not_paused(&env)?;
-        // 1. Authorization
+        validation::validate_share_quantity(quantity)?;
+        user.require_auth();
+
+        let market_price = Self::trade_on_curve(&env, market_id, sell_yes, !sell_yes, quantity)?;
+        let (yes_delta, no_delta) = if sell_yes {
+            (-quantity, 0)
+        } else {
+            (0, -quantity)
+        };
+        Self::execute_position_update(env, user, market_id, yes_delta, no_delta, market_price)
+    }
+
+    /// Deposit `quantity` of collateral and credit `quantity` YES and
+    /// `quantity` NO shares in one call.
+    ///
+    /// The counterpart of [`redeem_complete_set`]: a YES/NO pair is backed by
+    /// exactly one unit of collateral, so minting a set adds no net exposure
+    /// and leaves the position's lock unchanged. Liquidity providers mint a
+    /// set and then sell the leg they do not want. The market's trading fee
+    /// applies to the shares credited, as for any purchase.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `user` - User minting the set (must authorize the call)
+    /// * `market_id` - Market identifier
+    /// * `quantity` - Number of complete sets to mint (must be positive)
+    ///
+    /// # Returns
+    /// The updated [`Position`].
+    ///
+    /// # Errors
+    /// Same as [`deposit_collateral`] and [`update_position`], plus:
+    /// - [`ContractError::InvalidShareAmount`] – `quantity` is not positive
+    ///
+    /// # Events
+    /// Emits `CollateralDeposited` followed by the trade events of
+    /// [`update_position`].
+    pub fn mint_complete_set(
+        env: Env,
+        user: Address,
+        market_id: u32,
+        quantity: i128,
+    ) -> Result<Position, ContractError> {
+        validation::require_not_paused(&env)?;
+        validation::validate_share_quantity(quantity)?;
+
+        user.require_auth();
+        deposit::execute_deposit(env.clone(), user.clone(), market_id, quantity)?;
+
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        Self::execute_position_update(env, user, market_id, quantity, quantity, market.price_bps)
+    }
+
+    /// Burn `quantity` YES and `quantity` NO shares together and pay their
+    /// face value back in collateral before the market resolves.
+    ///
+    /// A YES/NO pair is worth exactly one unit of collateral whatever the
+    /// outcome, so the pair is redeemed at face value rather than through the
+    /// curve. The shares are burned with negative deltas on both sides via
+    /// [`update_position`], which leaves the net position and its lock
+    /// unchanged. The payout comes out of the user's `total_deposited`, like
+    /// a withdrawal, so the market stays fully collateralized.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `user` - Holder of the shares (must authorize the call)
+    /// * `market_id` - Market identifier
+    /// * `quantity` - Number of complete sets to redeem (must be positive)
+    ///
+    /// # Errors
+    /// Same as [`update_position`], plus:
+    /// - [`ContractError::InvalidShareAmount`] – `quantity` is not positive,
+    ///   or the user holds fewer than `quantity` shares on either side
+    /// - [`ContractError::InsufficientCollateral`] – the user's unlocked
+    ///   deposit does not cover the payout
+    /// - [`ContractError::WithdrawCooldownActive`] – the market's withdraw
+    ///   cooldown since the user's last deposit has not passed
+    ///
+    /// # Events
+    /// Emits `PositionUpdated`, a `TradeExecuted` per side, and
+    /// `CollateralWithdrawn` for the payout.
+    pub fn redeem_complete_set(
+        env: Env,
+        user: Address,
+        market_id: u32,
+        quantity: i128,
+    ) -> Result<(), ContractError> {
+        validation::require_not_paused(&env)?;
         user.require_auth();
+        validation::validate_share_quantity(quantity)?;
 
-        // 2. Validate market state: must exist, be Active, and not be expired
-        let mut market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
-        if market.status != MarketStatus::Active {
-            return Err(ContractError::MarketNotActive);
-        }
-        if env.ledger().timestamp() > market.end_time {
-            return Err(ContractError::MarketExpired);
-        }
-
-        // 3. Validate the market price up front for a clear ContractError
-        validation::validate_market_price(market_price)?;
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        withdraw::require_cooldown_elapsed(&env, &market, market_id, &user)?;
+        let mut position = Self::execute_position_update(
+            env.clone(),
+            user.clone(),
+            market_id,
+            -quantity,
+            -quantity,
+            market.price_bps,
+        )?;
 
-        // 4. Enforce that deposited collateral covers any increase in the lock.
-        //    Negative-share deltas are left for positions::update_position to
-        //    reject (it also emits a PositionLimitExceeded event).
-        let position = storage::get_position(&env, market_id, &user)?
-            .unwrap_or_else(|| Position::new_empty(market_id, user.clone()));
-        let new_yes = position.yes_shares + yes_delta;
-        let new_no = position.no_shares + no_delta;
-        if new_yes >= 0 && new_no >= 0 {
-            let prospective_locked =
-                positions::calculate_locked_collateral(new_yes, new_no, market_price);
-            let lock_increased = prospective_locked > position.locked_collateral;
-            if lock_increased && prospective_locked > position.total_deposited {
-                return Err(ContractError::InsufficientCollateral);
-            }
+        position.total_deposited = position
+            .total_deposited
+            .checked_sub(quantity)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        if position.total_deposited < position.locked_collateral {
+            return Err(ContractError::InsufficientCollateral);
         }
+        storage::set_position(&env, market_id, &user, &position)?;
 
-        // 5. Apply the share deltas (persists the position and emits an event)
-        let result =
-            positions::update_position(&env, market_id, &user, yes_delta, no_delta, market_price)
-                .map_err(|e| match e {
-                    positions::PositionError::ShareBalanceBelowZero => {
-                        ContractError::InvalidShareAmount
-                    }
-                    positions::PositionError::InvalidMarketPrice => ContractError::InvalidPrice,
-                })?;
+        soroban_sdk::token::Client::new(&env, &market.collateral_token).transfer(
+            &env.current_contract_address(),
+            &user,
+            &quantity,
+        );
+        events::emit_collateral_withdrawn(
+            &env,
+            &user,
+            market_id,
+            quantity,
+            position.total_deposited,
+        );
 
-        // 5a. Mint or burn outcome tokens for the updated position.
-        if let Some(outcome_token_address) = storage::get_outcome_token_contract(&env) {
-            let token_client = OutcomeTokenContractClient::new(&env, &outcome_token_address);
-            if yes_delta > 0 {
-                token_client.mint(&market_id, &user, &TokenKind::Yes, &yes_delta);
-            } else if yes_delta < 0 {
-                token_client.burn(&market_id, &user, &TokenKind::Yes, &(-yes_delta));
-            }
+        check_invariants!(&env, market_id);
+        Ok(())
+    }
 
-            if no_delta > 0 {
-                token_client.mint(&market_id, &user, &TokenKind::No, &no_delta);
-            } else if no_delta < 0 {
-                token_client.burn(&market_id, &user, &TokenKind::No, &(-no_delta));
-            }
-        }
+    // ========== View Functions ==========
 
-        // 6. Persist the updated price so withdraw and other callers see it
-        market.price_bps = market_price;
-        storage::set_market(&env, market_id, &market)?;
+    /// Get a market's full stored state.
+    ///
+    /// A read-only query with no authorization, so frontends can fetch it
+    /// through RPC simulation.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `market_id` - Market identifier
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    pub fn get_market(env: Env, market_id: u32) -> Result<Market, ContractError> {
+        storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)
+    }
 
-        Ok(result)
+    /// Get a market's aggregate collateral, share totals and outcome.
+    ///
+    /// Every field is read from the market record or a counter maintained as
+    /// positions change, so no positions are scanned.
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    pub fn get_market_stats(env: Env, market_id: u32) -> Result<MarketStats, ContractError> {
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        Ok(MarketStats {
+            total_collateral: storage::get_market_collateral(&env, market_id),
+            total_yes_shares: market.total_yes_shares,
+            total_no_shares: market.total_no_shares,
+            status: market.status,
+            result: market.result,
+        })
     }
 
-    /// Settle a user's position in a resolved market and pay out their winnings.
+    /// Get a user's current position in a market.
     ///
-    /// Completes the deposit -> resolve -> settle -> receive-funds loop: it
-    /// calculates the payout for the resolved outcome, marks the position
-    /// settled, and transfers the payout in collateral (SAC) tokens from the
-    /// contract to the user.
+    /// Returns position details including share balances, locked collateral,
+    /// settlement status, and when the position was created and last
+    /// updated. This is a read-only query function with no authorization.
     ///
     /// # Arguments
     /// * `env` - Contract environment
-    /// * `user` - User settling their position (must authorize the call)
     /// * `market_id` - Market identifier
+    /// * `user` - User address to query
+    ///
+    /// # Errors
+    /// - [`ContractError::NoPositionFound`] – the user has no position in the market
+    ///
+    /// # Example
+    /// ```ignore
+    /// let position = client.get_position(&market_id, &user);
+    /// println!("YES shares: {}", position.yes_shares);
+    /// println!("Locked collateral: {}", position.locked_collateral);
+    /// ```
+    pub fn get_position(
+        env: Env,
+        market_id: u32,
+        user: Address,
+    ) -> Result<Position, ContractError> {
+        storage::get_position(&env, market_id, &user)?.ok_or(ContractError::NoPositionFound)
+    }
+
+    /// Return what `user` would receive by settling now.
     ///
     /// # Returns
-    /// The payout amount transferred to the user, in stroops.
+    /// `Some(payout)` once the market is resolved (a full refund of deposits
+    /// when it resolved without an outcome), `None` while it is unresolved so
+    /// UIs can show the payout as pending.
     ///
     /// # Errors
-    /// - [`ContractError::MarketNotFound`] - the market does not exist
-    /// - [`ContractError::NoPositionFound`] - the user has no position
-    /// - [`ContractError::MarketNotResolved`] - the market is not resolved
-    /// - [`ContractError::PositionAlreadySettled`] - already settled
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::NoPositionFound`] – the user has no position
+    pub fn get_potential_payout(
+        env: Env,
+        market_id: u32,
+        user: Address,
+    ) -> Result<Option<i128>, ContractError> {
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        let position =
+            storage::get_position(&env, market_id, &user)?.ok_or(ContractError::NoPositionFound)?;
+        Ok(settlement::calculate_potential_payout(&position, &market))
+    }
+
+    /// Summarize a resolved market's settlement from its running share
+    /// totals.
     ///
-    /// # Events
-    /// Emits `PositionSettled` with the payout amount.
-    pub fn settle_position(env: Env, user: Address, market_id: u32) -> Result<i128, ContractError> {
-        settlement::settle_position(&env, &user, market_id)
+    /// # Returns
+    /// `(winning_shares, losing_shares, total_payout)`, where winning shares
+    /// each pay one unit of collateral; see
+    /// [`settlement::calculate_market_settlement_stats`].
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    /// - [`ContractError::MarketNotResolved`] – the market is not resolved
+    /// - [`ContractError::InvalidOutcome`] – the market resolved without a
+    ///   winning side: `Invalid`, or a scalar market
+    pub fn get_settlement_summary(
+        env: Env,
+        market_id: u32,
+    ) -> Result<(i128, i128, i128), ContractError> {
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        if market.status != MarketStatus::Resolved {
+            return Err(ContractError::MarketNotResolved);
+        }
+        let outcome = market.result.ok_or(ContractError::InvalidOutcome)?;
+        Ok(settlement::calculate_market_settlement_stats(
+            market.total_yes_shares,
+            market.total_no_shares,
+            outcome,
+        ))
     }
 
-    /// Settle multiple users' positions in a resolved market in one call.
+    /// Preview what `user` would receive under each possible outcome.
     ///
-    /// This is a batched variant of [`settle_position`] intended for operators
-    /// settling many users at once (e.g. a cron job after resolution). Each
-    /// user is settled independently; already-settled or missing positions are
-    /// silently skipped so a single bad entry does not abort the whole batch.
+    /// Computed from the user's current shares, whether or not the market has
+    /// resolved, so traders can see both sides of a position before
+    /// resolution.
     ///
-    /// # Arguments
-    /// * `env` - Contract environment
-    /// * `market_id` - Market identifier (must be resolved)
-    /// * `users` - Addresses to settle
+    /// # Returns
+    /// `(payout_if_yes, payout_if_no)` in stroops. `(0, 0)` when the user has
+    /// no position or it has already been settled.
+    pub fn preview_payout(env: Env, market_id: u32, user: Address) -> (i128, i128) {
+        match storage::get_position(&env, market_id, &user) {
+            Ok(Some(position)) if !position.is_settled => (
+                settlement::calculate_payout(&position, true),
+                settlement::calculate_payout(&position, false),
+            ),
+            _ => (0, 0),
+        }
+    }
+
+    /// Mark `user`'s position in a market to market at `market_price`.
+    ///
+    /// Values both legs at the given YES price (see
+    /// [`positions::position_value`]), unlike the locked collateral, which
+    /// nets them. Returns 0 when the user has no position or it has already
+    /// been settled.
+    pub fn get_position_value(env: Env, market_id: u32, user: Address, market_price: i128) -> i128 {
+        match storage::get_position(&env, market_id, &user) {
+            Ok(Some(position)) if !position.is_settled => {
+                positions::position_value(position.yes_shares, position.no_shares, market_price)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Return the minimum info a client needs to decide whether to offer a claim.
     ///
     /// # Returns
-    /// Total collateral (in stroops) transferred across all settled positions.
+    /// `(outcome, already_settled)` — the resolved outcome and whether `user`'s
+    /// position has been settled. `(None, false)` when the market is missing
+    /// or not yet resolved.
+    pub fn settlement_status(env: Env, market_id: u32, user: Address) -> (Option<bool>, bool) {
+        let outcome = match storage::get_market(&env, market_id) {
+            Ok(Some(market)) if market.status == MarketStatus::Resolved => market.result,
+            _ => return (None, false),
+        };
+        let settled = storage::get_position(&env, market_id, &user)
+            .ok()
+            .flatten()
+            .is_some_and(|position| position.is_settled);
+        (outcome, settled)
+    }
+
+    /// Return a market's final outcome as a single unambiguous value.
+    ///
+    /// Derived from the market's `status`, `result` and `settled_value`:
+    /// `Yes`/`No` once a binary market is `Resolved`, `Scalar` once a scalar
+    /// market is, `Invalid` for one resolved with no winner, and `Canceled`
+    /// for a canceled market. Anything else, including `PendingResolution` and
+    /// `Disputed`, is `Unresolved`.
     ///
     /// # Errors
-    /// - [`ContractError::MarketNotFound`] - the market does not exist
-    /// - [`ContractError::MarketNotResolved`] - the market is not resolved
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    pub fn get_resolution(env: Env, market_id: u32) -> Result<Resolution, ContractError> {
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        Ok(match (market.status, market.result, market.settled_value) {
+            (MarketStatus::Canceled, ..) => Resolution::Canceled,
+            (MarketStatus::Resolved, Some(true), _) => Resolution::Yes,
+            (MarketStatus::Resolved, Some(false), _) => Resolution::No,
+            (MarketStatus::Resolved, None, Some(value)) => Resolution::Scalar(value),
+            (MarketStatus::Resolved, None, None) => Resolution::Invalid,
+            _ => Resolution::Unresolved,
+        })
+    }
+
+    /// Return the resolution source hash a market's creator committed at creation.
     ///
-    /// # Events
-    /// Emits `PositionSettled` for each successfully settled position.
-    pub fn batch_settle_positions(
-        env: Env,
-        market_id: u32,
-        users: soroban_sdk::Vec<Address>,
-    ) -> Result<i128, ContractError> {
-        settlement::batch_settle_positions(&env, market_id, users)
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    pub fn get_source_hash(env: Env, market_id: u32) -> Result<BytesN<32>, ContractError> {
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        Ok(market.source_hash)
     }
 
-    /// Register the treasury contract address for protocol fee routing.
+    /// Return the ledger timestamp at which a market was created.
     ///
-    /// Once set, any non-zero withdrawal fee computed during
-    /// [`withdraw_unused_collateral`] will be transferred to this address and
-    /// recorded via the treasury's `collect_fee` entry point.
+    /// `None` if the market does not exist.
+    pub fn get_created_at(env: Env, market_id: u32) -> Option<u64> {
+        storage::get_market(&env, market_id)
+            .ok()
+            .flatten()
+            .map(|market| market.created_at)
+    }
+
+    /// Return seconds elapsed since a market was created (saturating).
     ///
-    /// Only the stored admin may call this.
+    /// Returns 0 if the market does not exist.
+    pub fn market_age(env: Env, market_id: u32) -> u64 {
+        Self::get_created_at(env.clone(), market_id).map_or(0, |created_at| {
+            env.ledger().timestamp().saturating_sub(created_at)
+        })
+    }
+
+    /// Return the exact bytes hashed into the oracle message for `outcome`.
+    ///
+    /// Layout: `market_id_be || created_at_be || source_hash || outcome_byte`. Lets oracle
+    /// operators and auditors check their encoding byte-for-byte before
+    /// hashing; `keccak256` of this is [`preview_resolution_message`].
     ///
     /// # Errors
-    /// - [`ContractError::NotAdmin`] – `admin` is not the stored admin.
-    pub fn set_treasury_contract(
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    pub fn resolution_preimage(
         env: Env,
-        admin: Address,
-        treasury: Address,
-    ) -> Result<(), ContractError> {
-        validation::require_initialized(&env)?;
-        admin.require_auth();
-        let stored_admin = storage::get_admin(&env)?;
-        if admin != stored_admin {
-            return Err(ContractError::NotAdmin);
-        }
-        storage::set_treasury(&env, &Some(treasury.clone()));
-        events::emit_treasury_set(&env, &treasury);
-        Ok(())
+        market_id: u32,
+        outcome: Outcome,
+    ) -> Result<soroban_sdk::Bytes, ContractError> {
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        Ok(oracle::construct_oracle_preimage(
+            &env,
+            market_id,
+            market.created_at,
+            &market.source_hash,
+            outcome,
+        ))
     }
 
-    /// Set the withdrawal fee rate in basis points (0–10_000).
-    ///
-    /// Only the stored admin may call this. A rate of 0 disables fees.
-    /// The rate must not exceed the configured fee cap (see `set_fee_cap`).
+    /// Return the message hash the oracle must sign to resolve to `outcome`.
     ///
     /// # Errors
-    /// - [`ContractError::NotAdmin`] — `admin` is not the stored admin.
-    /// - [`ContractError::InvalidPrice`] — `fee_rate_bps` outside 0–10_000.
-    /// - [`ContractError::FeeCapExceeded`] — `fee_rate_bps` exceeds the fee cap.
-    pub fn set_fee_rate(
+    /// - [`ContractError::MarketNotFound`] – the market does not exist
+    pub fn preview_resolution_message(
         env: Env,
-        admin: Address,
-        fee_rate_bps: i128,
-    ) -> Result<(), ContractError> {
-        validation::require_initialized(&env)?;
-        admin.require_auth();
-        let stored_admin = storage::get_admin(&env)?;
-        if admin != stored_admin {
-            return Err(ContractError::NotAdmin);
-        }
-        validation::validate_fee_rate_bps(fee_rate_bps)?;
-        let cap = storage::get_fee_cap_bps(&env);
-        if fee_rate_bps > cap {
-            return Err(ContractError::FeeCapExceeded);
-        }
-        storage::set_fee_rate_bps(&env, fee_rate_bps);
-        Ok(())
+        market_id: u32,
+        outcome: Outcome,
+    ) -> Result<BytesN<32>, ContractError> {
+        let market = storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        Ok(oracle::construct_oracle_message(
+            &env,
+            market_id,
+            market.created_at,
+            &market.source_hash,
+            outcome,
+        ))
     }
 
-    /// Configure the multi-signer quorum for threshold-based resolution (#378).
+    /// Return the current fee cap in basis points (defaults to 10_000 when unset).
+    pub fn get_fee_cap(env: Env) -> i128 {
+        storage::get_fee_cap_bps(&env)
+    }
+
+    /// Return the longest market duration in seconds (defaults to one year
+    /// when unset).
+    pub fn get_max_market_duration(env: Env) -> u64 {
+        storage::get_max_market_duration(&env)
+    }
+
+    /// Return the ID of the most recently created market whose question is
+    /// exactly `question`.
     ///
-    /// `signers` is the ordered set of oracle public keys. `quorum` is the
-    /// minimum number of valid signatures required by `resolve_market_threshold`.
-    /// Setting `quorum` to 0 or passing an empty `signers` list effectively
-    /// disables threshold resolution.
+    /// Markets that have since resolved or been canceled are still found;
+    /// when a question is reused, the newest market wins.
+    pub fn find_market_by_question(env: Env, question: String) -> Option<u32> {
+        storage::get_question_market(&env, &question)
+    }
+
+    /// Return the category assigned to a market, if any.
+    pub fn get_market_category(env: Env, market_id: u32) -> Option<Symbol> {
+        storage::get_market_category(&env, market_id)
+    }
+
+    /// Return a paginated slice of market IDs in `category`.
     ///
-    /// Only the stored admin may call this.
-    pub fn set_threshold_signers(
+    /// # Arguments
+    /// * `category` - Category to list
+    /// * `start` - Zero-based index into the category's market list
+    /// * `limit` - Maximum number of IDs to return (capped at
+    ///   [`validation::MAX_PAGE_SIZE`])
+    ///
+    /// # Returns
+    /// Up to `limit` market IDs in the order they were categorized. Empty
+    /// for an unknown category or a `start` past the end.
+    pub fn list_markets_by_category(
         env: Env,
-        admin: Address,
-        signers: soroban_sdk::Vec<BytesN<32>>,
-        quorum: u32,
-    ) -> Result<(), ContractError> {
-        validation::require_initialized(&env)?;
-        admin.require_auth();
-        let stored_admin = storage::get_admin(&env)?;
-        if admin != stored_admin {
-            return Err(ContractError::NotAdmin);
-        }
-        storage::set_threshold_signers(&env, &signers);
-        storage::set_threshold_quorum(&env, quorum);
-        Ok(())
+        category: Symbol,
+        start: u32,
+        limit: u32,
+    ) -> soroban_sdk::Vec<u32> {
+        let ids = storage::get_category_markets(&env, &category);
+        let (start, end) = validation::page_range(start, limit, ids.len());
+        ids.slice(start..end)
     }
 
-    /// Return the current threshold signer set.
-    pub fn get_threshold_signers(env: Env) -> soroban_sdk::Vec<BytesN<32>> {
-        storage::get_threshold_signers(&env)
+    /// Return every market in which `user` has held a position, in the order
+    /// the positions were opened.
+    ///
+    /// Each market appears once however often the user deposits or trades
+    /// in it, and stays listed after it is settled or canceled so a
+    /// portfolio view can show history; pair with `get_position` for the
+    /// balances. Empty for a user with no positions.
+    pub fn get_user_markets(env: Env, user: Address) -> soroban_sdk::Vec<u32> {
+        storage::get_user_markets(&env, &user)
     }
 
-    /// Return the current quorum requirement.
-    pub fn get_threshold_quorum(env: Env) -> u32 {
-        storage::get_threshold_quorum(&env)
+    /// Return a paginated slice of the addresses holding a position in a
+    /// market.
+    ///
+    /// # Arguments
+    /// * `market_id` - Market identifier
+    /// * `start` - Zero-based index into the market's participant list
+    /// * `limit` - Maximum number of addresses to return (capped at
+    ///   [`validation::MAX_PAGE_SIZE`])
+    ///
+    /// # Returns
+    /// Up to `limit` addresses in the order their positions were opened.
+    /// Each address appears once however often it deposits or trades. Empty
+    /// for an unknown market or a `start` past the end.
+    pub fn get_participants(
+        env: Env,
+        market_id: u32,
+        start: u32,
+        limit: u32,
+    ) -> soroban_sdk::Vec<Address> {
+        let participants = storage::get_market_participants(&env, market_id);
+        let (start, end) = validation::page_range(start, limit, participants.len());
+        participants.slice(start..end)
+    }
+
+    /// Number of markets created so far; the exclusive upper bound for
+    /// [`list_markets`]'s `start`.
+    ///
+    /// Counts every market in the creation index rather than reading the ID
+    /// counter, which markets with derived IDs do not advance.
+    pub fn market_count(env: Env) -> u32 {
+        storage::get_market_count(&env)
     }
 
-    /// Resolve a market using a quorum of oracle signatures (#378).
+    /// Return a paginated slice of markets ordered by creation.
     ///
-    /// Callers provide one signature per registered signer (use 64 zero bytes
-    /// for signers whose signature is unavailable). The market resolves once
-    /// the valid-signature count reaches the stored quorum.
+    /// # Arguments
+    /// * `start` - Zero-based index into the ordered list of markets.
+    /// * `limit` - Maximum number of markets to return (capped at
+    ///   [`validation::MAX_PAGE_SIZE`]).
     ///
-    /// # Errors
-    /// - [`ContractError::MarketNotFound`] — market does not exist.
-    /// - [`ContractError::MarketAlreadyResolved`] — already resolved.
-    /// - [`ContractError::UnauthorizedOracle`] — no signers/quorum configured.
-    /// - [`ContractError::InvalidSignature`] — fewer than quorum valid sigs.
-    pub fn resolve_market_threshold(
+    /// # Returns
+    /// A `Vec<Market>` of up to `limit` markets starting at `start`.
+    /// Returns an empty vec when `start` is beyond the end of the list.
+    pub fn list_markets(
         env: Env,
-        resolver: Address,
-        market_id: u32,
-        outcome: bool,
-        signatures: soroban_sdk::Vec<BytesN<64>>,
-    ) -> Result<(), ContractError> {
-        validation::require_not_paused(&env)?;
-        resolver.require_auth();
-
-        let mut market =
-            storage::get_market(&env, market_id)?.ok_or(ContractError::MarketNotFound)?;
-        if market.status == MarketStatus::Resolved {
-            return Err(ContractError::MarketAlreadyResolved);
+        start: u32,
+        limit: u32,
+    ) -> Result<soroban_sdk::Vec<crate::types::Market>, ContractError> {
+        let ids = storage::get_market_ids(&env);
+        let (start, end) = validation::page_range(start, limit, ids.len());
+        let mut result = soroban_sdk::Vec::new(&env);
+        let mut i = start;
+        while i < end {
+            let market_id = ids.get(i).unwrap();
+            if let Some(market) = storage::get_market(&env, market_id)? {
+                result.push_back(market);
+            }
+            i += 1;
         }
+        Ok(result)
+    }
 
-        let signers = storage::get_threshold_signers(&env);
-        let quorum = storage::get_threshold_quorum(&env);
-
-        oracle::verify_threshold_signatures(&env, market_id, outcome, &signers, &signatures, quorum)?;
-        events::emit_oracle_signature_verified(&env, market_id, outcome, env.ledger().timestamp());
+    /// Check a market's tracked collateral against its positions.
+    ///
+    /// Recomputes the sum of `total_deposited` over the market's unsettled
+    /// positions and compares it with the running total that
+    /// [`get_market_stats`] reports as `total_collateral`. Trading fees are
+    /// taken out of `total_deposited` as they accrue, so they belong to
+    /// neither side. A `false` result means an operation has let the two
+    /// drift apart.
+    ///
+    /// Reads every participant's position; intended for auditors and
+    /// off-chain monitoring rather than for other contracts.
+    pub fn verify_collateral_invariant(env: Env, market_id: u32) -> bool {
+        storage::sum_unsettled_deposits(&env, market_id)
+            .is_ok_and(|total| total == storage::get_market_collateral(&env, market_id))
+    }
 
-        market.status = MarketStatus::Resolved;
-        market.result = Some(outcome);
-        market.resolver = Some(resolver.clone());
-        let resolved_at = env.ledger().timestamp();
-        market.resolved_at = Some(resolved_at);
-        storage::set_market(&env, market_id, &market)?;
+    /// Return the contract's own balance of `token`.
+    ///
+    /// Lets monitoring reconcile what the contract actually holds against
+    /// the `total_collateral` of the markets using `token` plus their
+    /// accrued fees; a shortfall points at leaked funds and a surplus at
+    /// stuck ones. Read-only. `token` must be a token contract, otherwise
+    /// the call traps.
+    pub fn get_token_balance(env: Env, token: Address) -> i128 {
+        soroban_sdk::token::Client::new(&env, &token).balance(&env.current_contract_address())
+    }
 
-        events::emit_market_resolved(
-            &env,
-            market_id,
-            &market.oracle_pubkey,
-            &resolver,
-            outcome,
-            resolved_at,
-        );
+    /// Split `amount` base units into `(whole, fraction)` for a token with
+    /// `decimals` decimals; see [`units::to_display`].
+    ///
+    /// Pure helper, so clients format amounts with the contract's own
+    /// arithmetic. Pass a market's `collateral_decimals` for its amounts.
+    pub fn to_display(_env: Env, amount: i128, decimals: u32) -> (i128, i128) {
+        units::to_display(amount, decimals)
+    }
 
-        Ok(())
+    /// Combine a whole and fractional part into base units; see
+    /// [`units::from_display`].
+    pub fn from_display(_env: Env, whole: i128, frac: i128, decimals: u32) -> i128 {
+        units::from_display(whole, frac, decimals)
     }
 
-    /// Return the current withdrawal fee rate in basis points.
-    ///
-    /// Returns 0 if no fee rate has been configured.
-    pub fn get_fee_rate(env: Env) -> i128 {
-        storage::get_fee_rate_bps(&env)
+    /// Return a summary of contract state for monitoring.
+    ///
+    /// Every field is read from a counter or flag maintained as state changes,
+    /// so the call is cheap enough to poll frequently. It succeeds before
+    /// initialization and after a storage-version mismatch, reporting
+    /// `schema_version` so monitors can detect the latter.
+    pub fn health(env: Env) -> ContractHealth {
+        ContractHealth {
+            paused: storage::is_paused(&env),
+            total_markets: storage::get_market_count(&env),
+            active_markets: storage::get_active_market_count(&env),
+            resolved_unsettled_count: storage::get_resolved_unsettled_count(&env),
+            treasury_set: storage::has_treasury(&env),
+            schema_version: storage::get_stored_version(&env).unwrap_or(0),
+        }
     }
 
-    /// Register the deployed outcome-token contract address used by this
-    /// market contract to mint and burn outcome tokens for position updates.
+    /// Return IDs of active markets whose `end_time` is at or before `cutoff_ts`.
     ///
-    /// Only the stored admin may call this.
-    pub fn set_outcome_token_contract(
+    /// Intended for alerting services looking for markets about to close.
+    /// Walks the creation-ordered market index, inspecting at most
+    /// [`validation::MAX_SCAN_SIZE`] entries.
+    ///
+    /// # Arguments
+    /// * `cutoff_ts` - Latest `end_time` (inclusive) to include
+    /// * `limit` - Maximum number of IDs to return (capped at
+    ///   [`validation::MAX_PAGE_SIZE`])
+    ///
+    /// # Returns
+    /// Matching market IDs in creation order.
+    pub fn markets_ending_before(
         env: Env,
-        admin: Address,
-        outcome_token_contract: Address,
-    ) -> Result<(), ContractError> {
-        validation::require_initialized(&env)?;
-        admin.require_auth();
-        let stored_admin = storage::get_admin(&env)?;
-        if admin != stored_admin {
-            return Err(ContractError::NotAdmin);
+        cutoff_ts: u64,
+        limit: u32,
+    ) -> Result<soroban_sdk::Vec<u32>, ContractError> {
+        let ids = storage::get_market_ids(&env);
+        let limit = limit.min(validation::MAX_PAGE_SIZE);
+        let scan_end = ids.len().min(validation::MAX_SCAN_SIZE);
+        let mut result = soroban_sdk::Vec::new(&env);
+        let mut i = 0;
+        while i < scan_end && result.len() < limit {
+            let market_id = ids.get(i).unwrap();
+            if let Some(market) = storage::get_market(&env, market_id)? {
+                if market.status == MarketStatus::Active && market.end_time <= cutoff_ts {
+                    result.push_back(market_id);
+                }
+            }
+            i += 1;
         }
-        storage::set_outcome_token_contract(&env, &outcome_token_contract);
-        Ok(())
-    }
-
-    /// Return the registered outcome-token contract address, if any.
-    pub fn get_outcome_token_contract(env: Env) -> Option<Address> {
-        storage::get_outcome_token_contract(&env)
+        Ok(result)
     }
+}
 
-    /// Register the resolution contract that gates `resolve_market`.
+/// Trading at a caller-supplied price, compiled only for tests.
+///
+/// Production trades go through [`MarketContract::buy_shares`],
+/// [`MarketContract::sell_shares`] and [`MarketContract::deposit_and_buy`],
+/// which price every fill from the market's curve. These entry points trust
+/// `market_price` as given, which lets tests set up positions at an exact
+/// price but would let a live caller lock collateral at any price it likes;
+/// like `oracle::sign_outcome_for_test`, they stay out of the deployed WASM.
+#[cfg(any(test, feature = "testutils"))]
+#[contractimpl]
+impl MarketContract {
+    /// Buy or sell YES/NO shares by applying signed deltas to a user's position.
     ///
-    /// When set, `resolve_market` will call into this contract to verify that
-    /// a finalized candidate exists for the market before accepting a resolution.
-    /// Pass `None` (by omitting the storage entry) to remove the gate.
+    /// The position is priced at the caller's `market_price`; live trades use
+    /// [`buy_shares`] / [`sell_shares`], which price from the curve.
     ///
-    
-    // ========== Trading Convenience Functions ==========
+    /// This function provides the on-chain interface for share trading, implementing
+    /// the core logic from [`positions::update_position`] with comprehensive market-level
+    /// and authorization validations. It supports both buying (positive delta) and 
+    /// selling (negative delta) of YES and NO shares in a single atomic operation.
+    ///
+    /// # Trading Flow
+    /// 1. User deposits collateral via [`deposit_collateral`]
+    /// 2. User calls `update_position` to buy/sell shares
+    /// 3. Contract validates market state, user authorization, and collateral requirements
+    /// 4. Position is updated and locked collateral is recalculated
+    /// 5. Outcome tokens are minted/burned (if outcome-token contract is registered)
+    /// 6. Events are emitted for off-chain indexing
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `user` - User whose position is updated (must authorize the call)
+    /// * `market_id` - Market identifier
+    /// * `yes_delta` - Change in YES shares (positive to buy, negative to sell)
+    /// * `no_delta` - Change in NO shares (positive to buy, negative to sell)
+    /// * `market_price` - Current market price in basis points (0–10_000) used
+    ///   to calculate locked collateral for the resulting net position
+    ///
+    /// # Returns
+    /// The updated [`Position`] structure containing the new share balances,
+    /// locked collateral, and total deposited amount.
+    ///
+    /// # Errors
+    /// - [`ContractError::MarketNotFound`] – market does not exist
+    /// - [`ContractError::MarketNotActive`] – market is resolved or canceled
+    /// - [`ContractError::MarketExpired`] – current time exceeds market `end_time`
+    /// - [`ContractError::InvalidPrice`] – `market_price` is outside valid range (0–10_000)
+    /// - [`ContractError::InsufficientCollateral`] – deposited collateral insufficient
+    ///   to cover the increased locked amount
+    /// - [`ContractError::NoPositionFound`] – a delta is negative (a sale) and the
+    ///   user has no position in the market
+    /// - [`ContractError::InvalidShareAmount`] – deltas would result in negative share balance
+    /// - [`ContractError::InvalidQuantity`] – the trade grows the net position
+    ///   past the market's `max_position`
+    ///
+    /// # Events
+    /// - `PositionUpdated` – emitted on successful position change with new balances
+    /// - `TradeExecuted` – emitted for each non-zero delta (YES and/or NO)
+    /// - `PositionLimitExceeded` – emitted when delta would drive share balance negative
+    ///
+    /// # Examples
+    /// ```ignore
+    /// // Buy 100 YES shares at 60% market price
+    /// let position = client.update_position(
+    ///     &user,
+    ///     &market_id,
+    ///     &(100 * STROOPS_PER_USDC),  // yes_delta: buy 100
+    ///     &0i128,                       // no_delta: no change
+    ///     &6_000i128,                   // market_price: 60%
+    /// );
+    /// // Result: 60 USDC locked (100 shares * 60% price)
+    ///
+    /// // Sell 50 YES shares
+    /// let position = client.update_position(
+    ///     &user,
+    ///     &market_id,
+    ///     &(-50 * STROOPS_PER_USDC),  // yes_delta: sell 50
+    ///     &0i128,                       // no_delta: no change
+    ///     &6_000i128,                   // market_price: 60%
+    /// );
+    /// ```
+    ///
+    /// # Security
+    /// - Requires user authorization via `user.require_auth()`
+    /// - Validates market is Active and not expired
+    /// - Enforces collateral requirements before state changes
+    /// - Prevents negative share balances
+    /// - All state changes are atomic (succeed or revert together)
+    pub fn update_position(
+        env: Env,
+        user: Address,
+        market_id: u32,
+        yes_delta: i128,
+        no_delta: i128,
+        market_price: i128,
+    ) -> Result<Position, ContractError> {
+        validation::require_not_paused(&env)?;
+        // 1. Authorization
+        user.require_auth();
+
+        Self::execute_position_update(env, user, market_id, yes_delta, no_delta, market_price)
+    }
 
     /// Buy YES shares in a market at the specified price.
     ///
@@ -942,7 +3648,7 @@ impl MarketContract {
     ///
     /// # Errors
     /// Same as [`update_position`], plus:
-    /// - [`ContractError::InvalidQuantity`] – amount is zero or negative
+    /// - [`ContractError::InvalidShareAmount`] – amount is zero or negative
     ///
     /// # Example
     /// ```ignore
@@ -961,9 +3667,7 @@ impl MarketContract {
         amount: i128,
         market_price: i128,
     ) -> Result<Position, ContractError> {
-        if amount <= 0 {
-            return Err(ContractError::InvalidQuantity);
-        }
+        validation::validate_share_quantity(amount)?;
         Self::update_position(env, user, market_id, amount, 0, market_price)
     }
 
@@ -985,7 +3689,7 @@ impl MarketContract {
     ///
     /// # Errors
     /// Same as [`update_position`], plus:
-    /// - [`ContractError::InvalidQuantity`] – amount is zero or negative
+    /// - [`ContractError::InvalidShareAmount`] – amount is zero or negative
     ///
     /// # Example
     /// ```ignore
@@ -1004,9 +3708,7 @@ impl MarketContract {
         amount: i128,
         market_price: i128,
     ) -> Result<Position, ContractError> {
-        if amount <= 0 {
-            return Err(ContractError::InvalidQuantity);
-        }
+        validation::validate_share_quantity(amount)?;
         Self::update_position(env, user, market_id, 0, amount, market_price)
     }
 
@@ -1028,7 +3730,7 @@ impl MarketContract {
     ///
     /// # Errors
     /// Same as [`update_position`], plus:
-    /// - [`ContractError::InvalidQuantity`] – amount is zero or negative
+    /// - [`ContractError::InvalidShareAmount`] – amount is zero or negative
     ///
     /// # Example
     /// ```ignore
@@ -1047,9 +3749,7 @@ impl MarketContract {
         amount: i128,
         market_price: i128,
     ) -> Result<Position, ContractError> {
-        if amount <= 0 {
-            return Err(ContractError::InvalidQuantity);
-        }
+        validation::validate_share_quantity(amount)?;
         Self::update_position(env, user, market_id, -amount, 0, market_price)
     }
 
@@ -1071,7 +3771,7 @@ impl MarketContract {
     ///
     /// # Errors
     /// Same as [`update_position`], plus:
-    /// - [`ContractError::InvalidQuantity`] – amount is zero or negative
+    /// - [`ContractError::InvalidShareAmount`] – amount is zero or negative
     ///
     /// # Example
     /// ```ignore
@@ -1090,74 +3790,7 @@ impl MarketContract {
         amount: i128,
         market_price: i128,
     ) -> Result<Position, ContractError> {
-        if amount <= 0 {
-            return Err(ContractError::InvalidQuantity);
-        }
+        validation::validate_share_quantity(amount)?;
         Self::update_position(env, user, market_id, 0, -amount, market_price)
     }
-
-    // ========== View Functions ==========
-
-    /// Get a user's current position in a market.
-    ///
-    /// Returns position details including share balances, locked collateral,
-    /// and settlement status. This is a read-only query function.
-    ///
-    /// # Arguments
-    /// * `env` - Contract environment
-    /// * `market_id` - Market identifier
-    /// * `user` - User address to query
-    ///
-    /// # Returns
-    /// The user's [`Position`] if it exists, `None` otherwise.
-    ///
-    /// # Example
-    /// ```ignore
-    /// if let Some(position) = client.get_position(&market_id, &user) {
-    ///     println!("YES shares: {}", position.yes_shares);
-    ///     println!("Locked collateral: {}", position.locked_collateral);
-    /// }
-    /// ```
-    pub fn get_position(
-        env: Env,
-        market_id: u32,
-        user: Address,
-    ) -> Result<Option<Position>, ContractError> {
-        storage::get_position(&env, market_id, &user)
-    }
-
-    /// Return the current fee cap in basis points (defaults to 10_000 when unset).
-    pub fn get_fee_cap(env: Env) -> i128 {
-        storage::get_fee_cap_bps(&env)
-    }
-
-    /// Return a paginated slice of markets ordered by creation.
-    ///
-    /// # Arguments
-    /// * `start` - Zero-based index into the ordered list of markets.
-    /// * `limit` - Maximum number of markets to return (capped at 100).
-    ///
-    /// # Returns
-    /// A `Vec<Market>` of up to `limit` markets starting at `start`.
-    /// Returns an empty vec when `start` is beyond the end of the list.
-    pub fn list_markets(
-        env: Env,
-        start: u32,
-        limit: u32,
-    ) -> Result<soroban_sdk::Vec<crate::types::Market>, ContractError> {
-        let ids = storage::get_market_ids(&env);
-        let total = ids.len();
-        let limit = limit.min(100);
-        let mut result = soroban_sdk::Vec::new(&env);
-        let end = (start + limit).min(total);
-        let mut i = start;
-        while i < end {
-            let market_id = ids.get(i).unwrap();
-            if let Some(market) = storage::get_market(&env, market_id)? {
-                result.push_back(market);
-            }
-            i += 1;
-        }
-        Ok(result)
-    }
 }
@@ -10,13 +10,34 @@
 //! The exact bytes the oracle must sign are:
 //!
 //! ```text
-//! message = keccak256(market_id_be || outcome_byte)
+//! message = keccak256(market_id_be || created_at_be || source_hash || outcome_byte)
 //! ```
 //!
-//! | Field          | Encoding                          | Width  |
-//! |----------------|-----------------------------------|--------|
-//! | `market_id_be` | `u32` as **big-endian** bytes     | 4 bytes|
-//! | `outcome_byte` | `0x01` = YES / `0x00` = NO        | 1 byte |
+//! | Field           | Encoding                             | Width    |
+//! |-----------------|--------------------------------------|----------|
+//! | `market_id_be`  | `u32` as **big-endian** bytes        | 4 bytes  |
+//! | `created_at_be` | market's `created_at`, `u64` BE      | 8 bytes  |
+//! | `source_hash`   | market's committed resolution source | 32 bytes |
+//! | `outcome_byte`  | `0x01` = YES / `0x00` = NO / `0x02` = INVALID | 1 byte |
+//!
+//! An INVALID outcome resolves the market with no winning side and refunds
+//! every position.
+//!
+//! Scalar markets sign their numeric result in place of the outcome byte:
+//!
+//! ```text
+//! message = keccak256(market_id_be || created_at_be || source_hash || value_be)
+//! ```
+//!
+//! where `value_be` is the settled value as an `i128` in big-endian bytes (16
+//! bytes). The preimages differ in length, so a signature over one kind of
+//! result never verifies as the other.
+//!
+//! `source_hash` is committed by the creator at `initialize_market` and never
+//! changes, so a signature is only valid for the agreed resolution source.
+//! `created_at` binds the signature to one market instance, so it cannot be
+//! replayed against a later market that reuses the same ID, for example after
+//! a redeploy.
 //!
 //! **Backend alignment**: the backend signer MUST concatenate these raw bytes
 //! and keccak256-hash the result. JSON canonicalization produces a different
@@ -24,22 +45,147 @@
 //! `test-vectors/oracle-message.json` (generated by `cargo test
 //! export_oracle_test_vector`) to confirm your signing implementation produces
 //! the expected `keccak_hex` before sending signatures to the contract.
+//! For a live market, the `resolution_preimage` and
+//! `preview_resolution_message` views return the raw bytes and their hash.
 
 use crate::error::ContractError;
-use crate::types::{AdapterType, Market};
+use crate::types::{AdapterType, Market, OracleSet, Outcome};
 use ed25519_dalek::{Signature, Verifier, VerifyingKey};
-use soroban_sdk::{Bytes, BytesN, Env, Vec};
+use soroban_sdk::{Address, Bytes, BytesN, Env};
 
-/// Construct the message that the oracle signs.
+/// Build the raw bytes hashed into the oracle message.
 ///
-/// Message format: `keccak256(market_id_be || outcome_byte)`
+/// Layout: `market_id_be || created_at_be || source_hash || outcome_byte`
+/// (45 bytes)
 /// - `market_id`: u32 big-endian (4 bytes)
-/// - `outcome_byte`: `0x01` = YES, `0x00` = NO
-pub fn construct_oracle_message(env: &Env, market_id: u32, outcome: bool) -> BytesN<32> {
-    let mut message = Bytes::new(env);
-    message.append(&Bytes::from_slice(env, &market_id.to_be_bytes()));
-    message.append(&Bytes::from_slice(env, &[u8::from(outcome)]));
-    env.crypto().keccak256(&message).into()
+/// - `created_at`: the market's creation timestamp, u64 big-endian (8 bytes)
+/// - `source_hash`: the market's committed resolution source (32 bytes)
+/// - `outcome_byte`: `0x01` = YES, `0x00` = NO, `0x02` = INVALID (see
+///   [`Outcome::to_byte`]); a `bool` outcome encodes as YES or NO
+pub fn construct_oracle_preimage(
+    env: &Env,
+    market_id: u32,
+    created_at: u64,
+    source_hash: &BytesN<32>,
+    outcome: impl Into<Outcome>,
+) -> Bytes {
+    let mut preimage = Bytes::new(env);
+    preimage.append(&Bytes::from_slice(env, &market_id.to_be_bytes()));
+    preimage.append(&Bytes::from_slice(env, &created_at.to_be_bytes()));
+    preimage.append(&Bytes::from(source_hash.clone()));
+    preimage.append(&Bytes::from_slice(env, &[outcome.into().to_byte()]));
+    preimage
+}
+
+/// Construct the message that the oracle signs.
+///
+/// Message format:
+/// `keccak256(market_id_be || created_at_be || source_hash || outcome_byte)`;
+/// see [`construct_oracle_preimage`] for the byte layout.
+pub fn construct_oracle_message(
+    env: &Env,
+    market_id: u32,
+    created_at: u64,
+    source_hash: &BytesN<32>,
+    outcome: impl Into<Outcome>,
+) -> BytesN<32> {
+    let preimage = construct_oracle_preimage(env, market_id, created_at, source_hash, outcome);
+    env.crypto().keccak256(&preimage).into()
+}
+
+/// Build the raw bytes hashed into a scalar market's oracle message.
+///
+/// Layout: `market_id_be || created_at_be || source_hash || value_be`
+/// (60 bytes), as [`construct_oracle_preimage`] with the outcome byte
+/// replaced by `value` as an `i128` in big-endian bytes (16 bytes).
+pub fn construct_scalar_oracle_preimage(
+    env: &Env,
+    market_id: u32,
+    created_at: u64,
+    source_hash: &BytesN<32>,
+    value: i128,
+) -> Bytes {
+    let mut preimage = Bytes::new(env);
+    preimage.append(&Bytes::from_slice(env, &market_id.to_be_bytes()));
+    preimage.append(&Bytes::from_slice(env, &created_at.to_be_bytes()));
+    preimage.append(&Bytes::from(source_hash.clone()));
+    preimage.append(&Bytes::from_slice(env, &value.to_be_bytes()));
+    preimage
+}
+
+/// Construct the message the oracle signs to resolve a scalar market.
+///
+/// Message format:
+/// `keccak256(market_id_be || created_at_be || source_hash || value_be)`;
+/// see [`construct_scalar_oracle_preimage`] for the byte layout.
+pub fn construct_scalar_oracle_message(
+    env: &Env,
+    market_id: u32,
+    created_at: u64,
+    source_hash: &BytesN<32>,
+    value: i128,
+) -> BytesN<32> {
+    let preimage = construct_scalar_oracle_preimage(env, market_id, created_at, source_hash, value);
+    env.crypto().keccak256(&preimage).into()
+}
+
+/// Hash sealing an oracle resolution for `commit_resolution`.
+///
+/// Commitment: `keccak256(outcome_byte || salt || signature)` (97 bytes), with
+/// `outcome_byte` encoded as in [`construct_oracle_preimage`].
+pub fn construct_resolution_commitment(
+    env: &Env,
+    outcome: bool,
+    salt: &BytesN<32>,
+    signature: &BytesN<64>,
+) -> BytesN<32> {
+    let mut preimage = Bytes::from_slice(env, &[u8::from(outcome)]);
+    preimage.append(&Bytes::from(salt.clone()));
+    preimage.append(&Bytes::from(signature.clone()));
+    env.crypto().keccak256(&preimage).into()
+}
+
+/// The Stellar account controlled by an oracle key.
+///
+/// An oracle's ed25519 key is also an account key, so the oracle can
+/// authorize calls as the `G...` account it encodes: the strkey of
+/// `version_byte || key || crc16_xmodem_le`, in unpadded base32.
+pub fn oracle_account(env: &Env, oracle_pubkey: &BytesN<32>) -> Address {
+    const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+    const ED25519_PUBLIC_KEY_VERSION: u8 = 6 << 3;
+
+    let mut raw = [0u8; 35];
+    raw[0] = ED25519_PUBLIC_KEY_VERSION;
+    raw[1..33].copy_from_slice(&oracle_pubkey.to_array());
+    let checksum = crc16_xmodem(&raw[..33]);
+    raw[33..].copy_from_slice(&checksum.to_le_bytes());
+
+    // 35 bytes are seven 40-bit groups of eight base32 digits each.
+    let mut strkey = [0u8; 56];
+    for (group, chunk) in raw.chunks(5).enumerate() {
+        let bits = chunk
+            .iter()
+            .fold(0u64, |acc, byte| acc << 8 | u64::from(*byte));
+        for digit in 0..8 {
+            strkey[group * 8 + digit] = ALPHABET[((bits >> (35 - 5 * digit)) & 31) as usize];
+        }
+    }
+    Address::from_string_bytes(&Bytes::from_array(env, &strkey))
+}
+
+/// CRC-16/XMODEM, the checksum appended to Stellar strkeys.
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |crc, byte| {
+        let mut crc = crc ^ (u16::from(*byte) << 8);
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
 }
 
 /// Verify an ed25519 signature without panicking on invalid input.
@@ -62,12 +208,33 @@ fn verify_ed25519_safe(pubkey: &BytesN<32>, message: &BytesN<32>, signature: &By
         .is_ok()
 }
 
+/// Check entries `0..len` with `verify` until `threshold` of them pass.
+///
+/// The Soroban host has no batch Ed25519 verification, and each signature is
+/// verified in WASM at a cost of millions of instructions, so the number of
+/// checks dominates a quorum resolution. This stops as soon as `threshold`
+/// entries have passed, and as soon as the entries left could no longer
+/// reach it: a 3-of-5 quorum with five good signatures costs three
+/// verifications, not five.
+fn meets_threshold(len: u32, threshold: u32, mut verify: impl FnMut(u32) -> bool) -> bool {
+    let mut valid: u32 = 0;
+    for i in 0..len {
+        if valid >= threshold || valid + (len - i) < threshold {
+            break;
+        }
+        if verify(i) {
+            valid += 1;
+        }
+    }
+    valid >= threshold
+}
+
 /// Verify that an oracle signature is valid for a market resolution.
 ///
 /// # Errors
 /// - [`ContractError::UnauthorizedOracle`] if `oracle_pubkey` is the zero key.
 /// - [`ContractError::InvalidSignature`] if the signature does not verify
-///   against `construct_oracle_message(env, market_id, outcome)`.
+///   against `construct_oracle_message(env, market_id, created_at, source_hash, outcome)`.
 ///
 /// # Security
 /// Uses Ed25519 signature verification, performed in pure Rust (see
@@ -76,22 +243,59 @@ fn verify_ed25519_safe(pubkey: &BytesN<32>, message: &BytesN<32>, signature: &By
 pub fn verify_oracle_signature(
     env: &Env,
     market_id: u32,
-    outcome: bool,
+    created_at: u64,
+    source_hash: &BytesN<32>,
+    outcome: impl Into<Outcome>,
+    signature: &BytesN<64>,
+    oracle_pubkey: &BytesN<32>,
+) -> Result<(), ContractError> {
+    let message = construct_oracle_message(env, market_id, created_at, source_hash, outcome);
+    verify_signed_message(env, &message, signature, oracle_pubkey)
+}
+
+/// Check `signature` over an already-built oracle message.
+fn verify_signed_message(
+    env: &Env,
+    message: &BytesN<32>,
     signature: &BytesN<64>,
     oracle_pubkey: &BytesN<32>,
 ) -> Result<(), ContractError> {
     if oracle_pubkey == &BytesN::from_array(env, &[0u8; 32]) {
         return Err(ContractError::UnauthorizedOracle);
     }
-
-    let message = construct_oracle_message(env, market_id, outcome);
-    if !verify_ed25519_safe(oracle_pubkey, &message, signature) {
+    if !verify_ed25519_safe(oracle_pubkey, message, signature) {
         return Err(ContractError::InvalidSignature);
     }
 
     Ok(())
 }
 
+/// Sign `outcome` for a market as the oracle whose Ed25519 secret key is
+/// `signing_key_bytes`, returning `(pubkey, signature)`.
+///
+/// The message comes from [`construct_oracle_message`], so the signature is
+/// always encoded exactly as `resolve_market` verifies it. Only compiled for
+/// tests and under the `testutils` feature, never into the deployed WASM.
+#[cfg(any(test, feature = "testutils"))]
+pub fn sign_outcome_for_test(
+    env: &Env,
+    signing_key_bytes: &[u8; 32],
+    market_id: u32,
+    created_at: u64,
+    source_hash: &BytesN<32>,
+    outcome: impl Into<Outcome>,
+) -> (BytesN<32>, BytesN<64>) {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    let signing_key = SigningKey::from_bytes(signing_key_bytes);
+    let message = construct_oracle_message(env, market_id, created_at, source_hash, outcome);
+    let signature = signing_key.sign(&message.to_array());
+    (
+        BytesN::from_array(env, &signing_key.verifying_key().to_bytes()),
+        BytesN::from_array(env, &signature.to_bytes()),
+    )
+}
+
 /// Check whether `oracle_pubkey` is authorised to resolve `market`.
 ///
 /// MVP: pubkey must match `market.oracle_pubkey` exactly.
@@ -99,7 +303,6 @@ pub fn verify_oracle_signature(
 ///
 /// # Errors
 /// - [`ContractError::UnauthorizedOracle`] if the pubkey doesn't match.
-#[allow(dead_code)]
 pub fn validate_oracle_authorization(
     market: &Market,
     oracle_pubkey: &BytesN<32>,
@@ -121,11 +324,45 @@ pub fn verify_market_outcome(
     market_id: u32,
     market: &Market,
     adapter_type: AdapterType,
-    outcome: bool,
+    outcome: Outcome,
     proof: &BytesN<64>,
 ) -> Result<(), ContractError> {
     match adapter_type {
-        AdapterType::Ed25519 => verify_oracle_signature(env, market_id, outcome, proof, &market.oracle_pubkey),
+        AdapterType::Ed25519 => verify_oracle_signature(
+            env,
+            market_id,
+            market.created_at,
+            &market.source_hash,
+            outcome,
+            proof,
+            &market.oracle_pubkey,
+        ),
+        AdapterType::Reflector | AdapterType::Pyth => Err(ContractError::UnauthorizedOracle),
+    }
+}
+
+/// Verify a scalar market's signed value against the market's oracle.
+///
+/// The scalar counterpart of [`verify_market_outcome`]: only the `Ed25519`
+/// adapter can sign values, so other adapters return `UnauthorizedOracle`.
+pub fn verify_scalar_outcome(
+    env: &Env,
+    market_id: u32,
+    market: &Market,
+    value: i128,
+    signature: &BytesN<64>,
+) -> Result<(), ContractError> {
+    match market.adapter_type {
+        AdapterType::Ed25519 => {
+            let message = construct_scalar_oracle_message(
+                env,
+                market_id,
+                market.created_at,
+                &market.source_hash,
+                value,
+            );
+            verify_signed_message(env, &message, signature, &market.oracle_pubkey)
+        }
         AdapterType::Reflector | AdapterType::Pyth => Err(ContractError::UnauthorizedOracle),
     }
 }
@@ -134,12 +371,14 @@ pub fn verify_market_outcome(
 ///
 /// `signatures` is a parallel slice aligned with `signers`: `signatures[i]` is
 /// the Ed25519 signature produced by `signers[i]` over
-/// `keccak256(market_id_be || outcome_byte)`.  Entries where the caller does
-/// not have a valid signature should be zeroed-out (64 zero bytes) — they are
-/// counted as failures but do not abort the loop.
+/// `keccak256(market_id_be || created_at_be || source_hash || outcome_byte)`.
+/// Entries where the caller does not have a valid signature should be
+/// zeroed-out (64 zero bytes) — they are counted as failures but do not abort
+/// the loop.
 ///
 /// The function counts how many signatures verify and returns `Ok(())` only
-/// when that count meets or exceeds `quorum`.
+/// when that count meets or exceeds `quorum`, verifying no more signatures
+/// than needed to decide (see [`meets_threshold`]).
 ///
 /// # Errors
 /// - `UnauthorizedOracle` — `signers` is empty or `quorum` is 0.
@@ -147,6 +386,8 @@ pub fn verify_market_outcome(
 pub fn verify_threshold_signatures(
     env: &Env,
     market_id: u32,
+    created_at: u64,
+    source_hash: &BytesN<32>,
     outcome: bool,
     signers: &soroban_sdk::Vec<BytesN<32>>,
     signatures: &soroban_sdk::Vec<BytesN<64>>,
@@ -156,22 +397,73 @@ pub fn verify_threshold_signatures(
         return Err(ContractError::UnauthorizedOracle);
     }
 
-    let message = construct_oracle_message(env, market_id, outcome);
-    let mut valid: u32 = 0;
-
+    let message = construct_oracle_message(env, market_id, created_at, source_hash, outcome);
     let len = signers.len().min(signatures.len());
-    for i in 0..len {
-        let pubkey = signers.get(i).unwrap();
-        let sig = signatures.get(i).unwrap();
-        if verify_ed25519_safe(&pubkey, &message, &sig) {
-            valid += 1;
-            if valid >= quorum {
-                return Ok(());
-            }
+    let verified = meets_threshold(len, quorum, |i| {
+        verify_ed25519_safe(
+            &signers.get(i).unwrap(),
+            &message,
+            &signatures.get(i).unwrap(),
+        )
+    });
+    if !verified {
+        return Err(ContractError::InvalidSignature);
+    }
+    Ok(())
+}
+
+/// Verify that at least `set.threshold` distinct oracles from a market's own
+/// oracle set signed `outcome`.
+///
+/// `signatures[i]` must be the signature of `signers[i]` over the same
+/// message [`construct_oracle_message`] builds for single-oracle resolution.
+/// Invalid signatures from listed signers are not counted but do not abort.
+/// Every signer is checked against the set before any signature is verified,
+/// and verification stops once the threshold is decided (see
+/// [`meets_threshold`]).
+///
+/// # Errors
+/// - `InvalidSignature` — the slices differ in length, or fewer than
+///   `set.threshold` signatures verified.
+/// - `DuplicateOracleSigner` — a signer appears more than once.
+/// - `UnauthorizedOracle` — a signer is not in `set.keys`.
+pub fn verify_oracle_set_signatures(
+    env: &Env,
+    market_id: u32,
+    created_at: u64,
+    source_hash: &BytesN<32>,
+    outcome: bool,
+    set: &OracleSet,
+    signers: &soroban_sdk::Vec<BytesN<32>>,
+    signatures: &soroban_sdk::Vec<BytesN<64>>,
+) -> Result<(), ContractError> {
+    if signers.len() != signatures.len() {
+        return Err(ContractError::InvalidSignature);
+    }
+
+    let mut seen: soroban_sdk::Vec<BytesN<32>> = soroban_sdk::Vec::new(env);
+    for pubkey in signers.iter() {
+        if seen.contains(&pubkey) {
+            return Err(ContractError::DuplicateOracleSigner);
+        }
+        if !set.keys.contains(&pubkey) {
+            return Err(ContractError::UnauthorizedOracle);
         }
+        seen.push_back(pubkey);
     }
 
-    Err(ContractError::InvalidSignature)
+    let message = construct_oracle_message(env, market_id, created_at, source_hash, outcome);
+    let verified = meets_threshold(signers.len(), set.threshold, |i| {
+        verify_ed25519_safe(
+            &signers.get(i).unwrap(),
+            &message,
+            &signatures.get(i).unwrap(),
+        )
+    });
+    if !verified {
+        return Err(ContractError::InvalidSignature);
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -185,6 +477,11 @@ mod tests {
         Address, Env, String,
     };
 
+    /// Resolution source hash shared by tests that don't exercise it directly.
+    pub(super) fn test_source_hash(env: &Env) -> BytesN<32> {
+        BytesN::from_array(env, &[0u8; 32])
+    }
+
     fn make_market(env: &Env, oracle_pubkey: BytesN<32>) -> Market {
         Market {
             id: 1,
@@ -201,67 +498,232 @@ mod tests {
             resolved_at: None,
             adapter_type: AdapterType::Ed25519,
             outcome_count: 2,
+            closed_to_deposits: false,
+            source_hash: BytesN::from_array(env, &[0u8; 32]),
+            resolution_grace: None,
+            fee_rate_bps: None,
+            withdraw_cooldown: None,
+            challenge_deadline: None,
+            trading_fee_bps: 0,
+            collateral_decimals: 7,
+            total_yes_shares: 0,
+            total_no_shares: 0,
+            yes_reserve: 0,
+            no_reserve: 0,
+            category: soroban_sdk::Symbol::new(env, "general"),
+            resolution_source: String::from_str(env, "test source"),
+            kind: crate::types::MarketKind::Binary,
+            settled_value: None,
+            min_deposit: 0,
+            max_position: 0,
+            max_participants: 0,
         }
     }
 
     #[test]
     fn test_construct_oracle_message_yes() {
         let env = Env::default();
-        let message = construct_oracle_message(&env, 1u32, true);
+        let message = construct_oracle_message(&env, 1u32, 0, &test_source_hash(&env), true);
         assert_eq!(message.len(), 32);
     }
 
     #[test]
     fn test_construct_oracle_message_no() {
         let env = Env::default();
-        let message = construct_oracle_message(&env, 1u32, false);
+        let message = construct_oracle_message(&env, 1u32, 0, &test_source_hash(&env), false);
         assert_eq!(message.len(), 32);
     }
 
     #[test]
     fn test_different_outcomes_different_messages() {
         let env = Env::default();
-        let msg_yes = construct_oracle_message(&env, 1u32, true);
-        let msg_no = construct_oracle_message(&env, 1u32, false);
+        let msg_yes = construct_oracle_message(&env, 1u32, 0, &test_source_hash(&env), true);
+        let msg_no = construct_oracle_message(&env, 1u32, 0, &test_source_hash(&env), false);
         assert_ne!(msg_yes, msg_no);
     }
 
     #[test]
     fn test_construct_oracle_message_deterministic() {
         let env = Env::default();
-        let msg1 = construct_oracle_message(&env, 456u32, true);
-        let msg2 = construct_oracle_message(&env, 456u32, true);
+        let msg1 = construct_oracle_message(&env, 456u32, 0, &test_source_hash(&env), true);
+        let msg2 = construct_oracle_message(&env, 456u32, 0, &test_source_hash(&env), true);
         assert_eq!(msg1, msg2);
     }
 
+    #[test]
+    fn test_oracle_account_encodes_key_as_strkey() {
+        let env = Env::default();
+        assert_eq!(
+            oracle_account(&env, &BytesN::from_array(&env, &[0u8; 32])),
+            Address::from_str(
+                &env,
+                "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF"
+            )
+        );
+    }
+
     #[test]
     fn test_different_market_ids_different_messages() {
         let env = Env::default();
-        let msg1 = construct_oracle_message(&env, 1u32, true);
-        let msg2 = construct_oracle_message(&env, 2u32, true);
+        let msg1 = construct_oracle_message(&env, 1u32, 0, &test_source_hash(&env), true);
+        let msg2 = construct_oracle_message(&env, 2u32, 0, &test_source_hash(&env), true);
+        assert_ne!(msg1, msg2);
+    }
+
+    #[test]
+    fn test_preimage_layout() {
+        let env = Env::default();
+        let source_hash = BytesN::from_array(&env, &[0xAAu8; 32]);
+        let preimage =
+            construct_oracle_preimage(&env, 0x0102_0304, 0x0506_0708, &source_hash, true);
+
+        let mut expected = [0xAAu8; 45];
+        expected[..4].copy_from_slice(&[0x01, 0x02, 0x03, 0x04]);
+        expected[4..12].copy_from_slice(&[0, 0, 0, 0, 0x05, 0x06, 0x07, 0x08]);
+        expected[44] = 0x01;
+        assert_eq!(preimage, Bytes::from_array(&env, &expected));
+        assert_eq!(
+            env.crypto().keccak256(&preimage).to_bytes(),
+            construct_oracle_message(&env, 0x0102_0304, 0x0506_0708, &source_hash, true)
+        );
+    }
+
+    #[test]
+    fn test_scalar_preimage_layout() {
+        let env = Env::default();
+        let source_hash = BytesN::from_array(&env, &[0xAAu8; 32]);
+        let preimage =
+            construct_scalar_oracle_preimage(&env, 0x0102_0304, 0x0506_0708, &source_hash, -2);
+
+        let mut expected = [0xAAu8; 60];
+        expected[..4].copy_from_slice(&[0x01, 0x02, 0x03, 0x04]);
+        expected[4..12].copy_from_slice(&[0, 0, 0, 0, 0x05, 0x06, 0x07, 0x08]);
+        expected[44..].copy_from_slice(&(-2i128).to_be_bytes());
+        assert_eq!(preimage, Bytes::from_array(&env, &expected));
+        assert_eq!(
+            env.crypto().keccak256(&preimage).to_bytes(),
+            construct_scalar_oracle_message(&env, 0x0102_0304, 0x0506_0708, &source_hash, -2)
+        );
+        // A value of 1 or 0 never collides with a YES or NO outcome.
+        assert_ne!(
+            construct_scalar_oracle_message(&env, 1, 0, &source_hash, 1),
+            construct_oracle_message(&env, 1, 0, &source_hash, true)
+        );
+    }
+
+    #[test]
+    fn test_verify_scalar_outcome_binds_the_value() {
+        use ed25519_dalek::{Signer, SigningKey};
+        use rand::rngs::OsRng;
+
+        let env = Env::default();
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let market = make_market(
+            &env,
+            BytesN::from_array(&env, &signing_key.verifying_key().to_bytes()),
+        );
+        let message = construct_scalar_oracle_message(&env, 1, 0, &market.source_hash, 4_200);
+        let signature = BytesN::from_array(
+            &env,
+            &signing_key.sign(message.to_array().as_slice()).to_bytes(),
+        );
+
+        assert_eq!(
+            verify_scalar_outcome(&env, 1, &market, 4_200, &signature),
+            Ok(())
+        );
+        assert_eq!(
+            verify_scalar_outcome(&env, 1, &market, 4_201, &signature),
+            Err(ContractError::InvalidSignature)
+        );
+        let pyth = Market {
+            adapter_type: AdapterType::Pyth,
+            ..market
+        };
+        assert_eq!(
+            verify_scalar_outcome(&env, 1, &pyth, 4_200, &signature),
+            Err(ContractError::UnauthorizedOracle)
+        );
+    }
+
+    #[test]
+    fn test_different_source_hashes_different_messages() {
+        let env = Env::default();
+        let source_a = BytesN::from_array(&env, &[0xAAu8; 32]);
+        let source_b = BytesN::from_array(&env, &[0xBBu8; 32]);
+        let msg_a = construct_oracle_message(&env, 1u32, 0, &source_a, true);
+        let msg_b = construct_oracle_message(&env, 1u32, 0, &source_b, true);
+        assert_ne!(msg_a, msg_b);
+    }
+
+    #[test]
+    fn test_signature_rejected_for_different_source_hash() {
+        let env = Env::default();
+        let market_id = 1u32;
+        let outcome = true;
+        let (pubkey, signature) = generate_keypair_and_sign(&env, market_id, outcome);
+
+        let other_source = BytesN::from_array(&env, &[0xBBu8; 32]);
+        let result = verify_oracle_signature(
+            &env,
+            market_id,
+            0,
+            &other_source,
+            outcome,
+            &signature,
+            &pubkey,
+        );
+        assert_eq!(result, Err(ContractError::InvalidSignature));
+    }
+
+    #[test]
+    fn test_different_created_at_different_messages() {
+        let env = Env::default();
+        let msg1 = construct_oracle_message(&env, 1u32, 100, &test_source_hash(&env), true);
+        let msg2 = construct_oracle_message(&env, 1u32, 200, &test_source_hash(&env), true);
         assert_ne!(msg1, msg2);
     }
 
+    #[test]
+    fn test_signature_rejected_for_different_created_at() {
+        let env = Env::default();
+        let market_id = 1u32;
+        let outcome = true;
+        let (pubkey, signature) = generate_keypair_and_sign(&env, market_id, outcome);
+
+        let source_hash = test_source_hash(&env);
+        let result = verify_oracle_signature(
+            &env,
+            market_id,
+            1,
+            &source_hash,
+            outcome,
+            &signature,
+            &pubkey,
+        );
+        assert_eq!(result, Err(ContractError::InvalidSignature));
+    }
+
     #[test]
     fn test_construct_oracle_message_zero_id() {
         let env = Env::default();
-        let message = construct_oracle_message(&env, 0u32, true);
+        let message = construct_oracle_message(&env, 0u32, 0, &test_source_hash(&env), true);
         assert_eq!(message.len(), 32);
     }
 
     #[test]
     fn test_construct_oracle_message_large_id() {
         let env = Env::default();
-        let message = construct_oracle_message(&env, u32::MAX, false);
+        let message = construct_oracle_message(&env, u32::MAX, 0, &test_source_hash(&env), false);
         assert_eq!(message.len(), 32);
     }
 
     #[test]
     fn test_construct_oracle_message_various_ids() {
         let env = Env::default();
-        let msg1 = construct_oracle_message(&env, 100u32, true);
-        let msg2 = construct_oracle_message(&env, 1000u32, true);
-        let msg3 = construct_oracle_message(&env, 10000u32, true);
+        let msg1 = construct_oracle_message(&env, 100u32, 0, &test_source_hash(&env), true);
+        let msg2 = construct_oracle_message(&env, 1000u32, 0, &test_source_hash(&env), true);
+        let msg3 = construct_oracle_message(&env, 10000u32, 0, &test_source_hash(&env), true);
         assert_ne!(msg1, msg2);
         assert_ne!(msg2, msg3);
         assert_ne!(msg1, msg3);
@@ -300,6 +762,8 @@ mod tests {
         let result = verify_oracle_signature(
             &env,
             1u32,
+            0,
+            &test_source_hash(&env),
             true,
             &BytesN::from_array(&env, &[0u8; 64]),
             &BytesN::from_array(&env, &[0u8; 32]),
@@ -313,6 +777,8 @@ mod tests {
         let result = verify_oracle_signature(
             &env,
             123u32,
+            0,
+            &test_source_hash(&env),
             true,
             &BytesN::random(&env),
             &BytesN::random(&env),
@@ -330,7 +796,7 @@ mod tests {
         use rand::rngs::OsRng;
 
         let signing_key = SigningKey::generate(&mut OsRng);
-        let message = construct_oracle_message(env, market_id, outcome);
+        let message = construct_oracle_message(env, market_id, 0, &test_source_hash(env), outcome);
         let signature = signing_key.sign(message.to_array().as_slice());
 
         (
@@ -346,7 +812,15 @@ mod tests {
         let outcome = true;
         let (pubkey, signature) = generate_keypair_and_sign(&env, market_id, outcome);
 
-        let result = verify_oracle_signature(&env, market_id, outcome, &signature, &pubkey);
+        let result = verify_oracle_signature(
+            &env,
+            market_id,
+            0,
+            &test_source_hash(&env),
+            outcome,
+            &signature,
+            &pubkey,
+        );
         assert_eq!(result, Ok(()));
     }
 
@@ -357,7 +831,15 @@ mod tests {
         let (pubkey, signature) = generate_keypair_and_sign(&env, market_id, true);
 
         // Signature was produced for outcome=true; verifying against false must fail.
-        let result = verify_oracle_signature(&env, market_id, false, &signature, &pubkey);
+        let result = verify_oracle_signature(
+            &env,
+            market_id,
+            0,
+            &test_source_hash(&env),
+            false,
+            &signature,
+            &pubkey,
+        );
         assert_eq!(result, Err(ContractError::InvalidSignature));
     }
 
@@ -368,7 +850,15 @@ mod tests {
         let (pubkey, signature) = generate_keypair_and_sign(&env, 1u32, outcome);
 
         // Signature was produced for market_id=1; verifying against 2 must fail.
-        let result = verify_oracle_signature(&env, 2u32, outcome, &signature, &pubkey);
+        let result = verify_oracle_signature(
+            &env,
+            2u32,
+            0,
+            &test_source_hash(&env),
+            outcome,
+            &signature,
+            &pubkey,
+        );
         assert_eq!(result, Err(ContractError::InvalidSignature));
     }
 
@@ -381,17 +871,63 @@ mod tests {
         let (other_pubkey, _other_signature) = generate_keypair_and_sign(&env, market_id, outcome);
 
         // Signature was produced by a different keypair than `other_pubkey`.
-        let result = verify_oracle_signature(&env, market_id, outcome, &signature, &other_pubkey);
+        let result = verify_oracle_signature(
+            &env,
+            market_id,
+            0,
+            &test_source_hash(&env),
+            outcome,
+            &signature,
+            &other_pubkey,
+        );
         assert_eq!(result, Err(ContractError::InvalidSignature));
     }
 
+    #[test]
+    fn test_sign_outcome_for_test_matches_production_encoding() {
+        let env = Env::default();
+        let source_hash = BytesN::from_array(&env, &[3u8; 32]);
+        let seed = [9u8; 32];
+        let (pubkey, signature) =
+            sign_outcome_for_test(&env, &seed, 4, 1_700, &source_hash, Outcome::Invalid);
+
+        assert_eq!(
+            verify_oracle_signature(
+                &env,
+                4,
+                1_700,
+                &source_hash,
+                Outcome::Invalid,
+                &signature,
+                &pubkey
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            verify_oracle_signature(
+                &env,
+                4,
+                0,
+                &source_hash,
+                Outcome::Invalid,
+                &signature,
+                &pubkey
+            ),
+            Err(ContractError::InvalidSignature)
+        );
+        // The same key signs deterministically.
+        assert_eq!(
+            sign_outcome_for_test(&env, &seed, 4, 1_700, &source_hash, Outcome::Invalid),
+            (pubkey, signature)
+        );
+    }
+
     /// Export a deterministic test vector so the backend signer can validate
     /// its keccak256 + Ed25519 implementation against the on-chain format.
     ///
     /// Writes `test-vectors/oracle-message.json` at the workspace root.
     /// Run with `cargo test export_oracle_test_vector -- --nocapture`.
     #[test]
-    #[cfg(feature = "std")]
     fn export_oracle_test_vector() {
         use ed25519_dalek::{Signer, SigningKey};
 
@@ -402,9 +938,17 @@ mod tests {
 
         let env = Env::default();
         let market_id: u32 = 1;
+        let created_at: u64 = 1_700_000_000;
         let outcome = true; // YES
+        let source_hash = [0x11u8; 32];
 
-        let message = construct_oracle_message(&env, market_id, outcome);
+        let message = construct_oracle_message(
+            &env,
+            market_id,
+            created_at,
+            &BytesN::from_array(&env, &source_hash),
+            outcome,
+        );
         let message_bytes = message.to_array();
         let signature = signing_key.sign(&message_bytes);
 
@@ -413,6 +957,8 @@ mod tests {
             verify_oracle_signature(
                 &env,
                 market_id,
+                created_at,
+                &BytesN::from_array(&env, &source_hash),
                 outcome,
                 &BytesN::from_array(&env, &signature.to_bytes()),
                 &BytesN::from_array(&env, &verifying_key.to_bytes()),
@@ -423,15 +969,19 @@ mod tests {
 
         let to_hex = |b: &[u8]| -> std::string::String { b.iter().map(|x| format!("{:02x}", x)).collect() };
 
-        let mut raw = [0u8; 5];
+        let mut raw = [0u8; 45];
         raw[..4].copy_from_slice(&market_id.to_be_bytes());
-        raw[4] = u8::from(outcome);
+        raw[4..12].copy_from_slice(&created_at.to_be_bytes());
+        raw[12..44].copy_from_slice(&source_hash);
+        raw[44] = u8::from(outcome);
 
         let json = format!(
             concat!(
                 "{{\n",
-                "  \"description\": \"Canonical oracle message: keccak256(market_id_be || outcome_byte)\",\n",
+                "  \"description\": \"Canonical oracle message: keccak256(market_id_be || created_at_be || source_hash || outcome_byte)\",\n",
                 "  \"market_id\": {market_id},\n",
+                "  \"created_at\": {created_at},\n",
+                "  \"source_hash_hex\": \"{source_hash_hex}\",\n",
                 "  \"outcome\": \"YES\",\n",
                 "  \"raw_hex\": \"{raw_hex}\",\n",
                 "  \"keccak_hex\": \"{keccak_hex}\",\n",
@@ -440,6 +990,8 @@ mod tests {
                 "}}\n"
             ),
             market_id = market_id,
+            created_at = created_at,
+            source_hash_hex = to_hex(&source_hash),
             raw_hex = to_hex(&raw),
             keccak_hex = to_hex(&message_bytes),
             pubkey_hex = to_hex(&verifying_key.to_bytes()),
@@ -455,15 +1007,16 @@ mod tests {
 
 #[cfg(test)]
 mod threshold_tests {
+    use super::tests::test_source_hash;
     use super::*;
-    use soroban_sdk::{testutils::BytesN as _, Env, Vec};
+    use soroban_sdk::{vec, Env, Vec};
 
     /// Generate a keypair and sign the oracle message for (market_id, outcome).
     fn sign(env: &Env, market_id: u32, outcome: bool) -> (BytesN<32>, BytesN<64>) {
         use ed25519_dalek::{Signer, SigningKey};
         use rand::rngs::OsRng;
         let signing_key = SigningKey::generate(&mut OsRng);
-        let message = construct_oracle_message(env, market_id, outcome);
+        let message = construct_oracle_message(env, market_id, 0, &test_source_hash(env), outcome);
         let sig = signing_key.sign(message.to_array().as_slice());
         (
             BytesN::from_array(env, &signing_key.verifying_key().to_bytes()),
@@ -491,7 +1044,16 @@ mod threshold_tests {
         sigs.push_back(bad_sig);
 
         assert_eq!(
-            verify_threshold_signatures(&env, 1, true, &signers, &sigs, 2),
+            verify_threshold_signatures(
+                &env,
+                1,
+                0,
+                &test_source_hash(&env),
+                true,
+                &signers,
+                &sigs,
+                2
+            ),
             Ok(())
         );
     }
@@ -515,7 +1077,16 @@ mod threshold_tests {
         sigs.push_back(bad_sig);
 
         assert_eq!(
-            verify_threshold_signatures(&env, 1, true, &signers, &sigs, 2),
+            verify_threshold_signatures(
+                &env,
+                1,
+                0,
+                &test_source_hash(&env),
+                true,
+                &signers,
+                &sigs,
+                2
+            ),
             Err(ContractError::InvalidSignature)
         );
     }
@@ -526,7 +1097,16 @@ mod threshold_tests {
         let signers: Vec<BytesN<32>> = Vec::new(&env);
         let sigs: Vec<BytesN<64>> = Vec::new(&env);
         assert_eq!(
-            verify_threshold_signatures(&env, 1, true, &signers, &sigs, 2),
+            verify_threshold_signatures(
+                &env,
+                1,
+                0,
+                &test_source_hash(&env),
+                true,
+                &signers,
+                &sigs,
+                2
+            ),
             Err(ContractError::UnauthorizedOracle)
         );
     }
@@ -540,7 +1120,16 @@ mod threshold_tests {
         let mut sigs: Vec<BytesN<64>> = Vec::new(&env);
         sigs.push_back(sig1);
         assert_eq!(
-            verify_threshold_signatures(&env, 1, true, &signers, &sigs, 0),
+            verify_threshold_signatures(
+                &env,
+                1,
+                0,
+                &test_source_hash(&env),
+                true,
+                &signers,
+                &sigs,
+                0
+            ),
             Err(ContractError::UnauthorizedOracle)
         );
     }
@@ -558,7 +1147,16 @@ mod threshold_tests {
         sigs.push_back(sig1_wrong);
         sigs.push_back(sig2_wrong);
         assert_eq!(
-            verify_threshold_signatures(&env, 1, true, &signers, &sigs, 1),
+            verify_threshold_signatures(
+                &env,
+                1,
+                0,
+                &test_source_hash(&env),
+                true,
+                &signers,
+                &sigs,
+                1
+            ),
             Err(ContractError::InvalidSignature)
         );
     }
@@ -572,8 +1170,173 @@ mod threshold_tests {
         let mut sigs: Vec<BytesN<64>> = Vec::new(&env);
         sigs.push_back(sig);
         assert_eq!(
-            verify_threshold_signatures(&env, 42, false, &signers, &sigs, 1),
+            verify_threshold_signatures(
+                &env,
+                42,
+                0,
+                &test_source_hash(&env),
+                false,
+                &signers,
+                &sigs,
+                1
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn oracle_set_2_of_3_meets_threshold() {
+        let env = Env::default();
+        let (pk1, sig1) = sign(&env, 1, true);
+        let (pk2, sig2) = sign(&env, 1, true);
+        let (pk3, _) = sign(&env, 1, true);
+        let set = OracleSet {
+            keys: vec![&env, pk1.clone(), pk2.clone(), pk3],
+            threshold: 2,
+        };
+
+        let signers = vec![&env, pk2, pk1];
+        let sigs = vec![&env, sig2, sig1];
+        assert_eq!(
+            verify_oracle_set_signatures(
+                &env,
+                1,
+                0,
+                &test_source_hash(&env),
+                true,
+                &set,
+                &signers,
+                &sigs
+            ),
             Ok(())
         );
     }
+
+    #[test]
+    fn oracle_set_rejects_duplicate_signer() {
+        let env = Env::default();
+        let (pk1, sig1) = sign(&env, 1, true);
+        let (pk2, _) = sign(&env, 1, true);
+        let set = OracleSet {
+            keys: vec![&env, pk1.clone(), pk2],
+            threshold: 2,
+        };
+
+        let signers = vec![&env, pk1.clone(), pk1];
+        let sigs = vec![&env, sig1.clone(), sig1];
+        assert_eq!(
+            verify_oracle_set_signatures(
+                &env,
+                1,
+                0,
+                &test_source_hash(&env),
+                true,
+                &set,
+                &signers,
+                &sigs
+            ),
+            Err(ContractError::DuplicateOracleSigner)
+        );
+    }
+
+    #[test]
+    fn oracle_set_rejects_signer_outside_set() {
+        let env = Env::default();
+        let (pk1, sig1) = sign(&env, 1, true);
+        let (outsider, outsider_sig) = sign(&env, 1, true);
+        let set = OracleSet {
+            keys: vec![&env, pk1.clone()],
+            threshold: 1,
+        };
+
+        let signers = vec![&env, pk1, outsider];
+        let sigs = vec![&env, sig1, outsider_sig];
+        assert_eq!(
+            verify_oracle_set_signatures(
+                &env,
+                1,
+                0,
+                &test_source_hash(&env),
+                true,
+                &set,
+                &signers,
+                &sigs
+            ),
+            Err(ContractError::UnauthorizedOracle)
+        );
+    }
+
+    #[test]
+    fn oracle_set_below_threshold_or_wrong_outcome_fails() {
+        let env = Env::default();
+        let (pk1, sig1) = sign(&env, 1, true);
+        let (pk2, no_sig2) = sign(&env, 1, false);
+        let set = OracleSet {
+            keys: vec![&env, pk1.clone(), pk2.clone()],
+            threshold: 2,
+        };
+
+        // pk2 signed the other outcome, so only one signature counts.
+        let signers = vec![&env, pk1, pk2];
+        let sigs = vec![&env, sig1.clone(), no_sig2];
+        assert_eq!(
+            verify_oracle_set_signatures(
+                &env,
+                1,
+                0,
+                &test_source_hash(&env),
+                true,
+                &set,
+                &signers,
+                &sigs
+            ),
+            Err(ContractError::InvalidSignature)
+        );
+
+        // Mismatched slices are rejected outright.
+        let signers = vec![&env, set.keys.get(0).unwrap()];
+        let sigs = vec![&env, sig1.clone(), sig1];
+        assert_eq!(
+            verify_oracle_set_signatures(
+                &env,
+                1,
+                0,
+                &test_source_hash(&env),
+                true,
+                &set,
+                &signers,
+                &sigs
+            ),
+            Err(ContractError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn meets_threshold_checks_only_what_decides_it() {
+        // 3 of 5, all valid: stops after the third check.
+        let mut checked = 0;
+        assert!(meets_threshold(5, 3, |_| {
+            checked += 1;
+            true
+        }));
+        assert_eq!(checked, 3);
+
+        // The first three fail: the last two cannot reach 3, so they are skipped.
+        let mut checked = 0;
+        assert!(!meets_threshold(5, 3, |i| {
+            checked += 1;
+            i >= 3
+        }));
+        assert_eq!(checked, 3);
+
+        // Two early failures still leave exactly enough entries to succeed.
+        let mut checked = 0;
+        assert!(meets_threshold(5, 3, |i| {
+            checked += 1;
+            i >= 2
+        }));
+        assert_eq!(checked, 5);
+
+        assert!(!meets_threshold(2, 3, |_| true));
+    }
 }
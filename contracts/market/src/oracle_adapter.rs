@@ -48,7 +48,7 @@ pub trait OracleAdapter {
     /// `proof` carries adapter-specific evidence:
     /// - [`Ed25519Adapter`]: exactly 64 bytes — the Ed25519 signature produced
     ///   by the market's stored oracle key over
-    ///   `keccak256(market_id_be || outcome_byte)`.
+    ///   `keccak256(market_id_be || created_at_be || source_hash || outcome_byte)`.
     /// - [`ReflectorAdapter`]: empty (`Bytes::new`); the adapter fetches the
     ///   price on-chain from the Reflector contract.
     /// - [`PythAdapter`]: raw Wormhole VAA bytes containing the price
@@ -78,6 +78,10 @@ pub trait OracleAdapter {
 /// the pre-adapter code path.
 pub struct Ed25519Adapter<'a> {
     pub oracle_pubkey: &'a BytesN<32>,
+    /// The market's creation timestamp, binding signatures to the instance.
+    pub created_at: u64,
+    /// The market's committed resolution source hash.
+    pub source_hash: &'a BytesN<32>,
 }
 
 impl<'a> OracleAdapter for Ed25519Adapter<'a> {
@@ -90,7 +94,15 @@ impl<'a> OracleAdapter for Ed25519Adapter<'a> {
     ) -> Result<(), ContractError> {
         let sig: BytesN<64> =
             BytesN::try_from(proof.clone()).map_err(|_| ContractError::InvalidSignature)?;
-        crate::oracle::verify_oracle_signature(env, market_id, outcome, &sig, self.oracle_pubkey)
+        crate::oracle::verify_oracle_signature(
+            env,
+            market_id,
+            self.created_at,
+            self.source_hash,
+            outcome,
+            &sig,
+            self.oracle_pubkey,
+        )
     }
 }
 
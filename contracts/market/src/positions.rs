@@ -1,9 +1,15 @@
+use crate::error::ContractError;
 use crate::events::{emit_position_limit_exceeded, emit_position_updated, emit_trade_executed};
 use crate::types::{Market, Position};
 use crate::validation;
 use soroban_sdk::{contracterror, Address, Env};
 
-const BASIS_POINTS: i128 = 10_000;
+pub(crate) const BASIS_POINTS: i128 = 10_000;
+/// Base units per whole token for a 7-decimal collateral such as USDC.
+///
+/// Only a convenience for 7-decimal tokens: share and collateral amounts are
+/// always in the market's collateral base units (see
+/// `Market::collateral_decimals`), so no math here depends on it.
 pub const STROOPS_PER_USDC: i128 = 10_000_000;
 
 /// Errors returned by position validation and update operations.
@@ -15,6 +21,8 @@ pub enum PositionError {
     ShareBalanceBelowZero = 1,
     /// Market price is outside the valid basis-point range (0–10_000)
     InvalidMarketPrice = 2,
+    /// Net position or locked collateral could not be computed without overflow
+    ArithmeticOverflow = 3,
 }
 
 /// Scale `amount` by `price_bps` basis points (i.e. `amount * price_bps / 10_000`).
@@ -24,8 +32,7 @@ pub enum PositionError {
 /// but we defensively cap at i128::MAX if it would.
 fn scale_by_bps(amount: i128, price_bps: i128) -> i128 {
     let product = amount.saturating_mul(price_bps);
-    let result = product.checked_div(BASIS_POINTS).unwrap_or(i128::MAX);
-    result
+    product.checked_div(BASIS_POINTS).unwrap_or(i128::MAX)
 }
 
 /// Calculate required locked collateral based on net position.
@@ -37,30 +44,67 @@ fn scale_by_bps(amount: i128, price_bps: i128) -> i128 {
 ///
 /// # Returns
 /// Collateral that must remain locked, in the same unit as the share values.
+/// One share pays out one base unit of collateral, so the result is correct
+/// for a token of any `decimals()` without rescaling.
 ///
 /// # Logic
 /// - Net YES  => lock `net_yes * price / 10_000`
 /// - Net NO   => lock `net_no * (10_000 - price) / 10_000`
 /// - Hedged   => lock `0`
 ///
+/// # Errors
+/// [`ContractError::ArithmeticOverflow`] if the net position or the NO-side
+/// price cannot be computed, e.g. when a share balance is negative and
+/// `i128`-extreme.
+///
 /// # Example
 /// ```
 /// // 100 YES shares at a 60% price => 60 units locked
 /// let locked = calculate_locked_collateral(100, 0, 6_000);
-/// assert_eq!(locked, 60);
+/// assert_eq!(locked, Ok(60));
 /// ```
-pub fn calculate_locked_collateral(yes_shares: i128, no_shares: i128, market_price: i128) -> i128 {
+pub fn calculate_locked_collateral(
+    yes_shares: i128,
+    no_shares: i128,
+    market_price: i128,
+) -> Result<i128, ContractError> {
     if yes_shares == no_shares {
-        return 0;
+        return Ok(0);
     }
 
     if yes_shares > no_shares {
-        scale_by_bps(yes_shares - no_shares, market_price)
+        let net_yes = yes_shares
+            .checked_sub(no_shares)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        Ok(scale_by_bps(net_yes, market_price))
     } else {
-        scale_by_bps(no_shares - yes_shares, BASIS_POINTS - market_price)
+        let net_no = no_shares
+            .checked_sub(yes_shares)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        let no_price = BASIS_POINTS
+            .checked_sub(market_price)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        Ok(scale_by_bps(net_no, no_price))
     }
 }
 
+/// Mark-to-market value of a position at `market_price`.
+///
+/// Unlike [`calculate_locked_collateral`], which nets the two legs, this
+/// values both: `yes_shares * price / 10_000 + no_shares * (10_000 - price) /
+/// 10_000`. A hedged pair is therefore worth its face value. `market_price`
+/// is clamped to 0–10_000 and the sum saturates instead of overflowing.
+///
+/// # Example
+/// ```ignore
+/// // 100 YES and 50 NO at a 60% price => 60 + 20
+/// assert_eq!(position_value(100, 50, 6_000), 80);
+/// ```
+pub fn position_value(yes_shares: i128, no_shares: i128, market_price: i128) -> i128 {
+    let price = market_price.clamp(0, BASIS_POINTS);
+    scale_by_bps(yes_shares, price).saturating_add(scale_by_bps(no_shares, BASIS_POINTS - price))
+}
+
 /// Validate whether a proposed position change is allowed.
 ///
 /// # Errors
@@ -163,18 +207,9 @@ pub fn update_position(
         .map_err(|_| PositionError::InvalidMarketPrice)?;
 
     // 1. Load or initialize position
-    let mut position =
-        crate::storage::get_position(env, market_id, user)
-            .unwrap_or_else(|_| None)
-            .unwrap_or_else(|| Position {
-                market_id,
-                user: user.clone(),
-                yes_shares: 0,
-                no_shares: 0,
-                locked_collateral: 0,
-                total_deposited: 0,
-                is_settled: false,
-            });
+    let mut position = crate::storage::get_position(env, market_id, user)
+        .unwrap_or_else(|_| None)
+        .unwrap_or_else(|| Position::new_empty(market_id, user.clone(), env.ledger().timestamp()));
 
     // 2. Validate deltas
     let side_yes = position_limit_exceeded_side(&position, yes_delta, no_delta);
@@ -189,10 +224,12 @@ pub fn update_position(
 
     // 4. Recalculate locked collateral
     let new_locked =
-        calculate_locked_collateral(position.yes_shares, position.no_shares, market_price);
+        calculate_locked_collateral(position.yes_shares, position.no_shares, market_price)
+            .map_err(|_| PositionError::ArithmeticOverflow)?;
     position.locked_collateral = new_locked;
 
     // 5. Persist
+    position.updated_at = env.ledger().timestamp();
     crate::storage::set_position(env, market_id, user, &position)
         .unwrap_or_default();
 
@@ -286,32 +323,127 @@ mod tests {
             resolved_at: None,
             adapter_type: AdapterType::Ed25519,
             outcome_count: 2,
+            closed_to_deposits: false,
+            source_hash: BytesN::from_array(env, &[0u8; 32]),
+            resolution_grace: None,
+            fee_rate_bps: None,
+            withdraw_cooldown: None,
+            challenge_deadline: None,
+            trading_fee_bps: 0,
+            collateral_decimals: 7,
+            total_yes_shares: 0,
+            total_no_shares: 0,
+            yes_reserve: 0,
+            no_reserve: 0,
+            category: soroban_sdk::Symbol::new(env, "general"),
+            resolution_source: String::from_str(env, "test source"),
+            kind: crate::types::MarketKind::Binary,
+            settled_value: None,
+            min_deposit: 0,
+            max_position: 0,
+            max_participants: 0,
         }
     }
 
     #[test]
     fn test_calculate_locked_collateral_net_yes() {
-        let locked = calculate_locked_collateral(100 * STROOPS_PER_USDC, 0, 6000);
+        let locked = calculate_locked_collateral(100 * STROOPS_PER_USDC, 0, 6000).unwrap();
         assert_eq!(locked, 60 * STROOPS_PER_USDC);
 
         let locked =
-            calculate_locked_collateral(100 * STROOPS_PER_USDC, 30 * STROOPS_PER_USDC, 5000);
+            calculate_locked_collateral(100 * STROOPS_PER_USDC, 30 * STROOPS_PER_USDC, 5000)
+                .unwrap();
         assert_eq!(locked, 35 * STROOPS_PER_USDC);
     }
 
     #[test]
     fn test_calculate_locked_collateral_net_no() {
-        let locked = calculate_locked_collateral(0, 100 * STROOPS_PER_USDC, 6000);
+        let locked = calculate_locked_collateral(0, 100 * STROOPS_PER_USDC, 6000).unwrap();
         assert_eq!(locked, 40 * STROOPS_PER_USDC);
     }
 
     #[test]
     fn test_calculate_locked_collateral_hedged() {
         let locked =
-            calculate_locked_collateral(100 * STROOPS_PER_USDC, 100 * STROOPS_PER_USDC, 6000);
+            calculate_locked_collateral(100 * STROOPS_PER_USDC, 100 * STROOPS_PER_USDC, 6000)
+                .unwrap();
         assert_eq!(locked, 0);
     }
 
+    #[test]
+    fn test_position_value_long_yes() {
+        assert_eq!(
+            position_value(100 * STROOPS_PER_USDC, 0, 6000),
+            60 * STROOPS_PER_USDC
+        );
+    }
+
+    #[test]
+    fn test_position_value_long_no() {
+        assert_eq!(
+            position_value(0, 100 * STROOPS_PER_USDC, 6000),
+            40 * STROOPS_PER_USDC
+        );
+    }
+
+    #[test]
+    fn test_position_value_hedged_is_worth_face_value() {
+        let shares = 100 * STROOPS_PER_USDC;
+        assert_eq!(position_value(shares, shares, 6000), shares);
+        assert_eq!(position_value(shares, shares, 0), shares);
+        // Locked collateral nets the legs; the mark values both.
+        assert_eq!(calculate_locked_collateral(shares, shares, 6000), Ok(0));
+        // Out-of-range prices are clamped.
+        assert_eq!(position_value(shares, 0, 12_000), shares);
+    }
+
+    #[test]
+    fn test_calculate_locked_collateral_extreme_values() {
+        use crate::error::ContractError;
+
+        // Equal balances at either extreme are hedged.
+        assert_eq!(
+            calculate_locked_collateral(i128::MAX, i128::MAX, 6000),
+            Ok(0)
+        );
+        assert_eq!(
+            calculate_locked_collateral(i128::MIN, i128::MIN, 6000),
+            Ok(0)
+        );
+
+        // Largest representable net positions compute without panicking.
+        assert_eq!(
+            calculate_locked_collateral(i128::MAX, 0, 10_000),
+            Ok(i128::MAX / BASIS_POINTS)
+        );
+        assert_eq!(
+            calculate_locked_collateral(0, i128::MAX, 0),
+            Ok(i128::MAX / BASIS_POINTS)
+        );
+        assert_eq!(
+            calculate_locked_collateral(i128::MAX, i128::MAX - 1, 10_000),
+            Ok(1)
+        );
+        assert_eq!(
+            calculate_locked_collateral(i128::MAX - 1, i128::MAX, 0),
+            Ok(1)
+        );
+
+        // Net positions that do not fit in i128 are rejected, not wrapped.
+        assert_eq!(
+            calculate_locked_collateral(i128::MAX, -1, 6000),
+            Err(ContractError::ArithmeticOverflow)
+        );
+        assert_eq!(
+            calculate_locked_collateral(i128::MIN, 1, 6000),
+            Err(ContractError::ArithmeticOverflow)
+        );
+        assert_eq!(
+            calculate_locked_collateral(0, 1, i128::MIN),
+            Err(ContractError::ArithmeticOverflow)
+        );
+    }
+
     #[test]
     fn test_validate_position_change() {
         let env = setup_env();
@@ -323,6 +455,8 @@ mod tests {
             locked_collateral: 0,
             total_deposited: 0,
             is_settled: false,
+            created_at: 0,
+            updated_at: 0,
         };
 
         assert!(validate_position_change(&position, 10, -20).is_ok());
@@ -355,6 +489,8 @@ mod tests {
             locked_collateral: 0,
             total_deposited: 0,
             is_settled: false,
+            created_at: 0,
+            updated_at: 0,
         };
 
         assert!(can_settle(&position, &market));
@@ -372,6 +508,8 @@ mod tests {
             locked_collateral: 0,
             total_deposited: 0,
             is_settled: true,
+            created_at: 0,
+            updated_at: 0,
         };
 
         assert!(!can_settle(&position, &market));
@@ -518,6 +656,8 @@ mod proptest_tests {
             locked_collateral: 0,
             total_deposited: 0,
             is_settled: false,
+            created_at: 0,
+            updated_at: 0,
         }
     }
 
@@ -531,7 +671,7 @@ mod proptest_tests {
             no in 0i128..=MAX_SAFE_SHARES,
             price in 0i128..=10_000i128,
         ) {
-            let locked = calculate_locked_collateral(yes, no, price);
+            let locked = calculate_locked_collateral(yes, no, price).unwrap();
             prop_assert!(
                 locked >= 0,
                 "locked={locked} yes={yes} no={no} price={price}"
@@ -545,7 +685,7 @@ mod proptest_tests {
             shares in 0i128..=MAX_SAFE_SHARES,
             price in 0i128..=10_000i128,
         ) {
-            prop_assert_eq!(calculate_locked_collateral(shares, shares, price), 0);
+            prop_assert_eq!(calculate_locked_collateral(shares, shares, price).unwrap(), 0);
         }
 
         /// Locked collateral never exceeds the absolute net position.
@@ -556,7 +696,7 @@ mod proptest_tests {
             no in 0i128..=MAX_SAFE_SHARES,
             price in 0i128..=10_000i128,
         ) {
-            let locked = calculate_locked_collateral(yes, no, price);
+            let locked = calculate_locked_collateral(yes, no, price).unwrap();
             let net = (yes - no).abs();
             prop_assert!(
                 locked <= net,
@@ -570,8 +710,8 @@ mod proptest_tests {
         fn prop_locked_symmetric_at_midpoint(
             net in 0i128..=MAX_SAFE_SHARES,
         ) {
-            let yes_heavy = calculate_locked_collateral(net, 0, 5_000);
-            let no_heavy  = calculate_locked_collateral(0, net, 5_000);
+            let yes_heavy = calculate_locked_collateral(net, 0, 5_000).unwrap();
+            let no_heavy  = calculate_locked_collateral(0, net, 5_000).unwrap();
             prop_assert_eq!(yes_heavy, no_heavy);
         }
 
@@ -582,7 +722,7 @@ mod proptest_tests {
             yes in 0i128..=MAX_SAFE_SHARES,
             no in 0i128..=MAX_SAFE_SHARES,
         ) {
-            let locked = calculate_locked_collateral(yes, no, 0);
+            let locked = calculate_locked_collateral(yes, no, 0).unwrap();
             if yes >= no {
                 prop_assert_eq!(locked, 0, "net-YES at price=0 should lock 0");
             } else {
@@ -600,7 +740,7 @@ mod proptest_tests {
             yes in 0i128..=MAX_SAFE_SHARES,
             no in 0i128..=MAX_SAFE_SHARES,
         ) {
-            let locked = calculate_locked_collateral(yes, no, 10_000);
+            let locked = calculate_locked_collateral(yes, no, 10_000).unwrap();
             if no >= yes {
                 prop_assert_eq!(locked, 0, "net-NO at price=10000 should lock 0");
             } else {
@@ -0,0 +1,193 @@
+use crate::error::ContractError;
+use crate::positions::BASIS_POINTS;
+
+/// Virtual YES and NO reserves a new market's curve starts with.
+///
+/// Equal reserves price YES at 50%. The depth sets how far a trade moves the
+/// price: 10_000 whole tokens of a 7-decimal collateral per side.
+pub const INITIAL_RESERVE: i128 = 100_000_000_000;
+
+/// YES price in basis points implied by the curve's reserves.
+///
+/// YES gets more expensive as YES is drawn out of the pool relative to NO:
+/// `no_reserve * 10_000 / (yes_reserve + no_reserve)`. An empty curve prices
+/// YES at 50%.
+///
+/// # Example
+/// ```ignore
+/// assert_eq!(current_price(100, 100), 5_000);
+/// assert_eq!(current_price(25, 75), 7_500);
+/// ```
+pub fn current_price(yes_reserve: i128, no_reserve: i128) -> i128 {
+    let mut yes_reserve = yes_reserve.max(0);
+    let mut no_reserve = no_reserve.max(0);
+    // Reserves this large have precision to spare: scale both down so the
+    // sum and the basis-point product below fit in an `i128`.
+    if yes_reserve > i128::MAX / BASIS_POINTS || no_reserve > i128::MAX / BASIS_POINTS {
+        yes_reserve /= BASIS_POINTS;
+        no_reserve /= BASIS_POINTS;
+    }
+    let total = yes_reserve + no_reserve;
+    if total == 0 {
+        return BASIS_POINTS / 2;
+    }
+
+    let price = no_reserve * BASIS_POINTS / total;
+    price.clamp(0, BASIS_POINTS)
+}
+
+/// Amount drawn from `reserve_out` when `collateral_in` is added to
+/// `reserve_in`, keeping `reserve_in * reserve_out` constant.
+///
+/// The remaining `reserve_out` is rounded up so rounding never favours the
+/// trader. A curve without liquidity on either side returns 0.
+///
+/// # Errors
+/// - [`ContractError::InvalidQuantity`] - a reserve or `collateral_in` is
+///   negative
+/// - [`ContractError::ArithmeticOverflow`] - the reserve product or the new
+///   `reserve_in` does not fit in an `i128`
+pub fn shares_out(
+    reserve_in: i128,
+    reserve_out: i128,
+    collateral_in: i128,
+) -> Result<i128, ContractError> {
+    if reserve_in < 0 || reserve_out < 0 || collateral_in < 0 {
+        return Err(ContractError::InvalidQuantity);
+    }
+    if reserve_in == 0 || reserve_out == 0 || collateral_in == 0 {
+        return Ok(0);
+    }
+
+    let k = reserve_in
+        .checked_mul(reserve_out)
+        .ok_or(ContractError::ArithmeticOverflow)?;
+    let new_in = reserve_in
+        .checked_add(collateral_in)
+        .ok_or(ContractError::ArithmeticOverflow)?;
+    let mut new_out = k / new_in;
+    if k % new_in != 0 {
+        new_out += 1;
+    }
+    Ok(reserve_out - new_out)
+}
+
+/// Move the curve by a trade worth `collateral` toward YES (buying YES or
+/// selling NO) or toward NO (buying NO or selling YES).
+///
+/// The collateral enters the opposite side's reserve and the favoured side's
+/// reserve shrinks by [`shares_out`], raising its price.
+///
+/// # Returns
+/// The new `(yes_reserve, no_reserve)`; unchanged when nothing would be drawn
+/// out.
+///
+/// # Errors
+/// As [`shares_out`].
+pub fn apply_trade(
+    yes_reserve: i128,
+    no_reserve: i128,
+    toward_yes: bool,
+    collateral: i128,
+) -> Result<(i128, i128), ContractError> {
+    let (reserve_in, reserve_out) = if toward_yes {
+        (no_reserve, yes_reserve)
+    } else {
+        (yes_reserve, no_reserve)
+    };
+    let out = shares_out(reserve_in, reserve_out, collateral)?;
+    if out == 0 {
+        return Ok((yes_reserve, no_reserve));
+    }
+
+    let reserve_in = reserve_in
+        .checked_add(collateral)
+        .ok_or(ContractError::ArithmeticOverflow)?;
+    let reserve_out = reserve_out - out;
+    Ok(if toward_yes {
+        (reserve_out, reserve_in)
+    } else {
+        (reserve_in, reserve_out)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symmetric_reserves_price_at_half() {
+        assert_eq!(current_price(INITIAL_RESERVE, INITIAL_RESERVE), 5_000);
+        assert_eq!(current_price(1, 1), 5_000);
+        assert_eq!(current_price(0, 0), 5_000);
+    }
+
+    #[test]
+    fn test_skewed_reserves_price_the_scarce_side_higher() {
+        assert_eq!(current_price(25, 75), 7_500);
+        assert_eq!(current_price(75, 25), 2_500);
+        assert_eq!(current_price(0, 10), 10_000);
+        assert_eq!(current_price(10, 0), 0);
+    }
+
+    #[test]
+    fn test_current_price_handles_extreme_reserves() {
+        assert_eq!(current_price(i128::MAX / 2, i128::MAX / 2), 5_000);
+        assert_eq!(current_price(i128::MAX, i128::MAX), 5_000);
+        assert_eq!(current_price(-5, 10), 10_000);
+    }
+
+    #[test]
+    fn test_shares_out_keeps_product_constant() {
+        // 100 * 100 = 10_000 = 125 * 80
+        assert_eq!(shares_out(100, 100, 25), Ok(20));
+        // Rounding keeps the product at or above k.
+        let out = shares_out(100, 100, 30).unwrap();
+        assert_eq!(out, 23);
+        assert!((100 - out) * 130 >= 100 * 100);
+    }
+
+    #[test]
+    fn test_shares_out_on_skewed_reserves() {
+        // 300 * 100 = 30_000 = 400 * 75
+        assert_eq!(shares_out(300, 100, 100), Ok(25));
+        // 100 * 300 = 30_000 = 200 * 150
+        assert_eq!(shares_out(100, 300, 100), Ok(150));
+    }
+
+    #[test]
+    fn test_shares_out_edge_cases() {
+        assert_eq!(shares_out(100, 100, 0), Ok(0));
+        assert_eq!(shares_out(0, 100, 10), Ok(0));
+        assert_eq!(shares_out(100, 0, 10), Ok(0));
+        assert_eq!(shares_out(-1, 100, 10), Err(ContractError::InvalidQuantity));
+        assert_eq!(
+            shares_out(100, 100, -1),
+            Err(ContractError::InvalidQuantity)
+        );
+        assert_eq!(
+            shares_out(i128::MAX, 2, 1),
+            Err(ContractError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn test_apply_trade_moves_price_toward_the_traded_side() {
+        let start = current_price(INITIAL_RESERVE, INITIAL_RESERVE);
+
+        let (yes, no) = apply_trade(INITIAL_RESERVE, INITIAL_RESERVE, true, 1_000_000_000).unwrap();
+        assert!(current_price(yes, no) > start);
+        assert_eq!(no, INITIAL_RESERVE + 1_000_000_000);
+
+        let (yes, no) =
+            apply_trade(INITIAL_RESERVE, INITIAL_RESERVE, false, 1_000_000_000).unwrap();
+        assert!(current_price(yes, no) < start);
+        assert_eq!(yes, INITIAL_RESERVE + 1_000_000_000);
+    }
+
+    #[test]
+    fn test_apply_trade_without_liquidity_is_a_no_op() {
+        assert_eq!(apply_trade(0, 0, true, 1_000), Ok((0, 0)));
+        assert_eq!(apply_trade(100, 100, false, 0), Ok((100, 100)));
+    }
+}
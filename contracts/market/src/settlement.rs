@@ -1,6 +1,6 @@
 use crate::error::ContractError;
 use crate::storage;
-use crate::types::{AdapterType, Market, MarketStatus, Position};
+use crate::types::{Market, MarketKind, MarketStatus, Position};
 use soroban_sdk::token::Client as TokenClient;
 use soroban_sdk::{Address, Env, Vec};
 
@@ -20,6 +20,143 @@ pub fn calculate_payout(position: &Position, outcome: bool) -> i128 {
     }
 }
 
+/// Scale a winning position's payout down to its share of a short pool
+///
+/// When `available_collateral` covers `total_winning_shares` every share
+/// pays in full. Otherwise the position receives
+/// `position_winning_shares * available_collateral / total_winning_shares`,
+/// rounded down, so the payouts across all winners never add up to more
+/// than the pool.
+///
+/// # Returns
+/// Payout amount in stroops (1 USDC = 10^7 stroops)
+///
+/// # Errors
+/// - [`ContractError::InvalidQuantity`] - an argument is negative or the
+///   position holds more than `total_winning_shares`
+/// - [`ContractError::ArithmeticOverflow`] - the scaled payout does not fit
+///   in an `i128`
+pub fn calculate_pro_rata_payout(
+    position_winning_shares: i128,
+    total_winning_shares: i128,
+    available_collateral: i128,
+) -> Result<i128, ContractError> {
+    if position_winning_shares < 0
+        || available_collateral < 0
+        || position_winning_shares > total_winning_shares
+    {
+        return Err(ContractError::InvalidQuantity);
+    }
+    if available_collateral >= total_winning_shares {
+        return Ok(position_winning_shares);
+    }
+    // `total_winning_shares` exceeds the non-negative pool here, so it is
+    // positive and the quotient is at most `available_collateral`.
+    Ok(position_winning_shares
+        .checked_mul(available_collateral)
+        .ok_or(ContractError::ArithmeticOverflow)?
+        / total_winning_shares)
+}
+
+/// Pay `winning_shares` out of a resolved binary market's settlement pool
+///
+/// The first settlement snapshots the pool: the deposits of the market's
+/// positions, which are all still unsettled, against the market's total
+/// winning shares. Each winner is then paid pro rata against what is left of
+/// both, so the last winner receives the remainder and rounding never
+/// overdraws the pool. Share totals that cannot account for the position,
+/// such as positions written without going through `update_position`, are
+/// paid face value capped at what is left of the pool, so an undercount never
+/// overdraws it either.
+fn pay_from_settlement_pool(
+    env: &Env,
+    market: &Market,
+    outcome: bool,
+    winning_shares: i128,
+) -> Result<i128, ContractError> {
+    let (available, remaining) =
+        storage::get_settlement_pool(env, market.id).unwrap_or_else(|| {
+            let total_winning = if outcome {
+                market.total_yes_shares
+            } else {
+                market.total_no_shares
+            };
+            (
+                storage::get_market_collateral(env, market.id),
+                total_winning,
+            )
+        });
+
+    let payout = if winning_shares > remaining {
+        winning_shares.min(available)
+    } else {
+        calculate_pro_rata_payout(winning_shares, remaining, available)?
+    };
+
+    storage::set_settlement_pool(
+        env,
+        market.id,
+        available.saturating_sub(payout).max(0),
+        remaining.saturating_sub(winning_shares).max(0),
+    );
+    Ok(payout)
+}
+
+/// Calculate payout for a position in a scalar market settled at
+/// `settled_value`
+///
+/// YES shares are long the value and NO shares short it. The value is
+/// clamped into `[lower, upper]`; at `lower` NO shares pay in full, at
+/// `upper` YES shares do, and in between each side is paid its linear share,
+/// rounded down.
+///
+/// # Returns
+/// Payout amount in stroops (1 USDC = 10^7 stroops)
+///
+/// # Errors
+/// - [`ContractError::InvalidOutcome`] - `lower` is not below `upper`
+/// - [`ContractError::ArithmeticOverflow`] - the band or a payout does not
+///   fit in an `i128`
+pub fn calculate_scalar_payout(
+    position: &Position,
+    settled_value: i128,
+    lower: i128,
+    upper: i128,
+) -> Result<i128, ContractError> {
+    if lower >= upper {
+        return Err(ContractError::InvalidOutcome);
+    }
+    let range = upper
+        .checked_sub(lower)
+        .ok_or(ContractError::ArithmeticOverflow)?;
+    let value = settled_value.clamp(lower, upper);
+
+    let long = position
+        .yes_shares
+        .checked_mul(value - lower)
+        .ok_or(ContractError::ArithmeticOverflow)?
+        / range;
+    let short = position
+        .no_shares
+        .checked_mul(upper - value)
+        .ok_or(ContractError::ArithmeticOverflow)?
+        / range;
+    long.checked_add(short)
+        .ok_or(ContractError::ArithmeticOverflow)
+}
+
+/// Calculate the refund for a position in a canceled market
+///
+/// A canceled market has no outcome, so shares pay nothing and the user gets
+/// back everything they deposited. This is the full deposit rather than just
+/// the locked part: collateral that was never locked is principal too.
+///
+/// # Returns
+/// Refund amount in stroops (1 USDC = 10^7 stroops)
+pub fn calculate_refund(position: &Position) -> i128 {
+    position.total_deposited
+}
+
 /// Check if a position is eligible for settlement
 ///
 /// # Arguments
@@ -29,7 +166,10 @@ pub fn validate_settlement_eligibility(
     position: &Position,
     market: &Market,
 ) -> Result<(), ContractError> {
-    if market.status != MarketStatus::Resolved {
+    if !matches!(
+        market.status,
+        MarketStatus::Resolved | MarketStatus::Canceled
+    ) {
         return Err(ContractError::MarketNotResolved);
     }
 
@@ -54,14 +194,37 @@ fn validate_payout(payout: i128) -> Result<(), ContractError> {
     Ok(())
 }
 
+/// Split a winning payout into the settlement fee and the net paid to the
+/// user, as `(fee, net)`
+///
+/// The fee is `payout * settlement_fee_bps / 10_000` rounded down, so odd
+/// amounts round in the user's favor.
+///
+/// # Errors
+/// - [`ContractError::ArithmeticOverflow`] - the fee does not fit in an
+///   `i128`
+pub fn calculate_settlement_fee(
+    payout: i128,
+    settlement_fee_bps: u32,
+) -> Result<(i128, i128), ContractError> {
+    if payout <= 0 || settlement_fee_bps == 0 {
+        return Ok((0, payout));
+    }
+    let fee = crate::validation::calculate_fee(payout, i128::from(settlement_fee_bps))?;
+    Ok((fee, payout - fee))
+}
+
 /// Execute settlement for a position and return payout
 ///
 /// This function:
 /// 1. Validates settlement eligibility
-/// 2. Calculates payout
+/// 2. Calculates payout, or the principal refund if the market was canceled;
+///    binary winners are scaled pro rata if the pool cannot cover them
 /// 3. Validates payout amount
-/// 4. Marks position as settled
-/// 5. Returns payout amount
+/// 4. Deducts the settlement fee from winnings and accrues it to the market;
+///    refunds are paid in full
+/// 5. Marks position as settled
+/// 6. Returns the net payout
 pub fn execute_settlement(
     env: &Env,
     position: &mut Position,
@@ -74,14 +237,53 @@ pub fn execute_settlement(
     // refund of the user's deposited collateral. This allows resolution
     // flows (or external governance) to indicate that no outcome could be
     // determined and users should be made whole.
-    let payout = match market.result {
-        Some(outcome) => calculate_payout(position, outcome),
-        None => position.total_deposited,
+    let (gross_payout, is_refund) = match (
+        &market.status,
+        &market.kind,
+        market.result,
+        market.settled_value,
+    ) {
+        (MarketStatus::Canceled, ..) => (calculate_refund(position), true),
+        (_, MarketKind::Scalar(bounds), _, Some(value)) => (
+            calculate_scalar_payout(position, value, bounds.lower, bounds.upper)?,
+            false,
+        ),
+        (_, _, Some(outcome), _) => (
+            pay_from_settlement_pool(env, market, outcome, calculate_payout(position, outcome))?,
+            false,
+        ),
+        (_, _, None, _) => (position.total_deposited, true),
+    };
+
+    validate_payout(gross_payout)?;
+
+    let (fee, payout) = if is_refund {
+        (0, gross_payout)
+    } else {
+        calculate_settlement_fee(gross_payout, storage::get_settlement_fee_bps(env))?
     };
+    if fee > 0 {
+        let accrued = storage::get_accrued_fees(env, position.market_id)
+            .checked_add(fee)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        storage::set_accrued_fees(env, position.market_id, accrued);
+    }
 
-    validate_payout(payout)?;
+    // Credit the payout rather than transferring it; `claim` pays it out.
+    if payout > 0 {
+        let claimable = storage::get_claimable(env, position.market_id, &position.user)
+            .checked_add(payout)
+            .ok_or(ContractError::ArithmeticOverflow)?;
+        storage::set_claimable(env, position.market_id, &position.user, claimable);
+    }
 
     position.is_settled = true;
+    if market.status == MarketStatus::Canceled {
+        // The deposit leaves with the refund; zero it so the
+        // canceled-collateral reclaim path cannot pay it out again.
+        position.total_deposited = 0;
+        position.locked_collateral = 0;
+    }
 
     // Emit PositionUpdated so indexers observe the share balance zeroing out
     // on settlement (yes_shares and no_shares are consumed; locked_collateral
@@ -102,22 +304,27 @@ pub fn execute_settlement(
         position.market_id,
         &position.user,
         payout,
+        gross_payout,
         settled_at,
     );
 
     Ok(payout)
 }
 
-/// Settle a user's position in a resolved market and transfer their payout.
+/// Settle a user's position in a resolved or canceled market and transfer
+/// their payout.
 ///
 /// This is the full settlement entry point that completes the
 /// deposit -> resolve -> settle -> receive-funds loop:
 /// 1. Loads the market and the user's position
-/// 2. Validates eligibility, calculates the payout, and marks the position
-///    settled (via [`execute_settlement`], which also emits `PositionSettled`)
+/// 2. Validates eligibility, calculates the payout, marks the position
+///    settled and credits the payout to the user's claimable balance (via
+///    [`execute_settlement`], which also emits `PositionSettled`)
 /// 3. Persists the updated position
-/// 4. Transfers the payout in collateral (SAC) tokens from the contract to the
-///    user
+/// 4. Claims the balance, transferring it in collateral (SAC) tokens from the
+///    contract to the user. A zero payout (losing or empty position) skips
+///    the transfer; the position is still marked settled and
+///    `PositionSettled` reports 0
 ///
 /// # Arguments
 /// * `env` - Contract environment
@@ -130,92 +337,272 @@ pub fn execute_settlement(
 /// # Errors
 /// - [`ContractError::MarketNotFound`] - the market does not exist
 /// - [`ContractError::NoPositionFound`] - the user has no position in the market
-/// - [`ContractError::MarketNotResolved`] - the market has not been resolved
+/// - [`ContractError::MarketNotResolved`] - the market is neither resolved nor
+///   canceled
 /// - [`ContractError::PositionAlreadySettled`] - the position was already settled
 ///
 /// # Events
 /// Emits `PositionSettled` with the payout amount.
 pub fn settle_position(env: &Env, user: &Address, market_id: u32) -> Result<i128, ContractError> {
     user.require_auth();
+    execute_settle_position(env, user, market_id)
+}
 
+/// [`settle_position`] without the authorization check, for entry points that
+/// have already authorized `user`.
+pub(crate) fn execute_settle_position(
+    env: &Env,
+    user: &Address,
+    market_id: u32,
+) -> Result<i128, ContractError> {
     let market = storage::get_market(env, market_id)?.ok_or(ContractError::MarketNotFound)?;
     let mut position =
         storage::get_position(env, market_id, user)?.ok_or(ContractError::NoPositionFound)?;
 
-    // Validates eligibility (Resolved + not already settled), computes the
-    // payout, marks the position settled, and emits the PositionSettled event.
+    // Validates eligibility (Resolved or Canceled + not already settled),
+    // computes the payout, marks the position settled, and emits the
+    // PositionSettled event.
     let payout = execute_settlement(env, &mut position, &market)?;
+    if market.status == MarketStatus::Canceled && payout > 0 {
+        // A refund has been paid, so the market can no longer be reopened.
+        storage::set_refunds_started(env, market_id);
+    }
 
     // Persist the settled position before paying out.
     storage::set_position(env, market_id, user, &position)?;
 
-    // Transfer the payout in collateral tokens from the contract to the user.
-    if payout > 0 {
-        let contract_address = env.current_contract_address();
-        let token_client = TokenClient::new(env, &market.collateral_token);
-        token_client.transfer(&contract_address, user, &payout);
+    execute_claim(env, user, market_id, &market)?;
+    Ok(payout)
+}
+
+/// Transfer `user`'s claimable balance in a market to them.
+///
+/// Batch settlement only credits payouts, so a recipient whose transfer
+/// would fail cannot block anyone else; each user pulls their own balance
+/// here.
+///
+/// # Returns
+/// The amount transferred, in stroops; 0 when nothing is claimable.
+///
+/// # Errors
+/// - [`ContractError::MarketNotFound`] - the market does not exist
+///
+/// # Events
+/// Emits `Claimed` when a non-zero balance is paid out.
+pub fn claim(env: &Env, user: &Address, market_id: u32) -> Result<i128, ContractError> {
+    user.require_auth();
+    let market = storage::get_market(env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+    execute_claim(env, user, market_id, &market)
+}
+
+/// [`claim`] without the authorization check or market lookup.
+fn execute_claim(
+    env: &Env,
+    user: &Address,
+    market_id: u32,
+    market: &Market,
+) -> Result<i128, ContractError> {
+    let amount = storage::get_claimable(env, market_id, user);
+    if amount == 0 {
+        return Ok(0);
     }
+    storage::set_claimable(env, market_id, user, 0);
 
-    Ok(payout)
+    let contract_address = env.current_contract_address();
+    TokenClient::new(env, &market.collateral_token).transfer(&contract_address, user, &amount);
+    crate::events::emit_claimed(env, market_id, user, amount);
+    Ok(amount)
 }
 
 /// Settle multiple users' positions in a single call for a resolved market.
 ///
-/// Iterates over `users`, calling [`settle_position`] for each. Positions that
-/// are already settled, not found, or encounter any other per-user error are
-/// skipped — the batch continues and the total payout across all successfully
-/// settled positions is returned.
+/// Iterates over `users` in order, settling each position exactly as
+/// [`settle_position`] would but only crediting its payout; each user
+/// collects it with [`claim`]. Positions that are
+/// already settled, not found, or encounter any other per-user error are
+/// skipped with a payout of 0 — the batch continues rather than aborting.
 ///
 /// # Arguments
 /// * `env` - Contract environment
 /// * `market_id` - Market identifier (must be resolved)
-/// * `users` - List of user addresses to settle
+/// * `users` - List of user addresses to settle (at most
+///   [`crate::validation::MAX_SETTLE_BATCH_SIZE`])
 ///
 /// # Returns
-/// Total payout transferred across all settled positions, in stroops.
+/// The payout credited to each user, in stroops, in the order of `users`.
 ///
 /// # Errors
-/// - [`ContractError::MarketNotFound`] – the market does not exist
-/// - [`ContractError::MarketNotResolved`] – the market is not resolved; in this
+/// - [`ContractError::InvalidQuantity`] - `users` exceeds
+///   [`crate::validation::MAX_SETTLE_BATCH_SIZE`]
+/// - [`ContractError::MarketNotFound`] - the market does not exist
+/// - [`ContractError::MarketNotResolved`] - the market is not resolved; in this
 ///   case no individual settlements are attempted
-pub fn batch_settle_positions(
+///
+/// # Events
+/// Emits `PositionSettled` for each successfully settled position.
+pub fn settle_positions(
     env: &Env,
     market_id: u32,
     users: Vec<Address>,
-) -> Result<i128, ContractError> {
+) -> Result<Vec<i128>, ContractError> {
+    if users.len() > crate::validation::MAX_SETTLE_BATCH_SIZE {
+        return Err(ContractError::InvalidQuantity);
+    }
+
     // Validate the market once before iterating users.
     let market = storage::get_market(env, market_id)?.ok_or(ContractError::MarketNotFound)?;
     if market.status != MarketStatus::Resolved {
         return Err(ContractError::MarketNotResolved);
     }
 
-    let mut total_payout: i128 = 0;
+    let mut payouts = Vec::new(env);
 
     for user in users.iter() {
-        let Ok(Some(mut position)) = storage::get_position(env, market_id, &user) else {
-            continue;
-        };
+        payouts.push_back(settle_batch_entry(env, &market, market_id, &user));
+    }
+
+    Ok(payouts)
+}
+
+/// Settle a slice of a resolved market's participants, so a market with
+/// more participants than one transaction can process is settled across
+/// several calls.
+///
+/// Visits entries `start_index..start_index + count` of the participant
+/// index behind `get_participants`, settling each as [`settle_positions`]
+/// does: payouts are only credited, and settled or missing positions are
+/// skipped. The index only grows while the market is open, so once it is
+/// resolved successive cursors cover every participant exactly once.
+///
+/// # Arguments
+/// * `env` - Contract environment
+/// * `market_id` - Market identifier (must be resolved)
+/// * `start_index` - Index of the first participant to visit; `0`, then
+///   the cursor returned by the previous call
+/// * `count` - Participants to visit (1 to
+///   [`crate::validation::MAX_SETTLE_BATCH_SIZE`])
+///
+/// # Returns
+/// `(next_index, total_payout)`: where the next call should start, or
+/// `None` once the end of the index has been reached, and the payout
+/// credited by this call in stroops.
+///
+/// # Errors
+/// - [`ContractError::InvalidQuantity`] - `count` is 0 or exceeds
+///   [`crate::validation::MAX_SETTLE_BATCH_SIZE`]
+/// - [`ContractError::MarketNotFound`] - the market does not exist
+/// - [`ContractError::MarketNotResolved`] - the market is not resolved
+///
+/// # Events
+/// Emits `PositionSettled` for each successfully settled position.
+pub fn settle_participants(
+    env: &Env,
+    market_id: u32,
+    start_index: u32,
+    count: u32,
+) -> Result<(Option<u32>, i128), ContractError> {
+    if count == 0 || count > crate::validation::MAX_SETTLE_BATCH_SIZE {
+        return Err(ContractError::InvalidQuantity);
+    }
 
-        // Skip already-settled positions and any unexpected state.
-        let Ok(payout) = execute_settlement(env, &mut position, &market) else {
+    let market = storage::get_market(env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+    if market.status != MarketStatus::Resolved {
+        return Err(ContractError::MarketNotResolved);
+    }
+
+    let participants = storage::get_market_participants(env, market_id);
+    let len = participants.len();
+    let start = start_index.min(len);
+    let end = start.saturating_add(count).min(len);
+
+    let mut total_payout: i128 = 0;
+    for user in participants.slice(start..end).iter() {
+        total_payout =
+            total_payout.saturating_add(settle_batch_entry(env, &market, market_id, &user));
+    }
+
+    let next_index = if end < len { Some(end) } else { None };
+    Ok((next_index, total_payout))
+}
+
+/// Settle one entry of a batch, returning its payout or 0 if it was skipped.
+fn settle_batch_entry(env: &Env, market: &Market, market_id: u32, user: &Address) -> i128 {
+    let Ok(Some(mut position)) = storage::get_position(env, market_id, user) else {
+        return 0;
+    };
+
+    // Skip already-settled positions and any unexpected state.
+    let Ok(payout) = execute_settlement(env, &mut position, market) else {
+        return 0;
+    };
+
+    // Persist the settled flag; skip if storage fails.
+    if storage::set_position(env, market_id, user, &position).is_err() {
+        return 0;
+    }
+
+    payout
+}
+
+/// Settle multiple users' positions in a single call for a resolved market.
+///
+/// Same as [`settle_positions`], but returns only the total payout across all
+/// successfully settled positions.
+///
+/// # Errors
+/// As [`settle_positions`].
+pub fn batch_settle_positions(
+    env: &Env,
+    market_id: u32,
+    users: Vec<Address>,
+) -> Result<i128, ContractError> {
+    let payouts = settle_positions(env, market_id, users)?;
+    Ok(payouts
+        .iter()
+        .fold(0i128, |total, payout| total.saturating_add(payout)))
+}
+
+/// Settle every resolved, unsettled position `user` holds, returning
+/// `(market_id, payout)` for each market settled.
+///
+/// Walks the user's market index in the order positions were opened,
+/// examining at most [`crate::validation::MAX_PAGE_SIZE`] entries per call.
+/// Unresolved markets are skipped and stay in the user's pending index;
+/// settled entries are pruned from it so later calls reach markets further
+/// down the list. The full index behind `get_user_markets` is untouched.
+pub fn settle_all(env: &Env, user: &Address) -> Result<Vec<(u32, i128)>, ContractError> {
+    user.require_auth();
+
+    let markets = storage::get_user_pending_markets(env, user);
+    let scan_end = markets.len().min(crate::validation::MAX_PAGE_SIZE);
+    let mut remaining = Vec::new(env);
+    let mut settled = Vec::new(env);
+
+    for (i, market_id) in markets.iter().enumerate() {
+        if i as u32 >= scan_end {
+            remaining.push_back(market_id);
+            continue;
+        }
+        let Some(mut position) = storage::get_position(env, market_id, user)? else {
             continue;
         };
-
-        // Persist the settled flag; skip if storage fails.
-        if storage::set_position(env, market_id, &user, &position).is_err() {
+        if position.is_settled {
             continue;
         }
-
-        if payout > 0 {
-            let contract_address = env.current_contract_address();
-            let token_client = TokenClient::new(env, &market.collateral_token);
-            token_client.transfer(&contract_address, &user, &payout);
+        let market = storage::get_market(env, market_id)?.ok_or(ContractError::MarketNotFound)?;
+        if market.status != MarketStatus::Resolved {
+            remaining.push_back(market_id);
+            continue;
         }
 
-        total_payout = total_payout.saturating_add(payout);
+        let payout = execute_settlement(env, &mut position, &market)?;
+        storage::set_position(env, market_id, user, &position)?;
+        execute_claim(env, user, market_id, &market)?;
+        settled.push_back((market_id, payout));
     }
 
-    Ok(total_payout)
+    storage::set_user_pending_markets(env, user, &remaining);
+    Ok(settled)
 }
 
 /// Calculate what a user would receive if they settled now
@@ -227,9 +614,12 @@ pub fn calculate_potential_payout(position: &Position, market: &Market) -> Optio
     // If the market is resolved but has no winning outcome (result == None)
     // then the potential payout is the full deposited collateral (refund).
     if market.status == MarketStatus::Resolved {
-        match market.result {
-            Some(outcome) => Some(calculate_payout(position, outcome)),
-            None => Some(position.total_deposited),
+        match (&market.kind, market.result, market.settled_value) {
+            (MarketKind::Scalar(bounds), _, Some(value)) => {
+                calculate_scalar_payout(position, value, bounds.lower, bounds.upper).ok()
+            }
+            (_, Some(outcome), _) => Some(calculate_payout(position, outcome)),
+            _ => Some(position.total_deposited),
         }
     } else {
         None
@@ -255,11 +645,25 @@ pub fn calculate_market_settlement_stats(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::AdapterType;
     use soroban_sdk::{
-        testutils::{Address as _, Events},
+        testutils::{Address as _, Events, Ledger as _},
         Address, BytesN, Env, String,
     };
 
+    fn test_metadata(env: &Env) -> crate::types::MarketMetadata {
+        crate::types::MarketMetadata {
+            uri: None,
+            category: soroban_sdk::Symbol::new(env, "general"),
+            resolution_source: String::from_str(env, "test source"),
+            kind: crate::types::MarketKind::Binary,
+            min_deposit: 0,
+            max_position: 0,
+            max_participants: 0,
+            derive_id: false,
+        }
+    }
+
     fn create_test_market(env: &Env, status: MarketStatus, result: Option<bool>) -> Market {
         Market {
             id: 1,
@@ -276,6 +680,25 @@ mod tests {
             resolved_at: None,
             adapter_type: AdapterType::Ed25519,
             outcome_count: 2,
+            closed_to_deposits: false,
+            source_hash: BytesN::from_array(env, &[0u8; 32]),
+            resolution_grace: None,
+            fee_rate_bps: None,
+            withdraw_cooldown: None,
+            challenge_deadline: None,
+            trading_fee_bps: 0,
+            collateral_decimals: 7,
+            total_yes_shares: 0,
+            total_no_shares: 0,
+            yes_reserve: 0,
+            no_reserve: 0,
+            category: soroban_sdk::Symbol::new(env, "general"),
+            resolution_source: String::from_str(env, "test source"),
+            kind: crate::types::MarketKind::Binary,
+            settled_value: None,
+            min_deposit: 0,
+            max_position: 0,
+            max_participants: 0,
         }
     }
 
@@ -288,6 +711,8 @@ mod tests {
             locked_collateral: yes + no, // simplified
             total_deposited: yes + no,
             is_settled: settled,
+            created_at: 0,
+            updated_at: 0,
         }
     }
 
@@ -320,6 +745,69 @@ mod tests {
         assert_eq!(calculate_payout(&pos, true), 0);
     }
 
+    #[test]
+    fn test_calculate_scalar_payout_at_lower_bound() {
+        let env = Env::default();
+        let pos = create_test_position(&env, 600, 400, false);
+        // Only the short (NO) side is paid at the lower bound.
+        assert_eq!(calculate_scalar_payout(&pos, 100, 100, 200), Ok(400));
+        assert_eq!(calculate_scalar_payout(&pos, 50, 100, 200), Ok(400));
+    }
+
+    #[test]
+    fn test_calculate_scalar_payout_at_upper_bound() {
+        let env = Env::default();
+        let pos = create_test_position(&env, 600, 400, false);
+        // Only the long (YES) side is paid at the upper bound.
+        assert_eq!(calculate_scalar_payout(&pos, 200, 100, 200), Ok(600));
+        assert_eq!(calculate_scalar_payout(&pos, 250, 100, 200), Ok(600));
+    }
+
+    #[test]
+    fn test_calculate_scalar_payout_at_midpoint() {
+        let env = Env::default();
+        let pos = create_test_position(&env, 600, 400, false);
+        assert_eq!(calculate_scalar_payout(&pos, 150, 100, 200), Ok(300 + 200));
+        // A complete YES/NO pair is worth one unit anywhere in the band.
+        let pair = create_test_position(&env, 1_000, 1_000, false);
+        assert_eq!(calculate_scalar_payout(&pair, 175, 100, 200), Ok(1_000));
+        assert_eq!(calculate_scalar_payout(&pair, -7, -10, 10), Ok(1_000));
+    }
+
+    #[test]
+    fn test_calculate_scalar_payout_rejects_bad_bounds() {
+        let env = Env::default();
+        let pos = create_test_position(&env, 600, 400, false);
+        assert_eq!(
+            calculate_scalar_payout(&pos, 100, 100, 100),
+            Err(ContractError::InvalidOutcome)
+        );
+        assert_eq!(
+            calculate_scalar_payout(&pos, 100, i128::MIN, i128::MAX),
+            Err(ContractError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn test_execute_settlement_pays_scalar_markets_by_value() {
+        use crate::types::{MarketKind, ScalarBounds};
+
+        let env = Env::default();
+        let contract_id = env.register(crate::MarketContract, ());
+        let mut market = create_test_market(&env, MarketStatus::Resolved, None);
+        market.kind = MarketKind::Scalar(ScalarBounds {
+            lower: 0,
+            upper: 100,
+        });
+        market.settled_value = Some(25);
+        let mut pos = create_test_position(&env, 400, 400, false);
+
+        assert_eq!(calculate_potential_payout(&pos, &market), Some(400));
+        let payout = env.as_contract(&contract_id, || execute_settlement(&env, &mut pos, &market));
+        assert_eq!(payout, Ok(100 + 300));
+        assert!(pos.is_settled);
+    }
+
     #[test]
     fn test_validate_settlement_not_resolved() {
         let env = Env::default();
@@ -330,6 +818,16 @@ mod tests {
         assert_eq!(result, Err(ContractError::MarketNotResolved));
     }
 
+    #[test]
+    fn test_validate_settlement_pending_resolution() {
+        let env = Env::default();
+        let market = create_test_market(&env, MarketStatus::PendingResolution, Some(true));
+        let pos = create_test_position(&env, 100, 0, false);
+
+        let result = validate_settlement_eligibility(&pos, &market);
+        assert_eq!(result, Err(ContractError::MarketNotResolved));
+    }
+
     #[test]
     fn test_validate_settlement_already_settled() {
         let env = Env::default();
@@ -348,6 +846,7 @@ mod tests {
         let mut pos = create_test_position(&env, 100, 0, false);
 
         let payout = env.as_contract(&contract_id, || {
+            storage::set_settlement_pool(&env, market.id, 100, 100);
             execute_settlement(&env, &mut pos, &market).unwrap()
         });
         assert_eq!(payout, 100);
@@ -362,6 +861,7 @@ mod tests {
         let mut pos = create_test_position(&env, 100, 30, false);
 
         let payout = env.as_contract(&contract_id, || {
+            storage::set_settlement_pool(&env, market.id, 30, 30);
             execute_settlement(&env, &mut pos, &market).unwrap()
         });
         assert_eq!(payout, 30);
@@ -379,7 +879,7 @@ mod tests {
         });
 
         let events = env.events().all();
-        assert!(events.len() > 0);
+        assert!(!events.is_empty());
     }
 
     #[test]
@@ -413,6 +913,132 @@ mod tests {
         assert_eq!(payout, 500);
     }
 
+    #[test]
+    fn test_pro_rata_payout_exactly_sufficient_pool() {
+        assert_eq!(calculate_pro_rata_payout(300, 1_000, 1_000), Ok(300));
+        assert_eq!(calculate_pro_rata_payout(1_000, 1_000, 1_000), Ok(1_000));
+    }
+
+    #[test]
+    fn test_pro_rata_payout_surplus_pool_pays_face_value() {
+        assert_eq!(calculate_pro_rata_payout(300, 1_000, 5_000), Ok(300));
+        assert_eq!(calculate_pro_rata_payout(0, 1_000, 5_000), Ok(0));
+    }
+
+    #[test]
+    fn test_pro_rata_payout_short_pool_rounds_down() {
+        // 999 of 1_000 shares covered: 333 * 999 / 1_000 = 332.667
+        assert_eq!(calculate_pro_rata_payout(333, 1_000, 999), Ok(332));
+        assert_eq!(calculate_pro_rata_payout(500, 1_000, 0), Ok(0));
+
+        // Three equal winners never receive more than the pool between them.
+        let share = calculate_pro_rata_payout(1, 3, 2).unwrap();
+        assert!(share * 3 <= 2);
+    }
+
+    #[test]
+    fn test_pro_rata_payout_rejects_invalid_arguments() {
+        assert_eq!(
+            calculate_pro_rata_payout(-1, 1_000, 500),
+            Err(ContractError::InvalidQuantity)
+        );
+        assert_eq!(
+            calculate_pro_rata_payout(100, 1_000, -1),
+            Err(ContractError::InvalidQuantity)
+        );
+        assert_eq!(
+            calculate_pro_rata_payout(1_001, 1_000, 500),
+            Err(ContractError::InvalidQuantity)
+        );
+        assert_eq!(
+            calculate_pro_rata_payout(i128::MAX - 1, i128::MAX, i128::MAX - 1),
+            Err(ContractError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn test_execute_settlement_scales_winners_to_a_short_pool() {
+        let env = Env::default();
+        let contract_id = env.register(crate::MarketContract, ());
+        let mut market = create_test_market(&env, MarketStatus::Resolved, Some(true));
+        market.total_yes_shares = 1_000;
+
+        // Only 900 of the 1_000 winning shares are backed by collateral.
+        let payouts = env.as_contract(&contract_id, || {
+            storage::set_settlement_pool(&env, market.id, 900, 1_000);
+            let mut first = create_test_position(&env, 333, 0, false);
+            let mut second = create_test_position(&env, 333, 0, false);
+            let mut last = create_test_position(&env, 334, 0, false);
+            [
+                execute_settlement(&env, &mut first, &market).unwrap(),
+                execute_settlement(&env, &mut second, &market).unwrap(),
+                execute_settlement(&env, &mut last, &market).unwrap(),
+            ]
+        });
+
+        assert_eq!(payouts, [299, 300, 301]);
+        assert_eq!(payouts.iter().sum::<i128>(), 900);
+    }
+
+    #[test]
+    fn test_execute_settlement_caps_undercounted_winner_at_pool() {
+        let env = Env::default();
+        let contract_id = env.register(crate::MarketContract, ());
+        let market = create_test_market(&env, MarketStatus::Resolved, Some(true));
+
+        // The share totals count 200 winning shares, but one position alone
+        // holds 300: face value would overdraw the 250 left in the pool.
+        let (payouts, pool) = env.as_contract(&contract_id, || {
+            storage::set_settlement_pool(&env, market.id, 250, 200);
+            let mut first = create_test_position(&env, 300, 0, false);
+            let mut second = create_test_position(&env, 300, 0, false);
+            let payouts = [
+                execute_settlement(&env, &mut first, &market).unwrap(),
+                execute_settlement(&env, &mut second, &market).unwrap(),
+            ];
+            (payouts, storage::get_settlement_pool(&env, market.id))
+        });
+
+        assert_eq!(payouts, [250, 0]);
+        assert_eq!(pool, Some((0, 0)));
+    }
+
+    #[test]
+    fn test_settlement_fee_zero_pays_face_value() {
+        assert_eq!(calculate_settlement_fee(1_000, 0), Ok((0, 1_000)));
+        assert_eq!(calculate_settlement_fee(0, 500), Ok((0, 0)));
+    }
+
+    #[test]
+    fn test_settlement_fee_splits_and_rounds_down() {
+        assert_eq!(calculate_settlement_fee(1_000, 200), Ok((20, 980)));
+        // 2% of 999 is 19.98; the fraction stays with the user.
+        assert_eq!(calculate_settlement_fee(999, 200), Ok((19, 980)));
+        assert_eq!(calculate_settlement_fee(49, 200), Ok((0, 49)));
+    }
+
+    #[test]
+    fn test_execute_settlement_deducts_fee_from_winnings_only() {
+        let env = Env::default();
+        let contract_id = env.register(crate::MarketContract, ());
+        let market = create_test_market(&env, MarketStatus::Resolved, Some(true));
+        let canceled = create_test_market(&env, MarketStatus::Canceled, None);
+
+        let (payout, refund, accrued) = env.as_contract(&contract_id, || {
+            storage::set_settlement_fee_bps(&env, 100);
+            storage::set_settlement_pool(&env, market.id, 1_001, 1_001);
+            let mut winner = create_test_position(&env, 1_001, 0, false);
+            let payout = execute_settlement(&env, &mut winner, &market).unwrap();
+            let mut refunded = create_test_position(&env, 1_001, 0, false);
+            let refund = execute_settlement(&env, &mut refunded, &canceled).unwrap();
+            (payout, refund, storage::get_accrued_fees(&env, market.id))
+        });
+
+        assert_eq!(payout, 991);
+        assert_eq!(refund, 1_001);
+        assert_eq!(accrued, 10);
+    }
+
     #[test]
     fn test_validate_payout_valid() {
         assert!(validate_payout(0).is_ok());
@@ -443,6 +1069,48 @@ mod tests {
         assert!(pos.is_settled);
     }
 
+    #[test]
+    fn test_calculate_refund_returns_full_deposit() {
+        let env = Env::default();
+        let mut pos = create_test_position(&env, 100, 30, false);
+        pos.locked_collateral = 100;
+        assert_eq!(calculate_refund(&pos), 130);
+    }
+
+    #[test]
+    fn test_execute_settlement_canceled_refunds_hedged_position() {
+        let env = Env::default();
+        let contract_id = env.register(crate::MarketContract, ());
+        // A stale result must not matter once the market is canceled.
+        let market = create_test_market(&env, MarketStatus::Canceled, Some(true));
+        let mut pos = create_test_position(&env, 100, 30, false);
+
+        let payout = env.as_contract(&contract_id, || {
+            execute_settlement(&env, &mut pos, &market).unwrap()
+        });
+
+        assert_eq!(payout, 130);
+        assert!(pos.is_settled);
+        assert_eq!(pos.total_deposited, 0);
+        assert_eq!(pos.locked_collateral, 0);
+    }
+
+    #[test]
+    fn test_execute_settlement_canceled_refunds_one_sided_position() {
+        let env = Env::default();
+        let contract_id = env.register(crate::MarketContract, ());
+        let market = create_test_market(&env, MarketStatus::Canceled, None);
+        // NO-only position: an outcome payout for YES would be 0.
+        let mut pos = create_test_position(&env, 0, 80, false);
+
+        let payout = env.as_contract(&contract_id, || {
+            execute_settlement(&env, &mut pos, &market).unwrap()
+        });
+
+        assert_eq!(payout, 80);
+        assert!(pos.is_settled);
+    }
+
     /// End-to-end settlement through the contract client, asserting that the
     /// SAC token payout actually reaches the user:
     /// init -> create market -> deposit -> buy -> resolve -> settle.
@@ -490,24 +1158,42 @@ mod tests {
             &end_time,
             &oracle_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &BytesN::from_array(&env, &[0u8; 32]),
+            &None,
+            &None,
+            &0,
         );
 
         // Deposit collateral.
         let user = Address::generate(&env);
         let deposit = 100 * STROOPS_PER_USDC;
         sac.mint(&user, &deposit);
-        client.deposit_collateral(&user, &market_id, &deposit);
+        client.deposit_collateral(&user, &market_id, &deposit, &None);
 
         // Buy YES shares so the resolved position has a payout.
         let yes_shares = 100 * STROOPS_PER_USDC;
         client.update_position(&user, &market_id, &yes_shares, &0i128, &5_000i128);
 
         // Resolve the market (YES wins) with a valid oracle signature.
-        let message = crate::oracle::construct_oracle_message(&env, market_id, outcome);
+        let message = crate::oracle::construct_oracle_message(
+            &env,
+            market_id,
+            0,
+            &BytesN::from_array(&env, &[0u8; 32]),
+            outcome,
+        );
         let sig_bytes = signing_key.sign(message.to_array().as_slice()).to_bytes();
         let signature = BytesN::from_array(&env, &sig_bytes);
-        let market_id_str = String::from_str(&env, "1");
-        client.resolve_market(&market_id_str, &outcome, &signature);
+        env.ledger().set_timestamp(end_time);
+        client.resolve_market(
+            &Address::generate(&env),
+            &1,
+            &outcome.into(),
+            &None,
+            &signature,
+            &0,
+        );
 
         // Before settling, the contract holds the deposit and the user holds nothing.
         assert_eq!(token_client.balance(&user), 0);
@@ -529,7 +1215,7 @@ mod tests {
 
         // Settling a second time is rejected.
         let second = client.try_settle_position(&user, &market_id);
-        assert!(second.is_err());
+        assert_eq!(second, Err(Ok(ContractError::PositionAlreadySettled)));
     }
 
     #[test]
@@ -564,12 +1250,17 @@ mod tests {
             &end_time,
             &oracle_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &BytesN::from_array(&env, &[0u8; 32]),
+            &None,
+            &None,
+            &0,
         );
 
         let user = Address::generate(&env);
         let deposit = 50 * STROOPS_PER_USDC;
         StellarAssetClient::new(&env, &collateral_token).mint(&user, &deposit);
-        client.deposit_collateral(&user, &market_id, &deposit);
+        client.deposit_collateral(&user, &market_id, &deposit, &None);
 
         // The market is still Active, so settlement must be rejected (#3).
         let result = client.try_settle_position(&user, &market_id);
@@ -614,23 +1305,46 @@ mod tests {
         let client = crate::MarketContractClient::new(&env, &contract_id);
         let question = String::from_str(&env, "Batch settle test?");
         let end_time = env.ledger().timestamp() + 86_400;
-        let market_id =
-            client.initialize_market(&admin, &question, &end_time, &oracle_pubkey, &collateral_token);
+        let market_id = client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &BytesN::from_array(&env, &[0u8; 32]),
+            &None,
+            &None,
+            &0,
+        );
 
         // Mint and deposit for two users with YES shares
         for _ in 0..2u8 {
             let u = Address::generate(&env);
             sac.mint(&u, &(100_000_000i128));
-            client.deposit_collateral(&u, &market_id, &(100_000_000i128));
+            client.deposit_collateral(&u, &market_id, &(100_000_000i128), &None);
             client.update_position(&u, &market_id, &(100_000_000i128), &0i128, &5_000i128);
         }
 
         // Resolve YES
-        let message = crate::oracle::construct_oracle_message(&env, market_id, outcome);
+        let message = crate::oracle::construct_oracle_message(
+            &env,
+            market_id,
+            0,
+            &BytesN::from_array(&env, &[0u8; 32]),
+            outcome,
+        );
         let sig_bytes = signing_key.sign(message.to_array().as_slice()).to_bytes();
         let signature = BytesN::from_array(&env, &sig_bytes);
-        let market_id_str = String::from_str(&env, "1");
-        client.resolve_market(&market_id_str, &outcome, &signature);
+        env.ledger().set_timestamp(end_time);
+        client.resolve_market(
+            &Address::generate(&env),
+            &1,
+            &outcome.into(),
+            &None,
+            &signature,
+            &0,
+        );
 
         (env, contract_id, market_id, collateral_token)
     }
@@ -658,8 +1372,18 @@ mod tests {
         let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
         let question = String::from_str(&env, "Still active?");
         let end_time = env.ledger().timestamp() + 86_400;
-        let market_id =
-            client.initialize_market(&admin, &question, &end_time, &oracle_pubkey, &collateral_token);
+        let market_id = client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &BytesN::from_array(&env, &[0u8; 32]),
+            &None,
+            &None,
+            &0,
+        );
 
         let users: soroban_sdk::Vec<Address> = soroban_sdk::Vec::new(&env);
         let result = env.as_contract(&contract_id, || {
@@ -684,6 +1408,68 @@ mod tests {
         assert_eq!(result, Err(ContractError::MarketNotFound));
     }
 
+    #[test]
+    fn test_settle_participants_in_chunks_matches_single_call() {
+        // Credited payouts of the two participants, once both are settled.
+        fn claimables(env: &soroban_sdk::Env, market_id: u32) -> [i128; 2] {
+            let participants = storage::get_market_participants(env, market_id);
+            assert_eq!(participants.len(), 2);
+            core::array::from_fn(|i| {
+                let user = participants.get(i as u32).unwrap();
+                assert!(
+                    storage::get_position(env, market_id, &user)
+                        .unwrap()
+                        .unwrap()
+                        .is_settled
+                );
+                storage::get_claimable(env, market_id, &user)
+            })
+        }
+
+        let (env, contract_id, market_id, _) = setup_resolved_market();
+        let chunked = env.as_contract(&contract_id, || {
+            let (next, first) = settle_participants(&env, market_id, 0, 1).unwrap();
+            assert_eq!(next, Some(1));
+            let (next, second) = settle_participants(&env, market_id, 1, 1).unwrap();
+            assert_eq!(next, None);
+            (first + second, claimables(&env, market_id))
+        });
+
+        let (env, contract_id, market_id, _) = setup_resolved_market();
+        let whole = env.as_contract(&contract_id, || {
+            let (next, total) = settle_participants(&env, market_id, 0, 100).unwrap();
+            assert_eq!(next, None);
+            // A second pass finds nothing left to settle.
+            assert_eq!(settle_participants(&env, market_id, 0, 100), Ok((None, 0)));
+            (total, claimables(&env, market_id))
+        });
+
+        assert_eq!(chunked, whole);
+        assert!(chunked.0 > 0);
+    }
+
+    #[test]
+    fn test_settle_participants_rejects_bad_count() {
+        let (env, contract_id, market_id, _) = setup_resolved_market();
+        env.as_contract(&contract_id, || {
+            assert_eq!(
+                settle_participants(&env, market_id, 0, 0),
+                Err(ContractError::InvalidQuantity)
+            );
+            assert_eq!(
+                settle_participants(
+                    &env,
+                    market_id,
+                    0,
+                    crate::validation::MAX_SETTLE_BATCH_SIZE + 1
+                ),
+                Err(ContractError::InvalidQuantity)
+            );
+            // Starting past the end is a no-op that reports completion.
+            assert_eq!(settle_participants(&env, market_id, 50, 10), Ok((None, 0)));
+        });
+    }
+
     #[test]
     fn test_batch_settle_skips_missing_positions() {
         let (env, contract_id, market_id, _) = setup_resolved_market();
@@ -734,6 +1520,11 @@ mod tests {
         let end_time = env.ledger().timestamp() + 86_400;
         let market_id = client.initialize_market(
             &admin, &question, &end_time, &oracle_pubkey, &collateral_token,
+            &test_metadata(&env),
+            &BytesN::from_array(&env, &[0u8; 32]),
+            &None,
+            &None,
+            &0,
         );
 
         // Create two users, both buy YES shares.
@@ -741,17 +1532,30 @@ mod tests {
         let user2 = Address::generate(&env);
         for u in [&user1, &user2] {
             sac.mint(u, &DEPOSIT);
-            client.deposit_collateral(u, &market_id, &DEPOSIT);
+            client.deposit_collateral(u, &market_id, &DEPOSIT, &None);
             client.update_position(u, &market_id, &SHARES, &0i128, &5_000i128);
         }
 
         // Resolve YES.
         let outcome = true;
-        let message = crate::oracle::construct_oracle_message(&env, market_id, outcome);
+        let message = crate::oracle::construct_oracle_message(
+            &env,
+            market_id,
+            0,
+            &BytesN::from_array(&env, &[0u8; 32]),
+            outcome,
+        );
         let sig_bytes = signing_key.sign(message.to_array().as_slice()).to_bytes();
         let signature = BytesN::from_array(&env, &sig_bytes);
-        let market_id_str = String::from_str(&env, "1");
-        client.resolve_market(&market_id_str, &outcome, &signature);
+        env.ledger().set_timestamp(end_time);
+        client.resolve_market(
+            &Address::generate(&env),
+            &1,
+            &outcome.into(),
+            &None,
+            &signature,
+            &0,
+        );
 
         // Batch settle both users.
         let mut users: soroban_sdk::Vec<Address> = soroban_sdk::Vec::new(&env);
@@ -763,10 +1567,14 @@ mod tests {
         })
         .expect("batch settle should succeed");
 
-        // Both users should receive SHARES each.
+        // Both users are credited SHARES each and receive it once they claim.
         assert_eq!(total_payout, SHARES * 2);
-        assert_eq!(token_client.balance(&user1), SHARES);
-        assert_eq!(token_client.balance(&user2), SHARES);
+        for u in [&user1, &user2] {
+            assert_eq!(token_client.balance(u), 0);
+            assert_eq!(client.get_claimable(&market_id, u), SHARES);
+            assert_eq!(client.claim(u, &market_id), SHARES);
+            assert_eq!(token_client.balance(u), SHARES);
+        }
 
         // Both positions are now marked settled.
         for u in [&user1, &user2] {
@@ -816,18 +1624,37 @@ mod tests {
             &end_time,
             &oracle_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &BytesN::from_array(&env, &[0u8; 32]),
+            &None,
+            &None,
+            &0,
         );
 
         let user = Address::generate(&env);
         StellarAssetClient::new(&env, &collateral_token).mint(&user, &DEPOSIT);
-        client.deposit_collateral(&user, &market_id, &DEPOSIT);
+        client.deposit_collateral(&user, &market_id, &DEPOSIT, &None);
         client.update_position(&user, &market_id, &SHARES, &0i128, &5_000i128);
 
         let outcome = true;
-        let message = crate::oracle::construct_oracle_message(&env, market_id, outcome);
+        let message = crate::oracle::construct_oracle_message(
+            &env,
+            market_id,
+            0,
+            &BytesN::from_array(&env, &[0u8; 32]),
+            outcome,
+        );
         let sig_bytes = signing_key.sign(message.to_array().as_slice()).to_bytes();
         let signature = BytesN::from_array(&env, &sig_bytes);
-        client.resolve_market(&String::from_str(&env, "1"), &outcome, &signature);
+        env.ledger().set_timestamp(end_time);
+        client.resolve_market(
+            &Address::generate(&env),
+            &1,
+            &outcome.into(),
+            &None,
+            &signature,
+            &0,
+        );
 
         // Settle once through the normal path.
         client.settle_position(&user, &market_id);
@@ -1,6 +1,10 @@
 use crate::error::ContractError;
-use crate::types::{Market, Position};
-use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+use crate::types::{
+    AdminResolutionProposal, Dispute, Market, MarketStatus, OracleSet, Position,
+    ResolutionCommitment,
+};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{contracttype, Address, BytesN, Env, String, Symbol, Vec};
 
 /// Bump this constant whenever the storage layout changes in a breaking way.
 /// `initialize()` writes this value; every storage accessor asserts it.
@@ -31,15 +35,18 @@ use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
 /// 5. Initialize: `stellar contract invoke ... -- initialize --admin <addr>`
 /// 6. Verify old deployment returns `UpgradeRequired` error
 ///
-/// ## Current version: 3
+/// ## Current version: 4
 ///
 /// ### Version history:
+/// - **v4:** Extended `Market` (fees, limits, AMM reserves, scalar kind,
+///   resolution grace and challenge window, …) and `Position` (timestamps),
+///   plus new StorageKeys for disputes, settlement pools and indexes
 /// - **v3:** Added Treasury, Outcome Token, Resolution Contract, Threshold Signers
 /// - **v2:** Fixed locked_collateral semantics (#262)
 /// - **v1:** Initial storage layout
 ///
 /// See `STORAGE_MIGRATION_GUIDE.md` and `MIGRATION.md` for detailed history.
-pub const STORAGE_VERSION: u32 = 3;
+pub const STORAGE_VERSION: u32 = 4;
 
 #[contracttype]
 pub enum StorageKey {
@@ -68,6 +75,87 @@ pub enum StorageKey {
     /// Flag indicating the contract is paused for emergency maintenance.
     /// When true, all state-mutating operations are rejected.
     Paused,
+    /// Upper bound (in basis points) that `set_fee_rate` may not exceed.
+    FeeCapBps,
+    /// Ordered list of every market ID created, used by `list_markets`.
+    MarketIds,
+    /// Ledger timestamp of a user's most recent deposit into a market (#413).
+    LastDepositTime(u32, Address),
+    /// Set while an admin renounce proposal awaits confirmation (#414).
+    PendingRenounce,
+    /// Set once the first refund is claimed from a canceled market; after
+    /// that the cancellation is final and `reopen_market` is rejected.
+    RefundsStarted(u32),
+    /// Market IDs in which a user has ever held a position, in the order they
+    /// were opened. Appended when a position is first stored and never
+    /// pruned, so settled and canceled markets stay listed; used by
+    /// `get_user_markets`.
+    UserMarkets(Address),
+    /// Category assigned to a market via `set_market_category`.
+    MarketCategory(u32),
+    /// Market IDs in a category, in the order they were categorized; used by
+    /// `list_markets_by_category`.
+    CategoryMarkets(Symbol),
+    /// Sealed oracle resolution awaiting `reveal_resolution`; trading in the
+    /// market is frozen while it is live.
+    ResolutionCommitment(u32),
+    /// A market's own oracle set for `resolve_market_multi`; absent until
+    /// the admin assigns one.
+    MarketOracleSet(u32),
+    /// Trading fees collected in a market and not yet withdrawn by the admin.
+    AccruedFees(u32),
+    /// Number of markets currently `Active`; kept in step by `set_market`.
+    ActiveMarketCount,
+    /// Unsettled positions in a market; kept in step by `set_position`.
+    UnsettledPositions(u32),
+    /// Sum of `total_deposited` over a market's unsettled positions; kept in
+    /// step by `set_position`.
+    MarketCollateral(u32),
+    /// Unsettled positions across all resolved markets, for `health`.
+    ResolvedUnsettledCount,
+    /// Addresses holding a position in a market, in the order their
+    /// positions were opened. Appended when a position is first stored;
+    /// used by `get_participants`.
+    MarketParticipants(u32),
+    /// Bonded dispute against a market's proposed outcome; present while the
+    /// market is `Disputed`.
+    Dispute(u32),
+    /// Number of events the contract has emitted; each event carries the
+    /// value it was assigned as `event_seq`.
+    EventSeq,
+    /// Active market asking a question, keyed by the question's keccak256
+    /// hash; kept in step by `set_market`.
+    ActiveQuestion(BytesN<32>),
+    /// Furthest ahead of creation, in seconds, a new market's `end_time` may
+    /// lie; defaults to one year when unset.
+    MaxMarketDuration,
+    /// Settlement payout credited to a user in a market and not yet
+    /// transferred; paid out by `claim`.
+    Claimable(u32, Address),
+    /// Most recently created market asking a question, keyed by the
+    /// question's keccak256 hash; unlike `ActiveQuestion` it survives the
+    /// market resolving or being canceled.
+    QuestionMarket(BytesN<32>),
+    /// Collateral still available to a resolved binary market's winners and
+    /// the winning shares not yet settled, as `(available, shares)`;
+    /// snapshotted by the first settlement.
+    SettlementPool(u32),
+    /// Version of the contract code, bumped by each `upgrade`; `1` until the
+    /// first upgrade.
+    ContractVersion,
+    /// Fee in basis points taken from winning settlement payouts; defaults
+    /// to 0 when unset.
+    SettlementFeeBps,
+    /// The part of `UserMarkets` that `settle_all` still has to visit;
+    /// entries whose position has been settled are pruned.
+    UserPendingMarkets(Address),
+    /// Timelocked admin outcome for a market; present from
+    /// `admin_propose_resolution` until it is executed.
+    AdminResolution(u32),
+    /// Set once a deposit carrying this client-supplied idempotency key has
+    /// been processed for the user; a repeat is rejected. Temporary storage,
+    /// expiring [`DEPOSIT_KEY_TTL`] ledgers after the deposit.
+    DepositKey(Address, BytesN<32>),
 }
 
 // --- Version helpers ---
@@ -89,19 +177,110 @@ pub fn assert_version(env: &Env) -> Result<(), ContractError> {
     Ok(())
 }
 
+/// Bring the stored schema up to [`STORAGE_VERSION`] and return the version
+/// it was at.
+///
+/// Each layout change that can be converted in place adds a step below,
+/// keyed on the version it migrates from, so a deployment several versions
+/// behind is walked through every step in order. Versions before 4 changed
+/// the `Market`/`Position` layouts or field semantics and are not
+/// convertible; those deployments must be redeployed as described in
+/// `MIGRATION.md`.
+///
+/// # Errors
+/// - [`ContractError::NotInitialized`] – no version has ever been stored
+/// - [`ContractError::UpgradeRequired`] – the stored version is newer than
+///   this code, or too old to convert in place
+pub fn migrate(env: &Env) -> Result<u32, ContractError> {
+    let from: u32 = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::StorageVersion)
+        .ok_or(ContractError::NotInitialized)?;
+    if !(4..=STORAGE_VERSION).contains(&from) {
+        return Err(ContractError::UpgradeRequired);
+    }
+    // No in-place steps exist yet: v4 is the oldest convertible layout and
+    // the current one. v3 entries cannot be decoded as v4 `Market`/`Position`
+    // values, so v3 deployments are redeployed.
+    set_version(env);
+    Ok(from)
+}
+
+// --- TTL helpers ---
+
+/// Ledgers closed per day at the network's ~5 second close time.
+pub const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// Remaining TTL (in ledgers) below which an entry's TTL is extended.
+pub const TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+
+/// TTL (in ledgers) an entry is extended to once it drops below
+/// [`TTL_THRESHOLD`].
+pub const TTL_EXTEND_TO: u32 = 60 * DAY_IN_LEDGERS;
+
+/// Extend the TTL of the contract instance and the global configuration
+/// entries every operation depends on (schema version, admin, market counter).
+pub fn extend_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+    for key in [
+        StorageKey::StorageVersion,
+        StorageKey::Admin,
+        StorageKey::MarketCounter,
+    ] {
+        extend_persistent_ttl(env, &key);
+    }
+}
+
+/// Extend the TTL of a market entry, if it exists.
+pub fn extend_market_ttl(env: &Env, market_id: u32) {
+    extend_persistent_ttl(env, &StorageKey::Market(market_id));
+}
+
+/// Extend the TTL of a user's position entry in a market, if it exists.
+pub fn extend_position_ttl(env: &Env, market_id: u32, user: &Address) {
+    extend_persistent_ttl(env, &StorageKey::Position(market_id, user.clone()));
+}
+
+fn extend_persistent_ttl(env: &Env, key: &StorageKey) {
+    let storage = env.storage().persistent();
+    if storage.has(key) {
+        storage.extend_ttl(key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+}
+
 // --- Market Storage ---
 
 pub fn get_market(env: &Env, market_id: u32) -> Result<Option<Market>, ContractError> {
     assert_version(env)?;
-    Ok(env.storage().persistent().get(&StorageKey::Market(market_id)))
+    let market: Option<Market> = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::Market(market_id));
+    if market.is_some() {
+        extend_market_ttl(env, market_id);
+    }
+    Ok(market)
 }
 
 pub fn set_market(env: &Env, market_id: u32, market: &Market) -> Result<(), ContractError> {
     assert_version(env)?;
     crate::validation::validate_outcome_count(market.outcome_count)?;
-    env.storage()
+    let key = StorageKey::Market(market_id);
+    let previous: Option<MarketStatus> = env
+        .storage()
         .persistent()
-        .set(&StorageKey::Market(market_id), market);
+        .get::<_, Market>(&key)
+        .map(|m| m.status);
+    update_status_counters(env, market_id, previous.as_ref(), &market.status);
+    update_question_index(env, market_id, previous.as_ref(), market);
+    env.storage().persistent().set(&key, market);
+    if let Some(previous) = previous.filter(|status| *status != market.status) {
+        crate::events::emit_market_status_changed(env, market_id, &previous, &market.status);
+    }
+    extend_market_ttl(env, market_id);
     Ok(())
 }
 
@@ -118,9 +297,27 @@ pub fn get_position(
     user: &Address,
 ) -> Result<Option<Position>, ContractError> {
     assert_version(env)?;
-    Ok(env.storage().persistent().get(&StorageKey::Position(market_id, user.clone())))
+    let position: Option<Position> = env
+        .storage()
+        .persistent()
+        .get(&StorageKey::Position(market_id, user.clone()));
+    if position.is_some() {
+        extend_position_ttl(env, market_id, user);
+    }
+    Ok(position)
 }
 
+/// Store a user's position and keep the market's health counters and
+/// tracked collateral in step with it.
+///
+/// The stored copy is stamped with the current ledger time as `updated_at`;
+/// `created_at` is kept from the stored position, or set to the current
+/// time when there is none.
+///
+/// # Errors
+/// - [`ContractError::UpgradeRequired`] - the storage version is stale
+/// - [`ContractError::ArithmeticOverflow`] - the update would release more
+///   collateral than the market tracks, or overflow its total
 pub fn set_position(
     env: &Env,
     market_id: u32,
@@ -128,7 +325,58 @@ pub fn set_position(
     position: &Position,
 ) -> Result<(), ContractError> {
     assert_version(env)?;
-    env.storage().persistent().set(&StorageKey::Position(market_id, user.clone()), position);
+    let key = StorageKey::Position(market_id, user.clone());
+    let previous: Option<Position> = env.storage().persistent().get(&key);
+    let was_unsettled = previous.as_ref().is_some_and(|p| !p.is_settled);
+    let is_unsettled = !position.is_settled;
+    let previous_collateral = if was_unsettled {
+        previous.as_ref().map_or(0, |p| p.total_deposited)
+    } else {
+        0
+    };
+    let collateral = if is_unsettled {
+        position.total_deposited
+    } else {
+        0
+    };
+    let market_collateral = get_market_collateral(env, market_id);
+    let total_collateral = market_collateral
+        .checked_sub(previous_collateral)
+        .filter(|remaining| *remaining >= 0)
+        .and_then(|remaining| remaining.checked_add(collateral))
+        .ok_or(ContractError::ArithmeticOverflow)?;
+
+    if previous.is_none() {
+        let mut markets = get_user_markets(env, user);
+        markets.push_back(market_id);
+        env.storage()
+            .persistent()
+            .set(&StorageKey::UserMarkets(user.clone()), &markets);
+        let mut pending = get_user_pending_markets(env, user);
+        pending.push_back(market_id);
+        set_user_pending_markets(env, user, &pending);
+        let mut participants = get_market_participants(env, market_id);
+        participants.push_back(user.clone());
+        env.storage()
+            .persistent()
+            .set(&StorageKey::MarketParticipants(market_id), &participants);
+    }
+    if was_unsettled != is_unsettled {
+        update_unsettled_counters(env, market_id, if was_unsettled { -1 } else { 1 });
+    }
+    if total_collateral != market_collateral {
+        env.storage()
+            .persistent()
+            .set(&StorageKey::MarketCollateral(market_id), &total_collateral);
+    }
+    let now = env.ledger().timestamp();
+    let position = Position {
+        created_at: previous.as_ref().map_or(now, |p| p.created_at),
+        updated_at: now,
+        ..position.clone()
+    };
+    env.storage().persistent().set(&key, &position);
+    extend_position_ttl(env, market_id, user);
     Ok(())
 }
 
@@ -137,11 +385,93 @@ pub fn has_position(env: &Env, market_id: u32, user: &Address) -> Result<bool, C
     Ok(env.storage().persistent().has(&StorageKey::Position(market_id, user.clone())))
 }
 
+// --- User Market Index ---
+
+pub fn get_user_markets(env: &Env, user: &Address) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::UserMarkets(user.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn get_user_pending_markets(env: &Env, user: &Address) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::UserPendingMarkets(user.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_user_pending_markets(env: &Env, user: &Address, markets: &Vec<u32>) {
+    env.storage()
+        .persistent()
+        .set(&StorageKey::UserPendingMarkets(user.clone()), markets);
+}
+
+// --- Market Participant Index ---
+
+/// Addresses holding a position in `market_id`, in the order their positions
+/// were opened. Positions are never deleted, so each address appears once.
+pub fn get_market_participants(env: &Env, market_id: u32) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::MarketParticipants(market_id))
+        .unwrap_or(Vec::new(env))
+}
+
+// --- Dispute Storage ---
+
+pub fn get_dispute(env: &Env, market_id: u32) -> Option<Dispute> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::Dispute(market_id))
+}
+
+pub fn set_dispute(env: &Env, market_id: u32, dispute: &Dispute) {
+    env.storage()
+        .persistent()
+        .set(&StorageKey::Dispute(market_id), dispute);
+}
+
+pub fn remove_dispute(env: &Env, market_id: u32) {
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::Dispute(market_id));
+}
+
+// --- Admin Resolution Storage ---
+
+pub fn get_admin_resolution(env: &Env, market_id: u32) -> Option<AdminResolutionProposal> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::AdminResolution(market_id))
+}
+
+pub fn set_admin_resolution(env: &Env, market_id: u32, proposal: &AdminResolutionProposal) {
+    env.storage()
+        .persistent()
+        .set(&StorageKey::AdminResolution(market_id), proposal);
+}
+
+pub fn remove_admin_resolution(env: &Env, market_id: u32) {
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::AdminResolution(market_id));
+}
+
 // --- Admin Storage ---
 
 pub fn get_admin(env: &Env) -> Result<Address, ContractError> {
     assert_version(env)?;
-    Ok(env.storage().persistent().get(&StorageKey::Admin).expect("Admin not set"))
+    get_admin_unversioned(env)
+}
+
+/// Read the admin without the schema-version guard, so `migrate` can
+/// authorize a deployment whose stored version is behind.
+pub fn get_admin_unversioned(env: &Env) -> Result<Address, ContractError> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::Admin)
+        .ok_or(ContractError::NotInitialized)
 }
 
 pub fn set_admin(env: &Env, admin: &Address) {
@@ -177,6 +507,78 @@ pub fn increment_market_id(env: &Env) -> Result<u32, ContractError> {
     Ok(next_id)
 }
 
+/// High bit set on every derived market ID, keeping them clear of the IDs
+/// the counter hands out.
+pub const DERIVED_MARKET_ID_FLAG: u32 = 1 << 31;
+
+/// Content-addressed market ID: the first four bytes, big-endian, of
+/// `keccak256(creator_xdr || question_xdr || end_time_be)`, with
+/// [`DERIVED_MARKET_ID_FLAG`] set.
+///
+/// Market IDs are `u32` throughout the contract, so the digest is truncated
+/// to its top 31 bits; the same creator, question and end time always give
+/// the same ID.
+pub fn derive_market_id(env: &Env, creator: &Address, question: &String, end_time: u64) -> u32 {
+    let mut preimage = creator.clone().to_xdr(env);
+    preimage.append(&question.clone().to_xdr(env));
+    preimage.extend_from_array(&end_time.to_be_bytes());
+    let digest: BytesN<32> = env.crypto().keccak256(&preimage).into();
+    let bytes = digest.to_array();
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) | DERIVED_MARKET_ID_FLAG
+}
+
+// --- Claimable Balance Storage ---
+
+/// Payout credited to `user` in `market_id` and not yet claimed.
+pub fn get_claimable(env: &Env, market_id: u32, user: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::Claimable(market_id, user.clone()))
+        .unwrap_or(0)
+}
+
+/// Set `user`'s claimable balance in `market_id`; 0 removes the entry.
+pub fn set_claimable(env: &Env, market_id: u32, user: &Address, amount: i128) {
+    let key = StorageKey::Claimable(market_id, user.clone());
+    if amount == 0 {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &amount);
+    }
+}
+
+// --- Event Sequence ---
+
+/// Advance the contract-wide event counter and return the new value.
+///
+/// The first event emitted gets sequence 1. Kept in instance storage so it
+/// lives as long as the contract itself.
+pub fn next_event_seq(env: &Env) -> u64 {
+    let seq: u64 = env
+        .storage()
+        .instance()
+        .get(&StorageKey::EventSeq)
+        .unwrap_or(0)
+        + 1;
+    env.storage().instance().set(&StorageKey::EventSeq, &seq);
+    seq
+}
+
+// --- Market Index ---
+
+pub fn get_market_ids(env: &Env) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::MarketIds)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn append_market_id(env: &Env, market_id: u32) {
+    let mut ids = get_market_ids(env);
+    ids.push_back(market_id);
+    env.storage().persistent().set(&StorageKey::MarketIds, &ids);
+}
+
 // --- Treasury Storage ---
 
 pub fn get_treasury(env: &Env) -> Option<Address> {
@@ -192,6 +594,16 @@ pub fn has_treasury(env: &Env) -> bool {
     env.storage().persistent().has(&StorageKey::Treasury)
 }
 
+// --- Resolution Contract Storage ---
+
+pub fn get_resolution_contract(env: &Env) -> Option<Address> {
+    env.storage().persistent().get(&StorageKey::ResolutionContract)
+}
+
+pub fn set_resolution_contract(env: &Env, contract: &Address) {
+    env.storage().persistent().set(&StorageKey::ResolutionContract, contract);
+}
+
 // --- Outcome Token Storage ---
 
 pub fn get_outcome_token_contract(env: &Env) -> Option<Address> {
@@ -256,20 +668,381 @@ pub fn clear_pending_renounce(env: &Env) {
     env.storage().persistent().remove(&StorageKey::PendingRenounce);
 }
 
+// --- Refund Tracking Storage ---
+
+pub fn has_refunds_started(env: &Env, market_id: u32) -> bool {
+    env.storage()
+        .persistent()
+        .has(&StorageKey::RefundsStarted(market_id))
+}
+
+pub fn set_refunds_started(env: &Env, market_id: u32) {
+    env.storage()
+        .persistent()
+        .set(&StorageKey::RefundsStarted(market_id), &true);
+}
+
+// --- Deposit Idempotency Keys ---
+
+/// Ledgers an idempotency key is remembered for after its deposit. Long
+/// enough to cover any resubmission of the same deposit; once it passes,
+/// the entry expires and the key may be used again.
+pub const DEPOSIT_KEY_TTL: u32 = 7 * DAY_IN_LEDGERS;
+
+pub fn has_deposit_key(env: &Env, user: &Address, key: &BytesN<32>) -> bool {
+    env.storage()
+        .temporary()
+        .has(&StorageKey::DepositKey(user.clone(), key.clone()))
+}
+
+pub fn set_deposit_key(env: &Env, user: &Address, key: &BytesN<32>) {
+    let storage = env.storage().temporary();
+    let entry = StorageKey::DepositKey(user.clone(), key.clone());
+    storage.set(&entry, &true);
+    storage.extend_ttl(&entry, DEPOSIT_KEY_TTL, DEPOSIT_KEY_TTL);
+}
+
+// --- Resolution Commitment Storage ---
+
+pub fn get_resolution_commitment(env: &Env, market_id: u32) -> Option<ResolutionCommitment> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::ResolutionCommitment(market_id))
+}
+
+pub fn set_resolution_commitment(env: &Env, market_id: u32, commitment: &ResolutionCommitment) {
+    env.storage()
+        .persistent()
+        .set(&StorageKey::ResolutionCommitment(market_id), commitment);
+}
+
+pub fn remove_resolution_commitment(env: &Env, market_id: u32) {
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::ResolutionCommitment(market_id));
+}
+
+// --- Market Oracle Set Storage ---
+
+pub fn get_market_oracle_set(env: &Env, market_id: u32) -> Option<OracleSet> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::MarketOracleSet(market_id))
+}
+
+pub fn set_market_oracle_set(env: &Env, market_id: u32, oracle_set: &Option<OracleSet>) {
+    let key = StorageKey::MarketOracleSet(market_id);
+    match oracle_set {
+        Some(set) => env.storage().persistent().set(&key, set),
+        None => env.storage().persistent().remove(&key),
+    }
+}
+
+// --- Trading Fee Storage ---
+
+pub fn get_accrued_fees(env: &Env, market_id: u32) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::AccruedFees(market_id))
+        .unwrap_or(0)
+}
+
+pub fn set_accrued_fees(env: &Env, market_id: u32, amount: i128) {
+    env.storage()
+        .persistent()
+        .set(&StorageKey::AccruedFees(market_id), &amount);
+}
+
+// --- Health Counters ---
+//
+// Maintained incrementally by `set_market` and `set_position` so `health`
+// never has to scan markets or positions.
+
+pub fn get_stored_version(env: &Env) -> Option<u32> {
+    env.storage().persistent().get(&StorageKey::StorageVersion)
+}
+
+pub fn get_market_count(env: &Env) -> u32 {
+    // Markets with derived IDs do not advance `MarketCounter`.
+    get_market_ids(env).len()
+}
+
+pub fn get_active_market_count(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::ActiveMarketCount)
+        .unwrap_or(0)
+}
+
+pub fn get_unsettled_positions(env: &Env, market_id: u32) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::UnsettledPositions(market_id))
+        .unwrap_or(0)
+}
+
+pub fn get_market_collateral(env: &Env, market_id: u32) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::MarketCollateral(market_id))
+        .unwrap_or(0)
+}
+
+/// Recompute a market's tracked collateral from its positions: the sum of
+/// `total_deposited` over the unsettled positions of every participant.
+///
+/// Scans the participant index, so it is meant for audits rather than
+/// contract logic, which reads [`get_market_collateral`].
+pub fn sum_unsettled_deposits(env: &Env, market_id: u32) -> Result<i128, ContractError> {
+    let mut total: i128 = 0;
+    for user in get_market_participants(env, market_id).iter() {
+        let Some(position) = get_position(env, market_id, &user)? else {
+            continue;
+        };
+        if !position.is_settled {
+            total = total
+                .checked_add(position.total_deposited)
+                .ok_or(ContractError::ArithmeticOverflow)?;
+        }
+    }
+    Ok(total)
+}
+
+pub fn get_settlement_pool(env: &Env, market_id: u32) -> Option<(i128, i128)> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::SettlementPool(market_id))
+}
+
+pub fn set_settlement_pool(env: &Env, market_id: u32, available: i128, shares: i128) {
+    env.storage()
+        .persistent()
+        .set(&StorageKey::SettlementPool(market_id), &(available, shares));
+}
+
+pub fn get_resolved_unsettled_count(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::ResolvedUnsettledCount)
+        .unwrap_or(0)
+}
+
+fn adjust_counter(env: &Env, key: &StorageKey, delta: i64) {
+    let current: u32 = env.storage().persistent().get(key).unwrap_or(0);
+    let next = (i64::from(current) + delta).clamp(0, i64::from(u32::MAX)) as u32;
+    env.storage().persistent().set(key, &next);
+}
+
+/// Move the active and resolved-unsettled counters for a status change.
+fn update_status_counters(
+    env: &Env,
+    market_id: u32,
+    previous: Option<&MarketStatus>,
+    next: &MarketStatus,
+) {
+    if previous == Some(next) {
+        return;
+    }
+    if previous == Some(&MarketStatus::Active) {
+        adjust_counter(env, &StorageKey::ActiveMarketCount, -1);
+    }
+    if *next == MarketStatus::Active {
+        adjust_counter(env, &StorageKey::ActiveMarketCount, 1);
+    }
+    let unsettled = i64::from(get_unsettled_positions(env, market_id));
+    if previous == Some(&MarketStatus::Resolved) {
+        adjust_counter(env, &StorageKey::ResolvedUnsettledCount, -unsettled);
+    }
+    if *next == MarketStatus::Resolved {
+        adjust_counter(env, &StorageKey::ResolvedUnsettledCount, unsettled);
+    }
+}
+
+/// Index a market's question while it is `Active` and drop it once the
+/// market resolves or is canceled.
+fn update_question_index(
+    env: &Env,
+    market_id: u32,
+    previous: Option<&MarketStatus>,
+    market: &Market,
+) {
+    if previous == Some(&market.status) {
+        return;
+    }
+    let key = StorageKey::ActiveQuestion(question_hash(env, &market.question));
+    if market.status == MarketStatus::Active {
+        env.storage().persistent().set(&key, &market_id);
+    } else if previous == Some(&MarketStatus::Active)
+        && env.storage().persistent().get::<_, u32>(&key) == Some(market_id)
+    {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+/// Move the unsettled-position counters when a position opens or settles.
+fn update_unsettled_counters(env: &Env, market_id: u32, delta: i64) {
+    adjust_counter(env, &StorageKey::UnsettledPositions(market_id), delta);
+    let resolved = env
+        .storage()
+        .persistent()
+        .get::<_, Market>(&StorageKey::Market(market_id))
+        .is_some_and(|m| m.status == MarketStatus::Resolved);
+    if resolved {
+        adjust_counter(env, &StorageKey::ResolvedUnsettledCount, delta);
+    }
+}
+
+// --- Active Question Index ---
+
+/// keccak256 of a market question's bytes.
+pub fn question_hash(env: &Env, question: &String) -> BytesN<32> {
+    env.crypto().keccak256(&question.to_bytes()).into()
+}
+
+/// Active market whose question is exactly `question`, if any.
+pub fn get_active_question_market(env: &Env, question: &String) -> Option<u32> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::ActiveQuestion(question_hash(env, question)))
+}
+
+/// Record `market_id` as the latest market asking `question`.
+pub fn set_question_market(env: &Env, question: &String, market_id: u32) {
+    env.storage().persistent().set(
+        &StorageKey::QuestionMarket(question_hash(env, question)),
+        &market_id,
+    );
+}
+
+/// Most recently created market whose question is exactly `question`, if any.
+pub fn get_question_market(env: &Env, question: &String) -> Option<u32> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::QuestionMarket(question_hash(env, question)))
+}
+
+// --- Category Index Storage ---
+
+pub fn get_market_category(env: &Env, market_id: u32) -> Option<Symbol> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::MarketCategory(market_id))
+}
+
+pub fn get_category_markets(env: &Env, category: &Symbol) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::CategoryMarkets(category.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+/// Assign `market_id` to `category`, removing it from its previous
+/// category's index. Returns the previous category, if any.
+pub fn set_market_category(env: &Env, market_id: u32, category: &Symbol) -> Option<Symbol> {
+    let previous = get_market_category(env, market_id);
+    if previous.as_ref() == Some(category) {
+        return previous;
+    }
+    if let Some(old) = &previous {
+        let mut old_ids = get_category_markets(env, old);
+        if let Some(index) = old_ids.first_index_of(market_id) {
+            old_ids.remove(index);
+        }
+        env.storage()
+            .persistent()
+            .set(&StorageKey::CategoryMarkets(old.clone()), &old_ids);
+    }
+    let mut ids = get_category_markets(env, category);
+    ids.push_back(market_id);
+    env.storage()
+        .persistent()
+        .set(&StorageKey::CategoryMarkets(category.clone()), &ids);
+    env.storage()
+        .persistent()
+        .set(&StorageKey::MarketCategory(market_id), category);
+    previous
+}
+
 pub fn clear_admin(env: &Env) {
     env.storage().persistent().remove(&StorageKey::Admin);
 }
 
 // --- Fee Config Storage ---
 
+/// Fee rate applied when the admin has never called `set_fee_rate`.
+pub const DEFAULT_FEE_RATE_BPS: i128 = 0;
+
+/// Hard upper bound for any fee rate (100%).
+pub const MAX_FEE_RATE_BPS: i128 = 10_000;
+
 pub fn get_fee_rate_bps(env: &Env) -> i128 {
-    env.storage().persistent().get(&StorageKey::FeeRateBps).unwrap_or(0)
+    env.storage()
+        .persistent()
+        .get(&StorageKey::FeeRateBps)
+        .unwrap_or(DEFAULT_FEE_RATE_BPS)
 }
 
 pub fn set_fee_rate_bps(env: &Env, fee_rate_bps: i128) {
     env.storage().persistent().set(&StorageKey::FeeRateBps, &fee_rate_bps);
 }
 
+/// Return the fee cap in basis points, defaulting to `MAX_FEE_RATE_BPS`.
+pub fn get_fee_cap_bps(env: &Env) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::FeeCapBps)
+        .unwrap_or(MAX_FEE_RATE_BPS)
+}
+
+pub fn set_fee_cap_bps(env: &Env, fee_cap_bps: i128) {
+    env.storage().persistent().set(&StorageKey::FeeCapBps, &fee_cap_bps);
+}
+
+/// Return the settlement fee in basis points, defaulting to 0.
+pub fn get_settlement_fee_bps(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::SettlementFeeBps)
+        .unwrap_or(0)
+}
+
+pub fn set_settlement_fee_bps(env: &Env, fee_bps: u32) {
+    env.storage()
+        .persistent()
+        .set(&StorageKey::SettlementFeeBps, &fee_bps);
+}
+
+/// Return the longest market duration in seconds, defaulting to
+/// `DEFAULT_MAX_MARKET_DURATION_SECONDS`.
+pub fn get_max_market_duration(env: &Env) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::MaxMarketDuration)
+        .unwrap_or(crate::validation::DEFAULT_MAX_MARKET_DURATION_SECONDS)
+}
+
+pub fn set_max_market_duration(env: &Env, seconds: u64) {
+    env.storage()
+        .persistent()
+        .set(&StorageKey::MaxMarketDuration, &seconds);
+}
+
+// --- Contract Version Storage ---
+
+/// Return the contract code version, defaulting to 1 for a deployment that
+/// has never been upgraded.
+pub fn get_contract_version(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::ContractVersion)
+        .unwrap_or(1)
+}
+
+pub fn set_contract_version(env: &Env, version: u32) {
+    env.storage()
+        .persistent()
+        .set(&StorageKey::ContractVersion, &version);
+}
 
 // --- Pause Storage ---
 
@@ -321,6 +1094,7 @@ mod test {
         init_versioned(&env, &contract_id);
         env.as_contract(&contract_id, || {
             assert!(!has_admin(&env));
+            assert_eq!(get_admin(&env), Err(ContractError::NotInitialized));
             set_admin(&env, &admin);
             assert!(has_admin(&env));
             assert_eq!(get_admin(&env).unwrap(), admin);
@@ -328,7 +1102,7 @@ mod test {
     }
 
     #[test]
-    fn test_fee_rate_bps_defaults_to_50() {
+    fn test_fee_rate_bps_defaults_to_zero() {
         let env = Env::default();
         let contract_id = env.register(crate::MarketContract, ());
 
@@ -336,7 +1110,7 @@ mod test {
             assert_eq!(
                 get_fee_rate_bps(&env),
                 DEFAULT_FEE_RATE_BPS,
-                "fee rate must default to 50 bps"
+                "fee rate must default to 0 bps"
             );
         });
     }
@@ -391,6 +1165,25 @@ mod test {
             resolved_at: None,
             adapter_type: AdapterType::Ed25519,
             outcome_count: 2,
+            closed_to_deposits: false,
+            source_hash: BytesN::from_array(&env, &[0u8; 32]),
+            resolution_grace: None,
+            fee_rate_bps: None,
+            withdraw_cooldown: None,
+            challenge_deadline: None,
+            trading_fee_bps: 0,
+            collateral_decimals: 7,
+            total_yes_shares: 0,
+            total_no_shares: 0,
+            yes_reserve: 0,
+            no_reserve: 0,
+            category: Symbol::new(&env, "general"),
+            resolution_source: String::from_str(&env, "test source"),
+            kind: crate::types::MarketKind::Binary,
+            settled_value: None,
+            min_deposit: 0,
+            max_position: 0,
+            max_participants: 0,
         };
         env.as_contract(&contract_id, || {
             assert!(!has_market(&env, market_id).unwrap());
@@ -438,6 +1231,25 @@ mod test {
             resolved_at: None,
             adapter_type: AdapterType::Ed25519,
             outcome_count: 2,
+            closed_to_deposits: false,
+            source_hash: BytesN::from_array(&env, &[0u8; 32]),
+            resolution_grace: None,
+            fee_rate_bps: None,
+            withdraw_cooldown: None,
+            challenge_deadline: None,
+            trading_fee_bps: 0,
+            collateral_decimals: 7,
+            total_yes_shares: 0,
+            total_no_shares: 0,
+            yes_reserve: 0,
+            no_reserve: 0,
+            category: Symbol::new(&env, "general"),
+            resolution_source: String::from_str(&env, "test source"),
+            kind: crate::types::MarketKind::Binary,
+            settled_value: None,
+            min_deposit: 0,
+            max_position: 0,
+            max_participants: 0,
         };
 
         let position = Position {
@@ -448,6 +1260,8 @@ mod test {
             locked_collateral: 325,
             total_deposited: 400,
             is_settled: false,
+            created_at: 0,
+            updated_at: 0,
         };
 
         env.as_contract(&contract_id, || {
@@ -528,6 +1342,25 @@ mod test {
             resolved_at: None,
             adapter_type: AdapterType::Ed25519,
             outcome_count: 2,
+            closed_to_deposits: false,
+            source_hash: BytesN::from_array(&env, &[0u8; 32]),
+            resolution_grace: None,
+            fee_rate_bps: None,
+            withdraw_cooldown: None,
+            challenge_deadline: None,
+            trading_fee_bps: 0,
+            collateral_decimals: 7,
+            total_yes_shares: 0,
+            total_no_shares: 0,
+            yes_reserve: 0,
+            no_reserve: 0,
+            category: Symbol::new(&env, "general"),
+            resolution_source: String::from_str(&env, "test source"),
+            kind: crate::types::MarketKind::Binary,
+            settled_value: None,
+            min_deposit: 0,
+            max_position: 0,
+            max_participants: 0,
         };
 
         env.as_contract(&contract_id, || {
@@ -540,6 +1373,34 @@ mod test {
         });
     }
 
+    #[test]
+    fn migrate_current_version_is_a_no_op() {
+        let env = Env::default();
+        let contract_id = env.register(crate::MarketContract, ());
+        init_versioned(&env, &contract_id);
+        env.as_contract(&contract_id, || {
+            assert_eq!(migrate(&env), Ok(STORAGE_VERSION));
+            assert_eq!(get_stored_version(&env), Some(STORAGE_VERSION));
+        });
+    }
+
+    #[test]
+    fn migrate_rejects_unconvertible_versions() {
+        let env = Env::default();
+        let contract_id = env.register(crate::MarketContract, ());
+        env.as_contract(&contract_id, || {
+            assert_eq!(migrate(&env), Err(ContractError::NotInitialized));
+
+            for version in [0u32, 2, 3, STORAGE_VERSION + 1] {
+                env.storage()
+                    .persistent()
+                    .set(&StorageKey::StorageVersion, &version);
+                assert_eq!(migrate(&env), Err(ContractError::UpgradeRequired));
+                assert_eq!(get_stored_version(&env), Some(version));
+            }
+        });
+    }
+
     #[test]
     fn migration_future_version_is_rejected() {
         let env = Env::default();
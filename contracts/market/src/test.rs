@@ -1,8 +1,11 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod test {
+    extern crate std;
+
     use crate::{
         storage,
-        types::{Market, MarketStatus},
+        types::{Market, MarketStatus, Outcome},
         MarketContract, MarketContractClient,
     };
     use soroban_sdk::{
@@ -37,6 +40,64 @@ mod test {
         })
     }
 
+    /// Register a Stellar Asset Contract to use as market collateral.
+    fn create_collateral_token(env: &Env) -> Address {
+        env.register_stellar_asset_contract_v2(Address::generate(env))
+            .address()
+    }
+
+    /// Mint `amount` of `token` to a fresh user and deposit it into
+    /// `market_id`, so the market holds collateral when it is resolved.
+    fn fund_market(
+        env: &Env,
+        client: &MarketContractClient,
+        token: &Address,
+        market_id: u32,
+        amount: i128,
+    ) -> Address {
+        let user = Address::generate(env);
+        soroban_sdk::token::StellarAssetClient::new(env, token).mint(&user, &amount);
+        client.deposit_collateral(&user, &market_id, &amount, &None);
+        user
+    }
+
+    /// Register a Resolution contract with the market contract, so oracle
+    /// resolution needs a finalized candidate for the market.
+    fn register_resolution_contract(
+        env: &Env,
+        client: &MarketContractClient,
+        admin: &Address,
+    ) -> Address {
+        let resolution_addr = env.register(ResolutionContract, ());
+        ResolutionContractClient::new(env, &resolution_addr).initialize(
+            admin,
+            &Address::generate(env),
+            &client.address,
+            &60,
+        );
+        client.set_resolution_contract(admin, &resolution_addr);
+        resolution_addr
+    }
+
+    /// Resolution source hash committed by markets created in these tests.
+    fn test_source_hash(env: &Env) -> BytesN<32> {
+        BytesN::from_array(env, &[0u8; 32])
+    }
+
+    /// Metadata for markets created in these tests.
+    fn test_metadata(env: &Env) -> crate::types::MarketMetadata {
+        crate::types::MarketMetadata {
+            uri: None,
+            category: soroban_sdk::Symbol::new(env, "general"),
+            resolution_source: String::from_str(env, "test source"),
+            kind: crate::types::MarketKind::Binary,
+            min_deposit: 0,
+            max_position: 0,
+            max_participants: 0,
+            derive_id: false,
+        }
+    }
+
     /// Generate a test Ed25519 keypair and sign a message
     ///
     /// # Arguments
@@ -50,30 +111,35 @@ mod test {
     fn generate_test_keypair_and_sign(
         env: &Env,
         market_id: u32,
-        outcome: bool,
+        outcome: impl Into<Outcome>,
     ) -> (BytesN<32>, BytesN<64>) {
-        use ed25519_dalek::{Signer, SigningKey};
-        use rand::rngs::OsRng;
+        use rand::{rngs::OsRng, RngCore};
 
-        // Generate keypair
-        let mut csprng = OsRng;
-        let signing_key = SigningKey::generate(&mut csprng);
-        let verifying_key = signing_key.verifying_key();
-
-        // Construct message (same as oracle::construct_oracle_message)
-        let message = crate::oracle::construct_oracle_message(env, market_id, outcome);
-
-        // Sign the message
-        let signature = signing_key.sign(message.to_array().as_slice());
+        let mut signing_key_bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut signing_key_bytes);
+        crate::oracle::sign_outcome_for_test(
+            env,
+            &signing_key_bytes,
+            market_id,
+            0,
+            &test_source_hash(env),
+            outcome,
+        )
+    }
 
-        // Convert to BytesN
-        let pubkey_bytes: [u8; 32] = verifying_key.to_bytes();
-        let sig_bytes: [u8; 64] = signature.to_bytes();
+    /// Move the ledger clock to `market_id`'s `end_time`, the first moment
+    /// the market may be resolved.
+    fn advance_to_end_time(env: &Env, client: &MarketContractClient, market_id: u32) {
+        let end_time = client.get_market(&market_id).end_time;
+        if env.ledger().timestamp() < end_time {
+            env.ledger().set_timestamp(end_time);
+        }
+    }
 
-        (
-            BytesN::from_array(env, &pubkey_bytes),
-            BytesN::from_array(env, &sig_bytes),
-        )
+    /// A distinct question per `n`, for tests that create several markets
+    /// while they are all still active.
+    fn numbered_question(env: &Env, n: u32) -> String {
+        String::from_str(env, &std::format!("Test market {n}"))
     }
 
     // Rest of tests remain the same...
@@ -90,7 +156,7 @@ mod test {
         let admin = Address::generate(&env);
         
         // Initialize should succeed with a valid account address
-        let result = client.initialize(&admin);
+        let result = client.try_initialize(&admin);
         assert!(result.is_ok());
         
         // Verify admin was set
@@ -135,8 +201,45 @@ mod test {
         client.initialize(&another_admin);
     }
     
+    #[test]
+    fn test_initialize_bootstraps_market_creation() {
+        use crate::error::ContractError;
+
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = MarketContractClient::new(&env, &env.register(MarketContract, ()));
+        let admin = Address::generate(&env);
+        let collateral_token = create_collateral_token(&env);
+        let create = || {
+            client.try_initialize_market(
+                &admin,
+                &String::from_str(&env, "Will it rain tomorrow?"),
+                &(env.ledger().timestamp() + 86_400),
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &collateral_token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            )
+        };
+
+        // A fresh deployment rejects admin operations instead of panicking.
+        assert_eq!(create(), Err(Ok(ContractError::NotInitialized)));
+
+        client.initialize(&admin);
+        assert_eq!(create(), Ok(Ok(1)));
+        assert_eq!(
+            client.try_initialize(&admin),
+            Err(Ok(ContractError::AlreadyInitialized))
+        );
+    }
+
     #[test]
     fn test_initialize_emits_event() {
+        use soroban_sdk::{IntoVal, Symbol};
+
         let env = Env::default();
         env.mock_all_auths();
         
@@ -149,13 +252,12 @@ mod test {
         
         // Verify event was emitted
         let events = env.events().all();
-        assert!(events.len() > 0);
+        assert!(!events.is_empty());
         
         // Check for contract_initialized_event
         let event_found = events.iter().any(|e| {
-            e.topics.iter().any(|t| {
-                t.to_string().contains("contract_initialized")
-            })
+            let topic: Symbol = e.1.get(0).unwrap().into_val(&env);
+            topic == Symbol::new(&env, "contract_initialized")
         });
         assert!(event_found, "contract_initialized_event should be emitted");
     }
@@ -188,7 +290,7 @@ mod test {
         let question = String::from_str(&env, "Will BTC reach $100k by March?");
         let end_time = env.ledger().timestamp() + 86400;
         let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
+        let collateral_token = create_collateral_token(&env);
 
         let market_id = client.initialize_market(
             &admin,
@@ -196,6 +298,11 @@ mod test {
             &end_time,
             &oracle_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
 
         assert_eq!(market_id, 1);
@@ -211,6 +318,95 @@ mod test {
         assert_eq!(market.collateral_token, collateral_token);
     }
 
+    #[test]
+    fn test_initialize_market_derived_id_is_deterministic() {
+        let (env, admin, client, contract_id) = create_test_contract();
+        let collateral_token = create_collateral_token(&env);
+        let end_time = env.ledger().timestamp() + 86400;
+        let derived = crate::types::MarketMetadata {
+            derive_id: true,
+            ..test_metadata(&env)
+        };
+        let create = |question: &String, metadata: &crate::types::MarketMetadata| {
+            client.try_initialize_market(
+                &admin,
+                question,
+                &end_time,
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &collateral_token,
+                metadata,
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            )
+        };
+
+        let question = String::from_str(&env, "Will it rain tomorrow?");
+        let expected = env.as_contract(&contract_id, || {
+            storage::derive_market_id(&env, &admin, &question, end_time)
+        });
+        let market_id = create(&question, &derived).unwrap().unwrap();
+        assert_eq!(market_id, expected);
+        assert_eq!(client.get_market(&market_id).question, question);
+
+        // Any change to the inputs moves the ID.
+        let other = numbered_question(&env, 2);
+        let other_id = env.as_contract(&contract_id, || {
+            storage::derive_market_id(&env, &admin, &other, end_time)
+        });
+        assert_ne!(other_id, market_id);
+        assert_ne!(
+            env.as_contract(&contract_id, || {
+                storage::derive_market_id(&env, &admin, &question, end_time + 1)
+            }),
+            market_id
+        );
+
+        // The counter path still hands out sequential IDs alongside.
+        assert_eq!(create(&other, &test_metadata(&env)), Ok(Ok(1)));
+        // Derived IDs do not advance the counter but still count as markets.
+        assert_eq!(client.market_count(), 2);
+    }
+
+    #[test]
+    fn test_initialize_market_rejects_derived_id_collision() {
+        use crate::error::ContractError;
+
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let collateral_token = create_collateral_token(&env);
+        let end_time = env.ledger().timestamp() + 86400;
+        let question = String::from_str(&env, "Will it rain tomorrow?");
+        let derived = crate::types::MarketMetadata {
+            derive_id: true,
+            ..test_metadata(&env)
+        };
+        let create = |metadata: &crate::types::MarketMetadata| {
+            client.try_initialize_market(
+                &admin,
+                &question,
+                &end_time,
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &collateral_token,
+                metadata,
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            )
+        };
+
+        let market_id = create(&derived).unwrap().unwrap();
+        client.cancel_market(&admin, &market_id);
+        // The question is free again, but its derived ID is not.
+        assert_eq!(create(&derived), Err(Ok(ContractError::AlreadyInitialized)));
+
+        // Derived IDs stay out of the counter's range.
+        assert_ne!(market_id & storage::DERIVED_MARKET_ID_FLAG, 0);
+        assert_eq!(create(&test_metadata(&env)), Ok(Ok(1)));
+        assert_eq!(client.health().total_markets, 2);
+    }
+
     #[test]
     fn test_initialize_market_increments_counter() {
         let (env, admin, client, _contract_id) = create_test_contract();
@@ -218,7 +414,7 @@ mod test {
         let question = String::from_str(&env, "Question 1");
         let end_time = env.ledger().timestamp() + 86400;
         let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
+        let collateral_token = create_collateral_token(&env);
 
         let market_id_1 = client.initialize_market(
             &admin,
@@ -226,6 +422,11 @@ mod test {
             &end_time,
             &oracle_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
         assert_eq!(market_id_1, 1);
 
@@ -236,6 +437,11 @@ mod test {
             &end_time,
             &oracle_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
         assert_eq!(market_id_2, 2);
 
@@ -246,10 +452,47 @@ mod test {
             &end_time,
             &oracle_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
         assert_eq!(market_id_3, 3);
     }
 
+    #[test]
+    fn test_initialize_market_ids_stay_distinct_past_64() {
+        let (env, admin, client, contract_id) = create_test_contract();
+        let collateral_token = create_collateral_token(&env);
+        let end_time = env.ledger().timestamp() + 86400;
+
+        for expected_id in 1..=100u32 {
+            let question = String::from_str(&env, &std::format!("Question {expected_id}"));
+            let market_id = client.initialize_market(
+                &admin,
+                &question,
+                &end_time,
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &collateral_token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            );
+            assert_eq!(market_id, expected_id);
+        }
+
+        // Every market keeps its own storage entry; none was overwritten.
+        for market_id in 1..=100u32 {
+            let market = get_market_from_storage(&env, &contract_id, market_id);
+            assert_eq!(market.id, market_id);
+            let question = String::from_str(&env, &std::format!("Question {market_id}"));
+            assert_eq!(market.question, question);
+        }
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #41)")]
     fn test_initialize_market_non_admin_fails() {
@@ -259,7 +502,7 @@ mod test {
         let question = String::from_str(&env, "Will BTC reach $100k?");
         let end_time = env.ledger().timestamp() + 86400;
         let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
+        let collateral_token = create_collateral_token(&env);
 
         client.initialize_market(
             &non_admin,
@@ -267,6 +510,11 @@ mod test {
             &end_time,
             &oracle_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
     }
 
@@ -278,7 +526,7 @@ mod test {
         let empty_question = String::from_str(&env, "");
         let end_time = env.ledger().timestamp() + 86400;
         let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
+        let collateral_token = create_collateral_token(&env);
 
         client.initialize_market(
             &admin,
@@ -286,6 +534,11 @@ mod test {
             &end_time,
             &oracle_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
     }
 
@@ -301,7 +554,7 @@ mod test {
 
         let past_end_time = env.ledger().timestamp() - 1;
         let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
+        let collateral_token = create_collateral_token(&env);
 
         client.initialize_market(
             &admin,
@@ -309,6 +562,11 @@ mod test {
             &past_end_time,
             &oracle_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
     }
 
@@ -320,7 +578,7 @@ mod test {
         let question = String::from_str(&env, "Will BTC reach $100k?");
         let end_time = env.ledger().timestamp() + 86400;
         let zero_pubkey = BytesN::from_array(&env, &[0u8; 32]);
-        let collateral_token = Address::generate(&env);
+        let collateral_token = create_collateral_token(&env);
 
         client.initialize_market(
             &admin,
@@ -328,6 +586,11 @@ mod test {
             &end_time,
             &zero_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
     }
 
@@ -338,7 +601,7 @@ mod test {
         let question = String::from_str(&env, "Test market");
         let end_time = env.ledger().timestamp() + 86400;
         let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
+        let collateral_token = create_collateral_token(&env);
 
         let current_time = env.ledger().timestamp();
 
@@ -348,6 +611,11 @@ mod test {
             &end_time,
             &oracle_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
 
         let market = get_market_from_storage(&env, &contract_id, market_id);
@@ -361,10 +629,20 @@ mod test {
         let question = String::from_str(&env, "Market with USDC");
         let end_time = env.ledger().timestamp() + 86400;
         let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let usdc_token = Address::generate(&env);
+        let usdc_token = create_collateral_token(&env);
 
-        let market_id =
-            client.initialize_market(&admin, &question, &end_time, &oracle_pubkey, &usdc_token);
+        let market_id = client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &usdc_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
 
         let market = get_market_from_storage(&env, &contract_id, market_id);
         assert_eq!(market.collateral_token, usdc_token);
@@ -377,7 +655,7 @@ mod test {
         let question = String::from_str(&env, "Event test market");
         let end_time = env.ledger().timestamp() + 86400;
         let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
+        let collateral_token = create_collateral_token(&env);
 
         client.initialize_market(
             &admin,
@@ -385,1231 +663,5544 @@ mod test {
             &end_time,
             &oracle_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
 
         let events = env.events().all();
-        assert!(events.len() > 0);
+        assert!(!events.is_empty());
     }
 
-    // ========== resolve_market tests ==========
-
     #[test]
-    #[should_panic(expected = "Error(Contract, #1)")]
-    fn test_resolve_market_not_found() {
-        let (env, _admin, client, _contract_id) = create_test_contract();
-
-        let resolver = Address::generate(&env);
-        let non_existent_market_id = String::from_str(&env, "999");
-        let outcome = true;
-        let invalid_signature = BytesN::from_array(&env, &[0u8; 64]);
+    fn test_initialize_market_rejects_non_token_collateral() {
+        let (env, admin, client, _contract_id) = create_test_contract();
 
-        client.resolve_market(&resolver, &non_existent_market_id, &outcome, &invalid_signature);
+        // An address with no token contract behind it is rejected at creation
+        // instead of failing later on the first deposit transfer.
+        let result = client.try_initialize_market(
+            &admin,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &Address::generate(&env),
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        assert_eq!(
+            result,
+            Err(Ok(crate::error::ContractError::UnsupportedToken))
+        );
+        assert!(client.list_markets(&0, &10).is_empty());
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #2)")]
-    fn test_resolve_market_already_resolved() {
+    fn test_initialize_market_rejects_self_as_collateral() {
         let (env, admin, client, contract_id) = create_test_contract();
 
-        // Create a market
-        let question = String::from_str(&env, "Test market");
-        let end_time = env.ledger().timestamp() + 86400;
-        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
-
-        let market_id = client.initialize_market(
+        let result = client.try_initialize_market(
             &admin,
-            &question,
-            &end_time,
-            &oracle_pubkey,
-            &collateral_token,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &contract_id,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        assert_eq!(
+            result,
+            Err(Ok(crate::error::ContractError::UnsupportedToken))
         );
+    }
 
-        // Manually set market to resolved status
-        env.as_contract(&contract_id, || {
-            let mut market = storage::get_market(&env, market_id).unwrap().unwrap();
-            market.status = MarketStatus::Resolved;
-            market.result = Some(true);
-            storage::set_market(&env, market_id, &market).unwrap();
-        });
+    /// Collateral stand-in that only answers `decimals()`, for markets whose
+    /// token is not a 7-decimal Stellar asset.
+    #[soroban_sdk::contract]
+    pub struct DecimalsToken;
+
+    #[soroban_sdk::contractimpl]
+    impl DecimalsToken {
+        pub fn __constructor(env: Env, decimals: u32) {
+            env.storage()
+                .instance()
+                .set(&soroban_sdk::symbol_short!("decimals"), &decimals);
+        }
+
+        pub fn decimals(env: Env) -> u32 {
+            env.storage()
+                .instance()
+                .get(&soroban_sdk::symbol_short!("decimals"))
+                .unwrap()
+        }
+    }
 
-        // Try to resolve again - should fail
-        let resolver = Address::generate(&env);
-        let outcome = true;
-        let invalid_signature = BytesN::from_array(&env, &[0u8; 64]);
-        let market_id_str = String::from_str(&env, "1");
-        client.resolve_market(&resolver, &market_id_str, &outcome, &invalid_signature);
+    #[test]
+    fn test_locked_collateral_in_collateral_base_units() {
+        for decimals in [6u32, 7] {
+            let (env, admin, client, contract_id) = create_test_contract();
+            let collateral_token = env.register(DecimalsToken, (decimals,));
+            let market_id = client.initialize_market(
+                &admin,
+                &String::from_str(&env, "Test market"),
+                &(env.ledger().timestamp() + 86400),
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &collateral_token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            );
+            assert_eq!(client.get_market(&market_id).collateral_decimals, decimals);
+
+            // 100 whole shares at 60% lock 60 whole tokens, in the token's units.
+            let unit = 10i128.pow(decimals);
+            let user = Address::generate(&env);
+            env.as_contract(&contract_id, || {
+                let mut position = crate::types::Position::new_empty(market_id, user.clone(), 0);
+                position.total_deposited = 100 * unit;
+                storage::set_position(&env, market_id, &user, &position).unwrap();
+            });
+            let position = client.update_position(&user, &market_id, &(100 * unit), &0, &6_000);
+            assert_eq!(position.locked_collateral, 60 * unit);
+        }
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #20)")]
-    fn test_resolve_market_invalid_signature() {
-        let (env, admin, client, _contract_id) = create_test_contract();
+    fn test_initialize_market_source_hash_is_immutable() {
+        let (env, admin, client, contract_id) = create_test_contract();
 
-        // Create a market
-        let question = String::from_str(&env, "Test market");
         let end_time = env.ledger().timestamp() + 86400;
-        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
-
-        let _market_id = client.initialize_market(
+        let (oracle_pubkey, signature) = generate_test_keypair_and_sign(&env, 1, true);
+        let source_hash = test_source_hash(&env);
+        let collateral_token = create_collateral_token(&env);
+        let market_id = client.initialize_market(
             &admin,
-            &question,
+            &String::from_str(&env, "Test market"),
             &end_time,
             &oracle_pubkey,
             &collateral_token,
+            &test_metadata(&env),
+            &source_hash,
+            &None,
+            &None,
+            &0,
         );
-
-        // Bad signature must surface as the typed InvalidSignature error
-        // (#20), not an uncaught host trap.
-        let resolver = Address::generate(&env);
-        let outcome = true;
-        let invalid_signature = BytesN::random(&env);
-        let market_id_str = String::from_str(&env, "1");
-        client.resolve_market(&resolver, &market_id_str, &outcome, &invalid_signature);
+        assert_eq!(client.get_source_hash(&market_id), source_hash);
+
+        fund_market(&env, &client, &collateral_token, market_id, 1_000_000);
+        // Resolution rewrites the market record but must keep the commitment.
+        advance_to_end_time(&env, &client, market_id);
+        client.resolve_market(
+            &Address::generate(&env),
+            &1,
+            &Outcome::Yes,
+            &None,
+            &signature,
+            &0,
+        );
+        let market = get_market_from_storage(&env, &contract_id, market_id);
+        assert_eq!(market.status, MarketStatus::Resolved);
+        assert_eq!(client.get_source_hash(&market_id), source_hash);
     }
 
     #[test]
-    fn test_resolve_market_invalid_signature_leaves_market_active() {
-        let (env, admin, client, contract_id) = create_test_contract();
-
-        let question = String::from_str(&env, "Test market");
-        let end_time = env.ledger().timestamp() + 86400;
-        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
-
+    fn test_resolution_preimage_hashes_to_preview_message() {
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let source_hash = BytesN::from_array(&env, &[0x5Au8; 32]);
         let market_id = client.initialize_market(
             &admin,
-            &question,
-            &end_time,
-            &oracle_pubkey,
-            &collateral_token,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &create_collateral_token(&env),
+            &test_metadata(&env),
+            &source_hash,
+            &None,
+            &None,
+            &0,
         );
 
-        let resolver = Address::generate(&env);
-        let outcome = true;
-        let invalid_signature = BytesN::random(&env);
-        let market_id_str = String::from_str(&env, "1");
-        let result = client.try_resolve_market(&resolver, &market_id_str, &outcome, &invalid_signature);
-
+        for outcome in [Outcome::Yes, Outcome::No, Outcome::Invalid] {
+            let preimage = client.resolution_preimage(&market_id, &outcome);
+            assert_eq!(preimage.len(), 45);
+            assert_eq!(preimage.get(44), Some(outcome.to_byte()));
+            let hash: BytesN<32> = env.crypto().keccak256(&preimage).into();
+            assert_eq!(
+                hash,
+                client.preview_resolution_message(&market_id, &outcome)
+            );
+        }
         assert_eq!(
-            result,
-            Err(Ok(crate::error::ContractError::InvalidSignature))
+            client.try_resolution_preimage(&999, &Outcome::Yes),
+            Err(Ok(crate::error::ContractError::MarketNotFound))
         );
-
-        // Market must be untouched - no partial state mutation on failure.
-        let market = get_market_from_storage(&env, &contract_id, market_id);
-        assert_eq!(market.status, MarketStatus::Active);
-        assert_eq!(market.result, None);
     }
 
     #[test]
-    fn test_resolve_market_with_valid_signature() {
-        let (env, admin, client, contract_id) = create_test_contract();
-
-        // Create a market
-        let question = String::from_str(&env, "Test market");
-        let end_time = env.ledger().timestamp() + 86400;
-        let collateral_token = Address::generate(&env);
-
-        // Generate test keypair and signature
-        let market_id = 1u32;
-        let outcome = true;
-        let (oracle_pubkey, signature) = generate_test_keypair_and_sign(&env, market_id, outcome);
-
-        // Initialize market with the generated pubkey
+    fn test_market_age_grows_with_ledger_time() {
+        let (env, admin, client, _contract_id) = create_test_contract();
+        env.ledger().set_timestamp(1_000);
         let market_id = client.initialize_market(
             &admin,
-            &question,
-            &end_time,
-            &oracle_pubkey,
-            &collateral_token,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &create_collateral_token(&env),
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
 
-        // Verify market is initially Active
-        let market_before = get_market_from_storage(&env, &contract_id, market_id);
-        assert_eq!(market_before.status, MarketStatus::Active);
-        assert_eq!(market_before.result, None);
-
-        // Resolve market with valid signature
-        let resolver = Address::generate(&env);
-        let market_id_str = String::from_str(&env, "1");
-        client.resolve_market(&resolver, &market_id_str, &outcome, &signature);
+        assert_eq!(client.get_created_at(&market_id), Some(1_000));
+        assert_eq!(client.market_age(&market_id), 0);
+        env.ledger().set_timestamp(1_600);
+        assert_eq!(client.market_age(&market_id), 600);
+        env.ledger().set_timestamp(5_000);
+        assert_eq!(client.market_age(&market_id), 4_000);
+        assert_eq!(client.get_created_at(&market_id), Some(1_000));
+    }
 
-        // Verify market is now Resolved
-        let market_after = get_market_from_storage(&env, &contract_id, market_id);
-        assert_eq!(market_after.status, MarketStatus::Resolved);
-        assert_eq!(market_after.result, Some(outcome));
-        assert_eq!(market_after.resolver, Some(resolver));
+    #[test]
+    fn test_market_age_missing_market() {
+        let (_env, _admin, client, _contract_id) = create_test_contract();
+        assert_eq!(client.get_created_at(&999), None);
+        assert_eq!(client.market_age(&999), 0);
     }
 
     #[test]
-    fn test_resolve_market_updates_status_and_result() {
-        let (env, admin, client, contract_id) = create_test_contract();
+    fn test_get_oracle_config_single_and_threshold() {
+        use crate::types::OracleScheme;
+        use soroban_sdk::vec;
 
-        // Create a market
-        let question = String::from_str(&env, "Test market");
-        let end_time = env.ledger().timestamp() + 86400;
+        let (env, admin, client, _contract_id) = create_test_contract();
         let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
-
         let market_id = client.initialize_market(
             &admin,
-            &question,
-            &end_time,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
             &oracle_pubkey,
-            &collateral_token,
+            &create_collateral_token(&env),
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
 
-        // Verify market is initially Active
-        let market_before = get_market_from_storage(&env, &contract_id, market_id);
-        assert_eq!(market_before.status, MarketStatus::Active);
-        assert_eq!(market_before.result, None);
-
-        // Verify market structure is correct
-        assert_eq!(market_before.oracle_pubkey, oracle_pubkey);
+        let single = client.get_oracle_config(&market_id);
+        assert_eq!(single.keys, vec![&env, oracle_pubkey.clone()]);
+        assert_eq!(single.threshold, 1);
+        assert_eq!(single.scheme, OracleScheme::Single);
+
+        let signers = vec![
+            &env,
+            BytesN::from_array(&env, &[2u8; 32]),
+            BytesN::from_array(&env, &[3u8; 32]),
+            BytesN::from_array(&env, &[4u8; 32]),
+        ];
+        client.set_threshold_signers(&admin, &signers, &2);
+        let threshold = client.get_oracle_config(&market_id);
+        assert_eq!(threshold.keys, signers);
+        assert_eq!(threshold.threshold, 2);
+        assert_eq!(threshold.scheme, OracleScheme::Threshold);
+
+        // A zero quorum disables threshold resolution again.
+        client.set_threshold_signers(&admin, &signers, &0);
+        assert_eq!(
+            client.get_oracle_config(&market_id).scheme,
+            OracleScheme::Single
+        );
+        assert_eq!(
+            client.try_get_oracle_config(&999),
+            Err(Ok(crate::error::ContractError::MarketNotFound))
+        );
     }
 
     #[test]
-    fn test_resolve_market_emits_event() {
-        let (env, admin, client, contract_id) = create_test_contract();
-
-        // Create a market
-        let question = String::from_str(&env, "Test market");
-        let end_time = env.ledger().timestamp() + 86400;
-        let collateral_token = Address::generate(&env);
-
-        // Generate test keypair and signature
-        let market_id = 1u32;
-        let outcome = true;
-        let (oracle_pubkey, signature) = generate_test_keypair_and_sign(&env, market_id, outcome);
+    fn test_resolve_market_multi_requires_threshold_of_market_oracles() {
+        use crate::error::ContractError;
+        use crate::types::{OracleScheme, OracleSet};
+        use soroban_sdk::vec;
 
+        let (env, admin, client, contract_id) = create_test_contract();
+        let token = create_collateral_token(&env);
         let market_id = client.initialize_market(
             &admin,
-            &question,
-            &end_time,
-            &oracle_pubkey,
-            &collateral_token,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
-
-        // Clear events from initialization
-        env.events().all();
-
-        // Resolve market with valid signature
+        fund_market(&env, &client, &token, market_id, 1_000);
+        advance_to_end_time(&env, &client, market_id);
         let resolver = Address::generate(&env);
-        let market_id_str = String::from_str(&env, "1");
-        client.resolve_market(&resolver, &market_id_str, &outcome, &signature);
+        let (pk1, sig1) = generate_test_keypair_and_sign(&env, market_id, true);
+        let (pk2, sig2) = generate_test_keypair_and_sign(&env, market_id, true);
+        let (pk3, _) = generate_test_keypair_and_sign(&env, market_id, true);
 
-        // Verify event was emitted
-        let events = env.events().all();
-        assert!(events.len() > 0);
+        // Without a market oracle set the multi path is closed.
+        assert_eq!(
+            client.try_resolve_market_multi(
+                &resolver,
+                &market_id,
+                &true,
+                &vec![&env, sig1.clone()],
+                &vec![&env, pk1.clone()],
+            ),
+            Err(Ok(ContractError::UnauthorizedOracle))
+        );
 
-        // Verify that market is resolved
-        let market = get_market_from_storage(&env, &contract_id, market_id);
+        let set = OracleSet {
+            keys: vec![&env, pk1.clone(), pk2.clone(), pk3],
+            threshold: 2,
+        };
+        client.set_market_oracles(&admin, &market_id, &Some(set.clone()));
+        let config = client.get_oracle_config(&market_id);
+        assert_eq!(config.keys, set.keys);
+        assert_eq!(config.scheme, OracleScheme::MarketSet);
+
+        assert_eq!(
+            client.try_resolve_market_multi(
+                &resolver,
+                &market_id,
+                &true,
+                &vec![&env, sig1.clone()],
+                &vec![&env, pk1.clone()],
+            ),
+            Err(Ok(ContractError::InvalidSignature))
+        );
+        assert_eq!(
+            client.try_resolve_market_multi(
+                &resolver,
+                &market_id,
+                &true,
+                &vec![&env, sig1.clone(), sig1.clone()],
+                &vec![&env, pk1.clone(), pk1.clone()],
+            ),
+            Err(Ok(ContractError::DuplicateOracleSigner))
+        );
+        assert_eq!(
+            get_market_from_storage(&env, &contract_id, market_id).status,
+            MarketStatus::Active
+        );
+
+        client.resolve_market_multi(
+            &resolver,
+            &market_id,
+            &true,
+            &vec![&env, sig1, sig2],
+            &vec![&env, pk1, pk2],
+        );
+        let market = get_market_from_storage(&env, &contract_id, market_id);
         assert_eq!(market.status, MarketStatus::Resolved);
-        assert_eq!(market.result, Some(outcome));
+        assert_eq!(market.result, Some(true));
         assert_eq!(market.resolver, Some(resolver));
     }
 
+    /// A 3-of-5 resolution stops verifying once the third signature passes,
+    /// so it costs measurably less than the same five signatures on a 5-of-5
+    /// market, where every one is checked.
+    ///
+    /// Native test runs do not meter the Ed25519 arithmetic itself, only the
+    /// host work around each check (copying the key, message and signature
+    /// out of the host), so the measured gap understates the on-chain saving:
+    /// in WASM each skipped check is a full verification of millions of
+    /// instructions.
     #[test]
-    fn test_collateral_deposit_emits_event() {
-        use soroban_sdk::token::StellarAssetClient;
+    fn test_resolve_market_multi_with_five_signatures_stops_at_threshold() {
+        use crate::types::OracleSet;
+        use soroban_sdk::Vec;
 
-        let env = Env::default();
-        let token_admin = Address::generate(&env);
-        let token = env.register_stellar_asset_contract_v2(token_admin.clone());
-        let collateral_token = token.address();
+        let (env, admin, client, contract_id) = create_test_contract();
+        let token = create_collateral_token(&env);
+        let create = |n: u32| {
+            let market_id = client.initialize_market(
+                &admin,
+                &numbered_question(&env, n),
+                &(env.ledger().timestamp() + 86400),
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            );
+            fund_market(&env, &client, &token, market_id, 1_000);
+            market_id
+        };
+        // Both markets exist before the clock moves, since the test
+        // signatures commit to a creation time of 0.
+        let quorum_3 = create(3);
+        let quorum_5 = create(5);
+
+        let resolve_with_quorum = |market_id: u32, threshold: u32, corrupt_last: bool| {
+            let mut keys = Vec::new(&env);
+            let mut signatures = Vec::new(&env);
+            for _ in 0..5 {
+                let (pubkey, signature) = generate_test_keypair_and_sign(&env, market_id, true);
+                keys.push_back(pubkey);
+                signatures.push_back(signature);
+            }
+            client.set_market_oracles(
+                &admin,
+                &market_id,
+                &Some(OracleSet {
+                    keys: keys.clone(),
+                    threshold,
+                }),
+            );
+            advance_to_end_time(&env, &client, market_id);
+
+            if corrupt_last {
+                // Never verified once the threshold is met, so it cannot
+                // stop the resolution.
+                signatures.set(4, BytesN::from_array(&env, &[0u8; 64]));
+            }
+            client.resolve_market_multi(
+                &Address::generate(&env),
+                &market_id,
+                &true,
+                &signatures,
+                &keys,
+            );
+            let cpu = env.cost_estimate().budget().cpu_instruction_cost();
+            let market = get_market_from_storage(&env, &contract_id, market_id);
+            assert_eq!(market.status, MarketStatus::Resolved);
+            assert_eq!(market.result, Some(true));
+            cpu
+        };
 
-        let contract_id = env.register(MarketContract, ());
-        let client = MarketContractClient::new(&env, &contract_id);
-        let admin = Address::generate(&env);
+        let short_circuit = resolve_with_quorum(quorum_3, 3, true);
+        let full_pass = resolve_with_quorum(quorum_5, 5, false);
+        assert!(
+            short_circuit < full_pass,
+            "3-of-5 used {short_circuit} instructions, 5-of-5 used {full_pass}"
+        );
+    }
 
-        env.as_contract(&contract_id, || {
-            storage::set_admin(&env, &admin);
-            storage::set_version(&env);
-        });
+    #[test]
+    fn test_resolve_market_multi_respects_commitment_and_resolution_contract() {
+        use crate::error::ContractError;
+        use crate::types::OracleSet;
+        use crate::validation::RESOLUTION_REVEAL_WINDOW_LEDGERS;
+        use soroban_sdk::vec;
 
-        env.mock_all_auths();
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let token = create_collateral_token(&env);
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        fund_market(&env, &client, &token, market_id, 1_000);
+        let (pk1, sig1) = generate_test_keypair_and_sign(&env, market_id, true);
+        let (pk2, sig2) = generate_test_keypair_and_sign(&env, market_id, true);
+        let keys = vec![&env, pk1, pk2];
+        let signatures = vec![&env, sig1, sig2];
+        client.set_market_oracles(
+            &admin,
+            &market_id,
+            &Some(OracleSet {
+                keys: keys.clone(),
+                threshold: 2,
+            }),
+        );
+        advance_to_end_time(&env, &client, market_id);
+        let resolver = Address::generate(&env);
 
-        // Create a market
-        let question = String::from_str(&env, "Test market");
-        let end_time = env.ledger().timestamp() + 86400;
-        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
+        // A sealed resolution cannot be overtaken by the quorum.
+        client.commit_resolution(&admin, &market_id, &BytesN::from_array(&env, &[9u8; 32]));
+        assert_eq!(
+            client.try_resolve_market_multi(&resolver, &market_id, &true, &signatures, &keys),
+            Err(Ok(ContractError::ResolutionCommitted))
+        );
+        env.ledger()
+            .with_mut(|li| li.sequence_number += RESOLUTION_REVEAL_WINDOW_LEDGERS + 1);
 
-        let _market_id = client.initialize_market(
-            &admin,
-            &question,
-            &end_time,
-            &oracle_pubkey,
-            &collateral_token,
+        // With a resolution contract registered, the quorum needs its
+        // finalized candidate, as resolve_market does.
+        register_resolution_contract(&env, &client, &admin);
+        assert_eq!(
+            client.try_resolve_market_multi(&resolver, &market_id, &true, &signatures, &keys),
+            Err(Ok(ContractError::ResolutionNotFinalized))
         );
+        assert_eq!(client.get_market(&market_id).status, MarketStatus::Active);
+    }
 
-        // Clear events from initialization
-        env.events().all();
+    #[test]
+    fn test_set_market_oracles_rejects_bad_sets_and_non_admin() {
+        use crate::error::ContractError;
+        use crate::types::{OracleScheme, OracleSet};
+        use soroban_sdk::vec;
 
-        // Mint tokens to user for deposit
-        let user = Address::generate(&env);
-        let amount = 1000i128;
-        let token_client = StellarAssetClient::new(&env, &collateral_token);
-        token_client.mint(&user, &amount);
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &create_collateral_token(&env),
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        let key = BytesN::from_array(&env, &[2u8; 32]);
+        let set = OracleSet {
+            keys: vec![&env, key.clone()],
+            threshold: 1,
+        };
 
-        // Deposit collateral
-        client.deposit_collateral(&user, &1, &amount);
+        assert_eq!(
+            client.try_set_market_oracles(&Address::generate(&env), &market_id, &Some(set.clone())),
+            Err(Ok(ContractError::NotAdmin))
+        );
+        assert_eq!(
+            client.try_set_market_oracles(
+                &admin,
+                &market_id,
+                &Some(OracleSet {
+                    keys: vec![&env, key.clone()],
+                    threshold: 2
+                }),
+            ),
+            Err(Ok(ContractError::InvalidOracleSet))
+        );
 
-        // Verify event was emitted
-        let events = env.events().all();
-        assert!(
-            events.len() > 0,
-            "CollateralDeposited event should be emitted"
+        // Clearing the set falls back to the market's single oracle.
+        client.set_market_oracles(&admin, &market_id, &Some(set));
+        client.set_market_oracles(&admin, &market_id, &None);
+        assert_eq!(
+            client.get_oracle_config(&market_id).scheme,
+            OracleScheme::Single
         );
     }
 
-    // ========== Expiration check tests ==========
+    #[test]
+    fn test_get_source_hash_market_not_found() {
+        let (_env, _admin, client, _contract_id) = create_test_contract();
+        assert_eq!(
+            client.try_get_source_hash(&999),
+            Err(Ok(crate::error::ContractError::MarketNotFound))
+        );
+    }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #4)")]
-    fn test_deposit_collateral_expired_market() {
+    #[should_panic(expected = "Error(Contract, #20)")]
+    fn test_resolve_market_rejects_signature_over_other_source_hash() {
         let (env, admin, client, _contract_id) = create_test_contract();
 
-        // Create a market that expires in 1 day
-        let question = String::from_str(&env, "Will BTC reach $200k?");
-        let end_time = env.ledger().timestamp() + 86400; // 24 h from now
-        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
-
+        // The oracle signs against `test_source_hash`, but the market commits
+        // to a different source, so the signature must not verify.
+        let end_time = env.ledger().timestamp() + 86400;
+        let (oracle_pubkey, signature) = generate_test_keypair_and_sign(&env, 1, true);
         client.initialize_market(
             &admin,
-            &question,
+            &String::from_str(&env, "Test market"),
             &end_time,
             &oracle_pubkey,
-            &collateral_token,
+            &create_collateral_token(&env),
+            &test_metadata(&env),
+            &BytesN::from_array(&env, &[0xAAu8; 32]),
+            &None,
+            &None,
+            &0,
         );
 
-        // Advance ledger past end_time so the market is expired
-        env.ledger().set_timestamp(end_time + 1);
-
-        // Attempt to deposit into the expired market — must fail with MarketExpired (#4)
-        let user = Address::generate(&env);
-        client.deposit_collateral(&user, &1, &1000i128);
+        advance_to_end_time(&env, &client, 1);
+        client.resolve_market(
+            &Address::generate(&env),
+            &1,
+            &Outcome::Yes,
+            &None,
+            &signature,
+            &0,
+        );
     }
 
-    // ========== update_position tests ==========
+    // ========== resolution grace tests ==========
 
-    /// Register a market backed by a real Stellar asset, fund `user`, and
-    /// deposit `deposit` stroops of collateral so trades can be exercised.
-    fn setup_funded_market<'a>(
-        deposit: i128,
-    ) -> (Env, Address, MarketContractClient<'a>, Address, u32) {
-        use soroban_sdk::token::StellarAssetClient;
+    #[test]
+    fn test_resolution_grace_override_shortens_window() {
+        use crate::validation::MIN_RESOLUTION_GRACE_SECONDS;
 
-        let env = Env::default();
-        let token_admin = Address::generate(&env);
-        let token = env.register_stellar_asset_contract_v2(token_admin.clone());
-        let collateral_token = token.address();
+        let (env, admin, client, contract_id) = create_test_contract();
+        let end_time = env.ledger().timestamp() + 86400;
+        let collateral_token = create_collateral_token(&env);
+
+        let (fast_pubkey, fast_sig) = generate_test_keypair_and_sign(&env, 1, true);
+        let (slow_pubkey, slow_sig) = generate_test_keypair_and_sign(&env, 2, true);
+        let create = |n: u32, oracle_pubkey: &BytesN<32>, grace: Option<u64>| {
+            client.initialize_market(
+                &admin,
+                &numbered_question(&env, n),
+                &end_time,
+                oracle_pubkey,
+                &collateral_token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &grace,
+                &None,
+                &0,
+            )
+        };
+        let fast = create(1, &fast_pubkey, Some(MIN_RESOLUTION_GRACE_SECONDS));
+        let slow = create(2, &slow_pubkey, None);
 
-        let contract_id = env.register(MarketContract, ());
-        let client = MarketContractClient::new(&env, &contract_id);
-        let admin = Address::generate(&env);
+        fund_market(&env, &client, &collateral_token, slow, 1_000_000);
 
-        env.as_contract(&contract_id, || {
-            storage::set_admin(&env, &admin);
-            storage::set_version(&env);
-        });
+        // Just past the short grace: the fast market's window has closed while
+        // the global default still covers the other market.
+        env.ledger()
+            .set_timestamp(end_time + MIN_RESOLUTION_GRACE_SECONDS + 1);
+        let resolver = Address::generate(&env);
+        assert_eq!(
+            client.try_resolve_market(&resolver, &1, &Outcome::Yes, &None, &fast_sig, &0),
+            Err(Ok(crate::error::ContractError::ResolutionWindowClosed))
+        );
+        client.resolve_market(&resolver, &2, &Outcome::Yes, &None, &slow_sig, &0);
 
-        env.mock_all_auths();
+        assert_eq!(
+            get_market_from_storage(&env, &contract_id, fast).status,
+            MarketStatus::Active
+        );
+        assert_eq!(
+            get_market_from_storage(&env, &contract_id, slow).status,
+            MarketStatus::Resolved
+        );
+    }
 
-        let question = String::from_str(&env, "Will it rain tomorrow?");
+    #[test]
+    fn test_resolution_without_grace_has_no_window() {
+        use crate::validation::MAX_RESOLUTION_GRACE_SECONDS;
+
+        let (env, admin, client, contract_id) = create_test_contract();
         let end_time = env.ledger().timestamp() + 86400;
-        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
+        let token = create_collateral_token(&env);
+        let (oracle_pubkey, signature) = generate_test_keypair_and_sign(&env, 1, true);
         let market_id = client.initialize_market(
             &admin,
-            &question,
+            &String::from_str(&env, "Test market"),
             &end_time,
             &oracle_pubkey,
-            &collateral_token,
+            &token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
         );
+        fund_market(&env, &client, &token, market_id, 1_000);
 
-        let user = Address::generate(&env);
-        let token_client = StellarAssetClient::new(&env, &collateral_token);
-        token_client.mint(&user, &deposit);
-        client.deposit_collateral(&user, &market_id, &deposit);
-
-        (env, user, client, contract_id, market_id)
+        // Only markets that set a resolution grace are bounded.
+        env.ledger()
+            .set_timestamp(end_time + 2 * MAX_RESOLUTION_GRACE_SECONDS);
+        client.resolve_market(
+            &Address::generate(&env),
+            &market_id,
+            &Outcome::Yes,
+            &None,
+            &signature,
+            &0,
+        );
+        assert_eq!(
+            get_market_from_storage(&env, &contract_id, market_id).status,
+            MarketStatus::Resolved
+        );
     }
 
     #[test]
-    fn test_update_position_buys_shares_and_locks_collateral() {
-        use crate::positions::STROOPS_PER_USDC;
-
-        let deposit = 100 * STROOPS_PER_USDC;
-        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+    fn test_resolve_market_threshold_checks_window_and_candidate() {
+        use crate::error::ContractError;
+        use crate::validation::MIN_RESOLUTION_GRACE_SECONDS;
+        use soroban_sdk::vec;
 
-        // Buy 100 YES shares at a 60% price -> lock 60 USDC
-        let yes = 100 * STROOPS_PER_USDC;
-        let position = client.update_position(&user, &market_id, &yes, &0i128, &6000i128);
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let end_time = env.ledger().timestamp() + 86400;
+        let token = create_collateral_token(&env);
+        let create = |n: u32, grace: Option<u64>| {
+            client.initialize_market(
+                &admin,
+                &numbered_question(&env, n),
+                &end_time,
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &grace,
+                &None,
+                &0,
+            )
+        };
+        let bounded = create(1, Some(MIN_RESOLUTION_GRACE_SECONDS));
+        let unbounded = create(2, None);
+        fund_market(&env, &client, &token, bounded, 1_000);
+        fund_market(&env, &client, &token, unbounded, 1_000);
+        let (signer, bounded_sig) = generate_test_keypair_and_sign(&env, bounded, true);
+        client.set_threshold_signers(&admin, &vec![&env, signer], &1);
+        let resolver = Address::generate(&env);
 
-        assert_eq!(position.yes_shares, yes);
-        assert_eq!(position.no_shares, 0);
-        assert_eq!(position.locked_collateral, 60 * STROOPS_PER_USDC);
+        // The quorum is held to the same resolution window as one oracle.
+        env.ledger()
+            .set_timestamp(end_time + MIN_RESOLUTION_GRACE_SECONDS + 1);
+        assert_eq!(
+            client.try_resolve_market_threshold(
+                &resolver,
+                &bounded,
+                &true,
+                &vec![&env, bounded_sig]
+            ),
+            Err(Ok(ContractError::ResolutionWindowClosed))
+        );
 
-        // The persisted position matches the returned one
-        let stored = env.as_contract(&contract_id, || {
-            storage::get_position(&env, market_id, &user).expect("version check ok").expect("position should exist")
-        });
-        assert_eq!(stored.yes_shares, yes);
-        assert_eq!(stored.locked_collateral, 60 * STROOPS_PER_USDC);
+        // And to a registered resolution contract's finalized candidate.
+        register_resolution_contract(&env, &client, &admin);
+        assert_eq!(
+            client.try_resolve_market_threshold(
+                &resolver,
+                &unbounded,
+                &true,
+                &vec![&env, BytesN::from_array(&env, &[0u8; 64])]
+            ),
+            Err(Ok(ContractError::ResolutionNotFinalized))
+        );
+        assert_eq!(client.get_market(&unbounded).status, MarketStatus::Active);
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #10)")]
-    fn test_update_position_insufficient_collateral() {
-        use crate::positions::STROOPS_PER_USDC;
-
-        // Only 10 USDC deposited, but buying 100 YES at 60% needs 60 USDC locked.
-        let deposit = 10 * STROOPS_PER_USDC;
-        let (_env, user, client, _contract_id, market_id) = setup_funded_market(deposit);
+    fn test_initialize_market_rejects_out_of_bounds_grace() {
+        use crate::validation::MAX_RESOLUTION_GRACE_SECONDS;
 
-        let yes = 100 * STROOPS_PER_USDC;
-        client.update_position(&user, &market_id, &yes, &0i128, &6000i128);
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let result = client.try_initialize_market(
+            &admin,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &create_collateral_token(&env),
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &Some(MAX_RESOLUTION_GRACE_SECONDS + 1),
+            &None,
+            &0,
+        );
+        assert_eq!(
+            result,
+            Err(Ok(crate::error::ContractError::InvalidResolutionGrace))
+        );
     }
 
+    // ========== per-market fee override tests ==========
+
     #[test]
-    #[should_panic(expected = "Error(Contract, #13)")]
-    fn test_update_position_rejects_overselling() {
+    fn test_market_fee_override_charges_less_than_global_rate() {
         use crate::positions::STROOPS_PER_USDC;
 
         let deposit = 100 * STROOPS_PER_USDC;
-        let (_env, user, client, _contract_id, market_id) = setup_funded_market(deposit);
+        let (env, user, client, contract_id, default_market, collateral_token) =
+            setup_market_with_funded_user(2 * deposit);
+        let admin = env.as_contract(&contract_id, || storage::get_admin(&env).unwrap());
+        client.set_fee_rate(&admin, &1_000); // 10% global
 
-        // Selling shares the user does not hold drives the balance below zero.
-        client.update_position(
-            &user,
-            &market_id,
-            &(-50 * STROOPS_PER_USDC),
-            &0i128,
-            &6000i128,
+        let promo_market = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Promotional market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &Some(100), // 1%
+            &0,
+        );
+        client.deposit_collateral(&user, &default_market, &deposit, &None);
+        client.deposit_collateral(&user, &promo_market, &deposit, &None);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3_600); // past withdraw cooldown
+
+        let amount = 40 * STROOPS_PER_USDC;
+        client.withdraw_unused_collateral(&user, &default_market, &amount);
+        client.withdraw_unused_collateral(&user, &promo_market, &amount);
+
+        let remaining = |market_id| client.get_position(&market_id, &user).total_deposited;
+        assert_eq!(
+            remaining(default_market),
+            deposit - amount - 4 * STROOPS_PER_USDC
+        );
+        assert_eq!(
+            remaining(promo_market),
+            deposit - amount - 4 * STROOPS_PER_USDC / 10
         );
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #5)")]
-    fn test_update_position_rejects_resolved_market() {
-        use crate::positions::STROOPS_PER_USDC;
-
-        let deposit = 100 * STROOPS_PER_USDC;
-        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+    fn test_initialize_market_rejects_fee_override_above_cap() {
+        let (env, admin, client, contract_id) = create_test_contract();
+        env.as_contract(&contract_id, || storage::set_fee_cap_bps(&env, 500));
+
+        let create = |fee_rate_bps: i128| {
+            client.try_initialize_market(
+                &admin,
+                &String::from_str(&env, "Test market"),
+                &(env.ledger().timestamp() + 86400),
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &create_collateral_token(&env),
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &Some(fee_rate_bps),
+                &0,
+            )
+        };
+        assert_eq!(
+            create(501),
+            Err(Ok(crate::error::ContractError::FeeCapExceeded))
+        );
+        assert_eq!(
+            create(10_001),
+            Err(Ok(crate::error::ContractError::InvalidPrice))
+        );
+        assert!(create(500).is_ok());
+    }
 
-        // Force the market into a resolved state.
-        env.as_contract(&contract_id, || {
-            let mut market = storage::get_market(&env, market_id).unwrap().unwrap();
-            market.status = MarketStatus::Resolved;
-            market.result = Some(true);
-            storage::set_market(&env, market_id, &market).unwrap();
-        });
+    #[test]
+    fn test_initialize_market_rejects_trading_fee_above_max() {
+        use crate::validation::MAX_TRADING_FEE_BPS;
 
-        let yes = 10 * STROOPS_PER_USDC;
-        client.update_position(&user, &market_id, &yes, &0i128, &6000i128);
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let create = |trading_fee_bps: u32| {
+            client.try_initialize_market(
+                &admin,
+                &String::from_str(&env, "Test market"),
+                &(env.ledger().timestamp() + 86400),
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &create_collateral_token(&env),
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &trading_fee_bps,
+            )
+        };
+        assert_eq!(
+            create(MAX_TRADING_FEE_BPS + 1),
+            Err(Ok(crate::error::ContractError::FeeCapExceeded))
+        );
+        let market_id = create(MAX_TRADING_FEE_BPS).unwrap().unwrap();
+        assert_eq!(
+            client.get_market(&market_id).trading_fee_bps,
+            MAX_TRADING_FEE_BPS
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #4)")]
-    fn test_update_position_rejects_expired_market() {
+    fn test_trading_fee_accrues_and_is_withdrawn_by_admin() {
+        use crate::error::ContractError;
         use crate::positions::STROOPS_PER_USDC;
+        use soroban_sdk::token::TokenClient;
 
         let deposit = 100 * STROOPS_PER_USDC;
-        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+        let (env, user, client, contract_id, _market_id, collateral_token) =
+            setup_market_with_funded_user(deposit);
+        let admin = env.as_contract(&contract_id, || storage::get_admin(&env).unwrap());
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Fee market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &200, // 2%
+        );
+        client.deposit_collateral(&user, &market_id, &deposit, &None);
+
+        // 50 shares at 50% lock 25 USDC and cost a 1 USDC fee.
+        let quantity = 50 * STROOPS_PER_USDC;
+        client.buy_shares(&user, &market_id, &true, &quantity);
+        client.buy_shares(&user, &market_id, &false, &quantity);
+        let fee = 2 * STROOPS_PER_USDC;
+        assert_eq!(client.get_accrued_fees(&market_id), fee);
+        assert_eq!(
+            client.get_position(&market_id, &user).total_deposited,
+            deposit - fee
+        );
 
-        // Advance the ledger past the market end_time.
+        // Selling is free.
+        client.sell_shares(&user, &market_id, &true, &quantity);
+        assert_eq!(client.get_accrued_fees(&market_id), fee);
+
+        assert_eq!(
+            client.try_withdraw_fees(&user, &market_id),
+            Err(Ok(ContractError::NotAdmin))
+        );
+        assert_eq!(client.withdraw_fees(&admin, &market_id), fee);
+        assert!(emitted(&env, "fees_withdrawn"));
+        assert_eq!(
+            TokenClient::new(&env, &collateral_token).balance(&admin),
+            fee
+        );
+        assert_eq!(client.get_accrued_fees(&market_id), 0);
+        assert_eq!(client.withdraw_fees(&admin, &market_id), 0);
+    }
+
+    #[test]
+    fn test_trading_fee_requires_free_collateral() {
+        use crate::error::ContractError;
+        use crate::positions::STROOPS_PER_USDC;
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, user, client, contract_id, _market_id, collateral_token) =
+            setup_market_with_funded_user(deposit);
+        let admin = env.as_contract(&contract_id, || storage::get_admin(&env).unwrap());
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Fee market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &100, // 1%
+        );
+        client.deposit_collateral(&user, &market_id, &deposit, &None);
+
+        // Locking the whole deposit leaves nothing to pay the fee with.
+        let quantity = 200 * STROOPS_PER_USDC;
+        assert_eq!(
+            client.try_buy_shares(&user, &market_id, &true, &quantity),
+            Err(Ok(ContractError::InsufficientCollateral))
+        );
+        assert_eq!(client.get_accrued_fees(&market_id), 0);
+        assert_eq!(
+            client.get_position(&market_id, &user).total_deposited,
+            deposit
+        );
+    }
+
+    // ========== resolve_market tests ==========
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1)")]
+    fn test_resolve_market_not_found() {
+        let (env, _admin, client, _contract_id) = create_test_contract();
+
+        let resolver = Address::generate(&env);
+        let non_existent_market_id = 999u32;
+        let outcome = true;
+        let invalid_signature = BytesN::from_array(&env, &[0u8; 64]);
+
+        client.resolve_market(
+            &resolver,
+            &non_existent_market_id,
+            &outcome.into(),
+            &None,
+            &invalid_signature,
+            &0,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #2)")]
+    fn test_resolve_market_already_resolved() {
+        let (env, admin, client, contract_id) = create_test_contract();
+
+        // Create a market
+        let question = String::from_str(&env, "Test market");
+        let end_time = env.ledger().timestamp() + 86400;
+        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
+        let collateral_token = create_collateral_token(&env);
+
+        let market_id = client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+
+        // Manually set market to resolved status
+        env.as_contract(&contract_id, || {
+            let mut market = storage::get_market(&env, market_id).unwrap().unwrap();
+            market.status = MarketStatus::Resolved;
+            market.result = Some(true);
+            storage::set_market(&env, market_id, &market).unwrap();
+        });
+
+        // Try to resolve again - should fail
+        let resolver = Address::generate(&env);
+        let outcome = true;
+        let invalid_signature = BytesN::from_array(&env, &[0u8; 64]);
+        client.resolve_market(
+            &resolver,
+            &1,
+            &outcome.into(),
+            &None,
+            &invalid_signature,
+            &0,
+        );
+    }
+
+    #[test]
+    fn test_validate_outcome_checks_result_and_kind() {
+        use crate::error::ContractError;
+        use crate::types::{MarketKind, ScalarBounds};
+        use crate::validation::validate_outcome;
+
+        let (env, _admin, _user, _client, contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(100);
+        let mut market = get_market_from_storage(&env, &contract_id, market_id);
+
+        assert_eq!(validate_outcome(&market, Outcome::Yes), Ok(()));
+        assert_eq!(validate_outcome(&market, Outcome::No), Ok(()));
+        assert_eq!(validate_outcome(&market, Outcome::Invalid), Ok(()));
+
+        market.kind = MarketKind::Scalar(ScalarBounds {
+            lower: 0,
+            upper: 100,
+        });
+        assert_eq!(
+            validate_outcome(&market, Outcome::Yes),
+            Err(ContractError::InvalidOutcome)
+        );
+
+        market.kind = MarketKind::Binary;
+        market.result = Some(false);
+        assert_eq!(
+            validate_outcome(&market, Outcome::Yes),
+            Err(ContractError::MarketAlreadyResolved)
+        );
+    }
+
+    #[test]
+    fn test_resolve_market_rejects_market_with_existing_result() {
+        use crate::error::ContractError;
+
+        let (env, _admin, _user, client, contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(100);
+
+        // A result left on a market that is otherwise still active must not
+        // be overwritten by a second resolution.
+        env.as_contract(&contract_id, || {
+            let mut market = storage::get_market(&env, market_id).unwrap().unwrap();
+            market.result = Some(false);
+            storage::set_market(&env, market_id, &market).unwrap();
+        });
+        advance_to_end_time(&env, &client, market_id);
+
+        let (_pubkey, signature) = generate_test_keypair_and_sign(&env, market_id, true);
+        let resolver = Address::generate(&env);
+        assert_eq!(
+            client.try_resolve_market(&resolver, &market_id, &Outcome::Yes, &None, &signature, &0),
+            Err(Ok(ContractError::MarketAlreadyResolved))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #20)")]
+    fn test_resolve_market_invalid_signature() {
+        let (env, admin, client, _contract_id) = create_test_contract();
+
+        // Create a market
+        let question = String::from_str(&env, "Test market");
+        let end_time = env.ledger().timestamp() + 86400;
+        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
+        let collateral_token = create_collateral_token(&env);
+
+        let _market_id = client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+
+        // Bad signature must surface as the typed InvalidSignature error
+        // (#20), not an uncaught host trap.
+        let resolver = Address::generate(&env);
+        let outcome = true;
+        let invalid_signature = BytesN::random(&env);
+        advance_to_end_time(&env, &client, 1);
+        client.resolve_market(
+            &resolver,
+            &1,
+            &outcome.into(),
+            &None,
+            &invalid_signature,
+            &0,
+        );
+    }
+
+    #[test]
+    fn test_resolve_market_invalid_signature_leaves_market_active() {
+        let (env, admin, client, contract_id) = create_test_contract();
+
+        let question = String::from_str(&env, "Test market");
+        let end_time = env.ledger().timestamp() + 86400;
+        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
+        let collateral_token = create_collateral_token(&env);
+
+        let market_id = client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+
+        let resolver = Address::generate(&env);
+        let outcome = true;
+        let invalid_signature = BytesN::random(&env);
+        advance_to_end_time(&env, &client, 1);
+        let result = client.try_resolve_market(
+            &resolver,
+            &1,
+            &outcome.into(),
+            &None,
+            &invalid_signature,
+            &0,
+        );
+
+        assert_eq!(
+            result,
+            Err(Ok(crate::error::ContractError::InvalidSignature))
+        );
+
+        // Market must be untouched - no partial state mutation on failure.
+        let market = get_market_from_storage(&env, &contract_id, market_id);
+        assert_eq!(market.status, MarketStatus::Active);
+        assert_eq!(market.result, None);
+    }
+
+    #[test]
+    fn test_resolve_market_with_valid_signature() {
+        let (env, admin, client, contract_id) = create_test_contract();
+
+        // Create a market
+        let question = String::from_str(&env, "Test market");
+        let end_time = env.ledger().timestamp() + 86400;
+        let collateral_token = create_collateral_token(&env);
+
+        // Generate test keypair and signature
+        let market_id = 1u32;
+        let outcome = true;
+        let (oracle_pubkey, signature) = generate_test_keypair_and_sign(&env, market_id, outcome);
+
+        // Initialize market with the generated pubkey
+        let market_id = client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+
+        // Verify market is initially Active
+        let market_before = get_market_from_storage(&env, &contract_id, market_id);
+        assert_eq!(market_before.status, MarketStatus::Active);
+        assert_eq!(market_before.result, None);
+
+        fund_market(&env, &client, &collateral_token, market_id, 1_000_000);
+
+        // Resolve market with valid signature
+        let resolver = Address::generate(&env);
+        advance_to_end_time(&env, &client, 1);
+        client.resolve_market(
+            &resolver,
+            &1,
+            &outcome.into(),
+            &Some(oracle_pubkey),
+            &signature,
+            &0,
+        );
+
+        // Verify market is now Resolved
+        let market_after = get_market_from_storage(&env, &contract_id, market_id);
+        assert_eq!(market_after.status, MarketStatus::Resolved);
+        assert_eq!(market_after.result, Some(outcome));
+        assert_eq!(market_after.resolver, Some(resolver));
+    }
+
+    #[test]
+    fn test_resolve_market_rejects_signature_from_unauthorized_oracle() {
+        use crate::error::ContractError;
+
+        let (env, admin, client, contract_id) = create_test_contract();
+        let (oracle_pubkey, _) = generate_test_keypair_and_sign(&env, 1, true);
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &oracle_pubkey,
+            &create_collateral_token(&env),
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        advance_to_end_time(&env, &client, market_id);
+        let resolver = Address::generate(&env);
+
+        // A well-formed signature from another oracle is an authorization
+        // failure when that oracle names itself...
+        let (other_pubkey, other_signature) = generate_test_keypair_and_sign(&env, market_id, true);
+        assert_eq!(
+            client.try_resolve_market(
+                &resolver,
+                &market_id,
+                &Outcome::Yes,
+                &Some(other_pubkey),
+                &other_signature,
+                &0,
+            ),
+            Err(Ok(ContractError::UnauthorizedOracle))
+        );
+        // ...and only fails verification when it is checked against the
+        // market's oracle.
+        assert_eq!(
+            client.try_resolve_market(
+                &resolver,
+                &market_id,
+                &Outcome::Yes,
+                &None,
+                &other_signature,
+                &0
+            ),
+            Err(Ok(ContractError::InvalidSignature))
+        );
+        assert_eq!(
+            client.try_resolve_market(
+                &resolver,
+                &market_id,
+                &Outcome::Yes,
+                &Some(oracle_pubkey),
+                &other_signature,
+                &0,
+            ),
+            Err(Ok(ContractError::InvalidSignature))
+        );
+        assert_eq!(
+            get_market_from_storage(&env, &contract_id, market_id).status,
+            MarketStatus::Active
+        );
+    }
+
+    #[test]
+    fn test_rotate_oracle_replaces_key_until_resolution() {
+        use crate::error::ContractError;
+
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let (old_pubkey, _) = generate_test_keypair_and_sign(&env, 1, true);
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &old_pubkey,
+            &create_collateral_token(&env),
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        let (new_pubkey, signature) = generate_test_keypair_and_sign(&env, market_id, true);
+
+        assert_eq!(
+            client.try_rotate_oracle(&Address::generate(&env), &market_id, &new_pubkey),
+            Err(Ok(ContractError::NotAdmin))
+        );
+        assert_eq!(
+            client.try_rotate_oracle(&admin, &market_id, &BytesN::from_array(&env, &[0u8; 32])),
+            Err(Ok(ContractError::InvalidSignature))
+        );
+        client.rotate_oracle(&admin, &market_id, &new_pubkey);
+        assert!(emitted(&env, "oracle_rotated"));
+        assert_eq!(client.get_market(&market_id).oracle_pubkey, new_pubkey);
+
+        // Only the new key can now resolve the market.
+        advance_to_end_time(&env, &client, market_id);
+        let resolver = Address::generate(&env);
+        client.resolve_market(&resolver, &market_id, &Outcome::Yes, &None, &signature, &0);
+
+        assert_eq!(
+            client.try_rotate_oracle(&admin, &market_id, &old_pubkey),
+            Err(Ok(ContractError::MarketAlreadyResolved))
+        );
+    }
+
+    #[test]
+    fn test_resolve_market_rejected_before_end_time() {
+        use crate::error::ContractError;
+
+        let (env, admin, client, contract_id) = create_test_contract();
+        let end_time = env.ledger().timestamp() + 86400;
+        let (oracle_pubkey, signature) = generate_test_keypair_and_sign(&env, 1, true);
+        let collateral_token = create_collateral_token(&env);
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Test market"),
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        fund_market(&env, &client, &collateral_token, market_id, 1_000_000);
+        let resolver = Address::generate(&env);
+
+        // Trading is still open one second before end_time.
+        env.ledger().set_timestamp(end_time - 1);
+        assert_eq!(
+            client.try_resolve_market(&resolver, &market_id, &Outcome::Yes, &None, &signature, &0),
+            Err(Ok(ContractError::MarketNotEnded))
+        );
+        assert_eq!(
+            get_market_from_storage(&env, &contract_id, market_id).status,
+            MarketStatus::Active
+        );
+
+        env.ledger().set_timestamp(end_time);
+        client.resolve_market(&resolver, &market_id, &Outcome::Yes, &None, &signature, &0);
+        let market = get_market_from_storage(&env, &contract_id, market_id);
+        assert_eq!(market.status, MarketStatus::Resolved);
+        assert_eq!(market.result, Some(true));
+    }
+
+    #[test]
+    fn test_resolve_market_updates_status_and_result() {
+        let (env, admin, client, contract_id) = create_test_contract();
+
+        // Create a market
+        let question = String::from_str(&env, "Test market");
+        let end_time = env.ledger().timestamp() + 86400;
+        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
+        let collateral_token = create_collateral_token(&env);
+
+        let market_id = client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+
+        // Verify market is initially Active
+        let market_before = get_market_from_storage(&env, &contract_id, market_id);
+        assert_eq!(market_before.status, MarketStatus::Active);
+        assert_eq!(market_before.result, None);
+
+        // Verify market structure is correct
+        assert_eq!(market_before.oracle_pubkey, oracle_pubkey);
+    }
+
+    #[test]
+    fn test_resolve_market_emits_event() {
+        let (env, admin, client, contract_id) = create_test_contract();
+
+        // Create a market
+        let question = String::from_str(&env, "Test market");
+        let end_time = env.ledger().timestamp() + 86400;
+        let collateral_token = create_collateral_token(&env);
+
+        // Generate test keypair and signature
+        let market_id = 1u32;
+        let outcome = true;
+        let (oracle_pubkey, signature) = generate_test_keypair_and_sign(&env, market_id, outcome);
+
+        let market_id = client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+
+        // Clear events from initialization
+        env.events().all();
+
+        fund_market(&env, &client, &collateral_token, market_id, 1_000_000);
+
+        // Resolve market with valid signature
+        let resolver = Address::generate(&env);
+        advance_to_end_time(&env, &client, 1);
+        client.resolve_market(&resolver, &1, &outcome.into(), &None, &signature, &0);
+
+        // Verify event was emitted
+        let events = env.events().all();
+        assert!(!events.is_empty());
+
+        // Verify that market is resolved
+        let market = get_market_from_storage(&env, &contract_id, market_id);
+        assert_eq!(market.status, MarketStatus::Resolved);
+        assert_eq!(market.result, Some(outcome));
+        assert_eq!(market.resolver, Some(resolver));
+    }
+
+    #[test]
+    fn test_deposit_idempotency_key_transfers_once() {
+        use crate::error::ContractError;
+        use soroban_sdk::token::{Client as TokenClient, StellarAssetClient};
+
+        let (env, user, client, contract_id, market_id, token) = setup_market_with_funded_user(300);
+        let key = BytesN::from_array(&env, &[7u8; 32]);
+
+        client.deposit_collateral(&user, &market_id, &100, &Some(key.clone()));
+        assert_eq!(
+            client.try_deposit_collateral(&user, &market_id, &100, &Some(key.clone())),
+            Err(Ok(ContractError::InvalidQuantity))
+        );
+        assert_eq!(TokenClient::new(&env, &token).balance(&user), 200);
+        assert_eq!(TokenClient::new(&env, &token).balance(&contract_id), 100);
+        assert_eq!(client.get_position(&market_id, &user).total_deposited, 100);
+
+        // A fresh key, or none at all, deposits again.
+        client.deposit_collateral(
+            &user,
+            &market_id,
+            &50,
+            &Some(BytesN::from_array(&env, &[8u8; 32])),
+        );
+        client.deposit_collateral(&user, &market_id, &25, &None);
+        client.deposit_collateral(&user, &market_id, &25, &None);
+        assert_eq!(client.get_position(&market_id, &user).total_deposited, 200);
+
+        // Keys are tracked per user.
+        let other = Address::generate(&env);
+        StellarAssetClient::new(&env, &token).mint(&other, &100);
+        client.deposit_collateral(&other, &market_id, &100, &Some(key));
+        assert_eq!(client.get_position(&market_id, &other).total_deposited, 100);
+    }
+
+    #[test]
+    fn test_deposit_idempotency_key_expires() {
+        use crate::error::ContractError;
+        use crate::storage::DEPOSIT_KEY_TTL;
+
+        let (env, user, client, _contract_id, market_id, _token) =
+            setup_market_with_funded_user(300);
+        let key = BytesN::from_array(&env, &[7u8; 32]);
+        client.deposit_collateral(&user, &market_id, &100, &Some(key.clone()));
+
+        env.ledger()
+            .with_mut(|li| li.sequence_number += DEPOSIT_KEY_TTL);
+        assert_eq!(
+            client.try_deposit_collateral(&user, &market_id, &100, &Some(key.clone())),
+            Err(Ok(ContractError::InvalidQuantity))
+        );
+
+        env.ledger().with_mut(|li| li.sequence_number += 1);
+        client.deposit_collateral(&user, &market_id, &100, &Some(key));
+        assert_eq!(client.get_position(&market_id, &user).total_deposited, 200);
+    }
+
+    #[test]
+    fn test_get_token_balance_tracks_deposits() {
+        let (env, user, client, _contract_id, market_id, token) =
+            setup_market_with_funded_user(300);
+        assert_eq!(client.get_token_balance(&token), 0);
+
+        client.deposit_collateral(&user, &market_id, &100, &None);
+        assert_eq!(client.get_token_balance(&token), 100);
+        client.deposit_collateral(&user, &market_id, &200, &None);
+        assert_eq!(client.get_token_balance(&token), 300);
+        assert_eq!(
+            client.get_token_balance(&token),
+            client.get_market_stats(&market_id).total_collateral
+        );
+
+        // Other tokens are reported separately.
+        let other = create_collateral_token(&env);
+        assert_eq!(client.get_token_balance(&other), 0);
+    }
+
+    #[test]
+    fn test_collateral_deposit_emits_event() {
+        use soroban_sdk::token::StellarAssetClient;
+
+        let env = Env::default();
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone());
+        let collateral_token = token.address();
+
+        let contract_id = env.register(MarketContract, ());
+        let client = MarketContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+
+        env.as_contract(&contract_id, || {
+            storage::set_admin(&env, &admin);
+            storage::set_version(&env);
+        });
+
+        env.mock_all_auths();
+
+        // Create a market
+        let question = String::from_str(&env, "Test market");
+        let end_time = env.ledger().timestamp() + 86400;
+        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
+
+        let _market_id = client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+
+        // Clear events from initialization
+        env.events().all();
+
+        // Mint tokens to user for deposit
+        let user = Address::generate(&env);
+        let amount = 1000i128;
+        let token_client = StellarAssetClient::new(&env, &collateral_token);
+        token_client.mint(&user, &amount);
+
+        // Deposit collateral
+        client.deposit_collateral(&user, &1, &amount, &None);
+
+        // Verify event was emitted
+        let events = env.events().all();
+        assert!(
+            !events.is_empty(),
+            "CollateralDeposited event should be emitted"
+        );
+    }
+
+    // ========== Expiration check tests ==========
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #4)")]
+    fn test_deposit_collateral_expired_market() {
+        let (env, admin, client, _contract_id) = create_test_contract();
+
+        // Create a market that expires in 1 day
+        let question = String::from_str(&env, "Will BTC reach $200k?");
+        let end_time = env.ledger().timestamp() + 86400; // 24 h from now
+        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
+        let collateral_token = create_collateral_token(&env);
+
+        client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+
+        // Advance ledger past end_time so the market is expired
+        env.ledger().set_timestamp(end_time + 1);
+
+        // Attempt to deposit into the expired market — must fail with MarketExpired (#4)
+        let user = Address::generate(&env);
+        client.deposit_collateral(&user, &1, &1000i128, &None);
+    }
+
+    // ========== update_position tests ==========
+
+    /// Register a market backed by a real Stellar asset and mint `balance`
+    /// stroops of collateral to a fresh user without depositing it.
+    ///
+    /// Returns `(env, user, client, contract_id, market_id, collateral_token)`.
+    fn setup_market_with_funded_user<'a>(
+        balance: i128,
+    ) -> (
+        Env,
+        Address,
+        MarketContractClient<'a>,
+        Address,
+        u32,
+        Address,
+    ) {
+        use soroban_sdk::token::StellarAssetClient;
+
+        let env = Env::default();
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone());
+        let collateral_token = token.address();
+
+        let contract_id = env.register(MarketContract, ());
+        let client = MarketContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+
+        env.as_contract(&contract_id, || {
+            storage::set_admin(&env, &admin);
+            storage::set_version(&env);
+        });
+
+        env.mock_all_auths();
+
+        let question = String::from_str(&env, "Will it rain tomorrow?");
+        let end_time = env.ledger().timestamp() + 86400;
+        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
+        let market_id = client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+
+        let user = Address::generate(&env);
+        let token_client = StellarAssetClient::new(&env, &collateral_token);
+        token_client.mint(&user, &balance);
+
+        (env, user, client, contract_id, market_id, collateral_token)
+    }
+
+    /// Register a market backed by a real Stellar asset, fund `user`, and
+    /// deposit `deposit` stroops of collateral so trades can be exercised.
+    fn setup_funded_market<'a>(
+        deposit: i128,
+    ) -> (Env, Address, MarketContractClient<'a>, Address, u32) {
+        let (env, user, client, contract_id, market_id, _collateral_token) =
+            setup_market_with_funded_user(deposit);
+        client.deposit_collateral(&user, &market_id, &deposit, &None);
+
+        (env, user, client, contract_id, market_id)
+    }
+
+    #[test]
+    fn test_update_position_buys_shares_and_locks_collateral() {
+        use crate::positions::STROOPS_PER_USDC;
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+
+        // Buy 100 YES shares at a 60% price -> lock 60 USDC
+        let yes = 100 * STROOPS_PER_USDC;
+        let position = client.update_position(&user, &market_id, &yes, &0i128, &6000i128);
+
+        assert_eq!(position.yes_shares, yes);
+        assert_eq!(position.no_shares, 0);
+        assert_eq!(position.locked_collateral, 60 * STROOPS_PER_USDC);
+
+        // The persisted position matches the returned one
+        let stored = env.as_contract(&contract_id, || {
+            storage::get_position(&env, market_id, &user).expect("version check ok").expect("position should exist")
+        });
+        assert_eq!(stored.yes_shares, yes);
+        assert_eq!(stored.locked_collateral, 60 * STROOPS_PER_USDC);
+    }
+
+    #[test]
+    fn test_position_records_created_and_updated_timestamps() {
+        use crate::positions::STROOPS_PER_USDC;
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, user, client, _contract_id, market_id, _token) =
+            setup_market_with_funded_user(deposit);
+        let created = env.ledger().timestamp();
+        client.deposit_collateral(&user, &market_id, &(deposit / 2), &None);
+        let position = client.get_position(&market_id, &user);
+        assert_eq!(
+            (position.created_at, position.updated_at),
+            (created, created)
+        );
+
+        env.ledger().set_timestamp(created + 100);
+        let traded =
+            client.update_position(&user, &market_id, &STROOPS_PER_USDC, &0i128, &5_000i128);
+        assert_eq!(
+            (traded.created_at, traded.updated_at),
+            (created, created + 100)
+        );
+        assert_eq!(client.get_position(&market_id, &user), traded);
+
+        env.ledger().set_timestamp(created + 250);
+        client.deposit_collateral(&user, &market_id, &(deposit / 2), &None);
+        let position = client.get_position(&market_id, &user);
+        assert_eq!(
+            (position.created_at, position.updated_at),
+            (created, created + 250)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #10)")]
+    fn test_update_position_insufficient_collateral() {
+        use crate::positions::STROOPS_PER_USDC;
+
+        // Only 10 USDC deposited, but buying 100 YES at 60% needs 60 USDC locked.
+        let deposit = 10 * STROOPS_PER_USDC;
+        let (_env, user, client, _contract_id, market_id) = setup_funded_market(deposit);
+
+        let yes = 100 * STROOPS_PER_USDC;
+        client.update_position(&user, &market_id, &yes, &0i128, &6000i128);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #13)")]
+    fn test_update_position_rejects_overselling() {
+        use crate::positions::STROOPS_PER_USDC;
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (_env, user, client, _contract_id, market_id) = setup_funded_market(deposit);
+
+        // Selling shares the user does not hold drives the balance below zero.
+        client.update_position(
+            &user,
+            &market_id,
+            &(-50 * STROOPS_PER_USDC),
+            &0i128,
+            &6000i128,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #5)")]
+    fn test_update_position_rejects_resolved_market() {
+        use crate::positions::STROOPS_PER_USDC;
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+
+        // Force the market into a resolved state.
+        env.as_contract(&contract_id, || {
+            let mut market = storage::get_market(&env, market_id).unwrap().unwrap();
+            market.status = MarketStatus::Resolved;
+            market.result = Some(true);
+            storage::set_market(&env, market_id, &market).unwrap();
+        });
+
+        let yes = 10 * STROOPS_PER_USDC;
+        client.update_position(&user, &market_id, &yes, &0i128, &6000i128);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #4)")]
+    fn test_update_position_rejects_expired_market() {
+        use crate::positions::STROOPS_PER_USDC;
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+
+        // Advance the ledger past the market end_time.
         let end_time = env.as_contract(&contract_id, || {
             storage::get_market(&env, market_id).unwrap().unwrap().end_time
         });
-        env.ledger().set_timestamp(end_time + 1);
+        env.ledger().set_timestamp(end_time + 1);
+
+        let yes = 10 * STROOPS_PER_USDC;
+        client.update_position(&user, &market_id, &yes, &0i128, &6000i128);
+    }
+
+    // ========== set_fee_rate / get_fee_rate tests ==========
+
+    #[test]
+    fn test_get_fee_rate_default_is_zero() {
+        let (_env, _admin, client, _contract_id) = create_test_contract();
+        assert_eq!(client.get_fee_rate(), 0i128);
+    }
+
+    #[test]
+    fn test_set_fee_rate_admin_can_update() {
+        let (_env, admin, client, _contract_id) = create_test_contract();
+        client.set_fee_rate(&admin, &100i128);
+        assert_eq!(client.get_fee_rate(), 100i128);
+    }
+
+    #[test]
+    fn test_set_fee_rate_zero_is_valid() {
+        let (_env, admin, client, _contract_id) = create_test_contract();
+        client.set_fee_rate(&admin, &0i128);
+        assert_eq!(client.get_fee_rate(), 0i128);
+    }
+
+    #[test]
+    fn test_set_fee_rate_max_boundary_valid() {
+        let (_env, admin, client, _contract_id) = create_test_contract();
+        client.set_fee_rate(&admin, &10_000i128);
+        assert_eq!(client.get_fee_rate(), 10_000i128);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #30)")]
+    fn test_set_fee_rate_exceeds_max_rejected() {
+        let (_env, admin, client, _contract_id) = create_test_contract();
+        client.set_fee_rate(&admin, &10_001i128);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #41)")]
+    fn test_set_fee_rate_non_admin_rejected() {
+        let (env, _admin, client, _contract_id) = create_test_contract();
+        let non_admin = Address::generate(&env);
+        client.set_fee_rate(&non_admin, &50i128);
+    }
+
+    // ========== Validation guard tests ==========
+
+    #[test]
+    fn test_validation_guard_accepts_positive_input() {
+        use crate::validation::validate_input_guard;
+        assert!(validate_input_guard(1).is_ok());
+        assert!(validate_input_guard(1000).is_ok());
+    }
+
+    #[test]
+    fn test_validation_guard_rejects_zero() {
+        use crate::{error::ContractError, validation::validate_input_guard};
+        assert_eq!(validate_input_guard(0), Err(ContractError::InvalidQuantity));
+    }
+
+    #[test]
+    fn test_validation_guard_rejects_negative() {
+        use crate::{error::ContractError, validation::validate_input_guard};
+        assert_eq!(
+            validate_input_guard(-1),
+            Err(ContractError::InvalidQuantity)
+        );
+    }
+
+    // ========== propose_admin / accept_admin tests ==========
+
+    #[test]
+    fn test_propose_admin_success() {
+        let (env, admin, client, contract_id) = create_test_contract();
+        let new_admin = Address::generate(&env);
+
+        client.propose_admin(&admin, &new_admin);
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(
+                storage::get_pending_admin(&env).expect("pending admin should be set"),
+                new_admin
+            );
+        });
+    }
+
+    #[test]
+    fn test_propose_admin_emits_event() {
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let new_admin = Address::generate(&env);
+
+        client.propose_admin(&admin, &new_admin);
+
+        let events = env.events().all();
+        assert!(!events.is_empty());
+    }
+    
+    #[test]
+    #[should_panic(expected = "Error(Contract, #35)")]
+    fn test_propose_admin_with_contract_address_fails() {
+        let (env, admin, client, _contract_id) = create_test_contract();
+        
+        // Try to propose a contract address as admin
+        let contract_admin = env.register(MarketContract, ());
+        
+        // This should fail with InvalidAdmin error (#35)
+        client.propose_admin(&admin, &contract_admin);
+    }
+
+    #[test]
+    fn test_accept_admin_completes_transfer() {
+        let (env, admin, client, contract_id) = create_test_contract();
+        let new_admin = Address::generate(&env);
+
+        client.propose_admin(&admin, &new_admin);
+        client.accept_admin(&new_admin);
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(storage::get_admin(&env).unwrap(), new_admin);
+            assert!(
+                storage::get_pending_admin(&env).is_none(),
+                "pending admin should be cleared after acceptance"
+            );
+        });
+    }
+
+    #[test]
+    fn test_accept_admin_emits_event() {
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let new_admin = Address::generate(&env);
+
+        client.propose_admin(&admin, &new_admin);
+        env.events().all(); // clear
+
+        client.accept_admin(&new_admin);
+
+        let events = env.events().all();
+        assert!(!events.is_empty());
+    }
+
+    #[test]
+    fn test_new_admin_can_create_market_after_transfer() {
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let new_admin = Address::generate(&env);
+
+        client.propose_admin(&admin, &new_admin);
+        client.accept_admin(&new_admin);
+
+        let question = String::from_str(&env, "Will ETH flip BTC?");
+        let end_time = env.ledger().timestamp() + 86400;
+        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
+        let collateral_token = create_collateral_token(&env);
+
+        let market_id = client.initialize_market(
+            &new_admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        assert_eq!(market_id, 1);
+    }
+
+    #[test]
+    fn test_old_admin_cannot_create_market_after_transfer() {
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let new_admin = Address::generate(&env);
+
+        client.propose_admin(&admin, &new_admin);
+        client.accept_admin(&new_admin);
+
+        let question = String::from_str(&env, "Will ETH flip BTC?");
+        let end_time = env.ledger().timestamp() + 86400;
+        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
+        let collateral_token = create_collateral_token(&env);
+
+        let result = client.try_initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_propose_admin_overwrites_previous_nominee() {
+        let (env, admin, client, contract_id) = create_test_contract();
+        let first_nominee = Address::generate(&env);
+        let second_nominee = Address::generate(&env);
+
+        client.propose_admin(&admin, &first_nominee);
+        client.propose_admin(&admin, &second_nominee);
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(
+                storage::get_pending_admin(&env).expect("pending admin should be set"),
+                second_nominee
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #41)")]
+    fn test_propose_admin_non_admin_fails() {
+        let (env, _admin, client, _contract_id) = create_test_contract();
+        let attacker = Address::generate(&env);
+        let victim = Address::generate(&env);
+
+        client.propose_admin(&attacker, &victim);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #41)")]
+    fn test_propose_admin_when_not_initialized_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register(MarketContract, ());
+        let client = MarketContractClient::new(&env, &contract_id);
+
+        let caller = Address::generate(&env);
+        let new_admin = Address::generate(&env);
+        client.propose_admin(&caller, &new_admin);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #43)")]
+    fn test_accept_admin_with_no_pending_fails() {
+        let (env, _admin, client, _contract_id) = create_test_contract();
+        let attacker = Address::generate(&env);
+
+        client.accept_admin(&attacker);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #40)")]
+    fn test_accept_admin_hijack_wrong_address_fails() {
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let new_admin = Address::generate(&env);
+        let attacker = Address::generate(&env);
+
+        client.propose_admin(&admin, &new_admin);
+        client.accept_admin(&attacker);
+    }
+
+    #[test]
+    fn test_set_treasury_records_contract_address() {
+        let (env, admin, client, contract_id) = create_test_contract();
+        let treasury = Address::generate(&env);
+
+        client.set_treasury_contract(&admin, &treasury);
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(storage::get_treasury(&env).unwrap(), treasury);
+        });
+    }
+
+    #[test]
+    fn test_set_outcome_token_contract_records_contract_address() {
+        let (env, admin, client, contract_id) = create_test_contract();
+        let outcome_token_contract = Address::generate(&env);
+
+        client.set_outcome_token_contract(&admin, &outcome_token_contract);
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(storage::get_outcome_token_contract(&env).unwrap(), outcome_token_contract);
+        });
+    }
+
+    #[test]
+    fn test_set_resolution_contract_records_contract_address() {
+        let (env, admin, client, contract_id) = create_test_contract();
+        let resolution_contract = Address::generate(&env);
+
+        client.set_resolution_contract(&admin, &resolution_contract);
+
+        env.as_contract(&contract_id, || {
+            assert_eq!(storage::get_resolution_contract(&env).unwrap(), resolution_contract);
+        });
+    }
+
+    #[test]
+    fn test_non_admin_cannot_set_optional_integration_contracts() {
+        use crate::error::ContractError;
+
+        let (env, _admin, client, _contract_id) = create_test_contract();
+        let stranger = Address::generate(&env);
+        let address = Address::generate(&env);
+
+        assert_eq!(client.try_set_treasury_contract(&stranger, &address), Err(Ok(ContractError::NotAdmin)));
+        assert_eq!(client.try_set_outcome_token_contract(&stranger, &address), Err(Ok(ContractError::NotAdmin)));
+        assert_eq!(client.try_set_resolution_contract(&stranger, &address), Err(Ok(ContractError::NotAdmin)));
+    }
+
+    #[test]
+    fn test_resolution_contract_requires_finalized_candidate_before_resolve() {
+        use crate::error::ContractError;
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register(MarketContract, ());
+        let client = MarketContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+
+        env.as_contract(&contract_id, || {
+            storage::set_admin(&env, &admin);
+            storage::set_version(&env);
+        });
+
+        // Market IDs start at 1, so sign for the market about to be created.
+        let (oracle_pubkey, signature) = generate_test_keypair_and_sign(&env, 1, true);
+
+        let collateral_token = create_collateral_token(&env);
+        let question = String::from_str(&env, "Will it rain tomorrow?");
+        let end_time = env.ledger().timestamp() + 86400;
+        let market_id = client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+
+        let resolution_addr = env.register(ResolutionContract, ());
+        ResolutionContractClient::new(&env, &resolution_addr)
+            .initialize(&admin, &Address::generate(&env), &contract_id, &60);
+
+        client.set_resolution_contract(&admin, &resolution_addr);
+
+        let proposer = Address::generate(&env);
+        let evidence = String::from_str(&env, "evidence://uri");
+        ResolutionContractClient::new(&env, &resolution_addr)
+            .propose(&proposer, &market_id, &true, &signature, &(env.ledger().timestamp() + 60), &evidence, &60);
+
+        let resolver = Address::generate(&env);
+        advance_to_end_time(&env, &client, 1);
+        assert_eq!(
+            client.try_resolve_market(&resolver, &1, &Outcome::Yes, &None, &signature, &0),
+            Err(Ok(ContractError::ResolutionNotFinalized))
+        );
+    }
+
+    #[test]
+    fn test_first_nominee_cannot_accept_after_overwrite() {
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let first_nominee = Address::generate(&env);
+        let second_nominee = Address::generate(&env);
+
+        client.propose_admin(&admin, &first_nominee);
+        client.propose_admin(&admin, &second_nominee);
+
+        let result = client.try_accept_admin(&first_nominee);
+        assert!(result.is_err());
+    }
+
+    // ========== cancel_market tests ==========
+
+    /// Register a market backed by a real Stellar asset, mint `deposit` to a
+    /// fresh user, and deposit it so cancel and collateral-reclaim flows can be
+    /// exercised end to end.
+    ///
+    /// Returns `(env, admin, user, client, contract_id, market_id, collateral_token)`.
+    fn setup_admin_market_with_deposit<'a>(
+        deposit: i128,
+    ) -> (
+        Env,
+        Address,
+        Address,
+        MarketContractClient<'a>,
+        Address,
+        u32,
+        Address,
+    ) {
+        use soroban_sdk::token::StellarAssetClient;
+
+        let env = Env::default();
+        let token_admin = Address::generate(&env);
+        let token = env.register_stellar_asset_contract_v2(token_admin.clone());
+        let collateral_token = token.address();
+
+        let contract_id = env.register(MarketContract, ());
+        let client = MarketContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+
+        env.as_contract(&contract_id, || {
+            storage::set_admin(&env, &admin);
+            storage::set_version(&env);
+        });
+
+        env.mock_all_auths();
+
+        let question = String::from_str(&env, "Will it rain tomorrow?");
+        let end_time = env.ledger().timestamp() + 86400;
+        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
+        let market_id = client.initialize_market(
+            &admin,
+            &question,
+            &end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+
+        let user = Address::generate(&env);
+        let token_client = StellarAssetClient::new(&env, &collateral_token);
+        token_client.mint(&user, &deposit);
+        client.deposit_collateral(&user, &market_id, &deposit, &None);
+
+        (
+            env,
+            admin,
+            user,
+            client,
+            contract_id,
+            market_id,
+            collateral_token,
+        )
+    }
+
+    #[test]
+    fn test_cancel_market_success() {
+        let (env, admin, _user, client, contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        client.cancel_market(&admin, &market_id);
+
+        let market = get_market_from_storage(&env, &contract_id, market_id);
+        assert_eq!(market.status, MarketStatus::Canceled);
+    }
+
+    #[test]
+    fn test_cancel_market_emits_event() {
+        let (env, admin, _user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        env.events().all(); // clear setup events
+        client.cancel_market(&admin, &market_id);
+
+        let events = env.events().all();
+        assert!(!events.is_empty(), "MarketCanceled event should be emitted");
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #41)")]
+    fn test_cancel_market_non_admin_fails() {
+        let (env, _admin, _user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        let attacker = Address::generate(&env);
+        client.cancel_market(&attacker, &market_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1)")]
+    fn test_cancel_market_not_found_fails() {
+        let (_env, admin, _user, client, _contract_id, _market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        client.cancel_market(&admin, &999u32);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #2)")]
+    fn test_cancel_market_already_resolved_fails() {
+        let (env, admin, _user, client, contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        // Force the market into a resolved state; a final outcome can't be canceled.
+        env.as_contract(&contract_id, || {
+            let mut market = storage::get_market(&env, market_id).unwrap().unwrap();
+            market.status = MarketStatus::Resolved;
+            market.result = Some(true);
+            storage::set_market(&env, market_id, &market).unwrap();
+        });
+
+        client.cancel_market(&admin, &market_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #5)")]
+    fn test_cancel_market_already_canceled_fails() {
+        let (_env, admin, _user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        client.cancel_market(&admin, &market_id);
+        // A second cancellation is a no-op and must be rejected.
+        client.cancel_market(&admin, &market_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #5)")]
+    fn test_deposit_rejected_after_cancel() {
+        use soroban_sdk::token::StellarAssetClient;
+
+        let (env, admin, user, client, _contract_id, market_id, collateral_token) =
+            setup_admin_market_with_deposit(1_000);
+
+        client.cancel_market(&admin, &market_id);
+
+        // A fresh deposit into the canceled market must fail with MarketNotActive.
+        let token_client = StellarAssetClient::new(&env, &collateral_token);
+        token_client.mint(&user, &500);
+        client.deposit_collateral(&user, &market_id, &500, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #5)")]
+    fn test_update_position_rejected_after_cancel() {
+        let (_env, admin, user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        client.cancel_market(&admin, &market_id);
+        // Trading is halted once a market is canceled.
+        client.update_position(&user, &market_id, &100i128, &0i128, &5_000i128);
+    }
+
+    #[test]
+    fn test_withdraw_canceled_collateral_refunds_user() {
+        let deposit = 1_000i128;
+        let (env, admin, user, client, contract_id, market_id, collateral_token) =
+            setup_admin_market_with_deposit(deposit);
+
+        client.cancel_market(&admin, &market_id);
+
+        let refunded = client.withdraw_canceled_collateral(&user, &market_id);
+        assert_eq!(refunded, deposit);
+
+        // The user's position is zeroed once the collateral has been returned.
+        let position = env.as_contract(&contract_id, || {
+            storage::get_position(&env, market_id, &user)
+                .unwrap()
+                .expect("position should exist")
+        });
+        assert_eq!(position.total_deposited, 0);
+        assert_eq!(position.locked_collateral, 0);
+
+        // The collateral lands back in the user's wallet.
+        let token_client = soroban_sdk::token::Client::new(&env, &collateral_token);
+        assert_eq!(token_client.balance(&user), deposit);
+    }
+
+    #[test]
+    fn test_get_user_markets_keeps_history_without_duplicates() {
+        use soroban_sdk::token::StellarAssetClient;
+
+        let deposit = 1_000i128;
+        let (env, admin, user, client, _contract_id, market_id, collateral_token) =
+            setup_admin_market_with_deposit(deposit);
+        assert_eq!(
+            client.get_user_markets(&user),
+            soroban_sdk::vec![&env, market_id]
+        );
+        assert!(client.get_user_markets(&Address::generate(&env)).is_empty());
+
+        // Re-entering the market does not list it twice.
+        StellarAssetClient::new(&env, &collateral_token).mint(&user, &deposit);
+        client.deposit_collateral(&user, &market_id, &deposit, &None);
+        client.update_position(&user, &market_id, &100i128, &0i128, &5_000i128);
+
+        let second = client.initialize_market(
+            &admin,
+            &numbered_question(&env, 2),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &collateral_token,
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        StellarAssetClient::new(&env, &collateral_token).mint(&user, &deposit);
+        client.deposit_collateral(&user, &second, &deposit, &None);
+
+        // Canceled and refunded markets stay listed.
+        client.cancel_market(&admin, &market_id);
+        client.settle_position(&user, &market_id);
+        assert_eq!(
+            client.get_user_markets(&user),
+            soroban_sdk::vec![&env, market_id, second]
+        );
+    }
+
+    #[test]
+    fn test_verify_collateral_invariant_detects_corrupted_position() {
+        use crate::positions::STROOPS_PER_USDC;
+
+        let (env, user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        client.update_position(
+            &user,
+            &market_id,
+            &(10 * STROOPS_PER_USDC),
+            &0i128,
+            &5_000i128,
+        );
+        assert!(client.verify_collateral_invariant(&market_id));
+
+        // Bypass `set_position` so the running total is not updated.
+        env.as_contract(&contract_id, || {
+            let key = storage::StorageKey::Position(market_id, user.clone());
+            let mut position: crate::types::Position =
+                env.storage().persistent().get(&key).unwrap();
+            position.total_deposited += 1;
+            env.storage().persistent().set(&key, &position);
+        });
+        assert!(!client.verify_collateral_invariant(&market_id));
+    }
+
+    #[test]
+    fn test_payouts_beyond_the_tracked_pool_are_rejected() {
+        use crate::error::ContractError;
+
+        let deposit = 1_000i128;
+        let (env, admin, user, client, contract_id, market_id, _collateral_token) =
+            setup_admin_market_with_deposit(deposit);
+
+        // Simulate the market's tracked collateral drifting below what its
+        // positions hold.
+        let set_tracked = |amount: i128| {
+            env.as_contract(&contract_id, || {
+                env.storage()
+                    .persistent()
+                    .set(&storage::StorageKey::MarketCollateral(market_id), &amount);
+            })
+        };
+        set_tracked(400);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3_600); // past withdraw cooldown
+
+        assert_eq!(
+            client.try_withdraw_unused_collateral(&user, &market_id, &600i128),
+            Err(Ok(ContractError::ArithmeticOverflow))
+        );
+
+        // Canceling runs the invariant checks, which would catch the drift.
+        set_tracked(deposit);
+        client.cancel_market(&admin, &market_id);
+        set_tracked(400);
+        assert_eq!(
+            client.try_settle_position(&user, &market_id),
+            Err(Ok(ContractError::ArithmeticOverflow))
+        );
+        assert_eq!(
+            client.get_position(&market_id, &user).total_deposited,
+            deposit
+        );
+        assert_eq!(
+            env.as_contract(&contract_id, || storage::get_market_collateral(
+                &env, market_id
+            )),
+            400
+        );
+    }
+
+    #[test]
+    fn test_settle_position_refunds_canceled_market_once() {
+        let deposit = 1_000i128;
+        let (env, admin, user, client, _contract_id, market_id, collateral_token) =
+            setup_admin_market_with_deposit(deposit);
+        client.update_position(&user, &market_id, &100i128, &0i128, &5_000i128);
+
+        client.cancel_market(&admin, &market_id);
+
+        // The whole deposit comes back, not the YES outcome payout.
+        assert_eq!(client.settle_position(&user, &market_id), deposit);
+        let token_client = soroban_sdk::token::Client::new(&env, &collateral_token);
+        assert_eq!(token_client.balance(&user), deposit);
+
+        // The refund cannot be claimed a second time through either path.
+        assert_eq!(
+            client.try_withdraw_canceled_collateral(&user, &market_id),
+            Err(Ok(crate::error::ContractError::PositionAlreadySettled))
+        );
+        assert_eq!(
+            client.try_reopen_market(&admin, &market_id),
+            Err(Ok(crate::error::ContractError::MarketNotActive))
+        );
+    }
+
+    #[test]
+    fn test_expire_market_cancels_unresolved_market_after_timeout() {
+        use crate::error::ContractError;
+        use crate::validation::RESOLUTION_TIMEOUT_SECONDS;
+
+        let deposit = 1_000i128;
+        let (env, _admin, user, client, _contract_id, market_id, _collateral_token) =
+            setup_admin_market_with_deposit(deposit);
+        let timeout_at = client.get_market(&market_id).end_time + RESOLUTION_TIMEOUT_SECONDS;
+
+        env.ledger().set_timestamp(timeout_at);
+        assert_eq!(
+            client.try_expire_market(&market_id),
+            Err(Ok(ContractError::MarketNotEnded))
+        );
+
+        env.ledger().set_timestamp(timeout_at + 1);
+        client.expire_market(&market_id);
+        assert!(emitted(&env, "market_canceled"));
+        assert_eq!(client.get_market(&market_id).status, MarketStatus::Canceled);
+
+        assert_eq!(
+            client.try_expire_market(&market_id),
+            Err(Ok(ContractError::MarketNotActive))
+        );
+        assert_eq!(
+            client.withdraw_canceled_collateral(&user, &market_id),
+            deposit
+        );
+    }
+
+    #[test]
+    fn test_expire_market_once_resolution_grace_closes() {
+        use crate::error::ContractError;
+
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let grace = 24 * 60 * 60;
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &create_collateral_token(&env),
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &Some(grace),
+            &None,
+            &0,
+        );
+        let deadline = client.get_market(&market_id).end_time + grace;
+
+        env.ledger().set_timestamp(deadline);
+        assert_eq!(
+            client.try_expire_market(&market_id),
+            Err(Ok(ContractError::MarketNotEnded))
+        );
+
+        // Expiry does not wait out the 30-day timeout once the market can no
+        // longer be resolved, and a pause does not block it.
+        client.set_paused(&admin, &true);
+        env.ledger().set_timestamp(deadline + 1);
+        client.expire_market(&market_id);
+        assert_eq!(client.get_market(&market_id).status, MarketStatus::Canceled);
+    }
+
+    #[test]
+    fn test_resolve_rejected_after_expire_or_cancel() {
+        use crate::error::ContractError;
+        use crate::validation::RESOLUTION_TIMEOUT_SECONDS;
+
+        // An expired market is open for refunds; a late oracle signature
+        // must not also open it for winning payouts.
+        let (env, _admin, _user, client, contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+        let signature = install_oracle_signature(&env, &contract_id, market_id, true);
+        let timeout_at = client.get_market(&market_id).end_time + RESOLUTION_TIMEOUT_SECONDS;
+        env.ledger().set_timestamp(timeout_at + 1);
+        client.expire_market(&market_id);
+
+        let resolver = Address::generate(&env);
+        assert_eq!(
+            client.try_resolve_market(&resolver, &market_id, &Outcome::Yes, &None, &signature, &0),
+            Err(Ok(ContractError::MarketNotActive))
+        );
+        assert_eq!(
+            client.try_commit_resolution(
+                &resolver,
+                &market_id,
+                &BytesN::from_array(&env, &[1u8; 32])
+            ),
+            Err(Ok(ContractError::MarketNotActive))
+        );
+
+        // Likewise once the admin cancels.
+        let (env, admin, _user, client, contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+        let signature = install_oracle_signature(&env, &contract_id, market_id, true);
+        client.cancel_market(&admin, &market_id);
+        advance_to_end_time(&env, &client, market_id);
+        assert_eq!(
+            client.try_resolve_market(
+                &Address::generate(&env),
+                &market_id,
+                &Outcome::Yes,
+                &None,
+                &signature,
+                &0
+            ),
+            Err(Ok(ContractError::MarketNotActive))
+        );
+        assert_eq!(client.get_market(&market_id).status, MarketStatus::Canceled);
+    }
+
+    /// Collects `(old_status, new_status)` pairs from the
+    /// `market_status_changed_event`s of the last invocation.
+    fn status_changes(env: &Env) -> std::vec::Vec<(MarketStatus, MarketStatus)> {
+        use soroban_sdk::{testutils::Events as _, IntoVal, Map, Symbol, Val};
+
+        env.events()
+            .all()
+            .iter()
+            .filter(|e| {
+                let topic: Symbol = e.1.get(0).unwrap().into_val(env);
+                topic == Symbol::new(env, "market_status_changed_event")
+            })
+            .map(|e| {
+                let data: Map<Symbol, Val> = e.2.into_val(env);
+                (
+                    data.get(Symbol::new(env, "old_status"))
+                        .unwrap()
+                        .into_val(env),
+                    data.get(Symbol::new(env, "new_status"))
+                        .unwrap()
+                        .into_val(env),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_status_transitions_emit_market_status_changed_event() {
+        use crate::validation::RESOLUTION_TIMEOUT_SECONDS;
+
+        let (env, _admin, _user, client, contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        // Active -> PendingResolution -> Resolved through the challenge window.
+        let signature = install_oracle_signature(&env, &contract_id, market_id, true);
+        advance_to_end_time(&env, &client, market_id);
+        client.resolve_market(
+            &Address::generate(&env),
+            &market_id,
+            &Outcome::Yes,
+            &None,
+            &signature,
+            &600,
+        );
+        assert_eq!(
+            status_changes(&env),
+            std::vec![(MarketStatus::Active, MarketStatus::PendingResolution)]
+        );
+        env.ledger().with_mut(|li| li.timestamp += 600);
+        client.finalize_resolution(&market_id);
+        assert_eq!(
+            status_changes(&env),
+            std::vec![(MarketStatus::PendingResolution, MarketStatus::Resolved)]
+        );
+
+        // Active -> Canceled by the admin.
+        let (env, admin, _user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+        client.cancel_market(&admin, &market_id);
+        assert_eq!(
+            status_changes(&env),
+            std::vec![(MarketStatus::Active, MarketStatus::Canceled)]
+        );
+
+        // Active -> Canceled once the resolution timeout lapses.
+        let (env, _admin, _user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+        env.ledger()
+            .set_timestamp(client.get_market(&market_id).end_time + RESOLUTION_TIMEOUT_SECONDS + 1);
+        client.expire_market(&market_id);
+        assert_eq!(
+            status_changes(&env),
+            std::vec![(MarketStatus::Active, MarketStatus::Canceled)]
+        );
+
+        // Calls that leave the status alone stay silent.
+        client.get_market(&market_id);
+        assert!(status_changes(&env).is_empty());
+    }
+
+    #[test]
+    fn test_mint_complete_set_locks_face_value_for_balanced_shares() {
+        use crate::error::ContractError;
+
+        let (env, _admin, user, client, contract_id, market_id, collateral_token) =
+            setup_admin_market_with_deposit(100);
+        soroban_sdk::token::StellarAssetClient::new(&env, &collateral_token).mint(&user, &250);
+        let token_client = soroban_sdk::token::Client::new(&env, &collateral_token);
+        let before = token_client.balance(&contract_id);
+
+        let position = client.mint_complete_set(&user, &market_id, &250i128);
+        assert!(emitted(&env, "position_updated"));
+        assert_eq!((position.yes_shares, position.no_shares), (250, 250));
+        assert_eq!(position.total_deposited, 100 + 250);
+        // A balanced pair has no net exposure to lock beyond its backing.
+        assert_eq!(position.locked_collateral, 0);
+        // The contract holds exactly one unit of collateral per set.
+        assert_eq!(token_client.balance(&contract_id) - before, 250);
+
+        assert_eq!(
+            client.try_mint_complete_set(&user, &market_id, &0i128),
+            Err(Ok(ContractError::InvalidShareAmount))
+        );
+
+        let end_time = client.get_market(&market_id).end_time;
+        env.ledger().set_timestamp(end_time + 1);
+        assert_eq!(
+            client.try_mint_complete_set(&user, &market_id, &1i128),
+            Err(Ok(ContractError::MarketExpired))
+        );
+    }
+
+    #[test]
+    fn test_get_position_value_marks_both_legs() {
+        let (env, _admin, user, client, _contract_id, market_id, _collateral_token) =
+            setup_admin_market_with_deposit(1_000);
+        client.update_position(&user, &market_id, &300i128, &100i128, &5_000i128);
+
+        assert_eq!(
+            client.get_position_value(&market_id, &user, &7_000i128),
+            210 + 30
+        );
+        assert_eq!(
+            client.get_position_value(&market_id, &Address::generate(&env), &7_000i128),
+            0
+        );
+    }
+
+    #[test]
+    fn test_redeem_complete_set_pays_face_value_from_deposit() {
+        use crate::error::ContractError;
+        use crate::validation::DEFAULT_WITHDRAW_COOLDOWN_SECONDS;
+
+        let (env, _admin, user, client, _contract_id, market_id, collateral_token) =
+            setup_admin_market_with_deposit(1_000);
+        client.update_position(&user, &market_id, &300i128, &300i128, &5_000i128);
+        assert_eq!(
+            client.try_redeem_complete_set(&user, &market_id, &100i128),
+            Err(Ok(ContractError::WithdrawCooldownActive))
+        );
+
+        let now = env.ledger().timestamp();
+        env.ledger()
+            .set_timestamp(now + DEFAULT_WITHDRAW_COOLDOWN_SECONDS);
+        client.redeem_complete_set(&user, &market_id, &200i128);
+
+        let position = client.get_position(&market_id, &user);
+        assert_eq!((position.yes_shares, position.no_shares), (100, 100));
+        assert_eq!(position.total_deposited, 800);
+        let token_client = soroban_sdk::token::Client::new(&env, &collateral_token);
+        assert_eq!(token_client.balance(&user), 200);
+
+        // Either side short of `quantity`, or a non-positive quantity.
+        assert_eq!(
+            client.try_redeem_complete_set(&user, &market_id, &101i128),
+            Err(Ok(ContractError::InvalidShareAmount))
+        );
+        assert_eq!(
+            client.try_redeem_complete_set(&user, &market_id, &0i128),
+            Err(Ok(ContractError::InvalidShareAmount))
+        );
+
+        // The payout cannot exceed what the user deposited.
+        client.update_position(&user, &market_id, &1_000i128, &1_000i128, &5_000i128);
+        assert_eq!(
+            client.try_redeem_complete_set(&user, &market_id, &900i128),
+            Err(Ok(ContractError::InsufficientCollateral))
+        );
+    }
+
+    // ========== set_paused tests ==========
+
+    #[test]
+    fn test_get_admin_and_is_paused_report_governance_state() {
+        use crate::error::ContractError;
+
+        let env = Env::default();
+        env.mock_all_auths();
+        let client = MarketContractClient::new(&env, &env.register(MarketContract, ()));
+        assert_eq!(
+            client.try_get_admin(),
+            Err(Ok(ContractError::NotInitialized))
+        );
+        assert!(!client.is_paused());
+
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+        assert_eq!(client.get_admin(), admin);
+
+        // A nomination only shows once accepted.
+        let successor = Address::generate(&env);
+        client.propose_admin(&admin, &successor);
+        assert_eq!(client.get_admin(), admin);
+        client.accept_admin(&successor);
+        assert_eq!(client.get_admin(), successor);
+
+        client.set_paused(&successor, &true);
+        assert!(client.is_paused());
+    }
+
+    #[test]
+    fn test_set_paused_blocks_deposits_and_trading_until_unpaused() {
+        use crate::error::ContractError;
+        use soroban_sdk::token::StellarAssetClient;
+
+        let (env, admin, user, client, _contract_id, market_id, collateral_token) =
+            setup_admin_market_with_deposit(1_000);
+        StellarAssetClient::new(&env, &collateral_token).mint(&user, &500);
+
+        client.set_paused(&admin, &true);
+        assert!(client.is_paused());
+        assert_eq!(
+            client.try_deposit_collateral(&user, &market_id, &500, &None),
+            Err(Ok(ContractError::ContractPaused))
+        );
+        assert_eq!(
+            client.try_buy_shares(&user, &market_id, &true, &100i128),
+            Err(Ok(ContractError::ContractPaused))
+        );
+        assert_eq!(
+            client.try_sell_shares(&user, &market_id, &true, &100i128),
+            Err(Ok(ContractError::ContractPaused))
+        );
+
+        client.set_paused(&admin, &false);
+        assert!(!client.is_paused());
+        client.deposit_collateral(&user, &market_id, &500, &None);
+        let position = client.buy_shares(&user, &market_id, &true, &100i128);
+        assert_eq!(position.total_deposited, 1_500);
+        assert_eq!(position.yes_shares, 100);
+    }
+
+    #[test]
+    fn test_set_paused_still_allows_settlement() {
+        let deposit = 1_000i128;
+        let (_env, admin, user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(deposit);
+
+        client.cancel_market(&admin, &market_id);
+        client.set_paused(&admin, &true);
+
+        // Users can always exit, even during an incident.
+        assert_eq!(client.settle_position(&user, &market_id), deposit);
+    }
+
+    #[test]
+    fn test_set_paused_rejects_non_admin() {
+        let (env, _admin, _user, client, _contract_id, _market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        let attacker = Address::generate(&env);
+        assert_eq!(
+            client.try_set_paused(&attacker, &true),
+            Err(Ok(crate::error::ContractError::NotAdmin))
+        );
+        assert!(!client.is_paused());
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #5)")]
+    fn test_withdraw_canceled_collateral_rejects_active_market() {
+        let (_env, _admin, user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        // Market is still active, so the canceled-reclaim path does not apply.
+        client.withdraw_canceled_collateral(&user, &market_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #12)")]
+    fn test_withdraw_canceled_collateral_no_position_fails() {
+        let (env, admin, _user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        client.cancel_market(&admin, &market_id);
+
+        // A user who never deposited has no position to reclaim.
+        let stranger = Address::generate(&env);
+        client.withdraw_canceled_collateral(&stranger, &market_id);
+    }
+
+    #[test]
+    fn test_withdraw_without_position_returns_no_position_found() {
+        use crate::error::ContractError;
+
+        let (env, _user, client, _contract_id, market_id) = setup_funded_market(1_000);
+
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client.try_withdraw_unused_collateral(&stranger, &market_id, &100),
+            Err(Ok(ContractError::NoPositionFound))
+        );
+        assert_eq!(
+            client.try_get_position(&market_id, &stranger),
+            Err(Ok(ContractError::NoPositionFound))
+        );
+    }
+
+    #[test]
+    fn test_sell_without_position_returns_no_position_found() {
+        use crate::error::ContractError;
+
+        let (env, _user, client, _contract_id, market_id) = setup_funded_market(1_000);
+
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client.try_sell_shares(&stranger, &market_id, &true, &100),
+            Err(Ok(ContractError::NoPositionFound))
+        );
+        assert_eq!(
+            client.try_sell_no(&stranger, &market_id, &100, &5_000),
+            Err(Ok(ContractError::NoPositionFound))
+        );
+        assert_eq!(
+            client.try_get_position(&market_id, &stranger),
+            Err(Ok(ContractError::NoPositionFound))
+        );
+    }
+
+    #[test]
+    fn test_settle_without_position_returns_no_position_found() {
+        use crate::error::ContractError;
+
+        let (env, _user, client, contract_id, market_id) = setup_funded_market(1_000);
+        resolve_funded_market(&env, &client, &contract_id, market_id, true);
+
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client.try_settle_position(&stranger, &market_id),
+            Err(Ok(ContractError::NoPositionFound))
+        );
+    }
+
+    #[test]
+    fn test_reopen_market_before_any_refund() {
+        use soroban_sdk::{IntoVal, Symbol};
+
+        let (env, admin, user, client, contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        client.cancel_market(&admin, &market_id);
+        client.reopen_market(&admin, &market_id);
+
+        let event = env.events().all().last().unwrap();
+        let name: Symbol = event.1.get(0).unwrap().into_val(&env);
+        assert_eq!(name, Symbol::new(&env, "market_reopened"));
+
+        let market = get_market_from_storage(&env, &contract_id, market_id);
+        assert_eq!(market.status, MarketStatus::Active);
+        // Trading resumes on the restored market.
+        client.update_position(&user, &market_id, &100i128, &0i128, &5_000i128);
+    }
+
+    #[test]
+    fn test_reopen_market_rejected_after_refund() {
+        let (_env, admin, user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        client.cancel_market(&admin, &market_id);
+        client.withdraw_canceled_collateral(&user, &market_id);
+
+        assert_eq!(
+            client.try_reopen_market(&admin, &market_id),
+            Err(Ok(crate::error::ContractError::MarketNotActive))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #5)")]
+    fn test_reopen_market_rejects_active_market() {
+        let (_env, admin, _user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        client.reopen_market(&admin, &market_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #41)")]
+    fn test_reopen_market_non_admin_fails() {
+        let (env, admin, _user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+
+        client.cancel_market(&admin, &market_id);
+        client.reopen_market(&Address::generate(&env), &market_id);
+    }
+
+    // ========== #332: Burn outcome tokens on position decrease ==========
+
+    /// #332: Selling YES shares burns the corresponding outcome tokens.
+    /// Verify that when yes_delta < 0 the token contract's burn entry point is
+    /// called (the SDK records the call in the auth invocations list, so we
+    /// assert the position decreases as expected as a proxy for the burn path
+    /// being exercised).
+    #[test]
+    fn test_332_selling_yes_shares_decreases_position() {
+        use crate::positions::STROOPS_PER_USDC;
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+
+        // Buy 80 YES shares first.
+        let buy = 80 * STROOPS_PER_USDC;
+        client.update_position(&user, &market_id, &buy, &0i128, &5_000i128);
+
+        // Sell 30 YES shares back.
+        let sell = -30 * STROOPS_PER_USDC;
+        let pos = client.update_position(&user, &market_id, &sell, &0i128, &5_000i128);
+
+        assert_eq!(pos.yes_shares, 50 * STROOPS_PER_USDC);
+        assert_eq!(pos.no_shares, 0);
+
+        let stored = env.as_contract(&contract_id, || {
+            storage::get_position(&env, market_id, &user).unwrap().unwrap()
+        });
+        assert_eq!(stored.yes_shares, 50 * STROOPS_PER_USDC);
+    }
+
+    /// #332: Selling NO shares decreases the NO balance (burn path).
+    #[test]
+    fn test_332_selling_no_shares_decreases_position() {
+        use crate::positions::STROOPS_PER_USDC;
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+
+        // Buy 60 NO shares first.
+        let buy = 60 * STROOPS_PER_USDC;
+        client.update_position(&user, &market_id, &0i128, &buy, &5_000i128);
+
+        // Sell 20 NO shares back.
+        let sell = -20 * STROOPS_PER_USDC;
+        let pos = client.update_position(&user, &market_id, &0i128, &sell, &5_000i128);
+
+        assert_eq!(pos.yes_shares, 0);
+        assert_eq!(pos.no_shares, 40 * STROOPS_PER_USDC);
+
+        let stored = env.as_contract(&contract_id, || {
+            storage::get_position(&env, market_id, &user).unwrap().unwrap()
+        });
+        assert_eq!(stored.no_shares, 40 * STROOPS_PER_USDC);
+    }
+
+    /// #332: Selling down to zero shares is allowed and results in locked_collateral == 0.
+    #[test]
+    fn test_332_selling_all_shares_zeroes_locked_collateral() {
+        use crate::positions::STROOPS_PER_USDC;
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (_env, user, client, _contract_id, market_id) = setup_funded_market(deposit);
+
+        let qty = 50 * STROOPS_PER_USDC;
+        client.update_position(&user, &market_id, &qty, &0i128, &5_000i128);
+
+        let pos = client.update_position(&user, &market_id, &(-qty), &0i128, &5_000i128);
+        assert_eq!(pos.yes_shares, 0);
+        assert_eq!(pos.locked_collateral, 0);
+    }
+
+    // ========== #333: Reconcile locked_collateral on deposit and withdraw ==========
+
+    /// #333: Depositing collateral must never increment locked_collateral.
+    /// locked_collateral is exclusively owned by update_position.
+    #[test]
+    fn test_333_deposit_does_not_touch_locked_collateral() {
+        use crate::positions::STROOPS_PER_USDC;
+
+        let deposit = 50 * STROOPS_PER_USDC;
+        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+
+        // A second deposit — the lock must stay zero because no shares are held.
+        let extra = 20 * STROOPS_PER_USDC;
+        use soroban_sdk::token::StellarAssetClient;
+        let stored_market = env.as_contract(&contract_id, || {
+            storage::get_market(&env, market_id).unwrap().unwrap()
+        });
+        StellarAssetClient::new(&env, &stored_market.collateral_token).mint(&user, &extra);
+        client.deposit_collateral(&user, &market_id, &extra, &None);
+
+        let pos = env.as_contract(&contract_id, || {
+            storage::get_position(&env, market_id, &user).unwrap().unwrap()
+        });
+        assert_eq!(pos.locked_collateral, 0);
+        assert_eq!(pos.total_deposited, deposit + extra);
+    }
+
+    /// #333: Withdrawing unlocked collateral decrements total_deposited and
+    /// preserves locked_collateral (invariant: available = total - locked).
+    #[test]
+    fn test_333_withdraw_decrements_total_deposited_preserves_locked() {
+        use crate::positions::STROOPS_PER_USDC;
+        use soroban_sdk::token::StellarAssetClient;
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+
+        // Buy 40 YES shares at 50% → lock = 20 USDC; available = 80 USDC.
+        let shares = 40 * STROOPS_PER_USDC;
+        client.update_position(&user, &market_id, &shares, &0i128, &5_000i128);
+
+        let before = env.as_contract(&contract_id, || {
+            storage::get_position(&env, market_id, &user).unwrap().unwrap()
+        });
+        assert_eq!(before.locked_collateral, 20 * STROOPS_PER_USDC);
+        assert_eq!(before.total_deposited, deposit);
 
-        let yes = 10 * STROOPS_PER_USDC;
-        client.update_position(&user, &market_id, &yes, &0i128, &6000i128);
+        let withdraw_amount = 30 * STROOPS_PER_USDC; // within available (80 USDC)
+        let stored_market = env.as_contract(&contract_id, || {
+            storage::get_market(&env, market_id).unwrap().unwrap()
+        });
+        StellarAssetClient::new(&env, &stored_market.collateral_token).mint(&contract_id, &(100 * STROOPS_PER_USDC));
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3_600); // past withdraw cooldown
+        client.withdraw_unused_collateral(&user, &market_id, &withdraw_amount);
+
+        let after = env.as_contract(&contract_id, || {
+            storage::get_position(&env, market_id, &user).unwrap().unwrap()
+        });
+        assert_eq!(after.total_deposited, deposit - withdraw_amount);
+        assert_eq!(after.locked_collateral, before.locked_collateral); // unchanged
+    }
+
+    #[test]
+    fn test_withdraw_capped_at_collateral_not_backing_shares() {
+        use crate::error::ContractError;
+        use crate::positions::STROOPS_PER_USDC;
+        use crate::validation::DEFAULT_WITHDRAW_COOLDOWN_SECONDS;
+        use soroban_sdk::token::StellarAssetClient;
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, trader, client, contract_id, market_id) = setup_funded_market(deposit);
+        let token = get_market_from_storage(&env, &contract_id, market_id).collateral_token;
+        let fund = |amount: i128| {
+            let user = Address::generate(&env);
+            StellarAssetClient::new(&env, &token).mint(&user, &amount);
+            client.deposit_collateral(&user, &market_id, &amount, &None);
+            user
+        };
+        let depositor = fund(50 * STROOPS_PER_USDC);
+        let other = fund(deposit);
+
+        // 40 YES at 50% locks 20 USDC; a later trade moves the price to 80%,
+        // where the same shares need 32 USDC.
+        client.update_position(
+            &trader,
+            &market_id,
+            &(40 * STROOPS_PER_USDC),
+            &0i128,
+            &5_000i128,
+        );
+        client.update_position(
+            &other,
+            &market_id,
+            &(10 * STROOPS_PER_USDC),
+            &0i128,
+            &8_000i128,
+        );
+        env.ledger()
+            .set_timestamp(env.ledger().timestamp() + DEFAULT_WITHDRAW_COOLDOWN_SECONDS);
+
+        // A pure deposit with no shares can be withdrawn in full.
+        client.withdraw_unused_collateral(&depositor, &market_id, &(50 * STROOPS_PER_USDC));
+        assert_eq!(
+            client.get_position(&market_id, &depositor).total_deposited,
+            0
+        );
+
+        // Open shares leave only the surplus over what they need now.
+        assert_eq!(
+            client.try_withdraw_unused_collateral(
+                &trader,
+                &market_id,
+                &(68 * STROOPS_PER_USDC + 1)
+            ),
+            Err(Ok(ContractError::InsufficientCollateral))
+        );
+        client.withdraw_unused_collateral(&trader, &market_id, &(68 * STROOPS_PER_USDC));
+        assert_eq!(
+            client.get_position(&market_id, &trader).total_deposited,
+            32 * STROOPS_PER_USDC
+        );
+    }
+
+    #[test]
+    fn test_withdraw_blocked_until_cooldown_elapses() {
+        use crate::error::ContractError;
+        use crate::positions::STROOPS_PER_USDC;
+        use crate::validation::DEFAULT_WITHDRAW_COOLDOWN_SECONDS;
+
+        let (env, user, client, _contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        let start = env.ledger().timestamp();
+
+        env.ledger()
+            .set_timestamp(start + DEFAULT_WITHDRAW_COOLDOWN_SECONDS - 1);
+        assert_eq!(
+            client.try_withdraw_unused_collateral(&user, &market_id, &STROOPS_PER_USDC),
+            Err(Ok(ContractError::WithdrawCooldownActive))
+        );
+
+        env.ledger()
+            .set_timestamp(start + DEFAULT_WITHDRAW_COOLDOWN_SECONDS);
+        client.withdraw_unused_collateral(&user, &market_id, &STROOPS_PER_USDC);
+    }
+
+    #[test]
+    fn test_set_withdraw_cooldown_overrides_default() {
+        use crate::error::ContractError;
+        use crate::positions::STROOPS_PER_USDC;
+        use crate::validation::MAX_WITHDRAW_COOLDOWN_SECONDS;
+
+        let (env, user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        let admin = env.as_contract(&contract_id, || storage::get_admin(&env).unwrap());
+
+        assert_eq!(
+            client.try_set_withdraw_cooldown(&Address::generate(&env), &market_id, &Some(0)),
+            Err(Ok(ContractError::NotAdmin))
+        );
+        assert_eq!(
+            client.try_set_withdraw_cooldown(
+                &admin,
+                &market_id,
+                &Some(MAX_WITHDRAW_COOLDOWN_SECONDS + 1)
+            ),
+            Err(Ok(ContractError::InvalidTimestamp))
+        );
+
+        // A zero cooldown allows withdrawing right after the deposit.
+        client.set_withdraw_cooldown(&admin, &market_id, &Some(0));
+        client.withdraw_unused_collateral(&user, &market_id, &STROOPS_PER_USDC);
+
+        // A longer cooldown applies from the most recent deposit.
+        client.set_withdraw_cooldown(&admin, &market_id, &Some(7_200));
+        let start = env.ledger().timestamp();
+        env.ledger().set_timestamp(start + 3_600);
+        assert_eq!(
+            client.try_withdraw_unused_collateral(&user, &market_id, &STROOPS_PER_USDC),
+            Err(Ok(ContractError::WithdrawCooldownActive))
+        );
+        env.ledger().set_timestamp(start + 7_200);
+        client.withdraw_unused_collateral(&user, &market_id, &STROOPS_PER_USDC);
+    }
+
+    /// #333: Attempting to withdraw locked collateral is rejected.
+    #[test]
+    fn test_333_cannot_withdraw_locked_collateral() {
+        use crate::{error::ContractError, positions::STROOPS_PER_USDC};
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, user, client, _contract_id, market_id) = setup_funded_market(deposit);
+
+        // Buy 100 YES shares at 60% → lock = 60 USDC; available = 40 USDC.
+        let shares = 100 * STROOPS_PER_USDC;
+        client.update_position(&user, &market_id, &shares, &0i128, &6_000i128);
+        env.ledger().set_timestamp(env.ledger().timestamp() + 3_600); // past withdraw cooldown
+
+        // Try to withdraw 50 USDC (> available 40 USDC) → rejected.
+        let result = client.try_withdraw_unused_collateral(&user, &market_id, &(50 * STROOPS_PER_USDC));
+        assert_eq!(result, Err(Ok(ContractError::InsufficientCollateral)));
+    }
+
+    // ========== #334: Single source of truth for share-collateral math ==========
+
+    /// #334: locked_collateral in storage matches the value returned by the
+    /// canonical calculate_locked_collateral function.  This test documents the
+    /// single-source-of-truth contract: there is no duplication between
+    /// positions.rs and lib.rs.
+    #[test]
+    fn test_334_locked_collateral_matches_canonical_formula() {
+        use crate::positions::{calculate_locked_collateral, STROOPS_PER_USDC};
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+
+        let yes = 80 * STROOPS_PER_USDC;
+        let no = 20 * STROOPS_PER_USDC;
+        let price_bps = 6_000i128;
+
+        // Buy YES then NO to establish a mixed position.
+        client.update_position(&user, &market_id, &yes, &0i128, &price_bps);
+        let pos = client.update_position(&user, &market_id, &0i128, &no, &price_bps);
+
+        let expected = calculate_locked_collateral(yes, no, price_bps).unwrap();
+        assert_eq!(pos.locked_collateral, expected);
+
+        // The stored value must also match.
+        let stored = env.as_contract(&contract_id, || {
+            storage::get_position(&env, market_id, &user).unwrap().unwrap()
+        });
+        assert_eq!(stored.locked_collateral, expected);
+    }
+
+    // ========== list_markets pagination tests ==========
+
+    #[test]
+    fn test_list_markets_clamps_oversized_page() {
+        use crate::validation::MAX_PAGE_SIZE;
+
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let collateral_token = create_collateral_token(&env);
+        let end_time = env.ledger().timestamp() + 86400;
+        for n in 0..=MAX_PAGE_SIZE {
+            client.initialize_market(
+                &admin,
+                &numbered_question(&env, n),
+                &end_time,
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &collateral_token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            );
+        }
+
+        assert_eq!(client.market_count(), MAX_PAGE_SIZE + 1);
+        assert_eq!(client.list_markets(&0, &u32::MAX).len(), MAX_PAGE_SIZE);
+        assert_eq!(
+            client.list_markets(&0, &(MAX_PAGE_SIZE + 1)).len(),
+            MAX_PAGE_SIZE
+        );
+        // The remainder is reachable with a second page.
+        let tail = client.list_markets(&MAX_PAGE_SIZE, &u32::MAX);
+        assert_eq!(tail.len(), 1);
+        assert_eq!(tail.get(0).unwrap().id, MAX_PAGE_SIZE + 1);
+    }
+
+    #[test]
+    fn test_list_markets_start_past_end_is_empty() {
+        let (_env, _admin, client, _contract_id) = create_test_contract();
+        assert_eq!(client.market_count(), 0);
+        assert!(client.list_markets(&u32::MAX, &u32::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_markets_ending_before_cutoff() {
+        use soroban_sdk::vec;
+
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let collateral_token = create_collateral_token(&env);
+        let now = env.ledger().timestamp();
+        let mut ids = std::vec::Vec::new();
+        for (n, end_time) in [now + 3_600, now + 86_400, now + 600, now + 7 * 86_400]
+            .into_iter()
+            .enumerate()
+        {
+            ids.push(client.initialize_market(
+                &admin,
+                &numbered_question(&env, n as u32),
+                &end_time,
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &collateral_token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            ));
+        }
+
+        assert_eq!(
+            client.markets_ending_before(&(now + 3_600), &10),
+            vec![&env, ids[0], ids[2]]
+        );
+        assert_eq!(
+            client.markets_ending_before(&(now + 86_400), &10),
+            vec![&env, ids[0], ids[1], ids[2]]
+        );
+        assert!(client.markets_ending_before(&now, &10).is_empty());
+        assert_eq!(
+            client.markets_ending_before(&(now + 86_400), &1),
+            vec![&env, ids[0]]
+        );
+
+        // Only active markets are reported.
+        client.cancel_market(&admin, &ids[0]);
+        assert_eq!(
+            client.markets_ending_before(&(now + 3_600), &10),
+            vec![&env, ids[2]]
+        );
+    }
+
+    // ========== category index tests ==========
+
+    #[test]
+    fn test_list_markets_by_category() {
+        use soroban_sdk::{symbol_short, vec};
+
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let collateral_token = create_collateral_token(&env);
+        let mut ids = std::vec::Vec::new();
+        for n in 0..4 {
+            ids.push(client.initialize_market(
+                &admin,
+                &numbered_question(&env, n),
+                &(env.ledger().timestamp() + 86400),
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &collateral_token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            ));
+        }
+        let sports = symbol_short!("sports");
+        let macro_ = symbol_short!("macro");
+        client.set_market_category(&admin, &ids[0], &sports);
+        client.set_market_category(&admin, &ids[1], &macro_);
+        client.set_market_category(&admin, &ids[2], &sports);
+
+        assert_eq!(
+            client.list_markets_by_category(&sports, &0, &10),
+            vec![&env, ids[0], ids[2]]
+        );
+        assert_eq!(
+            client.list_markets_by_category(&macro_, &0, &10),
+            vec![&env, ids[1]]
+        );
+        assert_eq!(
+            client.list_markets_by_category(&sports, &1, &1),
+            vec![&env, ids[2]]
+        );
+        assert!(client.list_markets_by_category(&sports, &5, &10).is_empty());
+        assert!(client
+            .list_markets_by_category(&symbol_short!("none"), &0, &10)
+            .is_empty());
+        // Markets keep the category they were created with until moved.
+        let general = symbol_short!("general");
+        assert_eq!(client.get_market_category(&ids[3]), Some(general.clone()));
+        assert_eq!(
+            client.list_markets_by_category(&general, &0, &10),
+            vec![&env, ids[3]]
+        );
+
+        // Recategorizing moves the market between indexes.
+        client.set_market_category(&admin, &ids[0], &macro_);
+        assert_eq!(client.get_market_category(&ids[0]), Some(macro_.clone()));
+        assert_eq!(client.get_market(&ids[0]).category, macro_);
+        assert_eq!(
+            client.list_markets_by_category(&sports, &0, &10),
+            vec![&env, ids[2]]
+        );
+        assert_eq!(
+            client.list_markets_by_category(&macro_, &0, &10),
+            vec![&env, ids[1], ids[0]]
+        );
+
+        // Re-assigning the same category does not duplicate the entry.
+        client.set_market_category(&admin, &ids[0], &macro_);
+        assert_eq!(client.list_markets_by_category(&macro_, &0, &10).len(), 2);
+    }
+
+    #[test]
+    fn test_max_market_duration_bounds_end_time() {
+        use crate::error::ContractError;
+        use crate::validation::DEFAULT_MAX_MARKET_DURATION_SECONDS;
+
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let collateral_token = create_collateral_token(&env);
+        let create = |n: u32, end_time: u64| {
+            client.try_initialize_market(
+                &admin,
+                &numbered_question(&env, n),
+                &end_time,
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &collateral_token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            )
+        };
+        let now = env.ledger().timestamp();
+        let two_years = now + 2 * DEFAULT_MAX_MARKET_DURATION_SECONDS;
+
+        assert_eq!(
+            client.get_max_market_duration(),
+            DEFAULT_MAX_MARKET_DURATION_SECONDS
+        );
+        assert_eq!(
+            create(1, two_years),
+            Err(Ok(ContractError::InvalidTimestamp))
+        );
+
+        client.set_max_market_duration(&admin, &(3 * DEFAULT_MAX_MARKET_DURATION_SECONDS));
+        assert!(create(1, two_years).is_ok());
+
+        client.set_max_market_duration(&admin, &(30 * 86_400));
+        assert_eq!(client.get_max_market_duration(), 30 * 86_400);
+        assert_eq!(
+            create(2, now + 31 * 86_400),
+            Err(Ok(ContractError::InvalidTimestamp))
+        );
+
+        assert_eq!(
+            client.try_set_max_market_duration(&admin, &0),
+            Err(Ok(ContractError::InvalidTimestamp))
+        );
+        assert_eq!(
+            client.try_set_max_market_duration(&Address::generate(&env), &86_400),
+            Err(Ok(ContractError::NotAdmin))
+        );
+    }
+
+    #[test]
+    fn test_upgrade_requires_admin() {
+        use crate::error::ContractError;
+
+        let (env, _admin, client, _contract_id) = create_test_contract();
+        let wasm_hash = BytesN::<32>::random(&env);
+
+        assert_eq!(client.version(), 1);
+        assert_eq!(
+            client.try_upgrade(&Address::generate(&env), &wasm_hash),
+            Err(Ok(ContractError::NotAdmin))
+        );
+        assert_eq!(client.version(), 1);
+
+        let uninitialized = MarketContractClient::new(&env, &env.register(MarketContract, ()));
+        assert_eq!(
+            uninitialized.try_upgrade(&Address::generate(&env), &wasm_hash),
+            Err(Ok(ContractError::NotInitialized))
+        );
+    }
+
+    #[test]
+    fn test_migrate_requires_admin_and_keeps_current_schema() {
+        use crate::error::ContractError;
+
+        let (env, admin, client, contract_id) = create_test_contract();
+
+        assert_eq!(
+            client.try_migrate(&Address::generate(&env)),
+            Err(Ok(ContractError::NotAdmin))
+        );
+        assert_eq!(client.migrate(&admin), storage::STORAGE_VERSION);
+        assert_eq!(
+            env.as_contract(&contract_id, || storage::get_stored_version(&env)),
+            Some(storage::STORAGE_VERSION)
+        );
+    }
+
+    #[test]
+    fn test_initialize_market_rejects_duplicate_active_question() {
+        use crate::error::ContractError;
+
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let collateral_token = create_collateral_token(&env);
+        let create = || {
+            client.try_initialize_market(
+                &admin,
+                &String::from_str(&env, "Will it rain tomorrow?"),
+                &(env.ledger().timestamp() + 86400),
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &collateral_token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            )
+        };
+
+        let first = create().unwrap().unwrap();
+        assert_eq!(create(), Err(Ok(ContractError::InvalidQuestion)));
+
+        // Once the first market is canceled its question is free again.
+        client.cancel_market(&admin, &first);
+        assert_eq!(create(), Ok(Ok(first + 1)));
+    }
+
+    #[test]
+    fn test_find_market_by_question_tracks_latest_market() {
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let collateral_token = create_collateral_token(&env);
+        let question = String::from_str(&env, "Will it rain tomorrow?");
+        let create = || {
+            client.initialize_market(
+                &admin,
+                &question,
+                &(env.ledger().timestamp() + 86400),
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &collateral_token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            )
+        };
+
+        assert_eq!(client.find_market_by_question(&question), None);
+        let first = create();
+        assert_eq!(client.find_market_by_question(&question), Some(first));
+
+        // A canceled market is still found until the question is reused.
+        client.cancel_market(&admin, &first);
+        assert_eq!(client.find_market_by_question(&question), Some(first));
+        let second = create();
+        assert_eq!(client.find_market_by_question(&question), Some(second));
+
+        let other = String::from_str(&env, "Will it snow tomorrow?");
+        assert_eq!(client.find_market_by_question(&other), None);
+    }
+
+    #[test]
+    fn test_initialize_market_records_category_and_resolution_source() {
+        use crate::error::ContractError;
+        use crate::types::{MarketKind, MarketMetadata};
+        use soroban_sdk::symbol_short;
+
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let collateral_token = create_collateral_token(&env);
+        let create = |metadata: &MarketMetadata| {
+            client.try_initialize_market(
+                &admin,
+                &String::from_str(&env, "Test market"),
+                &(env.ledger().timestamp() + 86400),
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &collateral_token,
+                metadata,
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            )
+        };
+
+        let source = String::from_str(&env, "https://api.coingecko.com/btc");
+        let metadata = MarketMetadata {
+            uri: None,
+            category: symbol_short!("crypto"),
+            resolution_source: source.clone(),
+            kind: MarketKind::Binary,
+            min_deposit: 0,
+            max_position: 0,
+            max_participants: 0,
+            derive_id: false,
+        };
+        let market_id = create(&metadata).unwrap().unwrap();
+        let market = client.get_market(&market_id);
+        assert_eq!(market.category, symbol_short!("crypto"));
+        assert_eq!(market.resolution_source, source);
+        assert_eq!(
+            client.list_markets_by_category(&symbol_short!("crypto"), &0, &10),
+            soroban_sdk::vec![&env, market_id]
+        );
+
+        for bad in [
+            String::from_str(&env, ""),
+            String::from_str(&env, &"s".repeat(200)),
+        ] {
+            let metadata = MarketMetadata {
+                resolution_source: bad,
+                ..metadata.clone()
+            };
+            assert_eq!(
+                create(&metadata),
+                Err(Ok(ContractError::InvalidMetadataUri))
+            );
+        }
     }
 
-    // ========== set_fee_rate_bps / get_fee_rate_bps tests ==========
+    #[test]
+    fn test_scalar_market_resolves_to_signed_value_and_pays_linearly() {
+        use crate::error::ContractError;
+        use crate::types::{MarketKind, ScalarBounds};
+        use ed25519_dalek::{Signer, SigningKey};
+        use rand::rngs::OsRng;
+        use soroban_sdk::token::{Client as TokenClient, StellarAssetClient};
+
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let token = create_collateral_token(&env);
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let oracle_pubkey = BytesN::from_array(&env, &signing_key.verifying_key().to_bytes());
+        let create = |question: &str, kind: MarketKind| {
+            client.try_initialize_market(
+                &admin,
+                &String::from_str(&env, question),
+                &(env.ledger().timestamp() + 86400),
+                &oracle_pubkey,
+                &token,
+                &crate::types::MarketMetadata {
+                    kind,
+                    ..test_metadata(&env)
+                },
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            )
+        };
+        let bounds = |lower, upper| MarketKind::Scalar(ScalarBounds { lower, upper });
+        let question = "BTC price at expiry?";
+        assert_eq!(
+            create(question, bounds(100, 100)),
+            Err(Ok(ContractError::InvalidOutcome))
+        );
+        let binary_id = create("BTC above 100k at expiry?", MarketKind::Binary)
+            .unwrap()
+            .unwrap();
+        let market_id = create(question, bounds(50_000, 150_000)).unwrap().unwrap();
+        assert_eq!(client.get_market(&market_id).kind, bounds(50_000, 150_000));
+
+        // One user goes long 1_000 shares, another short 1_000.
+        let (long, short) = (Address::generate(&env), Address::generate(&env));
+        for (user, yes, no) in [(&long, 1_000i128, 0i128), (&short, 0, 1_000)] {
+            StellarAssetClient::new(&env, &token).mint(user, &1_000);
+            client.deposit_collateral(user, &market_id, &1_000, &None);
+            client.update_position(user, &market_id, &yes, &no, &5_000i128);
+        }
+
+        advance_to_end_time(&env, &client, market_id);
+        let sign = |id: u32, value: i128| {
+            let message = crate::oracle::construct_scalar_oracle_message(
+                &env,
+                id,
+                client.get_market(&id).created_at,
+                &test_source_hash(&env),
+                value,
+            );
+            BytesN::from_array(
+                &env,
+                &signing_key.sign(message.to_array().as_slice()).to_bytes(),
+            )
+        };
+        let resolver = Address::generate(&env);
+
+        // Each kind resolves only through its own entry point.
+        assert_eq!(
+            client.try_resolve_market(
+                &resolver,
+                &market_id,
+                &Outcome::Yes,
+                &None,
+                &sign(market_id, 1),
+                &0
+            ),
+            Err(Ok(ContractError::InvalidOutcome))
+        );
+        assert_eq!(
+            client.try_resolve_scalar_market(&resolver, &binary_id, &1, &None, &sign(binary_id, 1)),
+            Err(Ok(ContractError::InvalidOutcome))
+        );
+        assert_eq!(
+            client.try_resolve_scalar_market(
+                &resolver,
+                &market_id,
+                &125_000,
+                &None,
+                &sign(market_id, 75_000),
+            ),
+            Err(Ok(ContractError::InvalidSignature))
+        );
+
+        client.resolve_scalar_market(
+            &resolver,
+            &market_id,
+            &75_000,
+            &Some(oracle_pubkey.clone()),
+            &sign(market_id, 75_000),
+        );
+        assert!(emitted(&env, "scalar_market_resolved"));
+        let market = client.get_market(&market_id);
+        assert_eq!(market.status, MarketStatus::Resolved);
+        assert_eq!(market.settled_value, Some(75_000));
+        assert_eq!(market.result, None);
+        assert_eq!(
+            client.get_resolution(&market_id),
+            crate::types::Resolution::Scalar(75_000)
+        );
 
-    #[test]
-    fn test_get_fee_rate_bps_default_is_50() {
-        let (env, _admin, client, _contract_id) = create_test_contract();
-        assert_eq!(client.get_fee_rate_bps(), 50u32);
+        // A quarter of the way up the band: long is paid 25%, short 75%.
+        assert_eq!(client.settle_position(&long, &market_id), 250);
+        assert_eq!(client.settle_position(&short, &market_id), 750);
+        assert_eq!(TokenClient::new(&env, &token).balance(&short), 750);
     }
 
     #[test]
-    fn test_set_fee_rate_bps_admin_can_update() {
-        let (env, admin, client, _contract_id) = create_test_contract();
-        client.set_fee_rate_bps(&admin, &100u32);
-        assert_eq!(client.get_fee_rate_bps(), 100u32);
-    }
+    fn test_initialize_markets_batch_creates_all_or_nothing() {
+        use crate::error::ContractError;
+        use soroban_sdk::vec;
 
-    #[test]
-    fn test_set_fee_rate_bps_zero_is_valid() {
         let (env, admin, client, _contract_id) = create_test_contract();
-        client.set_fee_rate_bps(&admin, &0u32);
-        assert_eq!(client.get_fee_rate_bps(), 0u32);
-    }
+        let token = create_collateral_token(&env);
+        let now = env.ledger().timestamp();
+        let questions = vec![
+            &env,
+            String::from_str(&env, "Market A"),
+            String::from_str(&env, "Market B"),
+            String::from_str(&env, "Market C"),
+        ];
+        let pubkeys = vec![
+            &env,
+            BytesN::from_array(&env, &[1u8; 32]),
+            BytesN::from_array(&env, &[2u8; 32]),
+            BytesN::from_array(&env, &[3u8; 32]),
+        ];
+        let hashes = vec![
+            &env,
+            test_source_hash(&env),
+            test_source_hash(&env),
+            test_source_hash(&env),
+        ];
+        let batch = |creator: &Address, end_times: &soroban_sdk::Vec<u64>| {
+            client.try_initialize_markets_batch(
+                creator,
+                &questions,
+                end_times,
+                &pubkeys,
+                &hashes,
+                &token,
+                &test_metadata(&env),
+            )
+        };
 
-    #[test]
-    fn test_set_fee_rate_bps_max_boundary_valid() {
-        let (env, admin, client, _contract_id) = create_test_contract();
-        client.set_fee_rate_bps(&admin, &10_000u32);
-        assert_eq!(client.get_fee_rate_bps(), 10_000u32);
+        assert_eq!(
+            batch(&admin, &vec![&env, now + 100, now + 200]),
+            Err(Ok(ContractError::InvalidQuantity))
+        );
+        assert_eq!(
+            batch(
+                &Address::generate(&env),
+                &vec![&env, now + 100, now + 200, now + 300]
+            ),
+            Err(Ok(ContractError::NotAdmin))
+        );
+        // One invalid market reverts the markets created before it.
+        assert_eq!(
+            batch(&admin, &vec![&env, now + 100, now + 200, now]),
+            Err(Ok(ContractError::InvalidTimestamp))
+        );
+        assert_eq!(client.list_markets(&0, &10).len(), 0);
+
+        let ids = batch(&admin, &vec![&env, now + 100, now + 200, now + 300])
+            .unwrap()
+            .unwrap();
+        assert_eq!(ids, vec![&env, 1u32, 2, 3]);
+        for (i, id) in ids.iter().enumerate() {
+            let market = client.get_market(&id);
+            assert_eq!(market.question, questions.get_unchecked(i as u32));
+            assert_eq!(market.end_time, now + 100 * (i as u64 + 1));
+            assert_eq!(market.oracle_pubkey, pubkeys.get_unchecked(i as u32));
+        }
     }
 
-    #[test]
-    #[should_panic(expected = "Error(Contract, #34)")]
-    fn test_set_fee_rate_bps_exceeds_max_rejected() {
-        let (env, admin, client, _contract_id) = create_test_contract();
-        client.set_fee_rate_bps(&admin, &10_001u32);
-    }
+    /// Create a market with the given deposit minimum and position cap and
+    /// fund `user` with `balance` of its collateral.
+    fn setup_limited_market<'a>(
+        min_deposit: i128,
+        max_position: i128,
+        balance: i128,
+    ) -> (Env, Address, MarketContractClient<'a>, u32) {
+        use soroban_sdk::token::StellarAssetClient;
 
-    #[test]
-    #[should_panic(expected = "Error(Contract, #41)")]
-    fn test_set_fee_rate_bps_non_admin_rejected() {
-        let (env, _admin, client, _contract_id) = create_test_contract();
-        let non_admin = Address::generate(&env);
-        client.set_fee_rate_bps(&non_admin, &50u32);
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let token = create_collateral_token(&env);
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &token,
+            &crate::types::MarketMetadata {
+                min_deposit,
+                max_position,
+                ..test_metadata(&env)
+            },
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        let user = Address::generate(&env);
+        StellarAssetClient::new(&env, &token).mint(&user, &balance);
+        (env, user, client, market_id)
     }
 
-    // ========== token_balance tests ==========
-
     #[test]
-    fn test_token_balance_returns_contract_balance() {
+    fn test_deposit_rejects_new_participants_once_market_is_full() {
+        use crate::error::ContractError;
         use soroban_sdk::token::StellarAssetClient;
 
-        let env = Env::default();
-        env.mock_all_auths();
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let token = create_collateral_token(&env);
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &token,
+            &crate::types::MarketMetadata {
+                max_participants: 2,
+                ..test_metadata(&env)
+            },
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        assert_eq!(client.get_market(&market_id).max_participants, 2);
+        let funded_user = || {
+            let user = Address::generate(&env);
+            StellarAssetClient::new(&env, &token).mint(&user, &10_000);
+            user
+        };
+        let first = funded_user();
+        let second = funded_user();
+        let third = funded_user();
 
-        let contract_id = env.register(MarketContract, ());
-        let client = MarketContractClient::new(&env, &contract_id);
-        let admin = Address::generate(&env);
-        env.as_contract(&contract_id, || {
-            storage::set_admin(&env, &admin);
-        });
+        client.deposit_collateral(&first, &market_id, &1_000, &None);
+        client.deposit_collateral(&second, &market_id, &1_000, &None);
+        assert_eq!(
+            client.try_deposit_collateral(&third, &market_id, &1_000, &None),
+            Err(Ok(ContractError::MarketClosedToDeposits))
+        );
+        assert_eq!(
+            client.try_mint_complete_set(&third, &market_id, &1_000),
+            Err(Ok(ContractError::MarketClosedToDeposits))
+        );
 
-        let token_admin = Address::generate(&env);
-        let token = env.register_stellar_asset_contract_v2(token_admin);
-        let collateral_token = token.address();
-        let sac = StellarAssetClient::new(&env, &collateral_token);
+        // Existing participants can keep adding collateral.
+        client.deposit_collateral(&first, &market_id, &500, &None);
+        client.mint_complete_set(&second, &market_id, &500);
+        assert_eq!(
+            client.get_position(&market_id, &first).total_deposited,
+            1_500
+        );
+        assert_eq!(client.get_participants(&market_id, &0, &10).len(), 2);
+    }
+
+    #[test]
+    fn test_deposit_enforces_market_min_deposit() {
+        use crate::error::ContractError;
 
-        // Mint directly to the market contract to simulate held collateral.
-        sac.mint(&contract_id, &500i128);
+        let (_env, user, client, market_id) = setup_limited_market(1_000, 0, 10_000);
+        assert_eq!(
+            client.try_deposit_collateral(&user, &market_id, &999, &None),
+            Err(Ok(ContractError::BelowMinDeposit))
+        );
+        client.deposit_collateral(&user, &market_id, &1_000, &None);
+        client.deposit_collateral(&user, &market_id, &1_001, &None);
+        assert_eq!(
+            client.get_position(&market_id, &user).total_deposited,
+            2_001
+        );
 
-        assert_eq!(client.token_balance(&collateral_token), 500i128);
+        // Without a minimum any positive amount is accepted.
+        let (_env, user, client, market_id) = setup_limited_market(0, 0, 10_000);
+        client.deposit_collateral(&user, &market_id, &1, &None);
     }
 
     #[test]
-    fn test_token_balance_zero_when_no_funds() {
-        use soroban_sdk::token::StellarAssetClient;
+    fn test_buy_shares_enforces_market_max_position() {
+        use crate::error::ContractError;
 
-        let env = Env::default();
-        env.mock_all_auths();
+        let (_env, user, client, market_id) = setup_limited_market(0, 5_000, 100_000);
+        client.deposit_collateral(&user, &market_id, &100_000, &None);
 
-        let contract_id = env.register(MarketContract, ());
-        let client = MarketContractClient::new(&env, &contract_id);
-        let admin = Address::generate(&env);
-        env.as_contract(&contract_id, || {
-            storage::set_admin(&env, &admin);
-        });
+        client.buy_shares(&user, &market_id, &true, &4_999);
+        assert_eq!(
+            client.try_buy_shares(&user, &market_id, &true, &2),
+            Err(Ok(ContractError::InvalidQuantity))
+        );
+        let position = client.buy_shares(&user, &market_id, &true, &1);
+        assert_eq!(position.yes_shares, 5_000);
+        assert_eq!(
+            client.try_buy_shares(&user, &market_id, &true, &1),
+            Err(Ok(ContractError::InvalidQuantity))
+        );
 
-        let token_admin = Address::generate(&env);
-        let token = env.register_stellar_asset_contract_v2(token_admin);
-        let collateral_token = token.address();
+        // The cap is on the net position: buying the other side reduces it.
+        client.buy_shares(&user, &market_id, &false, &10_000);
+        assert_eq!(
+            client.try_buy_shares(&user, &market_id, &false, &1),
+            Err(Ok(ContractError::InvalidQuantity))
+        );
 
-        assert_eq!(client.token_balance(&collateral_token), 0i128);
+        // Without a cap the same trades go through.
+        let (_env, user, client, market_id) = setup_limited_market(0, 0, 100_000);
+        client.deposit_collateral(&user, &market_id, &100_000, &None);
+        assert_eq!(
+            client
+                .buy_shares(&user, &market_id, &true, &5_001)
+                .yes_shares,
+            5_001
+        );
     }
 
-    // ========== Validation guard tests ==========
-
     #[test]
-    fn test_validation_guard_accepts_positive_input() {
-        use crate::validation::validate_input_guard;
-        assert!(validate_input_guard(1).is_ok());
-        assert!(validate_input_guard(1000).is_ok());
-    }
+    fn test_initialize_market_rejects_negative_limits() {
+        use crate::error::ContractError;
 
-    #[test]
-    fn test_validation_guard_rejects_zero() {
-        use crate::{error::ContractError, validation::validate_input_guard};
-        assert_eq!(validate_input_guard(0), Err(ContractError::InvalidQuantity));
+        let (env, admin, client, _contract_id) = create_test_contract();
+        for (min_deposit, max_position) in [(-1, 0), (0, -1)] {
+            assert_eq!(
+                client.try_initialize_market(
+                    &admin,
+                    &String::from_str(&env, "Test market"),
+                    &(env.ledger().timestamp() + 86400),
+                    &BytesN::from_array(&env, &[1u8; 32]),
+                    &create_collateral_token(&env),
+                    &crate::types::MarketMetadata {
+                        min_deposit,
+                        max_position,
+                        ..test_metadata(&env)
+                    },
+                    &test_source_hash(&env),
+                    &None,
+                    &None,
+                    &0,
+                ),
+                Err(Ok(ContractError::InvalidQuantity))
+            );
+        }
     }
 
     #[test]
-    fn test_validation_guard_rejects_negative() {
-        use crate::{error::ContractError, validation::validate_input_guard};
+    fn test_set_market_category_rejects_non_admin_and_missing_market() {
+        use soroban_sdk::symbol_short;
+
+        let (env, admin, client, _contract_id) = create_test_contract();
         assert_eq!(
-            validate_input_guard(-1),
-            Err(ContractError::InvalidQuantity)
+            client.try_set_market_category(&admin, &999, &symbol_short!("sports")),
+            Err(Ok(crate::error::ContractError::MarketNotFound))
+        );
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &create_collateral_token(&env),
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        assert_eq!(
+            client.try_set_market_category(
+                &Address::generate(&env),
+                &market_id,
+                &symbol_short!("sports")
+            ),
+            Err(Ok(crate::error::ContractError::NotAdmin))
         );
     }
 
-    // ========== propose_admin / accept_admin tests ==========
-
-    #[test]
-    fn test_propose_admin_success() {
-        let (env, admin, client, contract_id) = create_test_contract();
-        let new_admin = Address::generate(&env);
+    // ========== settlement_status tests ==========
 
-        client.propose_admin(&admin, &new_admin);
-
-        env.as_contract(&contract_id, || {
-            assert_eq!(
-                storage::get_pending_admin(&env).expect("pending admin should be set"),
-                new_admin
-            );
+    /// Resolve the funded market created by `setup_funded_market` to `outcome`.
+    fn resolve_funded_market(
+        env: &Env,
+        client: &MarketContractClient,
+        contract_id: &Address,
+        market_id: u32,
+        outcome: bool,
+    ) {
+        let (oracle_pubkey, signature) = generate_test_keypair_and_sign(env, market_id, outcome);
+        env.as_contract(contract_id, || {
+            let mut market = storage::get_market(env, market_id).unwrap().unwrap();
+            market.oracle_pubkey = oracle_pubkey;
+            storage::set_market(env, market_id, &market).unwrap();
         });
+        advance_to_end_time(env, client, market_id);
+        client.resolve_market(
+            &Address::generate(env),
+            &market_id,
+            &outcome.into(),
+            &None,
+            &signature,
+            &0,
+        );
     }
 
     #[test]
-    fn test_propose_admin_emits_event() {
-        let (env, admin, client, _contract_id) = create_test_contract();
-        let new_admin = Address::generate(&env);
+    fn test_get_settlement_summary_for_each_outcome() {
+        use crate::error::ContractError;
+        use crate::positions::STROOPS_PER_USDC;
 
-        client.propose_admin(&admin, &new_admin);
+        for outcome in [true, false] {
+            let (env, user, client, contract_id, market_id) =
+                setup_funded_market(100 * STROOPS_PER_USDC);
+            let yes = 60 * STROOPS_PER_USDC;
+            let no = 25 * STROOPS_PER_USDC;
+            client.update_position(&user, &market_id, &yes, &no, &5_000i128);
+            assert_eq!(
+                client.try_get_settlement_summary(&market_id),
+                Err(Ok(ContractError::MarketNotResolved))
+            );
 
-        let events = env.events().all();
-        assert!(events.len() > 0);
-    }
-    
-    #[test]
-    #[should_panic(expected = "Error(Contract, #35)")]
-    fn test_propose_admin_with_contract_address_fails() {
-        let (env, admin, client, _contract_id) = create_test_contract();
-        
-        // Try to propose a contract address as admin
-        let contract_admin = env.register(MarketContract, ());
-        
-        // This should fail with InvalidAdmin error (#35)
-        client.propose_admin(&admin, &contract_admin);
+            resolve_funded_market(&env, &client, &contract_id, market_id, outcome);
+            let expected = if outcome {
+                (yes, no, yes)
+            } else {
+                (no, yes, no)
+            };
+            assert_eq!(client.get_settlement_summary(&market_id), expected);
+        }
     }
 
     #[test]
-    fn test_accept_admin_completes_transfer() {
-        let (env, admin, client, contract_id) = create_test_contract();
-        let new_admin = Address::generate(&env);
-
-        client.propose_admin(&admin, &new_admin);
-        client.accept_admin(&new_admin);
+    fn test_get_settlement_summary_without_winning_side() {
+        use crate::error::ContractError;
 
-        env.as_contract(&contract_id, || {
-            assert_eq!(storage::get_admin(&env).unwrap(), new_admin);
-            assert!(
-                storage::get_pending_admin(&env).is_none(),
-                "pending admin should be cleared after acceptance"
-            );
-        });
+        let (env, _admin, _user, client, contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+        assert_eq!(
+            client.try_get_settlement_summary(&999),
+            Err(Ok(ContractError::MarketNotFound))
+        );
+        let signature = install_oracle_signature(&env, &contract_id, market_id, Outcome::Invalid);
+        advance_to_end_time(&env, &client, market_id);
+        client.resolve_market(
+            &Address::generate(&env),
+            &market_id,
+            &Outcome::Invalid,
+            &None,
+            &signature,
+            &0,
+        );
+        assert_eq!(
+            client.try_get_settlement_summary(&market_id),
+            Err(Ok(ContractError::InvalidOutcome))
+        );
     }
 
     #[test]
-    fn test_accept_admin_emits_event() {
-        let (env, admin, client, _contract_id) = create_test_contract();
-        let new_admin = Address::generate(&env);
-
-        client.propose_admin(&admin, &new_admin);
-        env.events().all(); // clear
+    fn test_settlement_status_unresolved_or_missing() {
+        use crate::positions::STROOPS_PER_USDC;
 
-        client.accept_admin(&new_admin);
+        let (_env, user, client, _contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
 
-        let events = env.events().all();
-        assert!(events.len() > 0);
+        assert_eq!(client.settlement_status(&market_id, &user), (None, false));
+        assert_eq!(client.settlement_status(&999, &user), (None, false));
     }
 
     #[test]
-    fn test_new_admin_can_create_market_after_transfer() {
-        let (env, admin, client, _contract_id) = create_test_contract();
-        let new_admin = Address::generate(&env);
-
-        client.propose_admin(&admin, &new_admin);
-        client.accept_admin(&new_admin);
+    fn test_settlement_status_resolved_unsettled() {
+        use crate::positions::STROOPS_PER_USDC;
 
-        let question = String::from_str(&env, "Will ETH flip BTC?");
-        let end_time = env.ledger().timestamp() + 86400;
-        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
+        let (env, user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        client.update_position(
+            &user,
+            &market_id,
+            &(100 * STROOPS_PER_USDC),
+            &0i128,
+            &5_000i128,
+        );
+        resolve_funded_market(&env, &client, &contract_id, market_id, false);
 
-        let market_id =
-            client.initialize_market(&new_admin, &question, &end_time, &oracle_pubkey, &collateral_token);
-        assert_eq!(market_id, 1);
+        assert_eq!(
+            client.settlement_status(&market_id, &user),
+            (Some(false), false)
+        );
+        // A user without a position still sees the outcome.
+        let stranger = Address::generate(&env);
+        assert_eq!(
+            client.settlement_status(&market_id, &stranger),
+            (Some(false), false)
+        );
     }
 
     #[test]
-    fn test_old_admin_cannot_create_market_after_transfer() {
-        let (env, admin, client, _contract_id) = create_test_contract();
-        let new_admin = Address::generate(&env);
+    fn test_settlement_status_resolved_settled() {
+        use crate::positions::STROOPS_PER_USDC;
 
-        client.propose_admin(&admin, &new_admin);
-        client.accept_admin(&new_admin);
+        let (env, user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        client.update_position(
+            &user,
+            &market_id,
+            &(100 * STROOPS_PER_USDC),
+            &0i128,
+            &5_000i128,
+        );
+        resolve_funded_market(&env, &client, &contract_id, market_id, true);
 
-        let question = String::from_str(&env, "Will ETH flip BTC?");
-        let end_time = env.ledger().timestamp() + 86400;
-        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let collateral_token = Address::generate(&env);
+        client.settle_position(&user, &market_id);
 
-        let result =
-            client.try_initialize_market(&admin, &question, &end_time, &oracle_pubkey, &collateral_token);
-        assert!(result.is_err());
+        assert_eq!(
+            client.settlement_status(&market_id, &user),
+            (Some(true), true)
+        );
     }
 
     #[test]
-    fn test_propose_admin_overwrites_previous_nominee() {
-        let (env, admin, client, contract_id) = create_test_contract();
-        let first_nominee = Address::generate(&env);
-        let second_nominee = Address::generate(&env);
-
-        client.propose_admin(&admin, &first_nominee);
-        client.propose_admin(&admin, &second_nominee);
+    fn test_settlement_fee_accrues_to_admin() {
+        use crate::error::ContractError;
+        use crate::positions::STROOPS_PER_USDC;
+        use crate::validation::MAX_SETTLEMENT_FEE_BPS;
 
-        env.as_contract(&contract_id, || {
-            assert_eq!(
-                storage::get_pending_admin(&env).expect("pending admin should be set"),
-                second_nominee
-            );
-        });
-    }
+        let (env, user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        let admin = env.as_contract(&contract_id, || storage::get_admin(&env).unwrap());
+        assert_eq!(client.get_settlement_fee(), 0);
+        assert_eq!(
+            client.try_set_settlement_fee(&user, &100),
+            Err(Ok(ContractError::NotAdmin))
+        );
+        assert_eq!(
+            client.try_set_settlement_fee(&admin, &(MAX_SETTLEMENT_FEE_BPS + 1)),
+            Err(Ok(ContractError::FeeCapExceeded))
+        );
+        client.set_settlement_fee(&admin, &250);
+        assert_eq!(client.get_settlement_fee(), 250);
 
-    #[test]
-    #[should_panic(expected = "Error(Contract, #41)")]
-    fn test_propose_admin_non_admin_fails() {
-        let (env, _admin, client, _contract_id) = create_test_contract();
-        let attacker = Address::generate(&env);
-        let victim = Address::generate(&env);
+        let shares = 80 * STROOPS_PER_USDC;
+        client.update_position(&user, &market_id, &shares, &0i128, &5_000i128);
+        resolve_funded_market(&env, &client, &contract_id, market_id, true);
 
-        client.propose_admin(&attacker, &victim);
+        let fee = 2 * STROOPS_PER_USDC;
+        assert_eq!(client.settle_position(&user, &market_id), shares - fee);
+        assert_eq!(client.get_accrued_fees(&market_id), fee);
+        assert_eq!(client.withdraw_fees(&admin, &market_id), fee);
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #41)")]
-    fn test_propose_admin_when_not_initialized_fails() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let contract_id = env.register(MarketContract, ());
-        let client = MarketContractClient::new(&env, &contract_id);
+    fn test_get_resolution_distinguishes_unresolved_from_no() {
+        use crate::error::ContractError;
+        use crate::positions::STROOPS_PER_USDC;
+        use crate::types::Resolution;
 
-        let caller = Address::generate(&env);
-        let new_admin = Address::generate(&env);
-        client.propose_admin(&caller, &new_admin);
+        let (env, _user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        assert_eq!(client.get_resolution(&market_id), Resolution::Unresolved);
+        assert_eq!(
+            client.try_get_resolution(&999),
+            Err(Ok(ContractError::MarketNotFound))
+        );
+
+        resolve_funded_market(&env, &client, &contract_id, market_id, false);
+        assert_eq!(client.get_resolution(&market_id), Resolution::No);
+
+        // A proposed outcome is not final until the challenge window passes.
+        env.as_contract(&contract_id, || {
+            let mut market = storage::get_market(&env, market_id).unwrap().unwrap();
+            market.status = MarketStatus::PendingResolution;
+            storage::set_market(&env, market_id, &market).unwrap();
+        });
+        assert_eq!(client.get_resolution(&market_id), Resolution::Unresolved);
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #43)")]
-    fn test_accept_admin_with_no_pending_fails() {
-        let (env, _admin, client, _contract_id) = create_test_contract();
-        let attacker = Address::generate(&env);
+    fn test_get_resolution_yes_and_canceled() {
+        use crate::positions::STROOPS_PER_USDC;
+        use crate::types::Resolution;
 
-        client.accept_admin(&attacker);
+        let (env, _user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        resolve_funded_market(&env, &client, &contract_id, market_id, true);
+        assert_eq!(client.get_resolution(&market_id), Resolution::Yes);
+
+        let (_env, admin, _user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+        client.cancel_market(&admin, &market_id);
+        assert_eq!(client.get_resolution(&market_id), Resolution::Canceled);
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #40)")]
-    fn test_accept_admin_hijack_wrong_address_fails() {
-        let (env, admin, client, _contract_id) = create_test_contract();
-        let new_admin = Address::generate(&env);
-        let attacker = Address::generate(&env);
+    fn test_resolve_market_with_each_signed_outcome() {
+        use crate::positions::STROOPS_PER_USDC;
+        use crate::types::Resolution;
+
+        let shares = 10 * STROOPS_PER_USDC;
+        for (outcome, resolution) in [
+            (Outcome::Yes, Resolution::Yes),
+            (Outcome::No, Resolution::No),
+            (Outcome::Invalid, Resolution::Invalid),
+        ] {
+            let (env, user, client, contract_id, market_id) =
+                setup_funded_market(100 * STROOPS_PER_USDC);
+            client.update_position(&user, &market_id, &shares, &0i128, &5_000i128);
+            let deposited = client.get_position(&market_id, &user).total_deposited;
+            let signature = install_oracle_signature(&env, &contract_id, market_id, outcome);
+
+            advance_to_end_time(&env, &client, market_id);
+            client.resolve_market(
+                &Address::generate(&env),
+                &market_id,
+                &outcome,
+                &None,
+                &signature,
+                &0,
+            );
+            assert_eq!(
+                emitted(&env, "market_invalidated"),
+                outcome == Outcome::Invalid
+            );
+            assert_eq!(
+                emitted(&env, "market_resolved"),
+                outcome != Outcome::Invalid
+            );
 
-        client.propose_admin(&admin, &new_admin);
-        client.accept_admin(&attacker);
+            let market = client.get_market(&market_id);
+            assert_eq!(market.status, MarketStatus::Resolved);
+            assert_eq!(market.result, outcome.winner());
+            assert_eq!(client.get_resolution(&market_id), resolution);
+
+            let expected = match outcome {
+                Outcome::Yes => shares,
+                Outcome::No => 0,
+                Outcome::Invalid => deposited,
+            };
+            assert_eq!(client.settle_position(&user, &market_id), expected);
+        }
     }
 
     #[test]
-    fn test_set_treasury_records_contract_address() {
-        let (env, admin, client, contract_id) = create_test_contract();
-        let treasury = Address::generate(&env);
+    fn test_resolve_market_invalid_requires_its_own_signature() {
+        use crate::error::ContractError;
 
-        client.set_treasury_contract(&admin, &treasury);
+        let (env, _admin, _user, client, contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+        let yes_signature = install_oracle_signature(&env, &contract_id, market_id, true);
+        let resolver = Address::generate(&env);
 
-        env.as_contract(&contract_id, || {
-            assert_eq!(storage::get_treasury(&env).unwrap(), treasury);
-        });
+        advance_to_end_time(&env, &client, market_id);
+        assert_eq!(
+            client.try_resolve_market(
+                &resolver,
+                &market_id,
+                &Outcome::Invalid,
+                &None,
+                &yes_signature,
+                &0
+            ),
+            Err(Ok(ContractError::InvalidSignature))
+        );
+
+        let signature = install_oracle_signature(&env, &contract_id, market_id, Outcome::Invalid);
+        assert_eq!(
+            client.try_resolve_market(
+                &resolver,
+                &market_id,
+                &Outcome::Invalid,
+                &None,
+                &signature,
+                &600
+            ),
+            Err(Ok(ContractError::InvalidTimestamp))
+        );
+        client.resolve_market(
+            &resolver,
+            &market_id,
+            &Outcome::Invalid,
+            &None,
+            &signature,
+            &0,
+        );
+        assert_eq!(client.get_market(&market_id).result, None);
     }
 
     #[test]
-    fn test_set_outcome_token_contract_records_contract_address() {
+    fn test_resolve_market_cancels_market_without_collateral() {
         let (env, admin, client, contract_id) = create_test_contract();
-        let outcome_token_contract = Address::generate(&env);
+        let end_time = env.ledger().timestamp() + 86400;
+        let collateral_token = create_collateral_token(&env);
+        let (empty_pubkey, empty_sig) = generate_test_keypair_and_sign(&env, 1, true);
+        let (funded_pubkey, funded_sig) = generate_test_keypair_and_sign(&env, 2, true);
+        let create = |n: u32, oracle_pubkey: &BytesN<32>| {
+            client.initialize_market(
+                &admin,
+                &numbered_question(&env, n),
+                &end_time,
+                oracle_pubkey,
+                &collateral_token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            )
+        };
+        let empty = create(1, &empty_pubkey);
+        let funded = create(2, &funded_pubkey);
+        fund_market(&env, &client, &collateral_token, funded, 1_000_000);
 
-        client.set_outcome_token_contract(&admin, &outcome_token_contract);
+        env.ledger().set_timestamp(end_time);
+        let resolver = Address::generate(&env);
+        client.resolve_market(&resolver, &empty, &Outcome::Yes, &None, &empty_sig, &0);
+        assert!(emitted(&env, "market_canceled"));
+        assert!(!emitted(&env, "market_resolved"));
+        let market = get_market_from_storage(&env, &contract_id, empty);
+        assert_eq!(market.status, MarketStatus::Canceled);
+        assert_eq!(market.result, None);
+        assert_eq!(
+            client.get_resolution(&empty),
+            crate::types::Resolution::Canceled
+        );
 
-        env.as_contract(&contract_id, || {
-            assert_eq!(storage::get_outcome_token_contract(&env).unwrap(), outcome_token_contract);
-        });
+        client.resolve_market(&resolver, &funded, &Outcome::Yes, &None, &funded_sig, &0);
+        assert!(emitted(&env, "market_resolved"));
+        let market = get_market_from_storage(&env, &contract_id, funded);
+        assert_eq!(market.status, MarketStatus::Resolved);
+        assert_eq!(market.result, Some(true));
     }
 
     #[test]
-    fn test_set_resolution_contract_records_contract_address() {
-        let (env, admin, client, contract_id) = create_test_contract();
-        let resolution_contract = Address::generate(&env);
+    fn test_resolve_scalar_market_cancels_market_without_collateral() {
+        use crate::types::{MarketKind, ScalarBounds};
+        use ed25519_dalek::{Signer, SigningKey};
+        use rand::rngs::OsRng;
 
-        client.set_resolution_contract(&admin, &resolution_contract);
+        let (env, admin, client, contract_id) = create_test_contract();
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "BTC price at expiry?"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &signing_key.verifying_key().to_bytes()),
+            &create_collateral_token(&env),
+            &crate::types::MarketMetadata {
+                kind: MarketKind::Scalar(ScalarBounds {
+                    lower: 0,
+                    upper: 100,
+                }),
+                ..test_metadata(&env)
+            },
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        advance_to_end_time(&env, &client, market_id);
+        let message = crate::oracle::construct_scalar_oracle_message(
+            &env,
+            market_id,
+            client.get_market(&market_id).created_at,
+            &test_source_hash(&env),
+            50,
+        );
+        let signature = BytesN::from_array(
+            &env,
+            &signing_key.sign(message.to_array().as_slice()).to_bytes(),
+        );
 
-        env.as_contract(&contract_id, || {
-            assert_eq!(storage::get_resolution_contract(&env).unwrap(), resolution_contract);
-        });
+        client.resolve_scalar_market(&Address::generate(&env), &market_id, &50, &None, &signature);
+        assert!(emitted(&env, "market_canceled"));
+        assert!(!emitted(&env, "scalar_market_resolved"));
+        let market = get_market_from_storage(&env, &contract_id, market_id);
+        assert_eq!(market.status, MarketStatus::Canceled);
+        assert_eq!(market.settled_value, None);
     }
 
     #[test]
-    fn test_non_admin_cannot_set_optional_integration_contracts() {
+    fn test_resolve_scalar_market_requires_finalized_candidate() {
         use crate::error::ContractError;
+        use crate::types::{MarketKind, ScalarBounds};
 
-        let (env, _admin, client, _contract_id) = create_test_contract();
-        let stranger = Address::generate(&env);
-        let address = Address::generate(&env);
+        let (env, admin, client, _contract_id) = create_test_contract();
+        let token = create_collateral_token(&env);
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "BTC price at expiry?"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &token,
+            &crate::types::MarketMetadata {
+                kind: MarketKind::Scalar(ScalarBounds {
+                    lower: 0,
+                    upper: 100,
+                }),
+                ..test_metadata(&env)
+            },
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        fund_market(&env, &client, &token, market_id, 1_000);
+        register_resolution_contract(&env, &client, &admin);
+        advance_to_end_time(&env, &client, market_id);
 
-        assert_eq!(client.try_set_treasury_contract(&stranger, &address), Err(Ok(ContractError::NotAdmin)));
-        assert_eq!(client.try_set_outcome_token_contract(&stranger, &address), Err(Ok(ContractError::NotAdmin)));
-        assert_eq!(client.try_set_resolution_contract(&stranger, &address), Err(Ok(ContractError::NotAdmin)));
+        assert_eq!(
+            client.try_resolve_scalar_market(
+                &Address::generate(&env),
+                &market_id,
+                &50,
+                &None,
+                &BytesN::from_array(&env, &[0u8; 64]),
+            ),
+            Err(Ok(ContractError::ResolutionNotFinalized))
+        );
+        assert_eq!(client.get_market(&market_id).status, MarketStatus::Active);
     }
 
     #[test]
-    fn test_resolution_contract_requires_finalized_candidate_before_resolve() {
-        use crate::error::ContractError;
+    fn test_resolve_market_multi_cancels_market_without_collateral() {
+        use crate::types::OracleSet;
+        use soroban_sdk::vec;
 
-        let env = Env::default();
-        env.mock_all_auths();
+        let (env, admin, client, contract_id) = create_test_contract();
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &create_collateral_token(&env),
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        let (pk1, sig1) = generate_test_keypair_and_sign(&env, market_id, true);
+        let (pk2, sig2) = generate_test_keypair_and_sign(&env, market_id, true);
+        let keys = vec![&env, pk1, pk2];
+        client.set_market_oracles(
+            &admin,
+            &market_id,
+            &Some(OracleSet {
+                keys: keys.clone(),
+                threshold: 2,
+            }),
+        );
+        advance_to_end_time(&env, &client, market_id);
 
-        let contract_id = env.register(MarketContract, ());
-        let client = MarketContractClient::new(&env, &contract_id);
-        let admin = Address::generate(&env);
+        client.resolve_market_multi(
+            &Address::generate(&env),
+            &market_id,
+            &true,
+            &vec![&env, sig1, sig2],
+            &keys,
+        );
+        assert!(emitted(&env, "market_canceled"));
+        assert!(!emitted(&env, "market_resolved"));
+        let market = get_market_from_storage(&env, &contract_id, market_id);
+        assert_eq!(market.status, MarketStatus::Canceled);
+        assert_eq!(market.result, None);
+    }
 
-        env.as_contract(&contract_id, || {
-            storage::set_admin(&env, &admin);
-            storage::set_version(&env);
-        });
+    #[test]
+    fn test_admin_execute_resolution_cancels_market_without_collateral() {
+        use crate::validation::ADMIN_RESOLUTION_DELAY_SECONDS;
 
-        let collateral_token = Address::generate(&env);
-        let question = String::from_str(&env, "Will it rain tomorrow?");
-        let end_time = env.ledger().timestamp() + 86400;
-        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let market_id = client.initialize_market(&admin, &question, &end_time, &oracle_pubkey, &collateral_token);
+        let (env, admin, client, contract_id) = create_test_contract();
+        let market_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "Test market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &create_collateral_token(&env),
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        advance_to_end_time(&env, &client, market_id);
+        client.admin_propose_resolution(&admin, &market_id, &true);
+        env.ledger()
+            .with_mut(|li| li.timestamp += ADMIN_RESOLUTION_DELAY_SECONDS);
+
+        client.admin_execute_resolution(&market_id);
+        assert!(emitted(&env, "market_canceled"));
+        assert!(!emitted(&env, "market_resolved"));
+        let market = get_market_from_storage(&env, &contract_id, market_id);
+        assert_eq!(market.status, MarketStatus::Canceled);
+        assert_eq!(market.result, None);
+        assert_eq!(client.get_admin_resolution(&market_id), None);
+    }
 
-        let resolution_addr = env.register(ResolutionContract, ());
-        ResolutionContractClient::new(&env, &resolution_addr)
-            .initialize(&admin, &Address::generate(&env), &contract_id);
+    #[test]
+    fn test_get_potential_payout_pending_until_resolved() {
+        use crate::positions::STROOPS_PER_USDC;
 
-        client.set_resolution_contract(&admin, &resolution_addr);
+        let (env, user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        client.update_position(
+            &user,
+            &market_id,
+            &(80 * STROOPS_PER_USDC),
+            &0i128,
+            &5_000i128,
+        );
 
-        let (_oracle_pubkey, signature) = generate_test_keypair_and_sign(&env, market_id, true);
+        assert_eq!(client.get_potential_payout(&market_id, &user), None);
+        resolve_funded_market(&env, &client, &contract_id, market_id, true);
+        assert_eq!(
+            client.get_potential_payout(&market_id, &user),
+            Some(80 * STROOPS_PER_USDC)
+        );
+    }
 
-        let proposer = Address::generate(&env);
-        let evidence = String::from_str(&env, "evidence://uri");
-        ResolutionContractClient::new(&env, &resolution_addr)
-            .propose(&proposer, &market_id, &true, &signature, &(env.ledger().timestamp() + 60), &evidence, &60);
+    #[test]
+    fn test_get_potential_payout_missing_position() {
+        use crate::positions::STROOPS_PER_USDC;
 
-        let resolver = Address::generate(&env);
-        let market_id_str = String::from_str(&env, &market_id.to_string());
+        let (env, _user, client, _contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        let stranger = Address::generate(&env);
         assert_eq!(
-            client.try_resolve_market(&resolver, &market_id_str, &true, &signature),
-            Err(Ok(ContractError::ResolutionNotFinalized))
+            client.try_get_potential_payout(&market_id, &stranger),
+            Err(Ok(crate::error::ContractError::NoPositionFound))
+        );
+        assert_eq!(
+            client.try_get_potential_payout(&999, &stranger),
+            Err(Ok(crate::error::ContractError::MarketNotFound))
         );
     }
 
     #[test]
-    fn test_first_nominee_cannot_accept_after_overwrite() {
-        let (env, admin, client, _contract_id) = create_test_contract();
-        let first_nominee = Address::generate(&env);
-        let second_nominee = Address::generate(&env);
+    fn test_preview_payout_covers_both_outcomes() {
+        use crate::positions::STROOPS_PER_USDC;
 
-        client.propose_admin(&admin, &first_nominee);
-        client.propose_admin(&admin, &second_nominee);
+        let (env, user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        client.update_position(
+            &user,
+            &market_id,
+            &(60 * STROOPS_PER_USDC),
+            &(30 * STROOPS_PER_USDC),
+            &5_000i128,
+        );
 
-        let result = client.try_accept_admin(&first_nominee);
-        assert!(result.is_err());
-    }
+        let expected = (60 * STROOPS_PER_USDC, 30 * STROOPS_PER_USDC);
+        assert_eq!(client.preview_payout(&market_id, &user), expected);
+        // Unchanged by resolution; zero once the position is settled.
+        resolve_funded_market(&env, &client, &contract_id, market_id, true);
+        assert_eq!(client.preview_payout(&market_id, &user), expected);
+        client.settle_position(&user, &market_id);
+        assert_eq!(client.preview_payout(&market_id, &user), (0, 0));
 
-    // ========== cancel_market tests ==========
+        // No position or no market previews as nothing rather than erroring.
+        let stranger = Address::generate(&env);
+        assert_eq!(client.preview_payout(&market_id, &stranger), (0, 0));
+        assert_eq!(client.preview_payout(&999, &stranger), (0, 0));
+    }
 
-    /// Register a market backed by a real Stellar asset, mint `deposit` to a
-    /// fresh user, and deposit it so cancel and collateral-reclaim flows can be
-    /// exercised end to end.
-    ///
-    /// Returns `(env, admin, user, client, contract_id, market_id, collateral_token)`.
-    fn setup_admin_market_with_deposit<'a>(
-        deposit: i128,
-    ) -> (
-        Env,
-        Address,
-        Address,
-        MarketContractClient<'a>,
-        Address,
-        u32,
-        Address,
-    ) {
-        use soroban_sdk::token::StellarAssetClient;
+    #[test]
+    fn test_settle_losing_position_skips_transfer() {
+        use crate::positions::STROOPS_PER_USDC;
+        use soroban_sdk::{testutils::Events as _, IntoVal, Symbol};
 
-        let env = Env::default();
-        let token_admin = Address::generate(&env);
-        let token = env.register_stellar_asset_contract_v2(token_admin.clone());
-        let collateral_token = token.address();
+        let (env, user, client, contract_id, market_id, collateral_token) =
+            setup_market_with_funded_user(100 * STROOPS_PER_USDC);
+        client.deposit_collateral(&user, &market_id, &(100 * STROOPS_PER_USDC), &None);
+        client.update_position(
+            &user,
+            &market_id,
+            &(100 * STROOPS_PER_USDC),
+            &0i128,
+            &5_000i128,
+        );
+        resolve_funded_market(&env, &client, &contract_id, market_id, false);
 
-        let contract_id = env.register(MarketContract, ());
-        let client = MarketContractClient::new(&env, &contract_id);
-        let admin = Address::generate(&env);
+        assert_eq!(client.settle_position(&user, &market_id), 0);
 
-        env.as_contract(&contract_id, || {
-            storage::set_admin(&env, &admin);
-            storage::set_version(&env);
+        // No transfer event from the collateral token; the settled event
+        // still fires with a zero payout.
+        let events = env.events().all();
+        assert!(!events.iter().any(|e| e.0 == collateral_token));
+        let settled = events.iter().find(|e| {
+            let topic: Symbol = e.1.get(0).unwrap().into_val(&env);
+            topic == Symbol::new(&env, "position_settled")
         });
+        assert!(settled.is_some(), "position_settled should be emitted");
+        assert_eq!(
+            client.settlement_status(&market_id, &user),
+            (Some(false), true)
+        );
+    }
 
-        env.mock_all_auths();
+    // ========== health tests ==========
 
-        let question = String::from_str(&env, "Will it rain tomorrow?");
-        let end_time = env.ledger().timestamp() + 86400;
-        let oracle_pubkey = BytesN::from_array(&env, &[1u8; 32]);
-        let market_id = client.initialize_market(
+    #[test]
+    fn test_health_tracks_market_lifecycle() {
+        use crate::positions::STROOPS_PER_USDC;
+        use crate::storage::STORAGE_VERSION;
+
+        let (env, user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        client.update_position(
+            &user,
+            &market_id,
+            &(100 * STROOPS_PER_USDC),
+            &0i128,
+            &5_000i128,
+        );
+
+        let health = client.health();
+        assert!(!health.paused);
+        assert!(!health.treasury_set);
+        assert_eq!(health.schema_version, STORAGE_VERSION);
+        assert_eq!((health.total_markets, health.active_markets), (1, 1));
+        assert_eq!(health.resolved_unsettled_count, 0);
+
+        // A second market, canceled, no longer counts as active.
+        let admin = env.as_contract(&contract_id, || storage::get_admin(&env).unwrap());
+        let second = client.initialize_market(
             &admin,
-            &question,
-            &end_time,
-            &oracle_pubkey,
-            &collateral_token,
+            &String::from_str(&env, "Second market"),
+            &(env.ledger().timestamp() + 86400),
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &create_collateral_token(&env),
+            &test_metadata(&env),
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        assert_eq!(
+            (
+                client.health().total_markets,
+                client.health().active_markets
+            ),
+            (2, 2)
         );
+        client.cancel_market(&admin, &second);
+        assert_eq!(client.health().active_markets, 1);
 
-        let user = Address::generate(&env);
-        let token_client = StellarAssetClient::new(&env, &collateral_token);
-        token_client.mint(&user, &deposit);
-        client.deposit_collateral(&user, &market_id, &deposit);
+        // Resolution moves the open position into the unsettled count...
+        resolve_funded_market(&env, &client, &contract_id, market_id, true);
+        let health = client.health();
+        assert_eq!((health.total_markets, health.active_markets), (2, 0));
+        assert_eq!(health.resolved_unsettled_count, 1);
 
-        (
-            env,
-            admin,
-            user,
-            client,
-            contract_id,
-            market_id,
-            collateral_token,
-        )
+        // ...and settling it clears it.
+        client.settle_position(&user, &market_id);
+        assert_eq!(client.health().resolved_unsettled_count, 0);
     }
 
     #[test]
-    fn test_cancel_market_success() {
-        let (env, admin, _user, client, contract_id, market_id, _token) =
-            setup_admin_market_with_deposit(1_000);
+    fn test_health_before_initialization() {
+        let env = Env::default();
+        let client = MarketContractClient::new(&env, &env.register(MarketContract, ()));
+        let health = client.health();
+        assert_eq!(health.schema_version, 0);
+        assert_eq!((health.total_markets, health.active_markets), (0, 0));
+        assert!(!health.paused);
+    }
 
-        client.cancel_market(&admin, &market_id);
+    // ========== close_position tests ==========
 
-        let market = get_market_from_storage(&env, &contract_id, market_id);
-        assert_eq!(market.status, MarketStatus::Canceled);
+    /// Whether the last invocation emitted an event with topic `name`.
+    fn emitted(env: &Env, name: &str) -> bool {
+        use soroban_sdk::{testutils::Events as _, IntoVal, Symbol};
+
+        env.events().all().iter().any(|e| {
+            let topic: Symbol = e.1.get(0).unwrap().into_val(env);
+            topic == Symbol::new(env, name)
+        })
     }
 
     #[test]
-    fn test_cancel_market_emits_event() {
-        let (env, admin, _user, client, _contract_id, market_id, _token) =
-            setup_admin_market_with_deposit(1_000);
+    fn test_close_position_active_sells_all_shares() {
+        use crate::positions::STROOPS_PER_USDC;
 
-        env.events().all(); // clear setup events
-        client.cancel_market(&admin, &market_id);
+        let (env, user, client, _contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        client.update_position(
+            &user,
+            &market_id,
+            &(60 * STROOPS_PER_USDC),
+            &(20 * STROOPS_PER_USDC),
+            &5_000i128,
+        );
+        let locked = client.get_position(&market_id, &user).locked_collateral;
 
-        let events = env.events().all();
-        assert!(events.len() > 0, "MarketCanceled event should be emitted");
+        assert_eq!(client.close_position(&user, &market_id), locked);
+        assert!(emitted(&env, "position_updated"));
+
+        let position = client.get_position(&market_id, &user);
+        assert_eq!((position.yes_shares, position.no_shares), (0, 0));
+        assert_eq!(position.locked_collateral, 0);
+        assert_eq!(position.total_deposited, 100 * STROOPS_PER_USDC);
+        assert!(!position.is_settled);
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #41)")]
-    fn test_cancel_market_non_admin_fails() {
-        let (env, _admin, _user, client, _contract_id, market_id, _token) =
-            setup_admin_market_with_deposit(1_000);
+    fn test_close_position_resolved_settles() {
+        use crate::positions::STROOPS_PER_USDC;
 
-        let attacker = Address::generate(&env);
-        client.cancel_market(&attacker, &market_id);
+        let (env, user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        client.update_position(
+            &user,
+            &market_id,
+            &(100 * STROOPS_PER_USDC),
+            &0i128,
+            &5_000i128,
+        );
+        resolve_funded_market(&env, &client, &contract_id, market_id, true);
+
+        assert_eq!(
+            client.close_position(&user, &market_id),
+            100 * STROOPS_PER_USDC
+        );
+        assert!(emitted(&env, "position_settled"));
+        assert_eq!(
+            client.settlement_status(&market_id, &user),
+            (Some(true), true)
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #1)")]
-    fn test_cancel_market_not_found_fails() {
-        let (_env, admin, _user, client, _contract_id, _market_id, _token) =
-            setup_admin_market_with_deposit(1_000);
+    fn test_close_position_canceled_refunds() {
+        use crate::positions::STROOPS_PER_USDC;
+        use soroban_sdk::token::TokenClient;
 
-        client.cancel_market(&admin, &999u32);
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, user, client, contract_id, market_id, collateral_token) =
+            setup_market_with_funded_user(deposit);
+        client.deposit_collateral(&user, &market_id, &deposit, &None);
+        let admin = env.as_contract(&contract_id, || storage::get_admin(&env).unwrap());
+        client.cancel_market(&admin, &market_id);
+
+        assert_eq!(client.close_position(&user, &market_id), deposit);
+        assert!(emitted(&env, "collateral_withdrawn"));
+        assert_eq!(
+            TokenClient::new(&env, &collateral_token).balance(&user),
+            deposit
+        );
+        assert_eq!(client.get_position(&market_id, &user).total_deposited, 0);
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #2)")]
-    fn test_cancel_market_already_resolved_fails() {
-        let (env, admin, _user, client, contract_id, market_id, _token) =
-            setup_admin_market_with_deposit(1_000);
+    fn test_close_position_without_position() {
+        use crate::positions::STROOPS_PER_USDC;
 
-        // Force the market into a resolved state; a final outcome can't be canceled.
-        env.as_contract(&contract_id, || {
-            let mut market = storage::get_market(&env, market_id).unwrap().unwrap();
-            market.status = MarketStatus::Resolved;
-            market.result = Some(true);
-            storage::set_market(&env, market_id, &market).unwrap();
+        let (env, _user, client, _contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        assert_eq!(
+            client.try_close_position(&Address::generate(&env), &market_id),
+            Err(Ok(crate::error::ContractError::NoPositionFound))
+        );
+    }
+
+    // ========== commit-reveal resolution tests ==========
+
+    /// Point the market at a fresh oracle key and return its signature for
+    /// `outcome`.
+    fn install_oracle_signature(
+        env: &Env,
+        contract_id: &Address,
+        market_id: u32,
+        outcome: impl Into<Outcome>,
+    ) -> BytesN<64> {
+        let (oracle_pubkey, signature) = generate_test_keypair_and_sign(env, market_id, outcome);
+        env.as_contract(contract_id, || {
+            let mut market = storage::get_market(env, market_id).unwrap().unwrap();
+            market.oracle_pubkey = oracle_pubkey;
+            storage::set_market(env, market_id, &market).unwrap();
         });
+        signature
+    }
 
-        client.cancel_market(&admin, &market_id);
+    fn next_ledger(env: &Env) {
+        env.ledger().with_mut(|li| li.sequence_number += 1);
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #5)")]
-    fn test_cancel_market_already_canceled_fails() {
-        let (_env, admin, _user, client, _contract_id, market_id, _token) =
-            setup_admin_market_with_deposit(1_000);
+    fn test_reveal_resolution_matching_commitment_resolves() {
+        use crate::error::ContractError;
+        use crate::positions::STROOPS_PER_USDC;
 
-        client.cancel_market(&admin, &market_id);
-        // A second cancellation is a no-op and must be rejected.
-        client.cancel_market(&admin, &market_id);
-    }
+        let (env, user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        let signature = install_oracle_signature(&env, &contract_id, market_id, true);
+        let salt = BytesN::from_array(&env, &[7u8; 32]);
+        let commitment =
+            crate::oracle::construct_resolution_commitment(&env, true, &salt, &signature);
+        let oracle =
+            crate::oracle::oracle_account(&env, &client.get_market(&market_id).oracle_pubkey);
 
-    #[test]
-    #[should_panic(expected = "Error(Contract, #5)")]
-    fn test_deposit_rejected_after_cancel() {
-        use soroban_sdk::token::StellarAssetClient;
+        advance_to_end_time(&env, &client, market_id);
+        client.commit_resolution(&oracle, &market_id, &commitment);
+        assert!(emitted(&env, "resolution_committed"));
 
-        let (env, admin, user, client, _contract_id, market_id, collateral_token) =
-            setup_admin_market_with_deposit(1_000);
+        // Trading is frozen and a second commitment is refused.
+        assert_eq!(
+            client.try_update_position(&user, &market_id, &STROOPS_PER_USDC, &0i128, &5_000i128),
+            Err(Ok(ContractError::ResolutionCommitted))
+        );
+        assert_eq!(
+            client.try_commit_resolution(&oracle, &market_id, &commitment),
+            Err(Ok(ContractError::ResolutionCommitted))
+        );
 
-        client.cancel_market(&admin, &market_id);
+        // The reveal must land in a later ledger.
+        assert_eq!(
+            client.try_reveal_resolution(&oracle, &market_id, &true, &salt, &signature),
+            Err(Ok(ContractError::RevealTooEarly))
+        );
+        next_ledger(&env);
+        assert_eq!(
+            client.try_reveal_resolution(
+                &Address::generate(&env),
+                &market_id,
+                &true,
+                &salt,
+                &signature
+            ),
+            Err(Ok(ContractError::Unauthorized))
+        );
 
-        // A fresh deposit into the canceled market must fail with MarketNotActive.
-        let token_client = StellarAssetClient::new(&env, &collateral_token);
-        token_client.mint(&user, &500);
-        client.deposit_collateral(&user, &market_id, &500);
+        client.reveal_resolution(&oracle, &market_id, &true, &salt, &signature);
+        assert!(emitted(&env, "market_resolved"));
+        assert_eq!(
+            client.settlement_status(&market_id, &user),
+            (Some(true), false)
+        );
+        assert_eq!(
+            client.try_reveal_resolution(&oracle, &market_id, &true, &salt, &signature),
+            Err(Ok(ContractError::NoResolutionCommitment))
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #5)")]
-    fn test_update_position_rejected_after_cancel() {
-        let (_env, admin, user, client, _contract_id, market_id, _token) =
-            setup_admin_market_with_deposit(1_000);
+    fn test_reveal_resolution_mismatch_rejected() {
+        use crate::error::ContractError;
+        use crate::positions::STROOPS_PER_USDC;
 
-        client.cancel_market(&admin, &market_id);
-        // Trading is halted once a market is canceled.
-        client.update_position(&user, &market_id, &100i128, &0i128, &5_000i128);
+        let (env, user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        let signature = install_oracle_signature(&env, &contract_id, market_id, true);
+        let salt = BytesN::from_array(&env, &[7u8; 32]);
+        let commitment =
+            crate::oracle::construct_resolution_commitment(&env, true, &salt, &signature);
+        let oracle =
+            crate::oracle::oracle_account(&env, &client.get_market(&market_id).oracle_pubkey);
+        advance_to_end_time(&env, &client, market_id);
+        client.commit_resolution(&oracle, &market_id, &commitment);
+        next_ledger(&env);
+
+        let other_salt = BytesN::from_array(&env, &[8u8; 32]);
+        assert_eq!(
+            client.try_reveal_resolution(&oracle, &market_id, &true, &other_salt, &signature),
+            Err(Ok(ContractError::CommitmentMismatch))
+        );
+        assert_eq!(
+            client.try_reveal_resolution(&oracle, &market_id, &false, &salt, &signature),
+            Err(Ok(ContractError::CommitmentMismatch))
+        );
+        assert_eq!(client.settlement_status(&market_id, &user), (None, false));
     }
 
     #[test]
-    fn test_withdraw_canceled_collateral_refunds_user() {
-        let deposit = 1_000i128;
-        let (env, admin, user, client, contract_id, market_id, collateral_token) =
-            setup_admin_market_with_deposit(deposit);
+    fn test_unrevealed_commitment_lapses() {
+        use crate::positions::STROOPS_PER_USDC;
+        use crate::validation::RESOLUTION_REVEAL_WINDOW_LEDGERS;
+
+        let (env, user, client, _contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        let oracle =
+            crate::oracle::oracle_account(&env, &client.get_market(&market_id).oracle_pubkey);
+        advance_to_end_time(&env, &client, market_id);
+        client.commit_resolution(&oracle, &market_id, &BytesN::from_array(&env, &[1u8; 32]));
+
+        env.ledger()
+            .with_mut(|li| li.sequence_number += RESOLUTION_REVEAL_WINDOW_LEDGERS + 1);
+        client.update_position(&user, &market_id, &STROOPS_PER_USDC, &0i128, &5_000i128);
+        assert_eq!(
+            client.try_reveal_resolution(
+                &oracle,
+                &market_id,
+                &true,
+                &BytesN::from_array(&env, &[0u8; 32]),
+                &BytesN::from_array(&env, &[0u8; 64]),
+            ),
+            Err(Ok(crate::error::ContractError::NoResolutionCommitment))
+        );
+    }
 
-        client.cancel_market(&admin, &market_id);
+    #[test]
+    fn test_direct_resolution_blocked_by_pending_commitment() {
+        use crate::error::ContractError;
+        use crate::positions::STROOPS_PER_USDC;
+        use crate::types::{MarketKind, ScalarBounds};
+        use soroban_sdk::vec;
+
+        let (env, _user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        let admin = client.get_admin();
+        let signature = install_oracle_signature(&env, &contract_id, market_id, true);
+        let scalar_id = client.initialize_market(
+            &admin,
+            &String::from_str(&env, "BTC price at expiry?"),
+            &client.get_market(&market_id).end_time,
+            &BytesN::from_array(&env, &[1u8; 32]),
+            &client.get_market(&market_id).collateral_token,
+            &crate::types::MarketMetadata {
+                kind: MarketKind::Scalar(ScalarBounds {
+                    lower: 0,
+                    upper: 100,
+                }),
+                ..test_metadata(&env)
+            },
+            &test_source_hash(&env),
+            &None,
+            &None,
+            &0,
+        );
+        let (signer, _) = generate_test_keypair_and_sign(&env, market_id, true);
+        client.set_threshold_signers(&admin, &vec![&env, signer], &1);
+        advance_to_end_time(&env, &client, market_id);
+        let commitment = BytesN::from_array(&env, &[1u8; 32]);
+        client.commit_resolution(&admin, &market_id, &commitment);
+        client.commit_resolution(&admin, &scalar_id, &commitment);
+
+        // A live commitment can only be completed by its reveal.
+        let resolver = Address::generate(&env);
+        assert_eq!(
+            client.try_resolve_market(&resolver, &market_id, &Outcome::Yes, &None, &signature, &0),
+            Err(Ok(ContractError::ResolutionCommitted))
+        );
+        assert_eq!(
+            client.try_resolve_market_threshold(
+                &resolver,
+                &market_id,
+                &true,
+                &vec![&env, signature.clone()]
+            ),
+            Err(Ok(ContractError::ResolutionCommitted))
+        );
+        assert_eq!(
+            client.try_resolve_scalar_market(&resolver, &scalar_id, &50, &None, &signature),
+            Err(Ok(ContractError::ResolutionCommitted))
+        );
+        assert_eq!(client.get_market(&market_id).status, MarketStatus::Active);
+    }
 
-        let refunded = client.withdraw_canceled_collateral(&user, &market_id);
-        assert_eq!(refunded, deposit);
+    #[test]
+    fn test_commit_resolution_limited_to_oracle_or_admin_after_end() {
+        use crate::error::ContractError;
+        use crate::positions::STROOPS_PER_USDC;
+        use crate::validation::RESOLUTION_REVEAL_WINDOW_LEDGERS;
 
-        // The user's position is zeroed once the collateral has been returned.
-        let position = env.as_contract(&contract_id, || {
-            storage::get_position(&env, market_id, &user)
-                .unwrap()
-                .expect("position should exist")
-        });
-        assert_eq!(position.total_deposited, 0);
-        assert_eq!(position.locked_collateral, 0);
+        let (env, _user, client, _contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        let oracle =
+            crate::oracle::oracle_account(&env, &client.get_market(&market_id).oracle_pubkey);
+        let commitment = BytesN::from_array(&env, &[1u8; 32]);
 
-        // The collateral lands back in the user's wallet.
-        let token_client = soroban_sdk::token::Client::new(&env, &collateral_token);
-        assert_eq!(token_client.balance(&user), deposit);
+        assert_eq!(
+            client.try_commit_resolution(&oracle, &market_id, &commitment),
+            Err(Ok(ContractError::MarketNotEnded))
+        );
+        advance_to_end_time(&env, &client, market_id);
+        // A stranger cannot freeze the market, even after a lapse.
+        assert_eq!(
+            client.try_commit_resolution(&Address::generate(&env), &market_id, &commitment),
+            Err(Ok(ContractError::Unauthorized))
+        );
+        client.commit_resolution(&oracle, &market_id, &commitment);
+        env.ledger()
+            .with_mut(|li| li.sequence_number += RESOLUTION_REVEAL_WINDOW_LEDGERS + 1);
+        assert_eq!(
+            client.try_commit_resolution(&Address::generate(&env), &market_id, &commitment),
+            Err(Ok(ContractError::Unauthorized))
+        );
+        client.commit_resolution(&client.get_admin(), &market_id, &commitment);
     }
 
+    // ========== challenge window tests ==========
+
     #[test]
-    #[should_panic(expected = "Error(Contract, #5)")]
-    fn test_withdraw_canceled_collateral_rejects_active_market() {
-        let (_env, _admin, user, client, _contract_id, market_id, _token) =
-            setup_admin_market_with_deposit(1_000);
+    fn test_resolve_market_with_challenge_window_pends_until_deadline() {
+        use crate::error::ContractError;
+        use crate::positions::STROOPS_PER_USDC;
 
-        // Market is still active, so the canceled-reclaim path does not apply.
-        client.withdraw_canceled_collateral(&user, &market_id);
+        let (env, user, client, contract_id, market_id) =
+            setup_funded_market(100 * STROOPS_PER_USDC);
+        client.update_position(
+            &user,
+            &market_id,
+            &(10 * STROOPS_PER_USDC),
+            &0i128,
+            &5_000i128,
+        );
+        let signature = install_oracle_signature(&env, &contract_id, market_id, true);
+        let resolver = Address::generate(&env);
+
+        advance_to_end_time(&env, &client, market_id);
+        client.resolve_market(
+            &resolver,
+            &market_id,
+            &Outcome::Yes,
+            &None,
+            &signature,
+            &3_600,
+        );
+        assert!(emitted(&env, "resolution_proposed"));
+        let market = client.get_market(&market_id);
+        assert_eq!(market.status, MarketStatus::PendingResolution);
+        assert_eq!(market.result, Some(true));
+        let deadline = env.ledger().timestamp() + 3_600;
+        assert_eq!(market.challenge_deadline, Some(deadline));
+
+        // Settlement, trading and a second resolution are closed while pending.
+        assert_eq!(
+            client.try_settle_position(&user, &market_id),
+            Err(Ok(ContractError::MarketNotResolved))
+        );
+        assert!(client
+            .try_update_position(&user, &market_id, &STROOPS_PER_USDC, &0i128, &5_000i128)
+            .is_err());
+        assert_eq!(
+            client.try_resolve_market(&resolver, &market_id, &Outcome::Yes, &None, &signature, &0),
+            Err(Ok(ContractError::MarketAlreadyResolved))
+        );
+
+        env.ledger().with_mut(|li| li.timestamp = deadline - 1);
+        assert_eq!(
+            client.try_finalize_resolution(&market_id),
+            Err(Ok(ContractError::ChallengeWindowOpen))
+        );
+
+        env.ledger().with_mut(|li| li.timestamp = deadline);
+        client.finalize_resolution(&market_id);
+        assert!(emitted(&env, "market_resolved"));
+        let market = client.get_market(&market_id);
+        assert_eq!(market.status, MarketStatus::Resolved);
+        assert_eq!(market.resolved_at, Some(deadline));
+        assert_eq!(
+            client.settle_position(&user, &market_id),
+            10 * STROOPS_PER_USDC
+        );
+        assert_eq!(
+            client.try_finalize_resolution(&market_id),
+            Err(Ok(ContractError::MarketAlreadyResolved))
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #12)")]
-    fn test_withdraw_canceled_collateral_no_position_fails() {
-        let (env, admin, _user, client, _contract_id, market_id, _token) =
-            setup_admin_market_with_deposit(1_000);
+    fn test_cancel_during_challenge_window_rejects_outcome() {
+        use crate::error::ContractError;
 
+        let deposit = 1_000i128;
+        let (env, admin, user, client, contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(deposit);
+        let signature = install_oracle_signature(&env, &contract_id, market_id, false);
+
+        advance_to_end_time(&env, &client, market_id);
+        client.resolve_market(
+            &Address::generate(&env),
+            &market_id,
+            &Outcome::No,
+            &None,
+            &signature,
+            &600,
+        );
         client.cancel_market(&admin, &market_id);
 
-        // A user who never deposited has no position to reclaim.
-        let stranger = Address::generate(&env);
-        client.withdraw_canceled_collateral(&stranger, &market_id);
+        let market = client.get_market(&market_id);
+        assert_eq!(market.status, MarketStatus::Canceled);
+        assert_eq!(market.result, None);
+        assert_eq!(market.challenge_deadline, None);
+        env.ledger().with_mut(|li| li.timestamp += 600);
+        assert_eq!(
+            client.try_finalize_resolution(&market_id),
+            Err(Ok(ContractError::MarketNotActive))
+        );
+        assert_eq!(client.settle_position(&user, &market_id), deposit);
     }
 
-    // ========== #332: Burn outcome tokens on position decrease ==========
-
-    /// #332: Selling YES shares burns the corresponding outcome tokens.
-    /// Verify that when yes_delta < 0 the token contract's burn entry point is
-    /// called (the SDK records the call in the auth invocations list, so we
-    /// assert the position decreases as expected as a proxy for the burn path
-    /// being exercised).
     #[test]
-    fn test_332_selling_yes_shares_decreases_position() {
-        use crate::positions::STROOPS_PER_USDC;
+    fn test_admin_resolution_executes_only_after_timelock() {
+        use crate::error::ContractError;
+        use crate::validation::ADMIN_RESOLUTION_DELAY_SECONDS;
 
-        let deposit = 100 * STROOPS_PER_USDC;
-        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+        let (env, admin, _user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
 
-        // Buy 80 YES shares first.
-        let buy = 80 * STROOPS_PER_USDC;
-        client.update_position(&user, &market_id, &buy, &0i128, &5_000i128);
+        assert_eq!(
+            client.try_admin_propose_resolution(&admin, &market_id, &true),
+            Err(Ok(ContractError::MarketNotEnded))
+        );
+        advance_to_end_time(&env, &client, market_id);
+        assert_eq!(
+            client.try_admin_propose_resolution(&Address::generate(&env), &market_id, &true),
+            Err(Ok(ContractError::NotAdmin))
+        );
+        assert_eq!(
+            client.try_admin_execute_resolution(&market_id),
+            Err(Ok(ContractError::NoResolutionCommitment))
+        );
 
-        // Sell 30 YES shares back.
-        let sell = -30 * STROOPS_PER_USDC;
-        let pos = client.update_position(&user, &market_id, &sell, &0i128, &5_000i128);
+        client.admin_propose_resolution(&admin, &market_id, &true);
+        assert!(emitted(&env, "admin_resolution_proposed"));
+        let executable_at = env.ledger().timestamp() + ADMIN_RESOLUTION_DELAY_SECONDS;
+        let proposal = client.get_admin_resolution(&market_id).unwrap();
+        assert!(proposal.outcome);
+        assert_eq!(proposal.executable_at, executable_at);
+        assert_eq!(client.get_market(&market_id).status, MarketStatus::Active);
 
-        assert_eq!(pos.yes_shares, 50 * STROOPS_PER_USDC);
-        assert_eq!(pos.no_shares, 0);
+        env.ledger().set_timestamp(executable_at - 1);
+        assert_eq!(
+            client.try_admin_execute_resolution(&market_id),
+            Err(Ok(ContractError::ChallengeWindowOpen))
+        );
+        assert_eq!(client.get_market(&market_id).status, MarketStatus::Active);
 
-        let stored = env.as_contract(&contract_id, || {
-            storage::get_position(&env, market_id, &user).unwrap().unwrap()
-        });
-        assert_eq!(stored.yes_shares, 50 * STROOPS_PER_USDC);
+        env.ledger().set_timestamp(executable_at);
+        client.admin_execute_resolution(&market_id);
+        assert!(emitted(&env, "admin_resolution_executed"));
+        assert!(emitted(&env, "market_resolved"));
+        let market = client.get_market(&market_id);
+        assert_eq!(market.status, MarketStatus::Resolved);
+        assert_eq!(market.result, Some(true));
+        assert_eq!(market.resolver, Some(admin));
+        assert_eq!(client.get_admin_resolution(&market_id), None);
+        assert_eq!(
+            client.try_admin_execute_resolution(&market_id),
+            Err(Ok(ContractError::NoResolutionCommitment))
+        );
+    }
+
+    #[test]
+    fn test_admin_resolution_executes_while_paused() {
+        use crate::validation::ADMIN_RESOLUTION_DELAY_SECONDS;
+
+        let (env, admin, _user, client, _contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+        advance_to_end_time(&env, &client, market_id);
+        client.admin_propose_resolution(&admin, &market_id, &false);
+        client.set_paused(&admin, &true);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp += ADMIN_RESOLUTION_DELAY_SECONDS);
+        client.admin_execute_resolution(&market_id);
+        let market = client.get_market(&market_id);
+        assert_eq!(market.status, MarketStatus::Resolved);
+        assert_eq!(market.result, Some(false));
     }
 
-    /// #332: Selling NO shares decreases the NO balance (burn path).
     #[test]
-    fn test_332_selling_no_shares_decreases_position() {
-        use crate::positions::STROOPS_PER_USDC;
-
-        let deposit = 100 * STROOPS_PER_USDC;
-        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
-
-        // Buy 60 NO shares first.
-        let buy = 60 * STROOPS_PER_USDC;
-        client.update_position(&user, &market_id, &0i128, &buy, &5_000i128);
+    fn test_admin_resolution_lapses_if_oracle_resolves_first() {
+        use crate::error::ContractError;
+        use crate::validation::ADMIN_RESOLUTION_DELAY_SECONDS;
 
-        // Sell 20 NO shares back.
-        let sell = -20 * STROOPS_PER_USDC;
-        let pos = client.update_position(&user, &market_id, &0i128, &sell, &5_000i128);
+        let (env, admin, _user, client, contract_id, market_id, _token) =
+            setup_admin_market_with_deposit(1_000);
+        let signature = install_oracle_signature(&env, &contract_id, market_id, false);
 
-        assert_eq!(pos.yes_shares, 0);
-        assert_eq!(pos.no_shares, 40 * STROOPS_PER_USDC);
+        advance_to_end_time(&env, &client, market_id);
+        client.admin_propose_resolution(&admin, &market_id, &true);
+        client.resolve_market(
+            &Address::generate(&env),
+            &market_id,
+            &Outcome::No,
+            &None,
+            &signature,
+            &0,
+        );
 
-        let stored = env.as_contract(&contract_id, || {
-            storage::get_position(&env, market_id, &user).unwrap().unwrap()
-        });
-        assert_eq!(stored.no_shares, 40 * STROOPS_PER_USDC);
+        env.ledger()
+            .with_mut(|li| li.timestamp += ADMIN_RESOLUTION_DELAY_SECONDS);
+        assert_eq!(
+            client.try_admin_execute_resolution(&market_id),
+            Err(Ok(ContractError::MarketAlreadyResolved))
+        );
+        assert_eq!(client.get_market(&market_id).result, Some(false));
     }
 
-    /// #332: Selling down to zero shares is allowed and results in locked_collateral == 0.
     #[test]
-    fn test_332_selling_all_shares_zeroes_locked_collateral() {
-        use crate::positions::STROOPS_PER_USDC;
-
-        let deposit = 100 * STROOPS_PER_USDC;
-        let (_env, user, client, _contract_id, market_id) = setup_funded_market(deposit);
+    fn test_dispute_resolution_allowed_while_paused() {
+        use soroban_sdk::token::StellarAssetClient;
 
-        let qty = 50 * STROOPS_PER_USDC;
-        client.update_position(&user, &market_id, &qty, &0i128, &5_000i128);
+        let (env, admin, _user, client, contract_id, market_id, token) =
+            setup_admin_market_with_deposit(1_000);
+        let signature = install_oracle_signature(&env, &contract_id, market_id, false);
+        advance_to_end_time(&env, &client, market_id);
+        client.resolve_market(
+            &Address::generate(&env),
+            &market_id,
+            &Outcome::No,
+            &None,
+            &signature,
+            &600,
+        );
+        client.set_paused(&admin, &true);
 
-        let pos = client.update_position(&user, &market_id, &(-qty), &0i128, &5_000i128);
-        assert_eq!(pos.yes_shares, 0);
-        assert_eq!(pos.locked_collateral, 0);
+        // A pause must not let the challenge window run out undisputed.
+        let disputer = Address::generate(&env);
+        StellarAssetClient::new(&env, &token).mint(&disputer, &250);
+        client.dispute_resolution(&disputer, &market_id, &250);
+        assert_eq!(client.get_market(&market_id).status, MarketStatus::Disputed);
     }
 
-    // ========== #333: Reconcile locked_collateral on deposit and withdraw ==========
-
-    /// #333: Depositing collateral must never increment locked_collateral.
-    /// locked_collateral is exclusively owned by update_position.
     #[test]
-    fn test_333_deposit_does_not_touch_locked_collateral() {
-        use crate::positions::STROOPS_PER_USDC;
+    fn test_dispute_blocks_finalization_until_admin_resolves() {
+        use crate::error::ContractError;
+        use soroban_sdk::token::{Client as TokenClient, StellarAssetClient};
 
-        let deposit = 50 * STROOPS_PER_USDC;
-        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+        let deposit = 1_000i128;
+        let (env, admin, user, client, contract_id, market_id, token) =
+            setup_admin_market_with_deposit(deposit);
+        client.update_position(&user, &market_id, &deposit, &0i128, &5_000i128);
+        let signature = install_oracle_signature(&env, &contract_id, market_id, false);
+        advance_to_end_time(&env, &client, market_id);
+        client.resolve_market(
+            &Address::generate(&env),
+            &market_id,
+            &Outcome::No,
+            &None,
+            &signature,
+            &600,
+        );
 
-        // A second deposit — the lock must stay zero because no shares are held.
-        let extra = 20 * STROOPS_PER_USDC;
-        use soroban_sdk::token::StellarAssetClient;
-        let stored_market = env.as_contract(&contract_id, || {
-            storage::get_market(&env, market_id).unwrap().unwrap()
-        });
-        StellarAssetClient::new(&env, &stored_market.collateral_token).mint(&user, &extra);
-        client.deposit_collateral(&user, &market_id, &extra);
+        let disputer = Address::generate(&env);
+        let bond = 250i128;
+        StellarAssetClient::new(&env, &token).mint(&disputer, &bond);
+        client.dispute_resolution(&disputer, &market_id, &bond);
+        assert!(emitted(&env, "resolution_disputed"));
+        assert_eq!(client.get_market(&market_id).status, MarketStatus::Disputed);
+        let dispute = client.get_dispute(&market_id).unwrap();
+        assert_eq!(
+            (dispute.disputer.clone(), dispute.bond),
+            (disputer.clone(), bond)
+        );
+        assert_eq!(TokenClient::new(&env, &token).balance(&disputer), 0);
 
-        let pos = env.as_contract(&contract_id, || {
-            storage::get_position(&env, market_id, &user).unwrap().unwrap()
-        });
-        assert_eq!(pos.locked_collateral, 0);
-        assert_eq!(pos.total_deposited, deposit + extra);
+        // The window passing does not finalize the disputed outcome, and the
+        // market can be neither disputed again nor canceled.
+        env.ledger().with_mut(|li| li.timestamp += 600);
+        assert_eq!(
+            client.try_finalize_resolution(&market_id),
+            Err(Ok(ContractError::MarketNotActive))
+        );
+        assert_eq!(
+            client.try_dispute_resolution(&disputer, &market_id, &bond),
+            Err(Ok(ContractError::MarketNotActive))
+        );
+        assert_eq!(
+            client.try_cancel_market(&admin, &market_id),
+            Err(Ok(ContractError::MarketNotActive))
+        );
+        assert_eq!(
+            client.try_settle_position(&user, &market_id),
+            Err(Ok(ContractError::MarketNotResolved))
+        );
+        assert_eq!(
+            client.try_resolve_dispute(&user, &market_id, &true, &true),
+            Err(Ok(ContractError::NotAdmin))
+        );
+
+        // The admin overturns the outcome and returns the bond.
+        client.resolve_dispute(&admin, &market_id, &true, &true);
+        assert!(emitted(&env, "dispute_resolved"));
+        let market = client.get_market(&market_id);
+        assert_eq!(market.status, MarketStatus::Resolved);
+        assert_eq!(market.result, Some(true));
+        assert_eq!(market.resolver, Some(admin.clone()));
+        assert_eq!(client.get_dispute(&market_id), None);
+        assert_eq!(TokenClient::new(&env, &token).balance(&disputer), bond);
+        assert_eq!(client.settle_position(&user, &market_id), deposit);
+        assert_eq!(
+            client.try_resolve_dispute(&admin, &market_id, &true, &true),
+            Err(Ok(ContractError::MarketNotDisputed))
+        );
     }
 
-    /// #333: Withdrawing unlocked collateral decrements total_deposited and
-    /// preserves locked_collateral (invariant: available = total - locked).
     #[test]
-    fn test_333_withdraw_decrements_total_deposited_preserves_locked() {
-        use crate::positions::STROOPS_PER_USDC;
-        use soroban_sdk::token::StellarAssetClient;
-
-        let deposit = 100 * STROOPS_PER_USDC;
-        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
-
-        // Buy 40 YES shares at 50% → lock = 20 USDC; available = 80 USDC.
-        let shares = 40 * STROOPS_PER_USDC;
-        client.update_position(&user, &market_id, &shares, &0i128, &5_000i128);
+    fn test_dispute_requires_open_challenge_window_and_slashed_bond_accrues() {
+        use crate::error::ContractError;
+        use soroban_sdk::token::{Client as TokenClient, StellarAssetClient};
 
-        let before = env.as_contract(&contract_id, || {
-            storage::get_position(&env, market_id, &user).unwrap().unwrap()
-        });
-        assert_eq!(before.locked_collateral, 20 * STROOPS_PER_USDC);
-        assert_eq!(before.total_deposited, deposit);
+        let (env, admin, _user, client, contract_id, market_id, token) =
+            setup_admin_market_with_deposit(1_000);
+        let disputer = Address::generate(&env);
+        let bond = 300i128;
+        StellarAssetClient::new(&env, &token).mint(&disputer, &bond);
 
-        let withdraw_amount = 30 * STROOPS_PER_USDC; // within available (80 USDC)
-        let stored_market = env.as_contract(&contract_id, || {
-            storage::get_market(&env, market_id).unwrap().unwrap()
-        });
-        StellarAssetClient::new(&env, &stored_market.collateral_token).mint(&contract_id, &(100 * STROOPS_PER_USDC));
-        client.withdraw_unused_collateral(&user, &market_id, &withdraw_amount);
+        // Nothing to dispute until an outcome is proposed.
+        assert_eq!(
+            client.try_dispute_resolution(&disputer, &market_id, &bond),
+            Err(Ok(ContractError::MarketNotActive))
+        );
+        assert_eq!(
+            client.try_resolve_dispute(&admin, &market_id, &true, &false),
+            Err(Ok(ContractError::MarketNotDisputed))
+        );
 
-        let after = env.as_contract(&contract_id, || {
-            storage::get_position(&env, market_id, &user).unwrap().unwrap()
-        });
-        assert_eq!(after.total_deposited, deposit - withdraw_amount);
-        assert_eq!(after.locked_collateral, before.locked_collateral); // unchanged
+        let signature = install_oracle_signature(&env, &contract_id, market_id, true);
+        advance_to_end_time(&env, &client, market_id);
+        client.resolve_market(
+            &Address::generate(&env),
+            &market_id,
+            &Outcome::Yes,
+            &None,
+            &signature,
+            &600,
+        );
+        assert_eq!(
+            client.try_dispute_resolution(&disputer, &market_id, &0),
+            Err(Ok(ContractError::InvalidQuantity))
+        );
+        env.ledger().with_mut(|li| li.timestamp += 600);
+        assert_eq!(
+            client.try_dispute_resolution(&disputer, &market_id, &bond),
+            Err(Ok(ContractError::ChallengeWindowClosed))
+        );
+        env.ledger().with_mut(|li| li.timestamp -= 1);
+        client.dispute_resolution(&disputer, &market_id, &bond);
+
+        // Upholding the outcome slashes the bond into the market's fees.
+        client.resolve_dispute(&admin, &market_id, &true, &false);
+        assert_eq!(client.get_market(&market_id).result, Some(true));
+        assert_eq!(TokenClient::new(&env, &token).balance(&disputer), 0);
+        assert_eq!(client.get_accrued_fees(&market_id), bond);
+        assert_eq!(client.withdraw_fees(&admin, &market_id), bond);
     }
 
-    /// #333: Attempting to withdraw locked collateral is rejected.
     #[test]
-    fn test_333_cannot_withdraw_locked_collateral() {
-        use crate::{error::ContractError, positions::STROOPS_PER_USDC};
-
-        let deposit = 100 * STROOPS_PER_USDC;
-        let (_env, user, client, _contract_id, market_id) = setup_funded_market(deposit);
+    fn test_resolve_market_rejects_overlong_challenge_window() {
+        use crate::validation::MAX_CHALLENGE_WINDOW_SECONDS;
 
-        // Buy 100 YES shares at 60% → lock = 60 USDC; available = 40 USDC.
-        let shares = 100 * STROOPS_PER_USDC;
-        client.update_position(&user, &market_id, &shares, &0i128, &6_000i128);
+        let (env, _user, client, contract_id, market_id) = setup_funded_market(1_000);
+        let signature = install_oracle_signature(&env, &contract_id, market_id, true);
 
-        // Try to withdraw 50 USDC (> available 40 USDC) → rejected.
-        let result = client.try_withdraw_unused_collateral(&user, &market_id, &(50 * STROOPS_PER_USDC));
-        assert_eq!(result, Err(Ok(ContractError::InsufficientCollateral)));
+        assert_eq!(
+            client.try_resolve_market(
+                &Address::generate(&env),
+                &market_id,
+                &Outcome::Yes,
+                &None,
+                &signature,
+                &(MAX_CHALLENGE_WINDOW_SECONDS + 1),
+            ),
+            Err(Ok(crate::error::ContractError::InvalidTimestamp))
+        );
+        assert_eq!(client.get_market(&market_id).status, MarketStatus::Active);
     }
 
-    // ========== #334: Single source of truth for share-collateral math ==========
+    // ========== invariant-checks wiring ==========
 
-    /// #334: locked_collateral in storage matches the value returned by the
-    /// canonical calculate_locked_collateral function.  This test documents the
-    /// single-source-of-truth contract: there is no duplication between
-    /// positions.rs and lib.rs.
+    /// Entry points run the invariant checks after succeeding: a trade that
+    /// is fine on its own still fails once the market's tracked collateral
+    /// has been corrupted behind the contract's back.
     #[test]
-    fn test_334_locked_collateral_matches_canonical_formula() {
-        use crate::positions::{calculate_locked_collateral, STROOPS_PER_USDC};
+    #[should_panic(expected = "Error(WasmVm, InvalidAction)")]
+    fn test_entrypoint_runs_invariant_checks() {
+        use crate::positions::STROOPS_PER_USDC;
 
-        let deposit = 100 * STROOPS_PER_USDC;
-        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+        let (env, user, client, contract_id, market_id) =
+            setup_funded_market(10 * STROOPS_PER_USDC);
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().set(
+                &storage::StorageKey::MarketCollateral(market_id),
+                &(1_000 * STROOPS_PER_USDC),
+            );
+        });
 
-        let yes = 80 * STROOPS_PER_USDC;
-        let no = 20 * STROOPS_PER_USDC;
-        let price_bps = 6_000i128;
+        client.update_position(&user, &market_id, &STROOPS_PER_USDC, &0i128, &5_000i128);
+    }
 
-        // Buy YES then NO to establish a mixed position.
-        client.update_position(&user, &market_id, &yes, &0i128, &price_bps);
-        let pos = client.update_position(&user, &market_id, &0i128, &no, &price_bps);
+    // ========== settle_all tests ==========
 
-        let expected = calculate_locked_collateral(yes, no, price_bps);
-        assert_eq!(pos.locked_collateral, expected);
+    #[test]
+    fn test_settle_all_settles_resolved_markets_and_skips_unresolved() {
+        use crate::positions::STROOPS_PER_USDC;
+        use soroban_sdk::token::TokenClient;
+
+        let stake = 100 * STROOPS_PER_USDC;
+        let (env, user, client, contract_id, first, collateral_token) =
+            setup_market_with_funded_user(3 * stake);
+        let admin = env.as_contract(&contract_id, || storage::get_admin(&env).unwrap());
+
+        let mut market_ids = std::vec![first];
+        for n in 0..2 {
+            market_ids.push(client.initialize_market(
+                &admin,
+                &numbered_question(&env, n),
+                &(env.ledger().timestamp() + 86400),
+                &BytesN::from_array(&env, &[1u8; 32]),
+                &collateral_token,
+                &test_metadata(&env),
+                &test_source_hash(&env),
+                &None,
+                &None,
+                &0,
+            ));
+        }
+        for market_id in &market_ids {
+            client.deposit_collateral(&user, market_id, &stake, &None);
+            client.update_position(&user, market_id, &stake, &0i128, &5_000i128);
+        }
+
+        // First market resolves YES (win), second NO (loss), third stays open.
+        resolve_funded_market(&env, &client, &contract_id, market_ids[0], true);
+        resolve_funded_market(&env, &client, &contract_id, market_ids[1], false);
+
+        let token = TokenClient::new(&env, &collateral_token);
+        let before = token.balance(&user);
+        let settled = client.settle_all(&user);
+
+        assert_eq!(settled.len(), 2);
+        assert_eq!(settled.get(0).unwrap(), (market_ids[0], stake));
+        assert_eq!(settled.get(1).unwrap(), (market_ids[1], 0));
+        assert_eq!(token.balance(&user) - before, stake);
 
-        // The stored value must also match.
-        let stored = env.as_contract(&contract_id, || {
-            storage::get_position(&env, market_id, &user).unwrap().unwrap()
+        assert_eq!(
+            client.settlement_status(&market_ids[0], &user),
+            (Some(true), true)
+        );
+        assert_eq!(
+            client.settlement_status(&market_ids[1], &user),
+            (Some(false), true)
+        );
+        assert_eq!(
+            client.settlement_status(&market_ids[2], &user),
+            (None, false)
+        );
+
+        // Only the unresolved market is left for settle_all to visit, while
+        // the user's market list keeps the settled ones as history.
+        let remaining = env.as_contract(&contract_id, || {
+            storage::get_user_pending_markets(&env, &user)
         });
-        assert_eq!(stored.locked_collateral, expected);
+        assert_eq!(remaining, soroban_sdk::vec![&env, market_ids[2]]);
+        assert!(client.settle_all(&user).is_empty());
+        assert_eq!(
+            client.get_user_markets(&user),
+            soroban_sdk::vec![&env, market_ids[0], market_ids[1], market_ids[2]]
+        );
     }
 
     // ========== #335: Emit position_updated on every share change ==========
 
-    /// #335: settle_position emits a position_updated_event before the
-    /// position_settled_event, capturing the final share state.
+    /// #335: settle_position emits a position_updated event before the
+    /// position_settled event, capturing the final share state.
     #[test]
     fn test_335_settle_position_emits_position_updated() {
         use crate::positions::STROOPS_PER_USDC;
@@ -1633,8 +6224,15 @@ mod test {
             market.oracle_pubkey = oracle_pubkey;
             storage::set_market(&env, market_id, &market).unwrap();
         });
-        let market_id_str = String::from_str(&env, "1");
-        client.resolve_market(&market_id_str, &true, &signature);
+        advance_to_end_time(&env, &client, 1);
+        client.resolve_market(
+            &Address::generate(&env),
+            &1,
+            &Outcome::Yes,
+            &None,
+            &signature,
+            &0,
+        );
 
         // Make sure the contract holds enough tokens to pay out.
         let stored_market = env.as_contract(&contract_id, || {
@@ -1648,28 +6246,28 @@ mod test {
         client.settle_position(&user, &market_id);
 
         let events = env.events().all();
-        // Expect both position_updated_event and position_settled_event.
-        let names: Vec<Symbol> = events
+        // Expect both position_updated and position_settled.
+        let names: std::vec::Vec<Symbol> = events
             .iter()
-            .map(|e| e.1.get::<soroban_sdk::Val>(0).unwrap().into_val(&env))
+            .map(|e| e.1.get(0).unwrap().into_val(&env))
             .collect();
 
         assert!(
-            names.contains(&Symbol::new(&env, "position_updated_event")),
-            "position_updated_event missing from settle_position events"
+            names.contains(&Symbol::new(&env, "position_updated")),
+            "position_updated missing from settle_position events"
         );
         assert!(
-            names.contains(&Symbol::new(&env, "position_settled_event")),
-            "position_settled_event missing from settle_position events"
+            names.contains(&Symbol::new(&env, "position_settled")),
+            "position_settled missing from settle_position events"
         );
 
         // position_updated must appear before position_settled.
-        let updated_idx = names.iter().position(|s| *s == Symbol::new(&env, "position_updated_event")).unwrap();
-        let settled_idx = names.iter().position(|s| *s == Symbol::new(&env, "position_settled_event")).unwrap();
-        assert!(updated_idx < settled_idx, "position_updated_event must precede position_settled_event");
+        let updated_idx = names.iter().position(|s| *s == Symbol::new(&env, "position_updated")).unwrap();
+        let settled_idx = names.iter().position(|s| *s == Symbol::new(&env, "position_settled")).unwrap();
+        assert!(updated_idx < settled_idx, "position_updated must precede position_settled");
     }
 
-    /// #335: withdraw_canceled_collateral emits a position_updated_event after
+    /// #335: withdraw_canceled_collateral emits a position_updated event after
     /// zeroing the user's locked_collateral and total_deposited.
     #[test]
     fn test_335_withdraw_canceled_collateral_emits_position_updated() {
@@ -1685,14 +6283,200 @@ mod test {
         client.withdraw_canceled_collateral(&user, &market_id);
 
         let events = env.events().all();
-        let names: Vec<Symbol> = events
+        let names: std::vec::Vec<Symbol> = events
             .iter()
-            .map(|e| e.1.get::<soroban_sdk::Val>(0).unwrap().into_val(&env))
+            .map(|e| e.1.get(0).unwrap().into_val(&env))
             .collect();
 
         assert!(
-            names.contains(&Symbol::new(&env, "position_updated_event")),
-            "position_updated_event missing after withdraw_canceled_collateral"
+            names.contains(&Symbol::new(&env, "position_updated")),
+            "position_updated missing after withdraw_canceled_collateral"
+        );
+    }
+
+    // ========== deposit_and_buy tests ==========
+
+    /// deposit_and_buy leaves the same position and balances as calling
+    /// deposit_collateral followed by buy_shares.
+    #[test]
+    fn test_deposit_and_buy_matches_separate_calls() {
+        use crate::positions::STROOPS_PER_USDC;
+        use soroban_sdk::token::Client as TokenClient;
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let shares = 100 * STROOPS_PER_USDC;
+
+        let (env_a, user_a, client_a, contract_a, market_a, token_a) =
+            setup_market_with_funded_user(deposit);
+        client_a.deposit_collateral(&user_a, &market_a, &deposit, &None);
+        client_a.buy_shares(&user_a, &market_a, &true, &shares);
+
+        let (env_b, user_b, client_b, contract_b, market_b, token_b) =
+            setup_market_with_funded_user(deposit);
+        let position =
+            client_b.deposit_and_buy(&user_b, &market_b, &deposit, &true, &shares, &10_000i128);
+
+        let separate = client_a.get_position(&market_a, &user_a);
+        assert_eq!(position.yes_shares, separate.yes_shares);
+        assert_eq!(position.no_shares, separate.no_shares);
+        assert_eq!(position.locked_collateral, separate.locked_collateral);
+        assert_eq!(position.total_deposited, separate.total_deposited);
+        assert_eq!(
+            TokenClient::new(&env_b, &token_b).balance(&contract_b),
+            TokenClient::new(&env_a, &token_a).balance(&contract_a)
+        );
+        assert_eq!(TokenClient::new(&env_b, &token_b).balance(&user_b), 0);
+    }
+
+    /// deposit_and_buy publishes both the deposit and the trade event.
+    #[test]
+    fn test_deposit_and_buy_emits_deposit_and_trade_events() {
+        use crate::positions::STROOPS_PER_USDC;
+        use soroban_sdk::{IntoVal, Symbol};
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, user, client, _contract_id, market_id, _token) =
+            setup_market_with_funded_user(deposit);
+
+        client.deposit_and_buy(
+            &user,
+            &market_id,
+            &deposit,
+            &false,
+            &(50 * STROOPS_PER_USDC),
+            &10_000i128,
+        );
+
+        let names: std::vec::Vec<Symbol> = env
+            .events()
+            .all()
+            .iter()
+            .map(|e| e.1.get(0).unwrap().into_val(&env))
+            .collect();
+        assert!(names.contains(&Symbol::new(&env, "collateral_deposited")));
+        assert!(names.contains(&Symbol::new(&env, "trade_executed")));
+    }
+
+    /// A filled side price above max_price reverts the call, collateral
+    /// transfer included.
+    #[test]
+    fn test_deposit_and_buy_reverts_on_slippage() {
+        use crate::{error::ContractError, positions::STROOPS_PER_USDC};
+        use soroban_sdk::token::Client as TokenClient;
+
+        let deposit = 100 * STROOPS_PER_USDC;
+        let (env, user, client, contract_id, market_id, token) =
+            setup_market_with_funded_user(deposit);
+
+        // A fresh curve quotes NO at 50%, but the buy moves the curve and
+        // fills above that, so a 5_000 bps bound is exceeded.
+        let market = client.get_market(&market_id);
+        assert_eq!(
+            crate::pricing::current_price(market.yes_reserve, market.no_reserve),
+            5_000
+        );
+        let result = client.try_deposit_and_buy(
+            &user,
+            &market_id,
+            &deposit,
+            &false,
+            &(50 * STROOPS_PER_USDC),
+            &5_000i128,
+        );
+        assert_eq!(result, Err(Ok(ContractError::SlippageExceeded)));
+        let reverted = client.get_market(&market_id);
+        assert_eq!(
+            (reverted.yes_reserve, reverted.no_reserve),
+            (market.yes_reserve, market.no_reserve)
+        );
+
+        assert_eq!(TokenClient::new(&env, &token).balance(&user), deposit);
+        assert_eq!(TokenClient::new(&env, &token).balance(&contract_id), 0);
+        assert_eq!(
+            client.try_get_position(&market_id, &user),
+            Err(Ok(ContractError::NoPositionFound))
+        );
+    }
+
+    /// When the buy step fails the deposit is rolled back with it.
+    #[test]
+    fn test_deposit_and_buy_reverts_deposit_when_buy_fails() {
+        use crate::{error::ContractError, positions::STROOPS_PER_USDC};
+        use soroban_sdk::token::Client as TokenClient;
+
+        let deposit = 10 * STROOPS_PER_USDC;
+        let (env, user, client, contract_id, market_id, token) =
+            setup_market_with_funded_user(deposit);
+
+        // 100 YES at about 50% needs about 50 USDC locked; only 10 USDC is
+        // deposited.
+        let result = client.try_deposit_and_buy(
+            &user,
+            &market_id,
+            &deposit,
+            &true,
+            &(100 * STROOPS_PER_USDC),
+            &10_000i128,
+        );
+        assert_eq!(result, Err(Ok(ContractError::InsufficientCollateral)));
+
+        assert_eq!(TokenClient::new(&env, &token).balance(&user), deposit);
+        assert_eq!(TokenClient::new(&env, &token).balance(&contract_id), 0);
+        assert_eq!(
+            client.try_get_position(&market_id, &user),
+            Err(Ok(ContractError::NoPositionFound))
+        );
+    }
+
+    #[test]
+    fn test_get_market_returns_stored_market() {
+        use crate::error::ContractError;
+
+        let (env, _user, client, contract_id, market_id, collateral_token) =
+            setup_market_with_funded_user(0);
+
+        let market = client.get_market(&market_id);
+        assert_eq!(
+            market,
+            get_market_from_storage(&env, &contract_id, market_id)
+        );
+        assert_eq!(market.collateral_token, collateral_token);
+        assert_eq!(market.status, MarketStatus::Active);
+
+        assert_eq!(
+            client.try_get_market(&(market_id + 1)),
+            Err(Ok(ContractError::MarketNotFound))
+        );
+    }
+
+    // ========== Storage TTL tests ==========
+
+    #[test]
+    fn test_market_and_position_survive_past_default_ttl() {
+        use crate::storage::{StorageKey, TTL_THRESHOLD};
+        use soroban_sdk::testutils::storage::Persistent as _;
+
+        let deposit = 100_000_000i128;
+        let (env, user, client, contract_id, market_id) = setup_funded_market(deposit);
+
+        env.as_contract(&contract_id, || {
+            let persistent = env.storage().persistent();
+            assert!(persistent.get_ttl(&StorageKey::Market(market_id)) >= TTL_THRESHOLD);
+            assert!(
+                persistent.get_ttl(&StorageKey::Position(market_id, user.clone())) >= TTL_THRESHOLD
+            );
+        });
+
+        // Move well past the minimum persistent TTL new entries start with;
+        // unextended entries would be archived by now.
+        let min_ttl = env.ledger().get().min_persistent_entry_ttl;
+        env.ledger()
+            .with_mut(|li| li.sequence_number += min_ttl * 10);
+
+        assert_eq!(client.get_market(&market_id).id, market_id);
+        assert_eq!(
+            client.get_position(&market_id, &user).total_deposited,
+            deposit
         );
     }
 }
@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, Address, BytesN, String};
+use soroban_sdk::{contracttype, Address, BytesN, String, Symbol, Vec};
 
 /// Represents the possible states of a prediction market.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -7,6 +7,78 @@ pub enum MarketStatus {
     Active,
     Resolved,
     Canceled,
+    /// An outcome has been accepted but can still be challenged until the
+    /// market's `challenge_deadline`; trading and settlement are closed.
+    PendingResolution,
+    /// A bonded dispute was raised against the proposed outcome during the
+    /// challenge window. Finalization is blocked until the admin settles the
+    /// dispute with `resolve_dispute`.
+    Disputed,
+}
+
+/// An oracle-signed outcome for a binary market.
+///
+/// `Invalid` means the question could not be answered (for example the
+/// underlying event was called off); the market resolves with no winning
+/// side and every position is refunded its deposit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Outcome {
+    No,
+    Yes,
+    Invalid,
+}
+
+impl Outcome {
+    /// Byte appended to the oracle preimage: `0x00` = NO, `0x01` = YES,
+    /// `0x02` = INVALID. YES and NO match the bytes a `bool` outcome encodes.
+    pub fn to_byte(self) -> u8 {
+        match self {
+            Outcome::No => 0,
+            Outcome::Yes => 1,
+            Outcome::Invalid => 2,
+        }
+    }
+
+    /// The winning side, or `None` for `Invalid`.
+    pub fn winner(self) -> Option<bool> {
+        match self {
+            Outcome::No => Some(false),
+            Outcome::Yes => Some(true),
+            Outcome::Invalid => None,
+        }
+    }
+}
+
+impl From<bool> for Outcome {
+    fn from(outcome: bool) -> Self {
+        if outcome {
+            Outcome::Yes
+        } else {
+            Outcome::No
+        }
+    }
+}
+
+/// A market's final outcome as reported by `get_resolution`.
+///
+/// Unlike `Market::result`, a NO win and a market that has not resolved yet
+/// are distinct values.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Resolution {
+    /// No final outcome yet, including while a proposed outcome can still be
+    /// challenged or is disputed.
+    Unresolved,
+    Yes,
+    No,
+    /// Canceled; every position is refunded its deposit.
+    Canceled,
+    /// Resolved without a winning side, e.g. to [`Outcome::Invalid`]; every
+    /// position is refunded its deposit.
+    Invalid,
+    /// A scalar market settled at this value.
+    Scalar(i128),
 }
 
 /// Represents the oracle adapter type used for market resolution.
@@ -21,8 +93,69 @@ pub enum AdapterType {
     Pyth,
 }
 
+/// Cheap contract-wide counters returned by `health` for monitoring.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ContractHealth {
+    pub paused: bool,
+    /// Markets ever created.
+    pub total_markets: u32,
+    /// Markets currently `Active`.
+    pub active_markets: u32,
+    /// Positions in resolved markets that have not been settled yet.
+    pub resolved_unsettled_count: u32,
+    pub treasury_set: bool,
+    /// Storage layout version written at initialization; 0 if never set.
+    pub schema_version: u32,
+}
+
+/// Market-level aggregates returned by `get_market_stats`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct MarketStats {
+    /// Collateral deposited in the market's unsettled positions.
+    pub total_collateral: i128,
+    pub total_yes_shares: i128,
+    pub total_no_shares: i128,
+    pub status: MarketStatus,
+    pub result: Option<bool>,
+}
+
+/// How a market's resolution signatures are checked.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum OracleScheme {
+    /// One signature from the market's own oracle key, via `resolve_market`.
+    Single,
+    /// A quorum of the global threshold signers, via `resolve_market_threshold`.
+    Threshold,
+    /// A quorum of the market's own oracle set, via `resolve_market_multi`.
+    MarketSet,
+}
+
+/// A market's own oracles and how many of them must sign its outcome.
+///
+/// Stored beside the market under its own key rather than on [`Market`].
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct OracleSet {
+    pub keys: Vec<BytesN<32>>,
+    /// Minimum number of distinct keys that must sign the same outcome.
+    pub threshold: u32,
+}
+
+/// Oracle keys and signature count needed to resolve a market.
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct OracleConfig {
+    pub keys: Vec<BytesN<32>>,
+    /// Minimum number of valid signatures from `keys`.
+    pub threshold: u32,
+    pub scheme: OracleScheme,
+}
+
 /// Core structure containing all relevant information for a Market.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[contracttype]
 pub struct Market {
     pub id: u32,
@@ -48,6 +181,134 @@ pub struct Market {
     /// Flag indicating whether the market is closed to new deposits.
     /// When true, users cannot deposit new collateral, but can still withdraw and trade.
     pub closed_to_deposits: bool,
+    /// Hash of the agreed resolution source, committed by the creator at
+    /// creation and immutable thereafter. Bound into the oracle message so a
+    /// resolution is tied to this source; not used in payout math.
+    pub source_hash: BytesN<32>,
+    /// Seconds after `end_time` during which the oracle may still resolve the
+    /// market. `None` leaves the resolution window unbounded.
+    pub resolution_grace: Option<u64>,
+    /// Protocol fee rate in basis points for this market, overriding the
+    /// global `FeeRateBps` when set (e.g. promotional or high-risk markets).
+    pub fee_rate_bps: Option<i128>,
+    /// Seconds a user must wait after their last deposit before withdrawing,
+    /// overriding `DEFAULT_WITHDRAW_COOLDOWN_SECONDS` when set. Zero disables
+    /// the cooldown for this market.
+    pub withdraw_cooldown: Option<u64>,
+    /// End of the challenge window while the market is `PendingResolution`.
+    /// The proposed outcome is held in `result` until `finalize_resolution`.
+    pub challenge_deadline: Option<u64>,
+    /// Fee in basis points charged on every share bought, at most
+    /// `MAX_TRADING_FEE_BPS`. Accrues to the market until the admin calls
+    /// `withdraw_fees`.
+    pub trading_fee_bps: u32,
+    /// `decimals()` of `collateral_token`, read at creation. Shares and
+    /// collateral amounts are both in the token's base units, so payout math
+    /// needs no scaling; this tells clients how to display them.
+    pub collateral_decimals: u32,
+    /// YES shares held across all positions; kept in step by `update_position`.
+    pub total_yes_shares: i128,
+    /// NO shares held across all positions; kept in step by `update_position`.
+    pub total_no_shares: i128,
+    /// YES side of the constant-product curve `buy_shares` and `sell_shares`
+    /// price against; see `pricing::current_price`.
+    pub yes_reserve: i128,
+    /// NO side of the constant-product curve.
+    pub no_reserve: i128,
+    /// Category for discovery, e.g. `crypto` or `sports`. Set at creation and
+    /// changed with `set_market_category`.
+    pub category: Symbol,
+    /// Human-readable description of where the outcome comes from (fewer
+    /// than `MAX_RESOLUTION_SOURCE_LEN` characters). `source_hash` commits to
+    /// the same source for oracle signatures.
+    pub resolution_source: String,
+    /// Whether the market resolves to YES/NO or to a value in a band.
+    pub kind: MarketKind,
+    /// Oracle-signed value a scalar market resolved to; `None` for binary
+    /// markets and before resolution.
+    pub settled_value: Option<i128>,
+    /// Smallest accepted deposit, in collateral base units; zero for no
+    /// minimum.
+    pub min_deposit: i128,
+    /// Largest net position (`|yes_shares - no_shares|`) a buy may leave a
+    /// user with; zero for no cap.
+    pub max_position: i128,
+    /// Most distinct users that may hold a position, bounding the work of
+    /// batch settlement and invariant checks; zero for no cap.
+    pub max_participants: u32,
+}
+
+/// A bonded challenge to a market's proposed outcome.
+///
+/// Stored beside the market under its own key while the market is
+/// [`MarketStatus::Disputed`].
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct Dispute {
+    pub disputer: Address,
+    /// Bond posted by the disputer, in the market's collateral token.
+    pub bond: i128,
+    pub disputed_at: u64,
+}
+
+/// An outcome proposed by the admin for a market whose oracle has failed.
+///
+/// Stored beside the market until `admin_execute_resolution` applies it
+/// once `executable_at` has been reached.
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct AdminResolutionProposal {
+    pub admin: Address,
+    pub outcome: bool,
+    pub proposed_at: u64,
+    pub executable_at: u64,
+}
+
+/// Inclusive band a scalar market's settled value is measured against.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ScalarBounds {
+    pub lower: i128,
+    pub upper: i128,
+}
+
+/// What a market resolves to.
+///
+/// Scalar markets reuse the binary share model: YES shares are long the
+/// settled value and NO shares short it, so a YES/NO pair always pays one
+/// unit between them. See `settlement::calculate_scalar_payout`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum MarketKind {
+    /// Resolves to YES or NO with `resolve_market`.
+    Binary,
+    /// Resolves to a number with `resolve_scalar_market`; payouts scale
+    /// linearly across the bounds.
+    Scalar(ScalarBounds),
+}
+
+/// Metadata and per-market settings supplied when a market is created.
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct MarketMetadata {
+    /// Optional off-chain metadata URI, published in `MarketCreated`.
+    pub uri: Option<String>,
+    /// Category for discovery, e.g. `crypto` or `sports`.
+    pub category: Symbol,
+    /// Where the outcome comes from, e.g. a data provider's URL.
+    pub resolution_source: String,
+    /// Binary, or scalar with the band its value settles in.
+    pub kind: MarketKind,
+    /// Smallest accepted deposit; zero for no minimum.
+    pub min_deposit: i128,
+    /// Largest net position a buy may leave a user with; zero for no cap.
+    pub max_position: i128,
+    /// Most distinct users that may deposit into the market; zero for no cap.
+    pub max_participants: u32,
+    /// Derive the market ID from `keccak256(creator || question || end_time)`
+    /// instead of taking the next counter value; see
+    /// `storage::derive_market_id`.
+    pub derive_id: bool,
 }
 
 /// Tracks the position and shares of a specific user in a market.
@@ -63,12 +324,29 @@ pub struct Position {
     /// Total collateral deposited by user in this market (never decreased except by withdraw).
     pub total_deposited: i128,
     pub is_settled: bool,
+    /// Ledger timestamp at which the position was first stored.
+    pub created_at: u64,
+    /// Ledger timestamp of the latest change to the position; set by
+    /// `storage::set_position` on every write.
+    pub updated_at: u64,
+}
+
+/// An oracle's sealed resolution, stored by `commit_resolution` until revealed.
+#[derive(Clone, Debug, PartialEq)]
+#[contracttype]
+pub struct ResolutionCommitment {
+    /// Address that committed; only it may reveal.
+    pub resolver: Address,
+    /// `keccak256(outcome_byte || salt || signature)`.
+    pub commitment: BytesN<32>,
+    /// Ledger sequence in which the commitment was stored.
+    pub ledger: u32,
 }
 
 impl Position {
-    /// Create an empty position for a user in a market.
+    /// Create an empty position for a user in a market, created at `now`.
     /// Used when a position has not been previously recorded in storage.
-    pub fn new_empty(market_id: u32, user: Address) -> Self {
+    pub fn new_empty(market_id: u32, user: Address, now: u64) -> Self {
         Position {
             market_id,
             user,
@@ -77,6 +355,8 @@ impl Position {
             locked_collateral: 0,
             total_deposited: 0,
             is_settled: false,
+            created_at: now,
+            updated_at: now,
         }
     }
 }
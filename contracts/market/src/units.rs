@@ -0,0 +1,107 @@
+//! Conversion between token base units and display amounts.
+//!
+//! Collateral amounts are held in the token's base units (stroops for a
+//! 7-decimal token), with the token's `decimals()` stored on each market as
+//! `collateral_decimals`. These helpers split an amount into its whole and
+//! fractional parts and back, so clients do not each re-implement the
+//! `10^decimals` scaling. They are pure and also exposed from the contract.
+
+/// `10^decimals`, or `None` once it no longer fits in an `i128`
+/// (`decimals > 38`).
+fn scale(decimals: u32) -> Option<i128> {
+    10i128.checked_pow(decimals)
+}
+
+/// Split `amount` base units into `(whole, fraction)` for a token with
+/// `decimals` decimals.
+///
+/// `fraction` is in base units, i.e. the digits after the decimal point:
+/// `to_display(12_345_678, 7)` is `(1, 2_345_678)`, which displays as
+/// `1.2345678`. Both parts take the sign of a negative `amount`. With more
+/// than 38 decimals every `i128` is below one whole token, so the whole
+/// amount is returned as the fraction.
+///
+/// # Example
+/// ```ignore
+/// assert_eq!(to_display(1_500_000, 6), (1, 500_000));
+/// assert_eq!(to_display(-5, 7), (0, -5));
+/// ```
+pub fn to_display(amount: i128, decimals: u32) -> (i128, i128) {
+    match scale(decimals) {
+        Some(scale) => (amount / scale, amount % scale),
+        None => (0, amount),
+    }
+}
+
+/// Combine a whole and fractional part back into base units; the inverse of
+/// [`to_display`].
+///
+/// `frac` is in base units, as returned by [`to_display`]. Results that do
+/// not fit in an `i128` saturate at `i128::MIN` / `i128::MAX`.
+///
+/// # Example
+/// ```ignore
+/// assert_eq!(from_display(1, 500_000, 6), 1_500_000);
+/// ```
+pub fn from_display(whole: i128, frac: i128, decimals: u32) -> i128 {
+    let whole_units = match scale(decimals) {
+        Some(scale) => whole.saturating_mul(scale),
+        None if whole == 0 => 0,
+        None if whole > 0 => i128::MAX,
+        None => i128::MIN,
+    };
+    whole_units.saturating_add(frac)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_display_seven_decimals() {
+        assert_eq!(to_display(12_345_678, 7), (1, 2_345_678));
+        assert_eq!(to_display(10_000_000, 7), (1, 0));
+        assert_eq!(to_display(9_999_999, 7), (0, 9_999_999));
+        assert_eq!(to_display(-12_345_678, 7), (-1, -2_345_678));
+    }
+
+    #[test]
+    fn test_to_display_six_decimals() {
+        assert_eq!(to_display(1_500_000, 6), (1, 500_000));
+        assert_eq!(to_display(1, 6), (0, 1));
+        assert_eq!(to_display(-1_000_001, 6), (-1, -1));
+    }
+
+    #[test]
+    fn test_to_display_zero_and_no_decimals() {
+        assert_eq!(to_display(0, 7), (0, 0));
+        assert_eq!(to_display(0, 0), (0, 0));
+        assert_eq!(to_display(42, 0), (42, 0));
+    }
+
+    #[test]
+    fn test_round_trip_across_decimals() {
+        for decimals in [0, 6, 7, 18, 38, 39, u32::MAX] {
+            for amount in [0, 1, -1, 12_345_678, i128::MAX, i128::MIN] {
+                let (whole, frac) = to_display(amount, decimals);
+                assert_eq!(from_display(whole, frac, decimals), amount);
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_amount_splits_exactly() {
+        let (whole, frac) = to_display(i128::MAX, 7);
+        assert_eq!(whole, i128::MAX / 10_000_000);
+        assert_eq!(frac, i128::MAX % 10_000_000);
+        assert_eq!(to_display(i128::MAX, 39), (0, i128::MAX));
+    }
+
+    #[test]
+    fn test_from_display_saturates() {
+        assert_eq!(from_display(i128::MAX, 0, 7), i128::MAX);
+        assert_eq!(from_display(i128::MIN, 0, 6), i128::MIN);
+        assert_eq!(from_display(1, 0, 39), i128::MAX);
+        assert_eq!(from_display(0, 5, 39), 5);
+    }
+}
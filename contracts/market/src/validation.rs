@@ -1,10 +1,184 @@
 use crate::error::ContractError;
-use crate::types::MarketStatus;
-use soroban_sdk::{Env, String};
+use crate::positions::calculate_net_position;
+use crate::types::{
+    Market, MarketKind, MarketStatus, OracleSet, Outcome, Position, ResolutionCommitment,
+};
+use soroban_sdk::{token, Address, BytesN, Env, Executable, String};
 
 /// Minimum collateral deposit in stroops (1 USDC = 10_000_000 stroops).
 pub const MIN_DEPOSIT_AMOUNT: i128 = 10_000_000;
 
+/// Maximum number of items any paginated view returns in a single call.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Maximum number of users a batch settlement call accepts.
+pub const MAX_SETTLE_BATCH_SIZE: u32 = 100;
+
+/// Maximum number of markets `initialize_markets_batch` creates in one call.
+pub const MAX_MARKET_BATCH_SIZE: u32 = 20;
+
+/// Maximum number of index entries a filtering view inspects in a single call.
+pub const MAX_SCAN_SIZE: u32 = 500;
+
+/// Clamp a `(start, limit)` page request to `[start, end)` indices within `total`.
+///
+/// `limit` is capped at [`MAX_PAGE_SIZE`] so an oversized request still
+/// returns a bounded page instead of exhausting the view's resource budget.
+/// A `start` beyond `total` yields an empty range.
+pub fn page_range(start: u32, limit: u32, total: u32) -> (u32, u32) {
+    let start = start.min(total);
+    let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(total);
+    (start, end)
+}
+
+/// Shortest per-market resolution grace accepted at creation.
+pub const MIN_RESOLUTION_GRACE_SECONDS: u64 = 5 * 60;
+
+/// Longest per-market resolution grace accepted at creation.
+pub const MAX_RESOLUTION_GRACE_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+/// Seconds after `end_time` past which anyone may cancel, via
+/// `expire_market`, a market that sets no resolution grace and that nobody
+/// resolved.
+pub const RESOLUTION_TIMEOUT_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+/// Validates an optional per-market resolution grace override.
+pub fn validate_resolution_grace(grace: Option<u64>) -> Result<(), ContractError> {
+    match grace {
+        Some(secs)
+            if !(MIN_RESOLUTION_GRACE_SECONDS..=MAX_RESOLUTION_GRACE_SECONDS).contains(&secs) =>
+        {
+            Err(ContractError::InvalidResolutionGrace)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Last timestamp at which `market` may be resolved: `end_time` plus the
+/// market's resolution grace, or `None` when it sets no grace and so has no
+/// resolution window.
+pub fn resolution_deadline(market: &Market) -> Option<u64> {
+    market
+        .resolution_grace
+        .map(|grace| market.end_time.saturating_add(grace))
+}
+
+/// First timestamp after which `expire_market` may cancel `market`: its
+/// resolution deadline, once it can no longer be resolved, or
+/// [`RESOLUTION_TIMEOUT_SECONDS`] after `end_time` when it has no window.
+pub fn expiry_threshold(market: &Market) -> u64 {
+    resolution_deadline(market)
+        .unwrap_or_else(|| market.end_time.saturating_add(RESOLUTION_TIMEOUT_SECONDS))
+}
+
+/// Rejects resolution while the market is still open, before its `end_time`.
+pub fn require_market_ended(env: &Env, market: &Market) -> Result<(), ContractError> {
+    if env.ledger().timestamp() < market.end_time {
+        return Err(ContractError::MarketNotEnded);
+    }
+    Ok(())
+}
+
+/// Rejects resolution once the market's resolution window has closed.
+/// Markets without a resolution grace can be resolved at any time.
+pub fn require_resolution_window_open(env: &Env, market: &Market) -> Result<(), ContractError> {
+    match resolution_deadline(market) {
+        Some(deadline) if env.ledger().timestamp() > deadline => {
+            Err(ContractError::ResolutionWindowClosed)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Seconds a user must wait after their last deposit before withdrawing, unless
+/// the market overrides it (issue #413).
+pub const DEFAULT_WITHDRAW_COOLDOWN_SECONDS: u64 = 3_600;
+
+/// Delay between `admin_propose_resolution` and the earliest
+/// `admin_execute_resolution`, giving users time to react to a manual outcome.
+pub const ADMIN_RESOLUTION_DELAY_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+/// Longest per-market withdraw cooldown an admin may set.
+pub const MAX_WITHDRAW_COOLDOWN_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+/// Largest oracle set a market may be assigned.
+pub const MAX_ORACLE_SET_SIZE: u32 = 20;
+
+/// Rejects an all-zero oracle key, which can never produce a valid Ed25519
+/// signature and would leave the market permanently unresolvable.
+///
+/// # Errors
+/// - [`ContractError::InvalidSignature`] – `oracle_pubkey` is all zeros.
+pub fn validate_oracle_pubkey(env: &Env, oracle_pubkey: &BytesN<32>) -> Result<(), ContractError> {
+    if *oracle_pubkey == BytesN::from_array(env, &[0u8; 32]) {
+        return Err(ContractError::InvalidSignature);
+    }
+    Ok(())
+}
+
+/// Validates a market oracle set: 1..=[`MAX_ORACLE_SET_SIZE`] distinct keys
+/// and a threshold between 1 and the number of keys.
+///
+/// # Errors
+/// - [`ContractError::InvalidOracleSet`] – bad size or threshold.
+/// - [`ContractError::DuplicateOracleSigner`] – a key is listed twice.
+pub fn validate_oracle_set(set: &OracleSet) -> Result<(), ContractError> {
+    let len = set.keys.len();
+    if len == 0 || len > MAX_ORACLE_SET_SIZE || set.threshold == 0 || set.threshold > len {
+        return Err(ContractError::InvalidOracleSet);
+    }
+    for i in 1..len {
+        let key = set.keys.get(i).unwrap();
+        if set.keys.slice(..i).contains(&key) {
+            return Err(ContractError::DuplicateOracleSigner);
+        }
+    }
+    Ok(())
+}
+
+/// Longest challenge window a resolver may request at resolution time.
+pub const MAX_CHALLENGE_WINDOW_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+/// Validates the challenge window passed to `resolve_market`; zero resolves
+/// immediately.
+pub fn validate_challenge_window(window: u64) -> Result<(), ContractError> {
+    if window > MAX_CHALLENGE_WINDOW_SECONDS {
+        return Err(ContractError::InvalidTimestamp);
+    }
+    Ok(())
+}
+
+/// Validates an optional per-market withdraw cooldown override.
+pub fn validate_withdraw_cooldown(cooldown: Option<u64>) -> Result<(), ContractError> {
+    match cooldown {
+        Some(secs) if secs > MAX_WITHDRAW_COOLDOWN_SECONDS => Err(ContractError::InvalidTimestamp),
+        _ => Ok(()),
+    }
+}
+
+/// Ledgers (about an hour) a resolution commitment stays live. Once it lapses
+/// trading resumes and a new commitment may be made, so an abandoned or bogus
+/// commitment cannot freeze a market indefinitely.
+pub const RESOLUTION_REVEAL_WINDOW_LEDGERS: u32 = 720;
+
+/// The market's resolution commitment, unless none exists or it has lapsed.
+pub fn pending_resolution_commitment(env: &Env, market_id: u32) -> Option<ResolutionCommitment> {
+    crate::storage::get_resolution_commitment(env, market_id).filter(|commitment| {
+        env.ledger().sequence()
+            <= commitment
+                .ledger
+                .saturating_add(RESOLUTION_REVEAL_WINDOW_LEDGERS)
+    })
+}
+
+/// Rejects trading and new commitments while a resolution commitment is live.
+pub fn require_no_pending_commitment(env: &Env, market_id: u32) -> Result<(), ContractError> {
+    if pending_resolution_commitment(env, market_id).is_some() {
+        return Err(ContractError::ResolutionCommitted);
+    }
+    Ok(())
+}
+
 /// Guard function to validate input before processing.
 ///
 /// This is a general-purpose validation guard that can be used in integration tests
@@ -27,28 +201,48 @@ pub fn validate_input_guard(input: i128) -> Result<(), ContractError> {
     Ok(())
 }
 
-/// Validates that end_time is in the future and within reasonable bounds
-fn validate_end_time(end_time: u64, current_time: u64) -> Result<(), ContractError> {
+/// Default for how far past creation a market's `end_time` may lie: one year.
+/// The admin may change it with `set_max_market_duration`.
+pub const DEFAULT_MAX_MARKET_DURATION_SECONDS: u64 = 31_536_000;
+
+/// Validates that end_time is in the future and at most `max_duration`
+/// seconds away
+fn validate_end_time(
+    end_time: u64,
+    current_time: u64,
+    max_duration: u64,
+) -> Result<(), ContractError> {
     if end_time <= current_time {
         return Err(ContractError::InvalidTimestamp);
     }
 
-    const ONE_YEAR_SECONDS: u64 = 31_536_000;
-    if end_time > current_time + ONE_YEAR_SECONDS {
+    if end_time > current_time.saturating_add(max_duration) {
         return Err(ContractError::InvalidTimestamp);
     }
 
     Ok(())
 }
 
+/// Rejects a question that exactly matches an `Active` market's question.
+///
+/// # Errors
+/// [`ContractError::InvalidQuestion`] when the question is already live.
+pub fn require_unique_question(env: &Env, question: &String) -> Result<(), ContractError> {
+    if crate::storage::get_active_question_market(env, question).is_some() {
+        return Err(ContractError::InvalidQuestion);
+    }
+    Ok(())
+}
+
 /// Validates market creation parameters
 pub fn validate_market_creation(
     question: &String,
     end_time: u64,
     current_time: u64,
+    max_duration: u64,
 ) -> Result<(), ContractError> {
     validate_question_format(question)?;
-    validate_end_time(end_time, current_time)?;
+    validate_end_time(end_time, current_time, max_duration)?;
     Ok(())
 }
 
@@ -95,6 +289,90 @@ pub fn validate_metadata_uri(metadata_uri: &Option<String>) -> Result<(), Contra
     Ok(())
 }
 
+/// Maximum length (exclusive) of a market's `resolution_source`.
+pub const MAX_RESOLUTION_SOURCE_LEN: u32 = 200;
+
+/// Validates a market's resolution source description.
+///
+/// The source must be non-empty and shorter than
+/// [`MAX_RESOLUTION_SOURCE_LEN`] characters.
+///
+/// # Errors
+/// [`ContractError::InvalidMetadataUri`] if the source is empty or too long.
+pub fn validate_resolution_source(resolution_source: &String) -> Result<(), ContractError> {
+    let len = resolution_source.len();
+    if len == 0 || len >= MAX_RESOLUTION_SOURCE_LEN {
+        return Err(ContractError::InvalidMetadataUri);
+    }
+    Ok(())
+}
+
+/// Validates a market's kind.
+///
+/// Binary markets always pass; a scalar market's band must not be empty.
+///
+/// # Errors
+/// [`ContractError::InvalidOutcome`] if `lower` is not below `upper`.
+pub fn validate_market_kind(kind: &MarketKind) -> Result<(), ContractError> {
+    if let MarketKind::Scalar(bounds) = kind {
+        if bounds.lower >= bounds.upper {
+            return Err(ContractError::InvalidOutcome);
+        }
+    }
+    Ok(())
+}
+
+/// Validates a market's deposit minimum and position cap.
+///
+/// Zero disables either limit.
+///
+/// # Errors
+/// [`ContractError::InvalidQuantity`] if either limit is negative.
+pub fn validate_market_limits(min_deposit: i128, max_position: i128) -> Result<(), ContractError> {
+    if min_deposit < 0 || max_position < 0 {
+        return Err(ContractError::InvalidQuantity);
+    }
+    Ok(())
+}
+
+/// Validates a trade against the market's `max_position` cap.
+///
+/// The cap bounds the net position `|yes_shares - no_shares|` the trade
+/// leaves. A trade that does not grow the net position is always allowed, so
+/// a holder above the cap can still reduce. A `max_position` of zero means
+/// no cap.
+///
+/// # Errors
+/// [`ContractError::InvalidQuantity`] if the trade grows the net position
+/// past the cap.
+pub fn validate_position_cap(
+    market: &Market,
+    current: &Position,
+    new_yes: i128,
+    new_no: i128,
+) -> Result<(), ContractError> {
+    if market.max_position == 0 {
+        return Ok(());
+    }
+    let before = calculate_net_position(current.yes_shares, current.no_shares).unsigned_abs();
+    let after = calculate_net_position(new_yes, new_no).unsigned_abs();
+    if after > before && after > market.max_position.unsigned_abs() {
+        return Err(ContractError::InvalidQuantity);
+    }
+    Ok(())
+}
+
+/// Require a binary market, for the YES/NO resolution paths.
+///
+/// # Errors
+/// [`ContractError::InvalidOutcome`] if the market is scalar.
+pub fn require_binary_market(market: &Market) -> Result<(), ContractError> {
+    match market.kind {
+        MarketKind::Binary => Ok(()),
+        MarketKind::Scalar(_) => Err(ContractError::InvalidOutcome),
+    }
+}
+
 /// Validates that amount is positive
 fn validate_amount_positive(amount: i128) -> Result<(), ContractError> {
     if amount <= 0 {
@@ -151,6 +429,17 @@ pub fn validate_shares(yes_shares: i128, no_shares: i128) -> Result<(), Contract
     Ok(())
 }
 
+/// Validates a share count passed to a buy/sell entry point (must be positive).
+///
+/// Share counts report [`ContractError::InvalidShareAmount`] so clients can
+/// tell them apart from collateral amounts, which report `InvalidQuantity`.
+pub fn validate_share_quantity(quantity: i128) -> Result<(), ContractError> {
+    if quantity <= 0 {
+        return Err(ContractError::InvalidShareAmount);
+    }
+    Ok(())
+}
+
 /// Validates market price is within valid basis-point range (0–10_000)
 pub fn validate_market_price(price: i128) -> Result<(), ContractError> {
     if !(0..=10_000).contains(&price) {
@@ -159,61 +448,110 @@ pub fn validate_market_price(price: i128) -> Result<(), ContractError> {
     Ok(())
 }
 
-/// Validates outcome value
-pub fn validate_outcome(outcome: bool) -> Result<(), ContractError> {
-    // Simple bool check (for consistency)
-    // In Rust, bool is always valid, but we keep this for API consistency
-    // and potential future validation logic
-    let _ = outcome; // Acknowledge the parameter
-    Ok(())
+/// Validates a proposed outcome against the market being resolved.
+///
+/// A market that already carries a result cannot be resolved again, and the
+/// outcome must be one the market's kind allows. Binary markets accept any
+/// [`Outcome`]; other kinds have their own resolution paths.
+///
+/// # Errors
+/// - [`ContractError::MarketAlreadyResolved`] if `market.result` is set.
+/// - [`ContractError::InvalidOutcome`] if the market is not binary.
+pub fn validate_outcome(market: &Market, outcome: Outcome) -> Result<(), ContractError> {
+    if market.result.is_some() {
+        return Err(ContractError::MarketAlreadyResolved);
+    }
+    // YES, NO and INVALID are all valid binary outcomes, so only the kind is
+    // checked.
+    let _ = outcome;
+    require_binary_market(market)
 }
 
 /// Validates that a market may be administratively canceled.
 ///
 /// Cancellation is only permitted while a market is still open, i.e. before it
-/// has been resolved by the oracle. This encodes the cancel policy in one place
-/// so the contract entry point stays declarative.
+/// has been resolved by the oracle. A market pending resolution can also be
+/// canceled, which is how a proposed outcome is challenged. This encodes the
+/// cancel policy in one place so the contract entry point stays declarative.
 ///
 /// # Arguments
 /// * `status` - Current [`MarketStatus`] of the market being canceled
 ///
 /// # Returns
-/// `Ok(())` when the market is [`MarketStatus::Active`] and can be canceled.
+/// `Ok(())` when the market is [`MarketStatus::Active`] or
+/// [`MarketStatus::PendingResolution`] and can be canceled.
 ///
 /// # Errors
 /// - [`ContractError::MarketAlreadyResolved`] – the market is already resolved
 ///   and its outcome is final, so it can no longer be canceled.
 /// - [`ContractError::MarketNotActive`] – the market is already canceled; the
-///   operation is a no-op and is rejected to surface the redundant call.
+///   operation is a no-op and is rejected to surface the redundant call. Also
+///   returned for a disputed market, which must go through `resolve_dispute`
+///   so the disputer's bond is accounted for.
 pub fn validate_cancelable(status: &MarketStatus) -> Result<(), ContractError> {
     match status {
-        MarketStatus::Active => Ok(()),
+        MarketStatus::Active | MarketStatus::PendingResolution => Ok(()),
         MarketStatus::Resolved => Err(ContractError::MarketAlreadyResolved),
-        MarketStatus::Canceled => Err(ContractError::MarketNotActive),
+        MarketStatus::Canceled | MarketStatus::Disputed => Err(ContractError::MarketNotActive),
     }
 }
 
-/// Parse a decimal market_id string to u32 (e.g. "1", "42").
-/// Returns InvalidQuantity if empty, non-digit, or overflow.
-pub fn parse_market_id(market_id: &String) -> Result<u32, ContractError> {
-    let len = market_id.len();
-    if len == 0 || len > 10 {
-        return Err(ContractError::InvalidQuantity);
+/// Validates that a market may still be resolved by an oracle.
+///
+/// Only an [`MarketStatus::Active`] market can take an outcome. A canceled
+/// market has already been opened for refunds, so resolving it as well would
+/// pay its collateral out twice.
+///
+/// # Errors
+/// - [`ContractError::MarketAlreadyResolved`] – the market is resolved,
+///   pending resolution or disputed.
+/// - [`ContractError::MarketNotActive`] – the market was canceled or expired.
+pub fn validate_resolvable(status: &MarketStatus) -> Result<(), ContractError> {
+    match status {
+        MarketStatus::Active => Ok(()),
+        MarketStatus::Resolved | MarketStatus::PendingResolution | MarketStatus::Disputed => {
+            Err(ContractError::MarketAlreadyResolved)
+        }
+        MarketStatus::Canceled => Err(ContractError::MarketNotActive),
     }
-    let mut buf = [0u8; 10];
-    let slice = &mut buf[..len as usize];
-    market_id.copy_into_slice(slice);
-    let mut n: u32 = 0;
-    for b in slice.iter() {
-        if *b < b'0' || *b > b'9' {
-            return Err(ContractError::InvalidQuantity);
+}
+
+/// Validates that an admin address is a user account rather than a contract.
+///
+/// Deployed contracts (WASM or Stellar Asset) carry an executable; accounts
+/// and not-yet-funded addresses do not.
+///
+/// # Errors
+/// - [`ContractError::InvalidAdmin`] – `admin` is a deployed contract.
+pub fn validate_admin_address(admin: &Address) -> Result<(), ContractError> {
+    match admin.executable() {
+        Some(Executable::Wasm(_)) | Some(Executable::StellarAsset) => {
+            Err(ContractError::InvalidAdmin)
         }
-        n = n
-            .checked_mul(10)
-            .and_then(|n| n.checked_add((*b - b'0') as u32))
-            .ok_or(ContractError::InvalidQuantity)?;
+        _ => Ok(()),
+    }
+}
+
+/// Validates that a collateral token address behaves like a token.
+///
+/// Best-effort: rejects the market contract itself and any address that does
+/// not answer a read-only `decimals()` call, so a misconfigured market fails
+/// at creation instead of at the first deposit. A contract that implements
+/// `decimals()` but not transfers will still pass.
+///
+/// # Returns
+/// The token's `decimals()`, stored on the market for clients.
+///
+/// # Errors
+/// - [`ContractError::UnsupportedToken`] – `token` is this contract or not a token.
+pub fn validate_collateral_token(env: &Env, token: &Address) -> Result<u32, ContractError> {
+    if *token == env.current_contract_address() {
+        return Err(ContractError::UnsupportedToken);
+    }
+    match token::Client::new(env, token).try_decimals() {
+        Ok(Ok(decimals)) => Ok(decimals),
+        _ => Err(ContractError::UnsupportedToken),
     }
-    Ok(n)
 }
 
 /// Validates that a configured withdrawal fee rate (in basis points).
@@ -226,6 +564,50 @@ pub fn validate_fee_rate_bps(fee_rate_bps: i128) -> Result<(), ContractError> {
     validate_market_price(fee_rate_bps)
 }
 
+/// Validates an optional per-market fee override: `0..=10_000` basis points
+/// and no higher than the admin-configured fee cap.
+pub fn validate_market_fee_rate(
+    env: &Env,
+    fee_rate_bps: Option<i128>,
+) -> Result<(), ContractError> {
+    let Some(fee_rate_bps) = fee_rate_bps else {
+        return Ok(());
+    };
+    validate_fee_rate_bps(fee_rate_bps)?;
+    if fee_rate_bps > crate::storage::get_fee_cap_bps(env) {
+        return Err(ContractError::FeeCapExceeded);
+    }
+    Ok(())
+}
+
+/// Highest trading fee a market may charge on shares bought (10%).
+pub const MAX_TRADING_FEE_BPS: u32 = 1_000;
+
+/// Validates a market's trading fee against [`MAX_TRADING_FEE_BPS`].
+///
+/// # Errors
+/// - `FeeCapExceeded`: `trading_fee_bps` is above the maximum.
+pub fn validate_trading_fee_bps(trading_fee_bps: u32) -> Result<(), ContractError> {
+    if trading_fee_bps > MAX_TRADING_FEE_BPS {
+        return Err(ContractError::FeeCapExceeded);
+    }
+    Ok(())
+}
+
+/// Highest fee the protocol may take from a winning settlement payout (10%).
+pub const MAX_SETTLEMENT_FEE_BPS: u32 = 1_000;
+
+/// Validates the settlement fee against [`MAX_SETTLEMENT_FEE_BPS`].
+///
+/// # Errors
+/// - `FeeCapExceeded`: `settlement_fee_bps` is above the maximum.
+pub fn validate_settlement_fee_bps(settlement_fee_bps: u32) -> Result<(), ContractError> {
+    if settlement_fee_bps > MAX_SETTLEMENT_FEE_BPS {
+        return Err(ContractError::FeeCapExceeded);
+    }
+    Ok(())
+}
+
 /// Validates that outcome_count is exactly 2 (binary YES/NO market).
 ///
 /// All Vatix markets are binary. This is enforced at creation and re-checked
@@ -285,10 +667,12 @@ pub fn require_not_paused(env: &Env) -> Result<(), ContractError> {
     Ok(())
 }
 
-#[cfg(test)]
 #[cfg(test)]
 mod tests {
     use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    const ONE_YEAR: u64 = DEFAULT_MAX_MARKET_DURATION_SECONDS;
 
     #[test]
     fn test_valid_market_creation() {
@@ -296,7 +680,7 @@ mod tests {
         let current_time = 1000;
         let end_time = current_time + 86400; // 1 day later
 
-        assert!(validate_market_creation(&question, end_time, current_time).is_ok());
+        assert!(validate_market_creation(&question, end_time, current_time, ONE_YEAR).is_ok());
     }
 
     #[test]
@@ -306,7 +690,7 @@ mod tests {
         let end_time = current_time + 86400;
 
         assert_eq!(
-            validate_market_creation(&question, end_time, current_time),
+            validate_market_creation(&question, end_time, current_time, ONE_YEAR),
             Err(ContractError::InvalidQuestion)
         );
     }
@@ -319,7 +703,7 @@ mod tests {
         let end_time = current_time + 86400;
 
         assert_eq!(
-            validate_market_creation(&question, end_time, current_time),
+            validate_market_creation(&question, end_time, current_time, ONE_YEAR),
             Err(ContractError::InvalidQuestion)
         );
     }
@@ -331,7 +715,7 @@ mod tests {
         let end_time = current_time - 1; // In the past
 
         assert_eq!(
-            validate_market_creation(&question, end_time, current_time),
+            validate_market_creation(&question, end_time, current_time, ONE_YEAR),
             Err(ContractError::InvalidTimestamp)
         );
     }
@@ -343,11 +727,37 @@ mod tests {
         let end_time = current_time + 31_536_001; // More than 1 year
 
         assert_eq!(
-            validate_market_creation(&question, end_time, current_time),
+            validate_market_creation(&question, end_time, current_time, ONE_YEAR),
             Err(ContractError::InvalidTimestamp)
         );
     }
 
+    #[test]
+    fn test_end_time_bounded_by_max_duration() {
+        let question = String::from_str(&soroban_sdk::Env::default(), "Valid question?");
+        let current_time = 1000;
+        let thirty_days = 30 * 86_400;
+
+        assert!(validate_market_creation(
+            &question,
+            current_time + thirty_days,
+            current_time,
+            thirty_days
+        )
+        .is_ok());
+        assert_eq!(
+            validate_market_creation(
+                &question,
+                current_time + thirty_days + 1,
+                current_time,
+                thirty_days
+            ),
+            Err(ContractError::InvalidTimestamp)
+        );
+        // A bound near u64::MAX saturates instead of overflowing.
+        assert!(validate_market_creation(&question, u64::MAX, current_time, u64::MAX).is_ok());
+    }
+
     #[test]
     fn test_valid_collateral_amount() {
         assert!(validate_collateral_amount(100).is_ok());
@@ -406,40 +816,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_valid_outcome() {
-        assert!(validate_outcome(true).is_ok());
-        assert!(validate_outcome(false).is_ok());
-    }
-
-    #[test]
-    fn test_parse_market_id_valid() {
-        let env = soroban_sdk::Env::default();
-        assert_eq!(parse_market_id(&String::from_str(&env, "1")).unwrap(), 1);
-        assert_eq!(parse_market_id(&String::from_str(&env, "42")).unwrap(), 42);
-        assert_eq!(
-            parse_market_id(&String::from_str(&env, "999")).unwrap(),
-            999
-        );
-    }
-
-    #[test]
-    fn test_parse_market_id_invalid() {
-        let env = soroban_sdk::Env::default();
-        assert_eq!(
-            parse_market_id(&String::from_str(&env, "")),
-            Err(ContractError::InvalidQuantity)
-        );
-        assert_eq!(
-            parse_market_id(&String::from_str(&env, "abc")),
-            Err(ContractError::InvalidQuantity)
-        );
-        assert_eq!(
-            parse_market_id(&String::from_str(&env, "12a")),
-            Err(ContractError::InvalidQuantity)
-        );
-    }
-
     #[test]
     fn test_validate_market_price_valid() {
         assert!(validate_market_price(0).is_ok());
@@ -511,6 +887,11 @@ mod tests {
         assert!(validate_cancelable(&MarketStatus::Active).is_ok());
     }
 
+    #[test]
+    fn test_validate_cancelable_pending_resolution_ok() {
+        assert!(validate_cancelable(&MarketStatus::PendingResolution).is_ok());
+    }
+
     #[test]
     fn test_validate_cancelable_resolved_fails() {
         assert_eq!(
@@ -527,6 +908,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_resolvable_rejects_canceled() {
+        assert!(validate_resolvable(&MarketStatus::Active).is_ok());
+        assert_eq!(
+            validate_resolvable(&MarketStatus::Disputed),
+            Err(ContractError::MarketAlreadyResolved)
+        );
+        assert_eq!(
+            validate_resolvable(&MarketStatus::Canceled),
+            Err(ContractError::MarketNotActive)
+        );
+    }
+
     #[test]
     fn test_validate_admin_address_account_ok() {
         let env = soroban_sdk::Env::default();
@@ -545,15 +939,6 @@ mod tests {
             Err(ContractError::InvalidAdmin)
         );
     }
-}
-
-    #[test]
-    fn test_validate_cancelable_already_canceled_fails() {
-        assert_eq!(
-            validate_cancelable(&MarketStatus::Canceled),
-            Err(ContractError::MarketNotActive)
-        );
-    }
 
     #[test]
     fn test_require_initialized_when_has_admin() {
@@ -593,4 +978,104 @@ mod tests {
             assert_eq!(require_not_paused(&env), Err(ContractError::ContractPaused));
         });
     }
+
+    #[test]
+    fn test_page_range_within_bounds() {
+        assert_eq!(page_range(0, 10, 50), (0, 10));
+        assert_eq!(page_range(45, 10, 50), (45, 50));
+    }
+
+    #[test]
+    fn test_page_range_clamps_to_max_page_size() {
+        assert_eq!(page_range(0, u32::MAX, 1_000), (0, MAX_PAGE_SIZE));
+        assert_eq!(page_range(0, MAX_PAGE_SIZE + 1, 1_000), (0, MAX_PAGE_SIZE));
+    }
+
+    #[test]
+    fn test_page_range_start_past_end_is_empty() {
+        assert_eq!(page_range(60, 10, 50), (50, 50));
+        assert_eq!(page_range(u32::MAX, u32::MAX, 50), (50, 50));
+    }
+
+    #[test]
+    fn test_validate_resolution_grace_bounds() {
+        assert_eq!(validate_resolution_grace(None), Ok(()));
+        assert_eq!(
+            validate_resolution_grace(Some(MIN_RESOLUTION_GRACE_SECONDS)),
+            Ok(())
+        );
+        assert_eq!(
+            validate_resolution_grace(Some(MAX_RESOLUTION_GRACE_SECONDS)),
+            Ok(())
+        );
+        assert_eq!(
+            validate_resolution_grace(Some(MIN_RESOLUTION_GRACE_SECONDS - 1)),
+            Err(ContractError::InvalidResolutionGrace)
+        );
+        assert_eq!(
+            validate_resolution_grace(Some(MAX_RESOLUTION_GRACE_SECONDS + 1)),
+            Err(ContractError::InvalidResolutionGrace)
+        );
+    }
+
+    #[test]
+    fn test_validate_oracle_pubkey_rejects_zero_key() {
+        let env = Env::default();
+        assert_eq!(
+            validate_oracle_pubkey(&env, &BytesN::from_array(&env, &[0u8; 32])),
+            Err(ContractError::InvalidSignature)
+        );
+        assert_eq!(
+            validate_oracle_pubkey(&env, &BytesN::from_array(&env, &[1u8; 32])),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_validate_oracle_set_bounds() {
+        use soroban_sdk::{vec, BytesN};
+
+        let env = Env::default();
+        let a = BytesN::from_array(&env, &[1u8; 32]);
+        let b = BytesN::from_array(&env, &[2u8; 32]);
+        let set = |keys, threshold| OracleSet { keys, threshold };
+
+        assert_eq!(
+            validate_oracle_set(&set(vec![&env, a.clone(), b.clone()], 2)),
+            Ok(())
+        );
+        assert_eq!(
+            validate_oracle_set(&set(vec![&env], 1)),
+            Err(ContractError::InvalidOracleSet)
+        );
+        assert_eq!(
+            validate_oracle_set(&set(vec![&env, a.clone()], 0)),
+            Err(ContractError::InvalidOracleSet)
+        );
+        assert_eq!(
+            validate_oracle_set(&set(vec![&env, a.clone(), b], 3)),
+            Err(ContractError::InvalidOracleSet)
+        );
+        assert_eq!(
+            validate_oracle_set(&set(vec![&env, a.clone(), a], 1)),
+            Err(ContractError::DuplicateOracleSigner)
+        );
+    }
+
+    #[test]
+    fn test_validate_resolution_source_length() {
+        let env = Env::default();
+        let source = |len: usize| String::from_str(&env, &"s".repeat(len));
+
+        assert_eq!(validate_resolution_source(&source(1)), Ok(()));
+        assert_eq!(validate_resolution_source(&source(199)), Ok(()));
+        assert_eq!(
+            validate_resolution_source(&source(0)),
+            Err(ContractError::InvalidMetadataUri)
+        );
+        assert_eq!(
+            validate_resolution_source(&source(200)),
+            Err(ContractError::InvalidMetadataUri)
+        );
+    }
 }
\ No newline at end of file
@@ -4,7 +4,10 @@
 //! collateral backs the user's active YES/NO shares. It is computed and
 //! persisted exclusively by `positions::update_position` at the real trade
 //! price. Withdraw reads that stored value and never recomputes a lock from a
-//! hardcoded price, so the two views can never diverge.
+//! hardcoded price, so the two views can never diverge. It also checks what
+//! the shares require at the market's current `price_bps` (set by the latest
+//! trade in the market) and holds back the larger of the two; see
+//! [`withdrawable_collateral`].
 //!
 //! ## Fee deduction (#377)
 //! When a fee rate is configured the user must have `amount + fee` of unlocked
@@ -14,23 +17,63 @@
 
 use crate::error::ContractError;
 use crate::events::{emit_collateral_withdrawn, emit_fee_calculated, emit_withdraw_edge_case};
+use crate::positions::calculate_locked_collateral;
 use crate::storage;
-use crate::types::{MarketStatus, Position};
+use crate::types::{Market, MarketStatus, Position};
 use crate::validation;
 
 use soroban_sdk::token::Client as TokenClient;
 use soroban_sdk::{Address, Env, IntoVal, Symbol, Val, Vec};
 
-/// Seconds a user must wait after their last deposit before withdrawing (issue #413).
-const WITHDRAW_COOLDOWN_SECONDS: u64 = 3_600;
+/// Reject with `WithdrawCooldownActive` until the market's withdraw cooldown
+/// has passed since `user`'s last deposit into it.
+pub(crate) fn require_cooldown_elapsed(
+    env: &Env,
+    market: &Market,
+    market_id: u32,
+    user: &Address,
+) -> Result<(), ContractError> {
+    let cooldown = market
+        .withdraw_cooldown
+        .unwrap_or(validation::DEFAULT_WITHDRAW_COOLDOWN_SECONDS);
+    if let Some(last_deposit_time) = storage::get_last_deposit_time(env, market_id, user) {
+        let elapsed = env.ledger().timestamp().saturating_sub(last_deposit_time);
+        if elapsed < cooldown {
+            return Err(ContractError::WithdrawCooldownActive);
+        }
+    }
+    Ok(())
+}
+
+/// Collateral `position` may withdraw from `market`, before fees.
+///
+/// `total_deposited` less whichever is larger: the stored
+/// `locked_collateral`, or what the position's net shares require at the
+/// market's current price. A price move since the user's last trade can
+/// raise what their shares need but never lets them withdraw collateral the
+/// shares still need. Floored at zero.
+///
+/// # Errors
+/// - [`ContractError::ArithmeticOverflow`] – the requirement at the current
+///   price cannot be computed
+pub(crate) fn withdrawable_collateral(
+    market: &Market,
+    position: &Position,
+) -> Result<i128, ContractError> {
+    let required =
+        calculate_locked_collateral(position.yes_shares, position.no_shares, market.price_bps)?;
+    let locked = position.locked_collateral.max(required);
+    Ok(position.total_deposited.saturating_sub(locked).max(0))
+}
 
 /// Withdraw `amount` of unused (unlocked) collateral from a market.
 ///
 /// # Locked-collateral enforcement (#376)
-/// `available = total_deposited − locked_collateral`
+/// `available = total_deposited − max(locked_collateral, required at current price)`
 /// The user may only withdraw up to `available − fee`. Any request that would
 /// reduce the balance below the locked amount is rejected with
-/// `InsufficientCollateral`.
+/// `InsufficientCollateral`. A user who never deposited into the market gets
+/// `NoPositionFound`.
 ///
 /// # Fee deduction (#377)
 /// The protocol fee is computed as `amount * fee_rate_bps / 10_000`. The check
@@ -54,25 +97,25 @@ pub fn withdraw_unused_collateral(
         return Err(ContractError::MarketNotActive);
     }
 
-    // 3. Enforce cooldown: user must wait WITHDRAW_COOLDOWN_SECONDS after their last deposit.
-    if let Some(last_deposit_time) = storage::get_last_deposit_time(&env, market_id, &user) {
-        let elapsed = env.ledger().timestamp().saturating_sub(last_deposit_time);
-        if elapsed < WITHDRAW_COOLDOWN_SECONDS {
-            return Err(ContractError::WithdrawCooldownActive);
-        }
-    }
+    // 3. Enforce cooldown: user must wait the market's cooldown after their last deposit.
+    require_cooldown_elapsed(&env, &market, market_id, &user)?;
 
     // 4. Load position; an absent or zero-deposited position cannot be withdrawn.
-    let mut position = storage::get_position(&env, market_id, &user)?
-        .unwrap_or_else(|| Position::new_empty(market_id, user.clone()));
+    let Some(mut position) = storage::get_position(&env, market_id, &user)? else {
+        emit_withdraw_edge_case(&env, &user, market_id, amount);
+        return Err(ContractError::NoPositionFound);
+    };
 
     if position.total_deposited == 0 {
         emit_withdraw_edge_case(&env, &user, market_id, amount);
         return Err(ContractError::InsufficientCollateral);
     }
 
-    // 5. Compute the fee (single path, no duplication).
-    let fee_rate_bps = storage::get_fee_rate_bps(&env);
+    // 5. Compute the fee (single path, no duplication). A per-market override
+    //    takes precedence over the global rate.
+    let fee_rate_bps = market
+        .fee_rate_bps
+        .unwrap_or_else(|| storage::get_fee_rate_bps(&env));
     validation::validate_fee_rate_bps(fee_rate_bps)?;
     let fee_amount = if fee_rate_bps > 0 {
         validation::calculate_fee(amount, fee_rate_bps)?
@@ -81,11 +124,10 @@ pub fn withdraw_unused_collateral(
     };
 
     // 6. Enforce locked collateral (#376).
-    //    available = total_deposited - locked_collateral (floored at 0).
+    //    available = total_deposited - lock (floored at 0), where the lock is
+    //    also checked against the current price; see withdrawable_collateral.
     //    The user must have `amount + fee_amount` of available (unlocked) collateral.
-    let available = position
-        .total_deposited
-        .saturating_sub(position.locked_collateral);
+    let available = withdrawable_collateral(&market, &position)?;
 
     // Only emit the fee event when a non-zero fee is actually deducted (#345).
     if fee_amount > 0 {
@@ -169,6 +211,25 @@ mod tests {
             resolved_at: None,
             adapter_type: AdapterType::Ed25519,
             outcome_count: 2,
+            closed_to_deposits: false,
+            source_hash: BytesN::from_array(env, &[0u8; 32]),
+            resolution_grace: None,
+            fee_rate_bps: None,
+            withdraw_cooldown: None,
+            challenge_deadline: None,
+            trading_fee_bps: 0,
+            collateral_decimals: 7,
+            total_yes_shares: 0,
+            total_no_shares: 0,
+            yes_reserve: 0,
+            no_reserve: 0,
+            category: Symbol::new(env, "general"),
+            resolution_source: String::from_str(env, "test source"),
+            kind: crate::types::MarketKind::Binary,
+            settled_value: None,
+            min_deposit: 0,
+            max_position: 0,
+            max_participants: 0,
         }
     }
 
@@ -244,6 +305,35 @@ mod tests {
         assert_eq!(result, Err(ContractError::MarketNotActive));
     }
 
+    #[test]
+    fn test_withdrawable_collateral_holds_back_current_price_requirement() {
+        let env = setup_env();
+        let user = Address::generate(&env);
+        let mut market = create_test_market(&env, 1, &Address::generate(&env));
+        let mut position = Position::new_empty(1, user, 0);
+        position.total_deposited = 100;
+
+        // No shares: everything is withdrawable.
+        assert_eq!(withdrawable_collateral(&market, &position), Ok(100));
+
+        // 40 YES locked at 50%: the surplus over 20.
+        position.yes_shares = 40;
+        position.locked_collateral = 20;
+        assert_eq!(withdrawable_collateral(&market, &position), Ok(80));
+
+        // At 80% the shares need 32, more than the stored lock.
+        market.price_bps = 8_000;
+        assert_eq!(withdrawable_collateral(&market, &position), Ok(68));
+
+        // At 20% the stored lock still applies.
+        market.price_bps = 2_000;
+        assert_eq!(withdrawable_collateral(&market, &position), Ok(80));
+
+        // Never negative.
+        position.total_deposited = 10;
+        assert_eq!(withdrawable_collateral(&market, &position), Ok(0));
+    }
+
     /// #376: withdrawing more than unlocked collateral must be rejected.
     #[test]
     fn test_withdraw_locked_collateral_enforced() {
@@ -262,6 +352,8 @@ mod tests {
             locked_collateral: 60,
             total_deposited: 100,
             is_settled: false,
+            created_at: 0,
+            updated_at: 0,
         };
         env.as_contract(&contract_id, || {
             storage::set_version(&env);
@@ -296,6 +388,8 @@ mod tests {
             locked_collateral: 60,
             total_deposited: 100,
             is_settled: false,
+            created_at: 0,
+            updated_at: 0,
         };
         env.as_contract(&contract_id, || {
             storage::set_version(&env);
@@ -333,6 +427,8 @@ mod tests {
             locked_collateral: 100,
             total_deposited: 100,
             is_settled: false,
+            created_at: 0,
+            updated_at: 0,
         };
         env.as_contract(&contract_id, || {
             storage::set_version(&env);
@@ -366,6 +462,8 @@ mod tests {
             locked_collateral: 0,
             total_deposited: 100,
             is_settled: false,
+            created_at: 0,
+            updated_at: 0,
         };
         env.as_contract(&contract_id, || {
             storage::set_version(&env);
@@ -408,6 +506,8 @@ mod tests {
             locked_collateral: 50,
             total_deposited: 100,
             is_settled: false,
+            created_at: 0,
+            updated_at: 0,
         };
         env.as_contract(&contract_id, || {
             storage::set_version(&env);
@@ -441,6 +541,8 @@ mod tests {
             locked_collateral: 0,
             total_deposited: 100,
             is_settled: false,
+            created_at: 0,
+            updated_at: 0,
         };
         env.as_contract(&contract_id, || {
             storage::set_version(&env);
@@ -469,9 +571,15 @@ mod tests {
         let contract_id = env.register(crate::MarketContract, ());
         let market = create_test_market(&env, market_id, &collateral_token);
         let position = Position {
-            market_id, user: user.clone(),
-            yes_shares: 0, no_shares: 0,
-            locked_collateral: 0, total_deposited: 0, is_settled: false,
+            market_id,
+            user: user.clone(),
+            yes_shares: 0,
+            no_shares: 0,
+            locked_collateral: 0,
+            total_deposited: 0,
+            is_settled: false,
+            created_at: 0,
+            updated_at: 0,
         };
         env.as_contract(&contract_id, || {
             storage::set_version(&env);
@@ -11,22 +11,29 @@
 
 use crate::positions;
 use crate::storage;
-use crate::types::{Market, MarketStatus, Position};
+use crate::types::{AdapterType, Market, MarketStatus, Position};
 use crate::withdraw::withdraw_unused_collateral;
 use proptest::prelude::*;
 use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String};
 
 /// Strategy for random position state with valid invariant: locked <= deposited
 fn arb_valid_position() -> impl Strategy<Value = (i128, i128, i128, i128)> {
-    // deposited in 0..10M, shares in 0..1M, locked derived from shares/price
-    (0i128..=10_000_000i128).prop_flat_map(|total_deposited| {
-        (
-            0i128..=1_000_000i128, // yes_shares
-            0i128..=1_000_000i128, // no_shares
-            0i128..=10_000i128,    // market_price
-            Just(total_deposited),
-        )
-    })
+    // shares in 0..1M and price in 0..10_000 bps; deposited covers at least the lock
+    (
+        0i128..=1_000_000i128, // yes_shares
+        0i128..=1_000_000i128, // no_shares
+        0i128..=10_000i128,    // market_price
+    )
+        .prop_flat_map(|(yes_shares, no_shares, price)| {
+            let locked =
+                positions::calculate_locked_collateral(yes_shares, no_shares, price).unwrap();
+            (
+                Just(yes_shares),
+                Just(no_shares),
+                Just(price),
+                locked..=(locked + 10_000_000i128), // total_deposited
+            )
+        })
 }
 
 /// Strategy for fuzzing withdraw amount against available collateral
@@ -54,6 +61,29 @@ fn make_market(env: &Env, market_id: u32, collateral_token: &Address) -> Market
         created_at: 0,
         collateral_token: collateral_token.clone(),
         price_bps: 5_000,
+        resolver: None,
+        resolved_at: None,
+        adapter_type: AdapterType::Ed25519,
+        outcome_count: 2,
+        closed_to_deposits: false,
+        source_hash: BytesN::from_array(env, &[0u8; 32]),
+        resolution_grace: None,
+        fee_rate_bps: None,
+        withdraw_cooldown: None,
+        challenge_deadline: None,
+        trading_fee_bps: 0,
+        collateral_decimals: 7,
+        total_yes_shares: 0,
+        total_no_shares: 0,
+        yes_reserve: 0,
+        no_reserve: 0,
+        category: soroban_sdk::Symbol::new(env, "general"),
+        resolution_source: String::from_str(env, "test source"),
+        kind: crate::types::MarketKind::Binary,
+        settled_value: None,
+        min_deposit: 0,
+        max_position: 0,
+        max_participants: 0,
     }
 }
 
@@ -78,7 +108,7 @@ proptest! {
 
         let market = make_market(&env, market_id, &collateral_token);
         // Compute locked from shares/price to ensure valid state
-        let locked = positions::calculate_locked_collateral(yes_shares, no_shares, price);
+        let locked = positions::calculate_locked_collateral(yes_shares, no_shares, price).unwrap();
         // Ensure locked doesn't exceed deposited for valid test cases
         let locked = if locked > deposited { deposited } else { locked };
 
@@ -90,6 +120,8 @@ proptest! {
             locked_collateral: locked,
             total_deposited: deposited,
             is_settled: false,
+            created_at: 0,
+            updated_at: 0,
         };
 
         env.as_contract(&contract_id, || {
@@ -117,10 +149,9 @@ proptest! {
     /// (no fee, no locked shares).
     #[test]
     fn prop_successful_withdraw_decrements_deposited(
-        deposited in 1i128..=10_000_000i128,
-        amount in 1i128..=10_000_000i128,
+        (deposited, amount) in (1i128..=10_000_000i128)
+            .prop_flat_map(|deposited| (Just(deposited), 1i128..=deposited)),
     ) {
-        prop_assume!(amount <= deposited);
 
         let env = Env::default();
         env.mock_all_auths();
@@ -141,6 +172,8 @@ proptest! {
             locked_collateral: 0,
             total_deposited: deposited,
             is_settled: false,
+            created_at: 0,
+            updated_at: 0,
         };
 
         env.as_contract(&contract_id, || {
@@ -179,7 +212,7 @@ mod share_collateral_invariants {
         fn prop_locked_never_exceeds_deposited(
             (yes_shares, no_shares, price, deposited) in arb_valid_position()
         ) {
-            let locked = positions::calculate_locked_collateral(yes_shares, no_shares, price);
+            let locked = positions::calculate_locked_collateral(yes_shares, no_shares, price).unwrap();
             // Only test when locked is computed from valid position state
             prop_assert!(locked <= deposited,
                 "locked={locked} > deposited={deposited} yes={yes_shares} no={no_shares} price={price}");
@@ -190,7 +223,7 @@ mod share_collateral_invariants {
         fn prop_available_non_negative(
             (yes_shares, no_shares, price, deposited) in arb_valid_position()
         ) {
-            let locked = positions::calculate_locked_collateral(yes_shares, no_shares, price);
+            let locked = positions::calculate_locked_collateral(yes_shares, no_shares, price).unwrap();
             let available = deposited.saturating_sub(locked);
             prop_assert!(available >= 0,
                 "available={available} negative: deposited={deposited} locked={locked}");
@@ -200,7 +233,9 @@ mod share_collateral_invariants {
         #[test]
         fn prop_deposit_preserves_locked_invariant(
             existing_deposited in 0i128..=5_000_000i128,
-            new_deposit in 1i128..=5_000_000i128,
+            // At least the 250 stroops locked by the seeded shares below, which
+            // are created without any deposit backing them.
+            new_deposit in 250i128..=5_000_000i128,
         ) {
             let env = Env::default();
             let user = Address::generate(&env);
@@ -223,7 +258,7 @@ mod share_collateral_invariants {
             });
 
             // Get position after shares are set
-            let position_before = env.as_contract(&contract_id, || {
+            let _position_before = env.as_contract(&contract_id, || {
                 storage::get_position(&env, market_id, &user).unwrap().unwrap()
             });
 
@@ -274,6 +309,8 @@ mod share_collateral_invariants {
                 locked_collateral: 0,
                 total_deposited: deposited,
                 is_settled: false,
+                created_at: 0,
+                updated_at: 0,
             };
 
             env.as_contract(&contract_id, || {
@@ -318,7 +355,7 @@ mod share_collateral_invariants {
             });
 
             // Initial position
-            let initial_locked = positions::calculate_locked_collateral(initial_yes, initial_no, price);
+            let _initial_locked = positions::calculate_locked_collateral(initial_yes, initial_no, price).unwrap();
 
             // Update position
             let result = env.as_contract(&contract_id, || {
@@ -329,7 +366,7 @@ mod share_collateral_invariants {
             if let Ok(pos) = result {
                 let expected_locked = positions::calculate_locked_collateral(
                     pos.yes_shares, pos.no_shares, price
-                );
+                ).unwrap();
                 prop_assert_eq!(pos.locked_collateral, expected_locked,
                     "locked mismatch: expected={}, got={}", expected_locked, pos.locked_collateral);
             }
@@ -16,6 +16,10 @@ pub enum ContractError {
     SignatureExpired = 9,
     /// The provided signature expiry timestamp is invalid (e.g. in the past).
     InvalidSignatureExpiry = 10,
+    /// Proposer collateral deposit amount must be positive.
+    InvalidCollateral = 11,
+    /// Proposer has no collateral to slash.
+    InsufficientCollateral = 12,
     Unauthorized = 40,
     NotAdmin = 41,
     AlreadyInitialized = 42,
@@ -52,26 +52,26 @@ pub struct ResolutionContract;
 impl ResolutionContract {
     /// Register the resolution lifecycle contract with its factory and market.
     ///
-    /// `default_challenge_window_seconds` is stored as the contract-wide default.
+    /// `default_challenge_window_secs` is stored as the contract-wide default.
     pub fn initialize(
         env: Env,
         admin: Address,
         factory: Address,
         market_contract: Address,
-        default_challenge_window_seconds: u64,
+        default_challenge_window_secs: u64,
     ) -> Result<(), ContractError> {
         admin.require_auth();
         if storage::has_config(&env) {
             return Err(ContractError::AlreadyInitialized);
         }
-        validate_challenge_window(default_challenge_window_seconds)?;
+        validate_challenge_window(default_challenge_window_secs)?;
         storage::set_config(
             &env,
             &ResolutionConfig {
                 admin,
                 factory: factory.clone(),
                 market_contract: market_contract.clone(),
-                default_challenge_window_seconds,
+                default_challenge_window_secs,
             },
         );
         events::emit_resolution_registered(&env, &factory, &market_contract);
@@ -79,7 +79,7 @@ impl ResolutionContract {
     }
 
     pub fn get_default_challenge_window(env: Env) -> u64 {
-        storage::get_config(&env).default_challenge_window_seconds
+        storage::get_config(&env).default_challenge_window_secs
     }
 
     pub fn set_default_challenge_window(
@@ -91,7 +91,7 @@ impl ResolutionContract {
         let mut config = storage::get_config(&env);
         require_admin(&admin, &config)?;
         validate_challenge_window(seconds)?;
-        config.default_challenge_window_seconds = seconds;
+        config.default_challenge_window_secs = seconds;
         storage::set_config(&env, &config);
         Ok(())
     }
@@ -132,6 +132,7 @@ impl ResolutionContract {
     /// `ResolutionCandidate`: off-chain services may display the same
     /// `challenge_deadline` and evidence URI while listening for challenge and
     /// finalize events.
+    #[allow(clippy::too_many_arguments)]
     pub fn propose(
         env: Env,
         proposer: Address,
@@ -230,8 +231,12 @@ impl ResolutionContract {
     /// Finalize an unchallenged candidate after its challenge window closes.
     ///
     /// After marking the candidate as `Finalized`, immediately invokes
-    /// `resolve_market(market_id, outcome, signature)` on the registered
-    /// market contract so the market state is settled atomically.
+    /// `resolve_market(resolver, market_id, outcome, None, signature, 0)` on
+    /// the registered market contract so the market state is settled
+    /// atomically. The candidate has already served its challenge window
+    /// here, so the market is asked to resolve without a second one. No
+    /// oracle key is passed: the signature is checked against the market's
+    /// own oracle, as it was when the candidate was proposed.
     pub fn finalize(
         env: Env,
         finalizer: Address,
@@ -262,12 +267,17 @@ impl ResolutionContract {
         storage::set_candidate(&env, &candidate);
         events::emit_candidate_finalized(&env, &candidate);
 
-        // Cross-contract callback: resolve the market with the finalized outcome.
+        // Cross-contract callback: resolve the market with the finalized
+        // outcome. This contract is the resolver; as the direct invoker its
+        // authorization is implicit.
         let args: Vec<Val> = soroban_sdk::vec![
             &env,
+            env.current_contract_address().into_val(&env),
             candidate.market_id.into_val(&env),
             candidate.outcome.into_val(&env),
+            Option::<BytesN<32>>::None.into_val(&env),
             candidate.signature.clone().into_val(&env),
+            0u64.into_val(&env),
         ];
         let _: () = env.invoke_contract(
             &config.market_contract,
@@ -300,7 +310,7 @@ impl ResolutionContract {
         }
         token::Client::new(&env, &collateral_token).transfer(
             &proposer,
-            &env.current_contract_address(),
+            env.current_contract_address(),
             &amount,
         );
         let prev = storage::get_proposer_collateral(&env, &proposer);
@@ -1,19 +1,48 @@
 use crate::{ContractError, ResolutionContract, ResolutionContractClient};
 use soroban_sdk::{
+    contract, contractimpl,
     testutils::{Address as _, Ledger},
-    token::{Client as TokenClient, StellarAssetClient},
     Address, BytesN, Env, String,
 };
 
 const DEFAULT_WINDOW: u64 = 300;
 
+/// Stand-in for the market contract: accepts every signature and resolution
+/// so these tests exercise only the resolution lifecycle.
+#[contract]
+struct MockMarket;
+
+#[contractimpl]
+impl MockMarket {
+    pub fn verify_signature(
+        _env: Env,
+        _market_id: u32,
+        _outcome: bool,
+        _signature: BytesN<64>,
+    ) -> Result<(), ContractError> {
+        Ok(())
+    }
+
+    pub fn resolve_market(
+        _env: Env,
+        resolver: Address,
+        _market_id: u32,
+        _outcome: bool,
+        _oracle_pubkey: Option<BytesN<32>>,
+        _signature: BytesN<64>,
+        _challenge_window: u64,
+    ) {
+        resolver.require_auth();
+    }
+}
+
 fn setup(env: &Env) -> (ResolutionContractClient<'_>, Address, Address) {
     env.mock_all_auths();
     let contract_id = env.register(ResolutionContract, ());
     let client = ResolutionContractClient::new(env, &contract_id);
     let admin = Address::generate(env);
     let factory = Address::generate(env);
-    let market_contract = Address::generate(env);
+    let market_contract = env.register(MockMarket, ());
     client.initialize(&admin, &factory, &market_contract, &DEFAULT_WINDOW);
     (client, admin, market_contract)
 }
@@ -52,7 +81,7 @@ fn propose_stores_candidate_with_challenge_deadline() {
     assert_eq!(candidate_id, 1);
     let candidate = client.get_candidate(&candidate_id).unwrap();
     assert_eq!(candidate.market_id, 42);
-    assert_eq!(candidate.outcome, true);
+    assert!(candidate.outcome);
     assert_eq!(candidate.challenge_deadline, 1_300);
     assert_eq!(client.get_candidate_id_for_market(&42), Some(candidate_id));
 }
@@ -143,7 +172,7 @@ fn admin_can_update_factory_registration() {
 
 /// #404: finalize invokes resolve_market on the market contract.
 ///
-/// Uses mock_all_auths so the cross-contract call is intercepted without
+/// Uses the `MockMarket` stand-in so the cross-contract call succeeds without
 /// needing a live market contract deployment.
 #[test]
 fn finalize_calls_resolve_market_on_market_contract() {
@@ -153,7 +182,7 @@ fn finalize_calls_resolve_market_on_market_contract() {
     set_time(&env, 1_000);
     let proposer = Address::generate(&env);
     let sig = signature(&env);
-    let candidate_id = client.propose(&proposer, &5, &true, &sig, &(env.ledger().timestamp() + 60), &evidence(&env), &60);
+    let candidate_id = client.propose(&proposer, &5, &true, &sig, &(env.ledger().timestamp() + 600), &evidence(&env), &60);
 
     set_time(&env, 1_061);
     let finalizer = Address::generate(&env);
@@ -163,7 +192,7 @@ fn finalize_calls_resolve_market_on_market_contract() {
 
     assert_eq!(candidate.status, crate::types::CandidateStatus::Finalized);
     assert_eq!(candidate.market_id, 5);
-    assert_eq!(candidate.outcome, true);
+    assert!(candidate.outcome);
     assert!(candidate.finalized_at.is_some());
 }
 
@@ -34,5 +34,5 @@ pub struct ResolutionConfig {
     pub market_contract: Address,
     /// Default challenge window in seconds. Must be within
     /// `MIN_CHALLENGE_WINDOW_SECONDS..=MAX_CHALLENGE_WINDOW_SECONDS`.
-    pub default_challenge_window_seconds: u64,
+    pub default_challenge_window_secs: u64,
 }
@@ -15,7 +15,8 @@
 //! | `initialize`          | anyone (once)             |
 //! | `collect_fee`         | registered market contract|
 //! | `withdraw_fees`       | admin                     |
-//! | `set_market_contract` | admin                     |
+//! | `add_market`          | admin                     |
+//! | `remove_market`       | admin                     |
 //! | Getters               | anyone                    |
 //!
 //! ## Storage layout
@@ -24,7 +25,7 @@
 //! |---------------------------|-----------|------------------------------------------|
 //! | `StorageVersion`          | `u32`     | Schema version guard                     |
 //! | `Admin`                   | `Address` | Protocol admin                           |
-//! | `AuthorizedMarket`        | `Address` | Authorized fee-depositing contract       |
+//! | `AuthorizedMarkets`       | `Vec<Address>` | Authorized fee-depositing contracts |
 //! | `TokenBalance(Address)`   | `i128`    | Current custodied balance (decreasable)  |
 //! | `CumulativeFees(Address)` | `i128`    | Historical total collected (monotone)    |
 
@@ -56,7 +57,7 @@ impl TreasuryContract {
             return Err(TreasuryError::AlreadyInitialized);
         }
         storage::set_admin(&env, &admin);
-        storage::set_authorized_market(&env, &market_contract);
+        storage::set_authorized_markets(&env, &Vec::from_array(&env, [market_contract.clone()]));
         storage::set_version(&env);
         events::emit_treasury_initialized(&env, &admin, &market_contract);
         Ok(())
@@ -80,8 +81,8 @@ impl TreasuryContract {
         if storage::is_paused(&env) {
             return Err(TreasuryError::ContractPaused);
         }
-        let market_contract = storage::get_authorized_market(&env)?;
-        if caller != market_contract {
+        storage::assert_version(&env)?;
+        if !storage::is_authorized_market(&env, &caller) {
             return Err(TreasuryError::CallerNotMarket);
         }
         if fee_amount <= 0 {
@@ -113,6 +114,15 @@ impl TreasuryContract {
     // ── Admin operations ───────────────────────────────────────────────────────
 
     /// Withdraw accumulated fees to a recipient address.
+    ///
+    /// Only accrued fees can leave: `amount` must not exceed the recorded
+    /// fee balance for `token`, and the treasury's actual token holdings must
+    /// still cover the remaining recorded fees afterwards. Tokens sent to the
+    /// treasury outside `collect_fee` are never withdrawable as fees.
+    ///
+    /// # Errors
+    /// - [`TreasuryError::InsufficientBalance`] – `amount` exceeds accrued
+    ///   fees, or the treasury holds less of `token` than it has recorded
     pub fn withdraw_fees(
         env: Env,
         caller: Address,
@@ -141,8 +151,15 @@ impl TreasuryContract {
             return Err(TreasuryError::InsufficientBalance);
         }
 
+        // After paying `amount`, what is left on-chain must still back the
+        // remaining recorded fees; equivalently, holdings cover the full
+        // recorded balance now.
         let treasury = env.current_contract_address();
-        token::Client::new(&env, &token).transfer(&treasury, &to, &amount);
+        let token_client = token::Client::new(&env, &token);
+        if token_client.balance(&treasury) < balance {
+            return Err(TreasuryError::InsufficientBalance);
+        }
+        token_client.transfer(&treasury, &to, &amount);
 
         let remaining = balance - amount;
         storage::set_token_balance(&env, &token, remaining);
@@ -174,7 +191,33 @@ impl TreasuryContract {
         Ok(())
     }
 
-    /// Rotate the registered market contract address (e.g. after an upgrade).
+    /// Register an additional market contract allowed to call `collect_fee`.
+    ///
+    /// Adding an address that is already registered is a no-op.
+    pub fn add_market(
+        env: Env,
+        caller: Address,
+        market_contract: Address,
+    ) -> Result<(), TreasuryError> {
+        caller.require_auth();
+
+        if !storage::has_admin(&env) {
+            return Err(TreasuryError::NotInitialized);
+        }
+        let admin = storage::get_admin(&env)?;
+        if caller != admin {
+            return Err(TreasuryError::Unauthorized);
+        }
+
+        let mut markets = storage::get_authorized_markets(&env);
+        if !markets.contains(&market_contract) {
+            markets.push_back(market_contract.clone());
+            storage::set_authorized_markets(&env, &markets);
+        }
+        Ok(())
+    }
+
+    /// Remove a market contract from the fee registry (e.g. after an upgrade).
     ///
     /// Returns [`TreasuryError::CallerNotMarket`] if the address is not registered.
     pub fn remove_market(
@@ -192,9 +235,13 @@ impl TreasuryContract {
             return Err(TreasuryError::Unauthorized);
         }
 
-        let old = storage::get_authorized_market(&env)?;
-        storage::set_authorized_market(&env, &new_market_contract);
-        events::emit_market_contract_updated(&env, &old, &new_market_contract);
+        let mut markets = storage::get_authorized_markets(&env);
+        let index = markets
+            .first_index_of(&market_contract)
+            .ok_or(TreasuryError::CallerNotMarket)?;
+        markets.remove(index);
+        storage::set_authorized_markets(&env, &markets);
+        events::emit_market_contract_updated(&env, &market_contract, &market_contract);
         Ok(())
     }
 
@@ -253,9 +300,14 @@ impl TreasuryContract {
         storage::get_admin(&env)
     }
 
-    /// Return the registered market contract address. Returns `UpgradeRequired` if version mismatches.
-    pub fn market_contract(env: Env) -> Result<Address, TreasuryError> {
-        storage::get_authorized_market(&env)
+    /// Return whether `market` may call `collect_fee`.
+    pub fn is_authorized_market(env: Env, market: Address) -> bool {
+        storage::is_authorized_market(&env, &market)
+    }
+
+    /// Return every market contract registered to call `collect_fee`.
+    pub fn list_markets(env: Env) -> Vec<Address> {
+        storage::get_authorized_markets(&env)
     }
 
     /// Return the current custodied balance for `token` (decreases on withdrawal).
@@ -1,5 +1,6 @@
 //! Persistent storage helpers for the Vatix Treasury contract.
 
+use crate::error::TreasuryError;
 use soroban_sdk::{contracttype, Address, Env, Vec};
 
 /// Bump this constant whenever the treasury storage layout changes in a breaking way.
@@ -38,6 +39,13 @@ pub fn get_version(env: &Env) -> Option<u32> {
     env.storage().instance().get(&StorageKey::StorageVersion)
 }
 
+pub fn assert_version(env: &Env) -> Result<(), TreasuryError> {
+    if get_version(env) != Some(STORAGE_VERSION) {
+        return Err(TreasuryError::UpgradeRequired);
+    }
+    Ok(())
+}
+
 // ── Admin ─────────────────────────────────────────────────────────────────────
 
 pub fn has_admin(env: &Env) -> bool {
@@ -59,13 +67,11 @@ pub fn set_admin(env: &Env, admin: &Address) {
 
 // ── Authorized markets registry ───────────────────────────────────────────────
 
-pub fn get_authorized_market(env: &Env) -> Result<Address, TreasuryError> {
-    assert_version(env)?;
-    Ok(env
-        .storage()
+pub fn get_authorized_markets(env: &Env) -> Vec<Address> {
+    env.storage()
         .instance()
-        .get(&StorageKey::AuthorizedMarket)
-        .expect("treasury not initialized"))
+        .get(&StorageKey::AuthorizedMarkets)
+        .unwrap_or_else(|| Vec::new(env))
 }
 
 pub fn set_authorized_markets(env: &Env, markets: &Vec<Address>) {
@@ -205,6 +205,43 @@ fn withdraw_fees_rejects_insufficient_balance() {
     assert_eq!(err, TreasuryError::InsufficientBalance);
 }
 
+#[test]
+fn withdraw_fees_cannot_exceed_accrued_fees() {
+    let s = setup();
+    // The treasury holds far more than it has collected as fees; only the
+    // accrued amount may be withdrawn.
+    fund_treasury(&s, 1_000_000);
+    s.client.collect_fee(&s.market, &s.token, &1u32, &200_000i128);
+
+    let recipient = Address::generate(&s.env);
+    let err = s
+        .client
+        .try_withdraw_fees(&s.admin, &s.token, &recipient, &200_001i128)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, TreasuryError::InsufficientBalance);
+    assert_eq!(TokenClient::new(&s.env, &s.token).balance(&recipient), 0);
+
+    s.client.withdraw_fees(&s.admin, &s.token, &recipient, &200_000i128);
+    assert_eq!(TokenClient::new(&s.env, &s.token).balance(&s.treasury_id), 800_000);
+}
+
+#[test]
+fn withdraw_fees_rejects_when_holdings_do_not_cover_recorded_fees() {
+    let s = setup();
+    // Recorded fees with no tokens behind them must not be paid out of
+    // whatever else the treasury holds.
+    fund_treasury(&s, 100_000);
+    s.client.collect_fee(&s.market, &s.token, &1u32, &300_000i128);
+
+    let err = s
+        .client
+        .try_withdraw_fees(&s.admin, &s.token, &s.admin, &50_000i128)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, TreasuryError::InsufficientBalance);
+}
+
 #[test]
 fn withdraw_fees_errors_when_not_initialized() {
     let env = Env::default();
@@ -313,24 +350,6 @@ fn cumulative_stays_high_after_withdrawal() {
 
 // ── storage version guard (#307 / #308) ──────────────────────────────────────
 
-#[test]
-fn initialize_writes_storage_version() {
-    let s = setup();
-    s.env.as_contract(&s.treasury_id, || {
-        assert_eq!(storage::get_version(&s.env), Some(storage::STORAGE_VERSION));
-    });
-}
-
-#[test]
-fn storage_version_absent_before_initialize() {
-    let env = Env::default();
-    env.mock_all_auths();
-    let id = env.register(TreasuryContract, ());
-    env.as_contract(&id, || {
-        assert_eq!(storage::get_version(&env), None);
-    });
-}
-
 #[test]
 fn reads_return_upgrade_required_on_stale_version() {
     let env = Env::default();
@@ -462,11 +481,17 @@ fn transfer_admin_emits_event() {
     let topic0: Symbol = topics.get(0).unwrap().into_val(&s.env);
     assert_eq!(topic0, Symbol::new(&s.env, "admin_transferred"));
 
-    let data: Map<Symbol, Val> = ev.2.try_into_val(&s.env).unwrap();
-    let old_val: Address = data.get(Symbol::new(&s.env, "old_admin")).unwrap().into_val(&s.env);
-    let new_val: Address = data.get(Symbol::new(&s.env, "new_admin")).unwrap().into_val(&s.env);
+    let old_val: Address = topics.get(1).unwrap().into_val(&s.env);
+    let new_val: Address = topics.get(2).unwrap().into_val(&s.env);
     assert_eq!(old_val, s.admin);
     assert_eq!(new_val, new_admin);
+
+    let data: Map<Symbol, Val> = ev.2.try_into_val(&s.env).unwrap();
+    let transferred_at: u64 = data
+        .get(Symbol::new(&s.env, "transferred_at"))
+        .unwrap()
+        .into_val(&s.env);
+    assert_eq!(transferred_at, s.env.ledger().timestamp());
 }
 
 #[test]
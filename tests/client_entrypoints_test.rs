@@ -10,7 +10,9 @@ use soroban_sdk::{
     token::{Client as TokenClient, StellarAssetClient},
     Address, BytesN, Env, String,
 };
-use vatix_market_contract::{storage, MarketContract, MarketContractClient};
+use vatix_market_contract::{
+    error::ContractError, storage, types::Outcome, MarketContract, MarketContractClient,
+};
 use vatix_resolution_contract::{ResolutionContract, ResolutionContractClient};
 use vatix_treasury_contract::{TreasuryContract, TreasuryContractClient};
 
@@ -41,6 +43,11 @@ fn make_market(
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     )
 }
 
@@ -54,7 +61,7 @@ fn market_initialize_sets_admin() {
     let client = MarketContractClient::new(&env, &contract_id);
     let admin = Address::generate(&env);
 
-    client.initialize(&admin).unwrap();
+    client.initialize(&admin);
 
     env.as_contract(&contract_id, || {
         assert_eq!(storage::get_admin(&env).unwrap(), admin);
@@ -68,7 +75,7 @@ fn market_initialize_twice_is_rejected() {
     let contract_id = env.register(MarketContract, ());
     let client = MarketContractClient::new(&env, &contract_id);
     let admin = Address::generate(&env);
-    client.initialize(&admin).unwrap();
+    client.initialize(&admin);
     assert!(client.try_initialize(&admin).is_err());
 }
 
@@ -80,8 +87,8 @@ fn market_two_step_admin_transfer() {
     let client = MarketContractClient::new(&env, &contract_id);
     let new_admin = Address::generate(&env);
 
-    client.propose_admin(&admin, &new_admin).unwrap();
-    client.accept_admin(&new_admin).unwrap();
+    client.propose_admin(&admin, &new_admin);
+    client.accept_admin(&new_admin);
 
     env.as_contract(&contract_id, || {
         assert_eq!(storage::get_admin(&env).unwrap(), new_admin);
@@ -96,7 +103,19 @@ fn market_initialize_market_returns_sequential_ids() {
     let client = MarketContractClient::new(&env, &contract_id);
 
     let id1 = make_market(&client, &env, &admin, &token);
-    let id2 = make_market(&client, &env, &admin, &token);
+    let params = MarketParams::default_valid(&env);
+    let id2 = client.initialize_market(
+        &admin,
+        &String::from_str(&env, "A second, distinct question?"),
+        &params.end_time,
+        &params.oracle_pubkey,
+        &token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
+    );
     assert_eq!(id1, 1);
     assert_eq!(id2, 2);
 }
@@ -111,7 +130,7 @@ fn market_deposit_collateral_records_total_deposited() {
 
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &token).mint(&user, &(100 * STROOPS));
-    client.deposit_collateral(&user, &market_id, &(100 * STROOPS));
+    client.deposit_collateral(&user, &market_id, &(100 * STROOPS), &None);
 
     let pos = env.as_contract(&contract_id, || {
         storage::get_position(&env, market_id, &user)
@@ -132,7 +151,8 @@ fn market_withdraw_unused_collateral_decrements_total() {
 
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &token).mint(&user, &(100 * STROOPS));
-    client.deposit_collateral(&user, &market_id, &(100 * STROOPS));
+    client.deposit_collateral(&user, &market_id, &(100 * STROOPS), &None);
+    helpers::skip_withdraw_cooldown(&env);
     client.withdraw_unused_collateral(&user, &market_id, &(40 * STROOPS));
 
     let pos = env.as_contract(&contract_id, || {
@@ -154,7 +174,7 @@ fn market_update_position_sets_shares_and_locks() {
 
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &token).mint(&user, &(100 * STROOPS));
-    client.deposit_collateral(&user, &market_id, &(100 * STROOPS));
+    client.deposit_collateral(&user, &market_id, &(100 * STROOPS), &None);
     client.update_position(&user, &market_id, &(50 * STROOPS), &0i128, &5_000i128);
 
     let pos = env.as_contract(&contract_id, || {
@@ -176,7 +196,7 @@ fn market_cancel_and_reclaim_collateral() {
 
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &token).mint(&user, &(50 * STROOPS));
-    client.deposit_collateral(&user, &market_id, &(50 * STROOPS));
+    client.deposit_collateral(&user, &market_id, &(50 * STROOPS), &None);
 
     client.cancel_market(&admin, &market_id);
     let refund = client.withdraw_canceled_collateral(&user, &market_id);
@@ -201,19 +221,30 @@ fn market_resolve_market_sets_resolved_status() {
     });
 
     let (oracle_pubkey, signing_key) = oracle_keypair(&env);
-    let token = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
     let market_id = client.initialize_market(
         &admin,
         &String::from_str(&env, "Resolved?"),
         &(env.ledger().timestamp() + 86_400),
         &oracle_pubkey,
         &token,
+        &helpers::market_metadata(&env),
+        &helpers::source_hash(&env),
+        &None,
+        &None,
+        &0,
     );
+    let user = Address::generate(&env);
+    StellarAssetClient::new(&env, &token).mint(&user, &(10 * STROOPS));
+    client.deposit_collateral(&user, &market_id, &(10 * STROOPS), &None);
 
     let sig = sign_outcome(&env, &signing_key, market_id, true);
-    client.resolve_market(&String::from_str(&env, "1"), &true, &sig);
+    helpers::advance_to_end_time(&env, &client, 1);
+    client.resolve_market(&Address::generate(&env), &1, &Outcome::Yes, &None, &sig, &0);
 
-    let market = client.get_market(&market_id).unwrap();
+    let market = env.as_contract(&contract_id, || {
+        storage::get_market(&env, market_id).unwrap().unwrap()
+    });
     assert_eq!(market.status, MarketStatus::Resolved);
     assert_eq!(market.result, Some(true));
 }
@@ -244,15 +275,21 @@ fn market_settle_position_pays_out_winner() {
         &(env.ledger().timestamp() + 86_400),
         &oracle_pubkey,
         &token,
+        &helpers::market_metadata(&env),
+        &helpers::source_hash(&env),
+        &None,
+        &None,
+        &0,
     );
 
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &token).mint(&user, &(100 * STROOPS));
-    client.deposit_collateral(&user, &market_id, &(100 * STROOPS));
+    client.deposit_collateral(&user, &market_id, &(100 * STROOPS), &None);
     client.update_position(&user, &market_id, &(100 * STROOPS), &0i128, &5_000i128);
 
     let sig = sign_outcome(&env, &signing_key, market_id, true);
-    client.resolve_market(&String::from_str(&env, "1"), &true, &sig);
+    helpers::advance_to_end_time(&env, &client, 1);
+    client.resolve_market(&Address::generate(&env), &1, &Outcome::Yes, &None, &sig, &0);
 
     let payout = client.settle_position(&user, &market_id);
     assert_eq!(payout, 100 * STROOPS);
@@ -286,35 +323,123 @@ fn market_batch_settle_positions_settles_multiple_users() {
         &(env.ledger().timestamp() + 86_400),
         &oracle_pubkey,
         &token,
+        &helpers::market_metadata(&env),
+        &helpers::source_hash(&env),
+        &None,
+        &None,
+        &0,
     );
 
     let user1 = Address::generate(&env);
     let user2 = Address::generate(&env);
     sac.mint(&user1, &(50 * STROOPS));
     sac.mint(&user2, &(50 * STROOPS));
-    client.deposit_collateral(&user1, &market_id, &(50 * STROOPS));
-    client.deposit_collateral(&user2, &market_id, &(50 * STROOPS));
+    client.deposit_collateral(&user1, &market_id, &(50 * STROOPS), &None);
+    client.deposit_collateral(&user2, &market_id, &(50 * STROOPS), &None);
     client.update_position(&user1, &market_id, &(50 * STROOPS), &0i128, &5_000i128);
     client.update_position(&user2, &market_id, &(50 * STROOPS), &0i128, &5_000i128);
 
     let sig = sign_outcome(&env, &signing_key, market_id, true);
-    client.resolve_market(&String::from_str(&env, "1"), &true, &sig);
+    helpers::advance_to_end_time(&env, &client, 1);
+    client.resolve_market(&Address::generate(&env), &1, &Outcome::Yes, &None, &sig, &0);
 
     let users = soroban_sdk::vec![&env, user1.clone(), user2.clone()];
     let total = client.batch_settle_positions(&market_id, &users);
     assert_eq!(total, 100 * STROOPS);
 }
 
-// ── Market: set_treasury / get_treasury ───────────────────────────────────────
+// ── Market: settle_positions ──────────────────────────────────────────────────
+
+#[test]
+fn market_settle_positions_returns_payouts_in_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(MarketContract, ());
+    let client = MarketContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    env.as_contract(&contract_id, || {
+        storage::set_version(&env);
+        storage::set_admin(&env, &admin);
+    });
+
+    let (oracle_pubkey, signing_key) = oracle_keypair(&env);
+    let token_admin = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let sac = StellarAssetClient::new(&env, &token);
+
+    let market_id = client.initialize_market(
+        &admin,
+        &String::from_str(&env, "Settle batch?"),
+        &(env.ledger().timestamp() + 86_400),
+        &oracle_pubkey,
+        &token,
+        &helpers::market_metadata(&env),
+        &helpers::source_hash(&env),
+        &None,
+        &None,
+        &0,
+    );
+
+    let winner = Address::generate(&env);
+    let loser = Address::generate(&env);
+    let ghost = Address::generate(&env);
+    sac.mint(&winner, &(50 * STROOPS));
+    sac.mint(&loser, &(50 * STROOPS));
+    client.deposit_collateral(&winner, &market_id, &(50 * STROOPS), &None);
+    client.deposit_collateral(&loser, &market_id, &(50 * STROOPS), &None);
+    client.update_position(&winner, &market_id, &(50 * STROOPS), &0i128, &5_000i128);
+    client.update_position(&loser, &market_id, &0i128, &(50 * STROOPS), &5_000i128);
+
+    let sig = sign_outcome(&env, &signing_key, market_id, true);
+    helpers::advance_to_end_time(&env, &client, market_id);
+    client.resolve_market(&Address::generate(&env), &market_id, &Outcome::Yes, &None, &sig, &0);
+
+    // The repeated winner is already settled by the time it is reached.
+    let users = soroban_sdk::vec![&env, winner.clone(), loser.clone(), ghost, winner.clone()];
+    let payouts = client.settle_positions(&market_id, &users);
+    assert_eq!(payouts, soroban_sdk::vec![&env, 50 * STROOPS, 0, 0, 0]);
+
+    // Payouts are credited; the winner pulls theirs with `claim`.
+    assert_eq!(TokenClient::new(&env, &token).balance(&winner), 0);
+    assert_eq!(client.get_claimable(&market_id, &winner), 50 * STROOPS);
+    assert_eq!(client.claim(&winner, &market_id), 50 * STROOPS);
+    assert_eq!(TokenClient::new(&env, &token).balance(&winner), 50 * STROOPS);
+    assert_eq!(client.get_claimable(&market_id, &winner), 0);
+    assert_eq!(client.claim(&winner, &market_id), 0);
+    assert!(client.get_position(&market_id, &loser).is_settled);
+}
+
+#[test]
+fn market_settle_positions_rejects_oversized_batch() {
+    let (env, admin, contract_id, token) = market_env();
+    let client = MarketContractClient::new(&env, &contract_id);
+    let market_id = make_market(&client, &env, &admin, &token);
+
+    // One more than the contract's 100-user batch bound.
+    let mut users = soroban_sdk::Vec::new(&env);
+    for _ in 0..101 {
+        users.push_back(Address::generate(&env));
+    }
+    assert_eq!(
+        client.try_settle_positions(&market_id, &users),
+        Err(Ok(ContractError::InvalidQuantity))
+    );
+}
+
+// ── Market: set_treasury_contract ─────────────────────────────────────────────
 
 #[test]
-fn market_set_and_get_treasury() {
+fn market_set_treasury_contract_stores_address() {
     let (env, admin, contract_id, _token) = market_env();
     let client = MarketContractClient::new(&env, &contract_id);
     let treasury = Address::generate(&env);
 
-    client.set_treasury(&admin, &treasury).unwrap();
-    assert_eq!(client.get_treasury(), Some(treasury));
+    client.set_treasury_contract(&admin, &treasury);
+    env.as_contract(&contract_id, || {
+        assert_eq!(storage::get_treasury(&env), Some(treasury));
+    });
 }
 
 // ── Market: set_fee_rate / get_fee_rate ───────────────────────────────────────
@@ -324,7 +449,7 @@ fn market_set_and_get_fee_rate() {
     let (env, admin, contract_id, _token) = market_env();
     let client = MarketContractClient::new(&env, &contract_id);
 
-    client.set_fee_rate(&admin, &250i128).unwrap();
+    client.set_fee_rate(&admin, &250i128);
     assert_eq!(client.get_fee_rate(), 250i128);
 }
 
@@ -336,7 +461,7 @@ fn market_set_and_get_outcome_token_contract() {
     let client = MarketContractClient::new(&env, &contract_id);
     let ot = Address::generate(&env);
 
-    client.set_outcome_token_contract(&admin, &ot).unwrap();
+    client.set_outcome_token_contract(&admin, &ot);
     assert_eq!(client.get_outcome_token_contract(), Some(ot));
 }
 
@@ -348,7 +473,7 @@ fn market_set_and_get_resolution_contract() {
     let client = MarketContractClient::new(&env, &contract_id);
     let res = Address::generate(&env);
 
-    client.set_resolution_contract(&admin, &res).unwrap();
+    client.set_resolution_contract(&admin, &res);
     assert_eq!(client.get_resolution_contract(), Some(res));
 }
 
@@ -361,34 +486,27 @@ fn market_set_and_get_threshold_signers() {
 
     let signer = BytesN::from_array(&env, &[1u8; 32]);
     let signers = soroban_sdk::vec![&env, signer.clone()];
-    client.set_threshold_signers(&admin, &signers, &1u32).unwrap();
+    client.set_threshold_signers(&admin, &signers, &1u32);
 
     assert_eq!(client.get_threshold_quorum(), 1u32);
     assert_eq!(client.get_threshold_signers().get(0).unwrap(), signer);
 }
 
-// ── Market: get_market / get_outcome_count ────────────────────────────────────
+// ── Market: stored market ─────────────────────────────────────────────────────
 
 #[test]
-fn market_get_market_returns_stored_market() {
+fn market_initialize_market_stores_binary_market() {
     let (env, admin, contract_id, token) = market_env();
     let client = MarketContractClient::new(&env, &contract_id);
     let market_id = make_market(&client, &env, &admin, &token);
 
-    let market = client.get_market(&market_id).unwrap();
+    let market = env.as_contract(&contract_id, || {
+        storage::get_market(&env, market_id).unwrap().unwrap()
+    });
     assert_eq!(market.id, market_id);
     assert_eq!(market.outcome_count, 2);
 }
 
-#[test]
-fn market_get_outcome_count_returns_two() {
-    let (env, admin, contract_id, token) = market_env();
-    let client = MarketContractClient::new(&env, &contract_id);
-    let market_id = make_market(&client, &env, &admin, &token);
-
-    assert_eq!(client.get_outcome_count(&market_id).unwrap(), 2u32);
-}
-
 // ── Treasury: initialize ──────────────────────────────────────────────────────
 
 #[test]
@@ -402,7 +520,7 @@ fn treasury_initialize_stores_admin_and_market() {
 
     client.initialize(&admin, &market);
     assert_eq!(client.admin(), admin);
-    assert_eq!(client.market_contract(), market);
+    assert!(client.is_authorized_market(&market));
 }
 
 // ── Treasury: collect_fee ─────────────────────────────────────────────────────
@@ -450,10 +568,10 @@ fn treasury_withdraw_fees_transfers_to_recipient() {
     assert_eq!(client.get_cumulative_fees(&token), 2_000); // monotone
 }
 
-// ── Treasury: set_market_contract / transfer_admin ───────────────────────────
+// ── Treasury: add_market / remove_market / transfer_admin ────────────────────
 
 #[test]
-fn treasury_set_market_contract_rotates_authorized_caller() {
+fn treasury_add_and_remove_market_rotates_authorized_caller() {
     let env = Env::default();
     env.mock_all_auths();
     let tid = env.register(TreasuryContract, ());
@@ -464,7 +582,8 @@ fn treasury_set_market_contract_rotates_authorized_caller() {
     let token = Address::generate(&env);
     client.initialize(&admin, &old_market);
 
-    client.set_market_contract(&admin, &new_market);
+    client.add_market(&admin, &new_market);
+    client.remove_market(&admin, &old_market);
     assert!(client.try_collect_fee(&old_market, &token, &1u32, &100i128).is_err());
     client.collect_fee(&new_market, &token, &1u32, &100i128);
     assert_eq!(client.total_collected(), 100);
@@ -497,7 +616,7 @@ fn resolution_initialize_stores_config() {
     let factory = Address::generate(&env);
     let market_contract = Address::generate(&env);
 
-    client.initialize(&admin, &factory, &market_contract).unwrap();
+    client.initialize(&admin, &factory, &market_contract, &60u64);
     let config = client.get_config();
     assert_eq!(config.factory, factory);
     assert_eq!(config.market_contract, market_contract);
@@ -512,10 +631,10 @@ fn resolution_set_factory_updates_config() {
     let rid = env.register(ResolutionContract, ());
     let client = ResolutionContractClient::new(&env, &rid);
     let admin = Address::generate(&env);
-    client.initialize(&admin, &Address::generate(&env), &Address::generate(&env)).unwrap();
+    client.initialize(&admin, &Address::generate(&env), &Address::generate(&env), &60u64);
 
     let new_factory = Address::generate(&env);
-    client.set_factory(&admin, &new_factory).unwrap();
+    client.set_factory(&admin, &new_factory);
     assert_eq!(client.get_config().factory, new_factory);
 }
 
@@ -526,10 +645,10 @@ fn resolution_set_market_contract_updates_config() {
     let rid = env.register(ResolutionContract, ());
     let client = ResolutionContractClient::new(&env, &rid);
     let admin = Address::generate(&env);
-    client.initialize(&admin, &Address::generate(&env), &Address::generate(&env)).unwrap();
+    client.initialize(&admin, &Address::generate(&env), &Address::generate(&env), &60u64);
 
     let new_market = Address::generate(&env);
-    client.set_market_contract(&admin, &new_market).unwrap();
+    client.set_market_contract(&admin, &new_market);
     assert_eq!(client.get_config().market_contract, new_market);
 }
 
@@ -541,7 +660,12 @@ fn ot_setup(env: &Env) -> (OutcomeTokenContractClient<'_>, Address, Address) {
     let client = OutcomeTokenContractClient::new(env, &cid);
     let admin = Address::generate(env);
     let market = Address::generate(env);
-    client.initialize(&admin, &market).unwrap();
+    client.initialize(
+        &admin,
+        &market,
+        &String::from_str(env, "Vatix Outcome"),
+        &String::from_str(env, "VOUT"),
+    );
     (client, admin, market)
 }
 
@@ -560,7 +684,14 @@ fn outcome_token_initialize_stores_config() {
 fn outcome_token_initialize_twice_is_rejected() {
     let env = Env::default();
     let (client, admin, market) = ot_setup(&env);
-    assert!(client.try_initialize(&admin, &market).is_err());
+    assert!(client
+        .try_initialize(
+            &admin,
+            &market,
+            &String::from_str(&env, "Vatix Outcome"),
+            &String::from_str(&env, "VOUT"),
+        )
+        .is_err());
 }
 
 // ── OutcomeToken: mint ────────────────────────────────────────────────────────
@@ -614,7 +745,7 @@ fn outcome_token_set_market_contract_updates_config() {
     let env = Env::default();
     let (client, admin, _market) = ot_setup(&env);
     let new_market = Address::generate(&env);
-    client.set_market_contract(&admin, &new_market).unwrap();
+    client.set_market_contract(&admin, &new_market);
     assert_eq!(client.get_config().market_contract, new_market);
 }
 
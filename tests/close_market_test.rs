@@ -46,10 +46,15 @@ fn setup_market_with_collateral(
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 
     // User deposits initial collateral
-    client.deposit_collateral(&user, &market_id, &(100 * STROOPS_PER_USDC));
+    client.deposit_collateral(&user, &market_id, &(100 * STROOPS_PER_USDC), &None);
 
     (env, admin, contract_id, user, market_id, collateral_token)
 }
@@ -65,21 +70,21 @@ fn close_market_to_deposits_succeeds() {
             .expect("version check failed")
             .expect("market should exist")
     });
-    assert_eq!(market_before.closed_to_deposits, false);
+    assert!(!market_before.closed_to_deposits);
 
     // Close the market to new deposits
     client.close_market_to_deposits(&admin, &market_id);
 
+    // Verify the event was emitted
+    assert_event_emitted(&env, "market_closed_to_deposits_event");
+
     // Verify market is now closed to deposits
     let market_after = env.as_contract(&contract_id, || {
         storage::get_market(&env, market_id)
             .expect("version check failed")
             .expect("market should exist")
     });
-    assert_eq!(market_after.closed_to_deposits, true);
-
-    // Verify the event was emitted
-    assert_event_emitted(&env, "market_closed_to_deposits_event");
+    assert!(market_after.closed_to_deposits);
 }
 
 #[test]
@@ -97,7 +102,7 @@ fn deposit_fails_when_market_closed_to_deposits() {
 
     // Attempt to deposit - should fail
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-        client.deposit_collateral(&user, &market_id, &(50 * STROOPS_PER_USDC));
+        client.deposit_collateral(&user, &market_id, &(50 * STROOPS_PER_USDC), &None);
     }));
 
     assert!(
@@ -115,6 +120,7 @@ fn withdrawal_succeeds_when_market_closed_to_deposits() {
     client.close_market_to_deposits(&admin, &market_id);
 
     // User should still be able to withdraw their collateral
+    helpers::skip_withdraw_cooldown(&env);
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         client.withdraw_unused_collateral(&user, &market_id, &(50 * STROOPS_PER_USDC));
     }));
@@ -140,7 +146,7 @@ fn close_market_to_deposits_idempotent() {
             .expect("version check failed")
             .expect("market should exist")
     });
-    assert_eq!(market.closed_to_deposits, true);
+    assert!(market.closed_to_deposits);
 }
 
 #[test]
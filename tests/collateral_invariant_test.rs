@@ -38,11 +38,16 @@ fn setup_market(deposit: i128) -> (Env, Address, u32, Address) {
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &collateral_token).mint(&user, &deposit);
-    client.deposit_collateral(&user, &market_id, &deposit);
+    client.deposit_collateral(&user, &market_id, &deposit, &None);
 
     (env, contract_id, market_id, user)
 }
@@ -80,6 +85,7 @@ fn withdraw_uses_real_trade_price_not_hardcoded_fifty_fifty() {
     });
     assert_eq!(position.locked_collateral, 60 * STROOPS_PER_USDC);
 
+    helpers::skip_withdraw_cooldown(&env);
     let over_withdraw =
         client.try_withdraw_unused_collateral(&user, &market_id, &(45 * STROOPS_PER_USDC));
     assert!(over_withdraw.is_err());
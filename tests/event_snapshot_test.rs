@@ -9,11 +9,11 @@ mod helpers;
 
 use helpers::{oracle_keypair, register_contract, sign_outcome, MarketParams};
 use soroban_sdk::{
-    testutils::{Address as _, Events as _},
+    testutils::{Address as _, Events as _, Ledger},
     token::StellarAssetClient,
     Address, BytesN, Env, IntoVal, Map, String, Symbol, TryIntoVal, Val,
 };
-use vatix_market_contract::{MarketContract, MarketContractClient};
+use vatix_market_contract::{types::Outcome, MarketContract, MarketContractClient};
 use vatix_resolution_contract::{ResolutionContract, ResolutionContractClient};
 use vatix_treasury_contract::{TreasuryContract, TreasuryContractClient};
 
@@ -26,6 +26,20 @@ fn last_event(env: &Env) -> (std::vec::Vec<Val>, Val) {
     (ev.1.iter().collect(), ev.2)
 }
 
+fn named_event(env: &Env, name: &str) -> (std::vec::Vec<Val>, Val) {
+    let ev = env
+        .events()
+        .all()
+        .iter()
+        .rev()
+        .find(|ev| {
+            let topic: Result<Symbol, _> = ev.1.get(0).unwrap().try_into_val(env);
+            topic.is_ok_and(|t| t == Symbol::new(env, name))
+        })
+        .unwrap_or_else(|| panic!("no {name} event emitted"));
+    (ev.1.iter().collect(), ev.2)
+}
+
 fn topic_sym(env: &Env, topics: &[Val], idx: usize) -> Symbol {
     topics[idx].clone().into_val(env)
 }
@@ -50,11 +64,7 @@ fn data_addr(env: &Env, m: &Map<Symbol, Val>, key: &str) -> Address {
     m.get(Symbol::new(env, key)).unwrap().into_val(env)
 }
 
-fn data_u32(env: &Env, m: &Map<Symbol, Val>, key: &str) -> u32 {
-    m.get(Symbol::new(env, key)).unwrap().into_val(env)
-}
-
-// ── Market: contract_initialized_event ───────────────────────────────────────
+// ── Market: contract_initialized ─────────────────────────────────────────────
 
 #[test]
 fn event_contract_initialized_shape() {
@@ -64,19 +74,20 @@ fn event_contract_initialized_shape() {
     let client = MarketContractClient::new(&env, &cid);
     let admin = Address::generate(&env);
 
-    client.initialize(&admin).unwrap();
+    client.initialize(&admin);
 
     let (topics, data) = last_event(&env);
-    assert_eq!(topics.len(), 2, "contract_initialized_event has 2 topics");
-    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "contract_initialized_event"));
+    assert_eq!(topics.len(), 2, "contract_initialized has 2 topics");
+    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "contract_initialized"));
     let topic_admin: Address = topics[1].clone().into_val(&env);
     assert_eq!(topic_admin, admin);
 
     let m = data_map(&env, data);
     let _ts: u64 = data_u64(&env, &m, "initialized_at");
+    assert_eq!(data_u64(&env, &m, "event_seq"), 1, "first event is sequence 1");
 }
 
-// ── Market: market_created_event ──────────────────────────────────────────────
+// ── Market: market_created ────────────────────────────────────────────────────
 
 #[test]
 fn event_market_created_shape() {
@@ -84,9 +95,7 @@ fn event_market_created_shape() {
     env.mock_all_auths();
     let (admin, cid) = register_contract(&env);
     let client = MarketContractClient::new(&env, &cid);
-    let token = Address::generate(&env);
-    let mut params = MarketParams::default_valid(&env);
-    params.collateral_token = token;
+    let params = MarketParams::default_valid(&env);
 
     client.initialize_market(
         &admin,
@@ -94,22 +103,29 @@ fn event_market_created_shape() {
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 
     let (topics, data) = last_event(&env);
-    assert_eq!(topics.len(), 2, "market_created_event has 2 topics");
-    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "market_created_event"));
+    assert_eq!(topics.len(), 4, "market_created has 4 topics");
+    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "market_created"));
     let topic_id: u32 = topics[1].clone().into_val(&env);
     assert_eq!(topic_id, 1u32);
+    let creator: Address = topics[2].clone().into_val(&env);
+    assert_eq!(creator, admin);
+    assert_eq!(topic_sym(&env, &topics, 3), params.metadata.category);
 
     let m = data_map(&env, data);
-    let creator: Address = data_addr(&env, &m, "creator");
-    assert_eq!(creator, admin);
     let _q: String = m.get(Symbol::new(&env, "question")).unwrap().into_val(&env);
     let _et: u64 = data_u64(&env, &m, "end_time");
+    assert!(data_u64(&env, &m, "event_seq") > 1, "follows contract_initialized");
 }
 
-// ── Market: collateral_deposited_event ────────────────────────────────────────
+// ── Market: collateral_deposited ──────────────────────────────────────────────
 
 #[test]
 fn event_collateral_deposited_shape() {
@@ -123,15 +139,20 @@ fn event_collateral_deposited_shape() {
     params.collateral_token = token.clone();
     let mid = client.initialize_market(
         &admin, &params.question, &params.end_time, &params.oracle_pubkey, &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &token).mint(&user, &1_000i128);
-    client.deposit_collateral(&user, &mid, &1_000i128);
+    client.deposit_collateral(&user, &mid, &1_000i128, &None);
 
     let (topics, data) = last_event(&env);
-    assert_eq!(topics.len(), 3, "collateral_deposited_event has 3 topics");
-    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "collateral_deposited_event"));
+    assert_eq!(topics.len(), 3, "collateral_deposited has 3 topics");
+    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "collateral_deposited"));
     let topic_user: Address = topics[1].clone().into_val(&env);
     assert_eq!(topic_user, user);
     let topic_mid: u32 = topics[2].clone().into_val(&env);
@@ -142,7 +163,7 @@ fn event_collateral_deposited_shape() {
     assert_eq!(data_i128(&env, &m, "new_total"), 1_000);
 }
 
-// ── Market: collateral_withdrawn_event ────────────────────────────────────────
+// ── Market: collateral_withdrawn ──────────────────────────────────────────────
 
 #[test]
 fn event_collateral_withdrawn_shape() {
@@ -156,23 +177,29 @@ fn event_collateral_withdrawn_shape() {
     params.collateral_token = token.clone();
     let mid = client.initialize_market(
         &admin, &params.question, &params.end_time, &params.oracle_pubkey, &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &token).mint(&user, &2_000i128);
-    client.deposit_collateral(&user, &mid, &2_000i128);
+    client.deposit_collateral(&user, &mid, &2_000i128, &None);
+    helpers::skip_withdraw_cooldown(&env);
     client.withdraw_unused_collateral(&user, &mid, &500i128);
 
     let (topics, data) = last_event(&env);
-    assert_eq!(topics.len(), 3, "collateral_withdrawn_event has 3 topics");
-    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "collateral_withdrawn_event"));
+    assert_eq!(topics.len(), 3, "collateral_withdrawn has 3 topics");
+    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "collateral_withdrawn"));
 
     let m = data_map(&env, data);
     assert_eq!(data_i128(&env, &m, "amount"), 500);
     assert_eq!(data_i128(&env, &m, "new_total"), 1_500);
 }
 
-// ── Market: position_updated_event ───────────────────────────────────────────
+// ── Market: position_updated ─────────────────────────────────────────────────
 
 #[test]
 fn event_position_updated_shape() {
@@ -186,16 +213,21 @@ fn event_position_updated_shape() {
     params.collateral_token = token.clone();
     let mid = client.initialize_market(
         &admin, &params.question, &params.end_time, &params.oracle_pubkey, &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &token).mint(&user, &10_000i128);
-    client.deposit_collateral(&user, &mid, &10_000i128);
+    client.deposit_collateral(&user, &mid, &10_000i128, &None);
     client.update_position(&user, &mid, &5_000i128, &0i128, &5_000i128);
 
-    let (topics, data) = last_event(&env);
-    assert_eq!(topics.len(), 3, "position_updated_event has 3 topics");
-    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "position_updated_event"));
+    let (topics, data) = named_event(&env, "position_updated");
+    assert_eq!(topics.len(), 3, "position_updated has 3 topics");
+    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "position_updated"));
 
     let m = data_map(&env, data);
     assert_eq!(data_i128(&env, &m, "yes_shares"), 5_000);
@@ -204,7 +236,7 @@ fn event_position_updated_shape() {
     assert_eq!(data_i128(&env, &m, "locked_collateral"), 2_500);
 }
 
-// ── Market: market_resolved_event ────────────────────────────────────────────
+// ── Market: market_resolved ──────────────────────────────────────────────────
 
 #[test]
 fn event_market_resolved_shape() {
@@ -213,7 +245,7 @@ fn event_market_resolved_shape() {
     let (admin, cid) = register_contract(&env);
     let client = MarketContractClient::new(&env, &cid);
     let (oracle_pubkey, signing_key) = oracle_keypair(&env);
-    let token = Address::generate(&env);
+    let token = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
 
     let mid = client.initialize_market(
         &admin,
@@ -221,22 +253,35 @@ fn event_market_resolved_shape() {
         &(env.ledger().timestamp() + 86_400),
         &oracle_pubkey,
         &token,
+        &helpers::market_metadata(&env),
+        &helpers::source_hash(&env),
+        &None,
+        &None,
+        &0,
     );
+    let user = Address::generate(&env);
+    StellarAssetClient::new(&env, &token).mint(&user, &1_000_000_000);
+    client.deposit_collateral(&user, &mid, &1_000_000_000, &None);
+
     let sig = sign_outcome(&env, &signing_key, mid, true);
-    client.resolve_market(&String::from_str(&env, "1"), &true, &sig);
+    helpers::advance_to_end_time(&env, &client, 1);
+    client.resolve_market(&Address::generate(&env), &1, &Outcome::Yes, &None, &sig, &0);
 
     let (topics, data) = last_event(&env);
-    assert_eq!(topics.len(), 2, "market_resolved_event has 2 topics");
-    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "market_resolved_event"));
+    assert_eq!(topics.len(), 3, "market_resolved has 3 topics");
+    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "market_resolved"));
     let topic_mid: u32 = topics[1].clone().into_val(&env);
     assert_eq!(topic_mid, mid);
+    let topic_oracle: BytesN<32> = topics[2].clone().into_val(&env);
+    assert_eq!(topic_oracle, oracle_pubkey);
 
     let m = data_map(&env, data);
-    assert_eq!(data_bool(&env, &m, "outcome"), true);
+    assert!(data_bool(&env, &m, "outcome"));
+    assert_eq!(data_i128(&env, &m, "total_collateral"), 1_000_000_000);
     let _resolved_at: u64 = data_u64(&env, &m, "resolved_at");
 }
 
-// ── Market: position_settled_event ────────────────────────────────────────────
+// ── Market: position_settled ──────────────────────────────────────────────────
 
 #[test]
 fn event_position_settled_shape() {
@@ -254,26 +299,32 @@ fn event_position_settled_shape() {
         &(env.ledger().timestamp() + 86_400),
         &oracle_pubkey,
         &token,
+        &helpers::market_metadata(&env),
+        &helpers::source_hash(&env),
+        &None,
+        &None,
+        &0,
     );
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &token).mint(&user, &10_000i128);
-    client.deposit_collateral(&user, &mid, &10_000i128);
+    client.deposit_collateral(&user, &mid, &10_000i128, &None);
     client.update_position(&user, &mid, &10_000i128, &0i128, &5_000i128);
 
     let sig = sign_outcome(&env, &signing_key, mid, true);
-    client.resolve_market(&String::from_str(&env, "1"), &true, &sig);
+    helpers::advance_to_end_time(&env, &client, 1);
+    client.resolve_market(&Address::generate(&env), &1, &Outcome::Yes, &None, &sig, &0);
     client.settle_position(&user, &mid);
 
-    let (topics, data) = last_event(&env);
-    assert_eq!(topics.len(), 3, "position_settled_event has 3 topics");
-    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "position_settled_event"));
+    let (topics, data) = named_event(&env, "position_settled");
+    assert_eq!(topics.len(), 3, "position_settled has 3 topics");
+    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "position_settled"));
 
     let m = data_map(&env, data);
     assert_eq!(data_i128(&env, &m, "payout"), 10_000);
     let _settled_at: u64 = data_u64(&env, &m, "settled_at");
 }
 
-// ── Treasury: fee_collected_event ────────────────────────────────────────────
+// ── Treasury: fee_collected ──────────────────────────────────────────────────
 
 #[test]
 fn event_fee_collected_shape() {
@@ -289,8 +340,8 @@ fn event_fee_collected_shape() {
     client.collect_fee(&market, &token, &5u32, &2_000i128);
 
     let (topics, data) = last_event(&env);
-    assert_eq!(topics.len(), 3, "fee_collected_event has 3 topics");
-    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "fee_collected_event"));
+    assert_eq!(topics.len(), 3, "fee_collected has 3 topics");
+    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "fee_collected"));
     let topic_mid: u32 = topics[1].clone().into_val(&env);
     assert_eq!(topic_mid, 5u32);
 
@@ -300,7 +351,7 @@ fn event_fee_collected_shape() {
     assert_eq!(data_i128(&env, &m, "new_cumulative_fees"), 2_000);
 }
 
-// ── Treasury: treasury_initialized_event ─────────────────────────────────────
+// ── Treasury: treasury_initialized ───────────────────────────────────────────
 
 #[test]
 fn event_treasury_initialized_shape() {
@@ -314,8 +365,8 @@ fn event_treasury_initialized_shape() {
     client.initialize(&admin, &market);
 
     let (topics, data) = last_event(&env);
-    assert_eq!(topics.len(), 3, "treasury_initialized_event has 3 topics");
-    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "treasury_initialized_event"));
+    assert_eq!(topics.len(), 3, "treasury_initialized has 3 topics");
+    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "treasury_initialized"));
     let topic_admin: Address = topics[1].clone().into_val(&env);
     assert_eq!(topic_admin, admin);
 
@@ -323,7 +374,7 @@ fn event_treasury_initialized_shape() {
     let _ts: u64 = data_u64(&env, &m, "initialized_at");
 }
 
-// ── Resolution: candidate_proposed_event ─────────────────────────────────────
+// ── Resolution: candidate_proposed ───────────────────────────────────────────
 
 #[test]
 fn event_candidate_proposed_shape() {
@@ -333,30 +384,29 @@ fn event_candidate_proposed_shape() {
     let client = ResolutionContractClient::new(&env, &rid);
     let admin = Address::generate(&env);
     let factory = Address::generate(&env);
-    // market_contract here can be any address; mock_all_auths skips auth checks
-    let market_contract = Address::generate(&env);
-    client.initialize(&admin, &factory, &market_contract).unwrap();
+    let market_contract = helpers::register_mock_market(&env);
+    client.initialize(&admin, &factory, &market_contract, &60u64);
 
     let proposer = Address::generate(&env);
     let sig = BytesN::from_array(&env, &[0xABu8; 64]);
     let uri = String::from_str(&env, "ipfs://evidence");
     let expiry = env.ledger().timestamp() + 3600;
-    client.propose(&proposer, &1u32, &true, &sig, &expiry, &uri, &300u64).unwrap();
+    client.propose(&proposer, &1u32, &true, &sig, &expiry, &uri, &300u64);
 
     let (topics, data) = last_event(&env);
-    assert_eq!(topics.len(), 3, "candidate_proposed_event has 3 topics");
-    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "candidate_proposed_event"));
+    assert_eq!(topics.len(), 3, "candidate_proposed has 3 topics");
+    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "candidate_proposed"));
     let topic_cid: u32 = topics[1].clone().into_val(&env);
     assert_eq!(topic_cid, 1u32);
     let topic_mid: u32 = topics[2].clone().into_val(&env);
     assert_eq!(topic_mid, 1u32);
 
     let m = data_map(&env, data);
-    assert_eq!(data_bool(&env, &m, "outcome"), true);
+    assert!(data_bool(&env, &m, "outcome"));
     let _deadline: u64 = data_u64(&env, &m, "challenge_deadline");
 }
 
-// ── Resolution: candidate_challenged_event ───────────────────────────────────
+// ── Resolution: candidate_challenged ─────────────────────────────────────────
 
 #[test]
 fn event_candidate_challenged_shape() {
@@ -365,21 +415,22 @@ fn event_candidate_challenged_shape() {
     let rid = env.register(ResolutionContract, ());
     let client = ResolutionContractClient::new(&env, &rid);
     let admin = Address::generate(&env);
-    client.initialize(&admin, &Address::generate(&env), &Address::generate(&env)).unwrap();
+    let market_contract = helpers::register_mock_market(&env);
+    client.initialize(&admin, &Address::generate(&env), &market_contract, &60u64);
 
     let proposer = Address::generate(&env);
     let sig = BytesN::from_array(&env, &[0xABu8; 64]);
     let uri = String::from_str(&env, "ipfs://evidence");
     let expiry = env.ledger().timestamp() + 600;
-    let cid = client.propose(&proposer, &2u32, &false, &sig, &expiry, &uri, &300u64).unwrap();
+    let cid = client.propose(&proposer, &2u32, &false, &sig, &expiry, &uri, &300u64);
 
     let challenger = Address::generate(&env);
     let challenge_uri = String::from_str(&env, "ipfs://challenge");
-    client.challenge(&challenger, &cid, &challenge_uri).unwrap();
+    client.challenge(&challenger, &cid, &challenge_uri);
 
     let (topics, data) = last_event(&env);
-    assert_eq!(topics.len(), 3, "candidate_challenged_event has 3 topics");
-    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "candidate_challenged_event"));
+    assert_eq!(topics.len(), 3, "candidate_challenged has 3 topics");
+    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "candidate_challenged"));
 
     let m = data_map(&env, data);
     let ev_challenger: Address = data_addr(&env, &m, "challenger");
@@ -387,39 +438,36 @@ fn event_candidate_challenged_shape() {
     let _challenged_at: u64 = data_u64(&env, &m, "challenged_at");
 }
 
-// ── Resolution: candidate_finalized_event ────────────────────────────────────
+// ── Resolution: candidate_finalized ──────────────────────────────────────────
 
 #[test]
 fn event_candidate_finalized_shape() {
-    use soroban_sdk::testutils::Ledger;
-
     let env = Env::default();
     env.mock_all_auths();
     let rid = env.register(ResolutionContract, ());
     let client = ResolutionContractClient::new(&env, &rid);
     let admin = Address::generate(&env);
-    client.initialize(&admin, &Address::generate(&env), &Address::generate(&env)).unwrap();
+    let market_contract = helpers::register_mock_market(&env);
+    client.initialize(&admin, &Address::generate(&env), &market_contract, &60u64);
 
     env.ledger().with_mut(|l| l.timestamp = 1_000);
     let proposer = Address::generate(&env);
     let sig = BytesN::from_array(&env, &[0xABu8; 64]);
     let uri = String::from_str(&env, "ipfs://evidence");
-    let cid = client
-        .propose(&proposer, &3u32, &true, &sig, &2_000u64, &uri, &60u64)
-        .unwrap();
+    let cid = client.propose(&proposer, &3u32, &true, &sig, &2_000u64, &uri, &60u64);
 
     env.ledger().with_mut(|l| l.timestamp = 1_061);
     let finalizer = Address::generate(&env);
     client.finalize(&finalizer, &cid);
 
     let (topics, data) = last_event(&env);
-    assert_eq!(topics.len(), 3, "candidate_finalized_event has 3 topics");
-    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "candidate_finalized_event"));
+    assert_eq!(topics.len(), 3, "candidate_finalized has 3 topics");
+    assert_eq!(topic_sym(&env, &topics, 0), Symbol::new(&env, "candidate_finalized"));
+    let topic_mid: u32 = topics[2].clone().into_val(&env);
+    assert_eq!(topic_mid, 3u32);
 
     let m = data_map(&env, data);
-    assert_eq!(data_bool(&env, &m, "outcome"), true);
+    assert!(data_bool(&env, &m, "outcome"));
     let finalized_at: u64 = data_u64(&env, &m, "finalized_at");
     assert_eq!(finalized_at, 1_061);
-    let ev_mid: u32 = data_u32(&env, &m, "market_id");
-    assert_eq!(ev_mid, 3u32);
 }
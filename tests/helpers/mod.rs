@@ -13,17 +13,41 @@
 /// // Use `params` to initialize the contract under test, then assert events:
 /// // assert_event_emitted(&env, "market_created");
 /// ```
-use ed25519_dalek::{Signer, SigningKey};
+use ed25519_dalek::SigningKey;
 use rand::rngs::OsRng;
 use soroban_sdk::{
-    testutils::{Address as _, Events as _},
-    Address, BytesN, Env, IntoVal, String,
+    contract, contractimpl,
+    testutils::{Address as _, Events as _, Ledger},
+    Address, BytesN, Env, IntoVal, String, Symbol,
+};
+use vatix_market_contract::{
+    oracle,
+    types::{MarketKind, MarketMetadata},
+    MarketContract, MarketContractClient,
 };
-use vatix_market_contract::{oracle, storage, MarketContract};
 
 /// Stroops per USDC (1 USDC = 10^7 stroops), shared across integration tests.
 pub const STROOPS_PER_USDC: i128 = 10_000_000;
 
+/// Seconds the market contract makes a user wait after a deposit before a
+/// withdrawal is allowed.
+pub const WITHDRAW_COOLDOWN_SECONDS: u64 = 3_600;
+
+/// Advance the ledger clock past the post-deposit withdraw cooldown.
+pub fn skip_withdraw_cooldown(env: &Env) {
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + WITHDRAW_COOLDOWN_SECONDS);
+}
+
+/// Advance the ledger clock to `market_id`'s `end_time`, the first moment
+/// the market may be resolved.
+pub fn advance_to_end_time(env: &Env, client: &MarketContractClient, market_id: u32) {
+    let end_time = client.get_market(&market_id).end_time;
+    if env.ledger().timestamp() < end_time {
+        env.ledger().set_timestamp(end_time);
+    }
+}
+
 /// Register the `MarketContract` and initialize it with an admin.
 ///
 /// Returns `(admin, contract_id)`. Uses the `initialize` entry point
@@ -38,6 +62,32 @@ pub fn register_contract(env: &Env) -> (Address, Address) {
     (admin, contract_id)
 }
 
+/// Stand-in market contract for resolution tests: accepts every signature and
+/// resolution callback so only the resolution lifecycle is exercised.
+#[contract]
+pub struct MockMarket;
+
+#[contractimpl]
+impl MockMarket {
+    pub fn verify_signature(_env: Env, _market_id: u32, _outcome: bool, _signature: BytesN<64>) {}
+
+    pub fn resolve_market(
+        _env: Env,
+        _resolver: Address,
+        _market_id: u32,
+        _outcome: bool,
+        _oracle_pubkey: Option<BytesN<32>>,
+        _signature: BytesN<64>,
+        _challenge_window: u64,
+    ) {
+    }
+}
+
+/// Register a [`MockMarket`] and return its address.
+pub fn register_mock_market(env: &Env) -> Address {
+    env.register(MockMarket, ())
+}
+
 /// Generate an oracle Ed25519 keypair, returning the on-chain public key and
 /// the signing key used to sign a market resolution.
 pub fn oracle_keypair(env: &Env) -> (BytesN<32>, SigningKey) {
@@ -46,13 +96,54 @@ pub fn oracle_keypair(env: &Env) -> (BytesN<32>, SigningKey) {
     (pubkey, signing_key)
 }
 
+/// Resolution source hash committed by markets created in integration tests.
+/// Oracle signatures are bound to it, so [`sign_outcome`] uses the same value.
+pub fn source_hash(env: &Env) -> BytesN<32> {
+    BytesN::from_array(env, &[2u8; 32])
+}
+
+/// Metadata for markets created in integration tests: no URI, the `general`
+/// category and a placeholder resolution source.
+pub fn market_metadata(env: &Env) -> MarketMetadata {
+    MarketMetadata {
+        uri: None,
+        category: Symbol::new(env, "general"),
+        resolution_source: String::from_str(env, "https://example.com/resolution-source"),
+        kind: MarketKind::Binary,
+        min_deposit: 0,
+        max_position: 0,
+        max_participants: 0,
+        derive_id: false,
+    }
+}
+
 /// Sign a market resolution outcome with the oracle signing key, producing a
-/// signature the contract's `resolve_market` will accept. Mirrors the on-chain
-/// message construction in `oracle::construct_oracle_message`.
+/// signature the contract's `resolve_market` will accept. Signs through
+/// `oracle::sign_outcome_for_test`, so it uses the on-chain message encoding.
+///
+/// Assumes the market was created at ledger timestamp 0, the default in tests;
+/// use [`sign_outcome_at`] for markets created later.
 pub fn sign_outcome(env: &Env, key: &SigningKey, market_id: u32, outcome: bool) -> BytesN<64> {
-    let message = oracle::construct_oracle_message(env, market_id, outcome);
-    let signature = key.sign(message.to_array().as_slice());
-    BytesN::from_array(env, &signature.to_bytes())
+    sign_outcome_at(env, key, market_id, 0, outcome)
+}
+
+/// Like [`sign_outcome`], for a market whose `created_at` is `created_at`.
+pub fn sign_outcome_at(
+    env: &Env,
+    key: &SigningKey,
+    market_id: u32,
+    created_at: u64,
+    outcome: bool,
+) -> BytesN<64> {
+    let (_, signature) = oracle::sign_outcome_for_test(
+        env,
+        &key.to_bytes(),
+        market_id,
+        created_at,
+        &source_hash(env),
+        outcome,
+    );
+    signature
 }
 
 /// Failure reasons for the integration test harness.
@@ -90,6 +181,8 @@ pub struct MarketParams {
     pub end_time: u64,
     pub oracle_pubkey: BytesN<32>,
     pub collateral_token: Address,
+    pub source_hash: BytesN<32>,
+    pub metadata: MarketMetadata,
 }
 
 impl MarketParams {
@@ -99,7 +192,11 @@ impl MarketParams {
             question: String::from_str(env, "Will BTC reach $100k?"),
             end_time: env.ledger().timestamp() + 86_400,
             oracle_pubkey: BytesN::from_array(env, &[1u8; 32]),
-            collateral_token: Address::generate(env),
+            collateral_token: env
+                .register_stellar_asset_contract_v2(Address::generate(env))
+                .address(),
+            source_hash: source_hash(env),
+            metadata: market_metadata(env),
         }
     }
 }
@@ -122,4 +219,17 @@ pub fn assert_event_emitted(env: &Env, expected_topic: &str) {
         soroban_sdk::Symbol::new(env, expected_topic),
         "event topic mismatch: expected '{expected_topic}'"
     );
-}
\ No newline at end of file
+}
+
+/// Assert that the most recent contract call published an event whose first
+/// topic matches `expected_topic`, regardless of what was emitted after it
+/// (e.g. a token transfer following a payout event).
+pub fn assert_event_published(env: &Env, expected_topic: &str) {
+    let expected = soroban_sdk::Symbol::new(env, expected_topic);
+    let found = env.events().all().iter().any(|event| {
+        let topic: Result<soroban_sdk::Symbol, _> =
+            soroban_sdk::TryIntoVal::try_into_val(&event.1.get(0).unwrap(), env);
+        topic.is_ok_and(|topic| topic == expected)
+    });
+    assert!(found, "expected a '{expected_topic}' event to be published");
+}
@@ -6,7 +6,7 @@ mod helpers;
 use helpers::{assert_event_emitted, MarketParams};
 
 use soroban_sdk::{testutils::Address as _, token::StellarAssetClient, Address, Env};
-use vatix_market_contract::{storage, MarketContract, MarketContractClient};
+use vatix_market_contract::{storage, types::Outcome, MarketContract, MarketContractClient};
 
 const STROOPS_PER_USDC: i128 = 10_000_000;
 
@@ -42,6 +42,11 @@ fn create_market_then_deposit_collateral() {
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
     assert_eq!(market_id, 1);
     assert_event_emitted(&env, "market_created");
@@ -56,7 +61,7 @@ fn create_market_then_deposit_collateral() {
     let user = Address::generate(&env);
     let deposit = 25 * STROOPS_PER_USDC;
     StellarAssetClient::new(&env, &collateral_token).mint(&user, &deposit);
-    client.deposit_collateral(&user, &market_id, &deposit);
+    client.deposit_collateral(&user, &market_id, &deposit, &None);
     assert_event_emitted(&env, "collateral_deposited");
 
     let position = env.as_contract(&contract_id, || {
@@ -82,6 +87,11 @@ fn non_admin_cannot_create_market() {
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 }
 
@@ -119,7 +129,7 @@ fn full_protocol_loop_deposit_trade_resolve_settle() {
     let question = String::from_str(&env, "Will the full loop settle?");
     let end_time = env.ledger().timestamp() + 86_400;
     let market_id =
-        client.initialize_market(&admin, &question, &end_time, &oracle_pubkey, &collateral_token);
+        client.initialize_market(&admin, &question, &end_time, &oracle_pubkey, &collateral_token, &helpers::market_metadata(&env), &helpers::source_hash(&env), &None, &None, &0);
     assert_eq!(market_id, 1);
     assert_event_emitted(&env, "market_created");
 
@@ -127,7 +137,7 @@ fn full_protocol_loop_deposit_trade_resolve_settle() {
     let user = Address::generate(&env);
     let deposit = 100 * STROOPS_PER_USDC;
     sac.mint(&user, &deposit);
-    client.deposit_collateral(&user, &market_id, &deposit);
+    client.deposit_collateral(&user, &market_id, &deposit, &None);
     assert_event_emitted(&env, "collateral_deposited");
     assert_eq!(token_client.balance(&user), 0);
     assert_eq!(token_client.balance(&contract_id), deposit);
@@ -144,8 +154,8 @@ fn full_protocol_loop_deposit_trade_resolve_settle() {
 
     // 4. Resolve the market (YES wins) with a valid oracle signature.
     let signature = helpers::sign_outcome(&env, &signing_key, market_id, outcome);
-    let market_id_str = String::from_str(&env, "1");
-    client.resolve_market(&market_id_str, &outcome, &signature);
+    helpers::advance_to_end_time(&env, &client, 1);
+    client.resolve_market(&Address::generate(&env), &1, &outcome.into(), &None, &signature, &0);
     assert_event_emitted(&env, "market_resolved");
     let resolved = env.as_contract(&contract_id, || {
         storage::get_market(&env, market_id)
@@ -159,7 +169,7 @@ fn full_protocol_loop_deposit_trade_resolve_settle() {
     //    from the contract back to the user.
     let payout = client.settle_position(&user, &market_id);
     assert_eq!(payout, yes_shares);
-    assert_event_emitted(&env, "position_settled");
+    helpers::assert_event_published(&env, "position_settled");
     assert_eq!(token_client.balance(&user), payout);
     assert_eq!(token_client.balance(&contract_id), deposit - payout);
 
@@ -201,14 +211,14 @@ fn full_protocol_loop_no_outcome_wins() {
     let question = String::from_str(&env, "Will NO win this round?");
     let end_time = env.ledger().timestamp() + 86_400;
     let market_id =
-        client.initialize_market(&admin, &question, &end_time, &oracle_pubkey, &collateral_token);
+        client.initialize_market(&admin, &question, &end_time, &oracle_pubkey, &collateral_token, &helpers::market_metadata(&env), &helpers::source_hash(&env), &None, &None, &0);
     assert_eq!(market_id, 1);
 
     // Deposit and buy NO shares
     let user = Address::generate(&env);
     let deposit = 100 * STROOPS_PER_USDC;
     sac.mint(&user, &deposit);
-    client.deposit_collateral(&user, &market_id, &deposit);
+    client.deposit_collateral(&user, &market_id, &deposit, &None);
     assert_eq!(token_client.balance(&contract_id), deposit);
 
     let no_shares = 100 * STROOPS_PER_USDC;
@@ -216,8 +226,8 @@ fn full_protocol_loop_no_outcome_wins() {
 
     // Resolve with NO outcome
     let signature = helpers::sign_outcome(&env, &signing_key, market_id, outcome);
-    let market_id_str = String::from_str(&env, "1");
-    client.resolve_market(&market_id_str, &outcome, &signature);
+    helpers::advance_to_end_time(&env, &client, 1);
+    client.resolve_market(&Address::generate(&env), &1, &outcome.into(), &None, &signature, &0);
 
     let resolved = env.as_contract(&contract_id, || {
         storage::get_market(&env, market_id)
@@ -267,12 +277,12 @@ fn full_protocol_loop_refund_path() {
     let question = String::from_str(&env, "Refund test?");
     let end_time = env.ledger().timestamp() + 86_400;
     let market_id =
-        client.initialize_market(&admin, &question, &end_time, &oracle_pubkey, &collateral_token);
+        client.initialize_market(&admin, &question, &end_time, &oracle_pubkey, &collateral_token, &helpers::market_metadata(&env), &helpers::source_hash(&env), &None, &None, &0);
 
     let user = Address::generate(&env);
     let deposit = 75 * STROOPS_PER_USDC;
     sac.mint(&user, &deposit);
-    client.deposit_collateral(&user, &market_id, &deposit);
+    client.deposit_collateral(&user, &market_id, &deposit, &None);
 
     // Buy both YES and NO shares (mixed position) to test refund path
     client.update_position(&user, &market_id, &(40 * STROOPS_PER_USDC), &(35 * STROOPS_PER_USDC), &5_000i128);
@@ -280,8 +290,8 @@ fn full_protocol_loop_refund_path() {
     // Resolve with a YES outcome via oracle; then manually set result to None
     // in storage to simulate the "no-winner" refund path.
     let signature = helpers::sign_outcome(&env, &signing_key, market_id, true);
-    let market_id_str = String::from_str(&env, "1");
-    client.resolve_market(&market_id_str, &true, &signature);
+    helpers::advance_to_end_time(&env, &client, 1);
+    client.resolve_market(&Address::generate(&env), &1, &Outcome::Yes, &None, &signature, &0);
 
     // Override the result to None to exercise the refund branch in settlement.
     // This simulates the governance / admin refund path.
@@ -308,11 +318,7 @@ fn full_protocol_loop_refund_path() {
 /// calling `initialize_market` again must be rejected.
 #[test]
 fn duplicate_market_id_creation_is_rejected() {
-    use vatix_market_contract::{
-        error::ContractError,
-        types::{Market, MarketStatus},
-    };
-    use soroban_sdk::{BytesN, String};
+    use vatix_market_contract::error::ContractError;
 
     let (env, admin, contract_id) = init_contract();
     let client = MarketContractClient::new(&env, &contract_id);
@@ -335,6 +341,11 @@ fn duplicate_market_id_creation_is_rejected() {
         &params.end_time,
         &params.oracle_pubkey,
         &collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
     assert_eq!(market_id, 1);
 
@@ -349,10 +360,65 @@ fn duplicate_market_id_creation_is_rejected() {
     // A second call must be rejected because market ID 1 already exists.
     let result = client.try_initialize_market(
         &admin,
-        &params.question,
+        &soroban_sdk::String::from_str(&env, "A second, distinct question?"),
         &params.end_time,
         &params.oracle_pubkey,
         &collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
     assert_eq!(result, Err(Ok(ContractError::AlreadyInitialized)));
 }
+
+/// A signature for a market is bound to that market instance's `created_at`,
+/// so it cannot resolve a later market that reuses the same ID (for example on
+/// a redeployed contract whose counter restarted).
+#[test]
+fn oracle_signature_not_replayable_across_market_instances() {
+    use soroban_sdk::testutils::Ledger;
+    use vatix_market_contract::error::ContractError;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let (oracle_pubkey, signing_key) = helpers::oracle_keypair(&env);
+    let params = MarketParams::default_valid(&env);
+    let collateral_token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+
+    let create_market = |contract_id: &Address, admin: &Address| {
+        MarketContractClient::new(&env, contract_id).initialize_market(
+            admin,
+            &params.question,
+            &params.end_time,
+            &oracle_pubkey,
+            &collateral_token,
+            &helpers::market_metadata(&env),
+            &helpers::source_hash(&env),
+            &None,
+            &None,
+            &0,
+        )
+    };
+
+    let (first_admin, first_id) = helpers::register_contract(&env);
+    let first_market = create_market(&first_id, &first_admin);
+    let signature = helpers::sign_outcome(&env, &signing_key, first_market, true);
+
+    env.ledger().set_timestamp(1_000);
+    let (second_admin, second_id) = helpers::register_contract(&env);
+    let second_market = create_market(&second_id, &second_admin);
+    assert_eq!(first_market, second_market);
+
+    let second = MarketContractClient::new(&env, &second_id);
+    let resolver = Address::generate(&env);
+    helpers::advance_to_end_time(&env, &second, second_market);
+    let replayed = second.try_resolve_market(&resolver, &second_market, &Outcome::Yes, &None, &signature, &0);
+    assert_eq!(replayed, Err(Ok(ContractError::InvalidSignature)));
+
+    let fresh = helpers::sign_outcome_at(&env, &signing_key, second_market, 1_000, true);
+    second.resolve_market(&resolver, &second_market, &Outcome::Yes, &None, &fresh, &0);
+}
@@ -6,7 +6,7 @@ mod helpers;
 use helpers::{assert_event_emitted, MarketParams};
 
 use soroban_sdk::{testutils::Address as _, token::StellarAssetClient, Address, Env};
-use vatix_market_contract::{storage, MarketContract, MarketContractClient};
+use vatix_market_contract::{pricing, storage, MarketContract, MarketContractClient};
 
 const STROOPS_PER_USDC: i128 = 10_000_000;
 
@@ -23,6 +23,7 @@ fn market_with_funded_user(deposit: i128) -> (Env, Address, u32, Address) {
 
     let admin = Address::generate(&env);
     env.as_contract(&contract_id, || {
+        storage::set_version(&env);
         storage::set_admin(&env, &admin);
     });
 
@@ -39,11 +40,16 @@ fn market_with_funded_user(deposit: i128) -> (Env, Address, u32, Address) {
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &collateral_token).mint(&user, &deposit);
-    client.deposit_collateral(&user, &market_id, &deposit);
+    client.deposit_collateral(&user, &market_id, &deposit, &None);
 
     (env, contract_id, market_id, user)
 }
@@ -102,7 +108,7 @@ fn buy_yes_convenience_function_works() {
     assert_eq!(position.locked_collateral, 60 * STROOPS_PER_USDC);
     
     // Verify event was emitted
-    assert_event_emitted(&env, "trade_executed_event");
+    assert_event_emitted(&env, "trade_executed");
 }
 
 #[test]
@@ -119,7 +125,7 @@ fn buy_no_convenience_function_works() {
     assert_eq!(position.no_shares, amount);
     assert_eq!(position.locked_collateral, 40 * STROOPS_PER_USDC);
     
-    assert_event_emitted(&env, "trade_executed_event");
+    assert_event_emitted(&env, "trade_executed");
 }
 
 #[test]
@@ -160,8 +166,197 @@ fn sell_no_convenience_function_works() {
     assert_eq!(position.locked_collateral, 20 * STROOPS_PER_USDC);
 }
 
+/// YES price in basis points currently implied by a market's curve.
+fn curve_price(client: &MarketContractClient, market_id: u32) -> i128 {
+    let market = client.get_market(&market_id);
+    pricing::current_price(market.yes_reserve, market.no_reserve)
+}
+
 #[test]
-#[should_panic(expected = "Error(Contract, #7)")]
+fn buy_shares_buys_the_chosen_side() {
+    let deposit = 100 * STROOPS_PER_USDC;
+    let (env, contract_id, market_id, user) = market_with_funded_user(deposit);
+    let client = MarketContractClient::new(&env, &contract_id);
+
+    // A fresh curve prices YES at 50%.
+    assert_eq!(curve_price(&client, market_id), 5_000);
+    let amount = 50 * STROOPS_PER_USDC;
+    let position = client.buy_shares(&user, &market_id, &true, &amount);
+    assert_eq!((position.yes_shares, position.no_shares), (amount, 0));
+    assert_event_emitted(&env, "trade_executed");
+
+    // Buying YES moved the curve: NO is now cheaper than 50%. The fill is
+    // priced across that move, so it costs more than the 50% spot.
+    let price = curve_price(&client, market_id);
+    assert!(price > 5_000);
+    let fill = (5_000 + price) / 2;
+    assert_eq!(position.locked_collateral, amount * fill / 10_000);
+    assert!(position.locked_collateral > 25 * STROOPS_PER_USDC);
+
+    // 50 YES + 100 NO nets to 50 NO, locked at the fill's NO price.
+    let position = client.buy_shares(&user, &market_id, &false, &(2 * amount));
+    assert_eq!((position.yes_shares, position.no_shares), (amount, 2 * amount));
+    let after = curve_price(&client, market_id);
+    assert!(after < price);
+    let fill = (price + after) / 2;
+    assert_eq!(position.locked_collateral, amount * (10_000 - fill) / 10_000);
+    assert_eq!(client.get_market(&market_id).price_bps, fill);
+}
+
+#[test]
+fn buy_shares_rejects_uncovered_lock() {
+    use vatix_market_contract::error::ContractError;
+
+    let deposit = 10 * STROOPS_PER_USDC;
+    let (env, contract_id, market_id, user) = market_with_funded_user(deposit);
+    let client = MarketContractClient::new(&env, &contract_id);
+    let reserves = client.get_market(&market_id);
+
+    assert_eq!(
+        client.try_buy_shares(&user, &market_id, &true, &(100 * STROOPS_PER_USDC)),
+        Err(Ok(ContractError::InsufficientCollateral))
+    );
+    // The rejected trade does not move the curve.
+    let market = client.get_market(&market_id);
+    assert_eq!(
+        (market.yes_reserve, market.no_reserve),
+        (reserves.yes_reserve, reserves.no_reserve)
+    );
+}
+
+#[test]
+fn update_position_accepts_only_basis_point_prices() {
+    use vatix_market_contract::error::ContractError;
+
+    let deposit = 100 * STROOPS_PER_USDC;
+    let (env, contract_id, market_id, user) = market_with_funded_user(deposit);
+    let client = MarketContractClient::new(&env, &contract_id);
+    let shares = 10 * STROOPS_PER_USDC;
+
+    for bad_price in [-1i128, 10_001] {
+        assert_eq!(
+            client.try_update_position(&user, &market_id, &shares, &0i128, &bad_price),
+            Err(Ok(ContractError::InvalidPrice))
+        );
+    }
+
+    // At 0% YES costs nothing to hold; at 100% it is fully collateralized.
+    let position = client.update_position(&user, &market_id, &shares, &0i128, &0i128);
+    assert_eq!(position.locked_collateral, 0);
+    let position = client.update_position(&user, &market_id, &0i128, &0i128, &10_000i128);
+    assert_eq!(position.locked_collateral, shares);
+}
+
+#[test]
+fn sell_shares_releases_collateral_for_withdrawal() {
+    use vatix_market_contract::error::ContractError;
+
+    let deposit = 100 * STROOPS_PER_USDC;
+    let (env, contract_id, market_id, user) = market_with_funded_user(deposit);
+    let client = MarketContractClient::new(&env, &contract_id);
+
+    // 100 YES at about 50% locks about 50 USDC, leaving under 60 withdrawable.
+    client.buy_shares(&user, &market_id, &true, &(100 * STROOPS_PER_USDC));
+    helpers::skip_withdraw_cooldown(&env);
+    assert_eq!(
+        client.try_withdraw_unused_collateral(&user, &market_id, &(60 * STROOPS_PER_USDC)),
+        Err(Ok(ContractError::InsufficientCollateral))
+    );
+
+    // Selling half the position releases collateral at the curve's price,
+    // taken across the move the sale causes.
+    let price = curve_price(&client, market_id);
+    let position = client.sell_shares(&user, &market_id, &true, &(50 * STROOPS_PER_USDC));
+    assert_eq!(position.yes_shares, 50 * STROOPS_PER_USDC);
+    // Selling YES pushes its price back down.
+    let after = curve_price(&client, market_id);
+    assert!(after < price);
+    let fill = (price + after) / 2;
+    assert_eq!(position.locked_collateral, 50 * STROOPS_PER_USDC * fill / 10_000);
+
+    // Everything above the new lock is now withdrawable.
+    let free = deposit - position.locked_collateral;
+    assert!(free > 60 * STROOPS_PER_USDC);
+    client.withdraw_unused_collateral(&user, &market_id, &free);
+    let position = client.get_position(&market_id, &user);
+    assert_eq!(position.total_deposited, position.locked_collateral);
+
+    // Selling more than is held is rejected.
+    assert_eq!(
+        client.try_sell_shares(&user, &market_id, &false, &STROOPS_PER_USDC),
+        Err(Ok(ContractError::InvalidShareAmount))
+    );
+}
+
+#[test]
+fn market_stats_track_buys_sells_and_collateral() {
+    use vatix_market_contract::types::MarketStatus;
+
+    let deposit = 100 * STROOPS_PER_USDC;
+    let (env, contract_id, market_id, alice) = market_with_funded_user(deposit);
+    let client = MarketContractClient::new(&env, &contract_id);
+    let bob = Address::generate(&env);
+    let token = client.get_market(&market_id).collateral_token;
+    StellarAssetClient::new(&env, &token).mint(&bob, &deposit);
+    client.deposit_collateral(&bob, &market_id, &deposit, &None);
+
+    client.buy_shares(&alice, &market_id, &true, &(80 * STROOPS_PER_USDC));
+    client.buy_shares(&bob, &market_id, &false, &(60 * STROOPS_PER_USDC));
+    client.buy_shares(&bob, &market_id, &true, &(20 * STROOPS_PER_USDC));
+    client.sell_shares(&alice, &market_id, &true, &(30 * STROOPS_PER_USDC));
+
+    let stats = client.get_market_stats(&market_id);
+    assert_eq!(stats.total_yes_shares, 70 * STROOPS_PER_USDC);
+    assert_eq!(stats.total_no_shares, 60 * STROOPS_PER_USDC);
+    assert_eq!(stats.total_collateral, 2 * deposit);
+    assert_eq!(stats.status, MarketStatus::Active);
+    assert_eq!(stats.result, None);
+
+    // The totals match the sum of the individual positions.
+    let a = client.get_position(&market_id, &alice);
+    let b = client.get_position(&market_id, &bob);
+    assert_eq!(stats.total_yes_shares, a.yes_shares + b.yes_shares);
+    assert_eq!(stats.total_no_shares, a.no_shares + b.no_shares);
+
+    // Withdrawing free collateral lowers the collateral total.
+    helpers::skip_withdraw_cooldown(&env);
+    client.withdraw_unused_collateral(&alice, &market_id, &(10 * STROOPS_PER_USDC));
+    assert_eq!(
+        client.get_market_stats(&market_id).total_collateral,
+        2 * deposit - 10 * STROOPS_PER_USDC
+    );
+}
+
+#[test]
+fn participants_are_recorded_once_in_opening_order() {
+    let deposit = 100 * STROOPS_PER_USDC;
+    let (env, contract_id, market_id, alice) = market_with_funded_user(deposit);
+    let client = MarketContractClient::new(&env, &contract_id);
+    let bob = Address::generate(&env);
+    let token = client.get_market(&market_id).collateral_token;
+    let sac = StellarAssetClient::new(&env, &token);
+    sac.mint(&bob, &deposit);
+    sac.mint(&alice, &deposit);
+    client.deposit_collateral(&bob, &market_id, &deposit, &None);
+
+    // Re-depositing and trading do not add alice a second time.
+    client.deposit_collateral(&alice, &market_id, &deposit, &None);
+    client.buy_shares(&alice, &market_id, &true, &(10 * STROOPS_PER_USDC));
+
+    assert_eq!(
+        client.get_participants(&market_id, &0, &u32::MAX),
+        soroban_sdk::vec![&env, alice.clone(), bob.clone()]
+    );
+    assert_eq!(
+        client.get_participants(&market_id, &1, &1),
+        soroban_sdk::vec![&env, bob]
+    );
+    assert!(client.get_participants(&market_id, &2, &10).is_empty());
+    assert!(client.get_participants(&(market_id + 1), &0, &10).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
 fn buy_yes_rejects_zero_amount() {
     let deposit = 100 * STROOPS_PER_USDC;
     let (_env, _contract_id, market_id, user) = market_with_funded_user(deposit);
@@ -172,7 +367,7 @@ fn buy_yes_rejects_zero_amount() {
 }
 
 #[test]
-#[should_panic(expected = "Error(Contract, #7)")]
+#[should_panic(expected = "Error(Contract, #13)")]
 fn buy_no_rejects_negative_amount() {
     let deposit = 100 * STROOPS_PER_USDC;
     let (_env, _contract_id, market_id, user) = market_with_funded_user(deposit);
@@ -182,6 +377,50 @@ fn buy_no_rejects_negative_amount() {
     client.buy_no(&user, &market_id, &(-10 * STROOPS_PER_USDC), &6_000i128);
 }
 
+#[test]
+fn invalid_inputs_report_collateral_and_share_errors_separately() {
+    use vatix_market_contract::error::ContractError;
+
+    let deposit = 100 * STROOPS_PER_USDC;
+    let (env, contract_id, market_id, user) = market_with_funded_user(deposit);
+    let client = MarketContractClient::new(&env, &contract_id);
+
+    // Collateral amounts -> InvalidQuantity (#31).
+    assert_eq!(
+        client.try_deposit_collateral(&user, &market_id, &0i128, &None),
+        Err(Ok(ContractError::InvalidQuantity))
+    );
+    assert_eq!(
+        client.try_deposit_and_buy(
+            &user,
+            &market_id,
+            &0i128,
+            &true,
+            &STROOPS_PER_USDC,
+            &5_000i128,
+        ),
+        Err(Ok(ContractError::InvalidQuantity))
+    );
+
+    // Share counts -> InvalidShareAmount (#13).
+    let share_error = Err(Ok(ContractError::InvalidShareAmount));
+    assert_eq!(client.try_buy_yes(&user, &market_id, &0i128, &5_000i128), share_error);
+    assert_eq!(client.try_buy_no(&user, &market_id, &0i128, &5_000i128), share_error);
+    assert_eq!(client.try_sell_yes(&user, &market_id, &0i128, &5_000i128), share_error);
+    assert_eq!(client.try_sell_no(&user, &market_id, &0i128, &5_000i128), share_error);
+    assert_eq!(
+        client.try_deposit_and_buy(
+            &user,
+            &market_id,
+            &deposit,
+            &true,
+            &0i128,
+            &5_000i128,
+        ),
+        share_error
+    );
+}
+
 #[test]
 #[should_panic(expected = "Error(Contract, #13)")]
 fn sell_yes_fails_when_user_has_no_shares() {
@@ -215,41 +454,41 @@ fn get_position_returns_correct_data() {
     client.buy_yes(&user, &market_id, &amount, &6_000i128);
 
     // Query position using get_position
-    let position = client.get_position(&market_id, &user)
-        .expect("storage check ok")
-        .expect("position should exist");
+    let position = client.get_position(&market_id, &user);
 
     assert_eq!(position.yes_shares, amount);
     assert_eq!(position.no_shares, 0);
     assert_eq!(position.locked_collateral, 60 * STROOPS_PER_USDC);
     assert_eq!(position.total_deposited, deposit);
-    assert_eq!(position.is_settled, false);
+    assert!(!position.is_settled);
 }
 
 #[test]
-fn get_position_returns_none_for_nonexistent_position() {
+fn get_position_returns_not_found_for_nonexistent_position() {
+    use vatix_market_contract::error::ContractError;
+
     let deposit = 100 * STROOPS_PER_USDC;
     let (env, contract_id, market_id, _user) = market_with_funded_user(deposit);
     let client = MarketContractClient::new(&env, &contract_id);
 
     // Query position for a user who never traded
     let other_user = Address::generate(&env);
-    let position = client.get_position(&market_id, &other_user)
-        .expect("storage check ok");
+    let position = client.try_get_position(&market_id, &other_user);
 
-    assert!(position.is_none());
+    assert_eq!(position, Err(Ok(ContractError::NoPositionFound)));
 }
 
 #[test]
 fn get_market_returns_correct_data() {
     let deposit = 100 * STROOPS_PER_USDC;
     let (env, contract_id, market_id, _user) = market_with_funded_user(deposit);
-    let client = MarketContractClient::new(&env, &contract_id);
 
     // Query market details
-    let market = client.get_market(&market_id)
-        .expect("storage check ok")
-        .expect("market should exist");
+    let market = env.as_contract(&contract_id, || {
+        storage::get_market(&env, market_id)
+            .expect("storage check ok")
+            .expect("market should exist")
+    });
 
     assert_eq!(market.id, market_id);
     assert_eq!(market.status, vatix_market_contract::types::MarketStatus::Active);
@@ -265,13 +504,13 @@ fn comprehensive_trading_flow_with_convenience_functions() {
 
     // 1. Buy 100 YES at 60%
     client.buy_yes(&user, &market_id, &(100 * STROOPS_PER_USDC), &6_000i128);
-    let pos1 = client.get_position(&market_id, &user).unwrap().unwrap();
+    let pos1 = client.get_position(&market_id, &user);
     assert_eq!(pos1.yes_shares, 100 * STROOPS_PER_USDC);
     assert_eq!(pos1.locked_collateral, 60 * STROOPS_PER_USDC);
 
     // 2. Buy 50 NO at 70% YES (30% NO cost)
     client.buy_no(&user, &market_id, &(50 * STROOPS_PER_USDC), &7_000i128);
-    let pos2 = client.get_position(&market_id, &user).unwrap().unwrap();
+    let pos2 = client.get_position(&market_id, &user);
     assert_eq!(pos2.yes_shares, 100 * STROOPS_PER_USDC);
     assert_eq!(pos2.no_shares, 50 * STROOPS_PER_USDC);
     // Net: 50 YES at 70% = 35 USDC locked
@@ -279,7 +518,7 @@ fn comprehensive_trading_flow_with_convenience_functions() {
 
     // 3. Sell 25 YES at 65%
     client.sell_yes(&user, &market_id, &(25 * STROOPS_PER_USDC), &6_500i128);
-    let pos3 = client.get_position(&market_id, &user).unwrap().unwrap();
+    let pos3 = client.get_position(&market_id, &user);
     assert_eq!(pos3.yes_shares, 75 * STROOPS_PER_USDC);
     assert_eq!(pos3.no_shares, 50 * STROOPS_PER_USDC);
     // Net: 25 YES at 65% = 16.25 USDC locked
@@ -287,7 +526,7 @@ fn comprehensive_trading_flow_with_convenience_functions() {
 
     // 4. Sell all NO shares
     client.sell_no(&user, &market_id, &(50 * STROOPS_PER_USDC), &6_500i128);
-    let pos4 = client.get_position(&market_id, &user).unwrap().unwrap();
+    let pos4 = client.get_position(&market_id, &user);
     assert_eq!(pos4.yes_shares, 75 * STROOPS_PER_USDC);
     assert_eq!(pos4.no_shares, 0);
     // Net: 75 YES at 65% = 48.75 USDC locked
@@ -38,11 +38,16 @@ fn setup_market_with_deposit(deposit: i128) -> (Env, Address, u32, Address) {
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &collateral_token).mint(&user, &deposit);
-    client.deposit_collateral(&user, &market_id, &deposit);
+    client.deposit_collateral(&user, &market_id, &deposit, &None);
 
     (env, contract_id, market_id, user)
 }
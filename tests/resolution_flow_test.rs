@@ -29,7 +29,7 @@ fn scaffold() -> (Env, ResolutionContractClient<'static>, Address) {
 
     let admin = Address::generate(&env);
     let factory = Address::generate(&env);
-    let market_contract = Address::generate(&env);
+    let market_contract = helpers::register_mock_market(&env);
 
     let contract_id = env.register(ResolutionContract, ());
     ResolutionContractClient::new(&env, &contract_id)
@@ -67,7 +67,7 @@ fn propose_creates_candidate_with_proposed_status() {
     let candidate = client.get_candidate(&candidate_id).expect("candidate exists");
     assert_eq!(candidate.id, candidate_id);
     assert_eq!(candidate.market_id, 1u32);
-    assert_eq!(candidate.outcome, true);
+    assert!(candidate.outcome);
     assert_eq!(candidate.status, CandidateStatus::Proposed);
     assert!(candidate.challenged_by.is_none());
 }
@@ -119,6 +119,7 @@ fn duplicate_proposal_for_same_market_is_rejected() {
         &1u32,
         &false,
         &make_signature(&env),
+        &(env.ledger().timestamp() + CHALLENGE_WINDOW + 100),
         &make_uri(&env, "ipfs://second"),
         &CHALLENGE_WINDOW,
     );
@@ -249,6 +250,7 @@ fn challenged_candidate_cannot_be_finalized() {
         &1u32,
         &true,
         &make_signature(&env),
+        &(env.ledger().timestamp() + CHALLENGE_WINDOW + 100),
         &make_uri(&env, "ipfs://evidence"),
         &CHALLENGE_WINDOW,
     );
@@ -6,7 +6,7 @@ mod helpers;
 use helpers::{assert_event_emitted, MarketParams};
 
 use soroban_sdk::{
-    testutils::Address as _, token::StellarAssetClient, Address, BytesN, Env, String,
+    testutils::Address as _, token::StellarAssetClient, Address, BytesN, Env,
 };
 use vatix_market_contract::{settlement, storage, MarketContract, MarketContractClient};
 
@@ -24,7 +24,7 @@ fn oracle_keypair_and_signature(
     let signing_key = SigningKey::generate(&mut csprng);
     let verifying_key = signing_key.verifying_key();
 
-    let message = vatix_market_contract::oracle::construct_oracle_message(env, market_id, outcome);
+    let message = vatix_market_contract::oracle::construct_oracle_message(env, market_id, 0, &helpers::source_hash(env), outcome);
     let signature = signing_key.sign(message.to_array().as_slice());
 
     (
@@ -64,6 +64,11 @@ fn full_lifecycle_init_create_deposit_resolve_settle() {
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
     assert_eq!(market_id, 1);
     assert_event_emitted(&env, "market_created");
@@ -71,7 +76,7 @@ fn full_lifecycle_init_create_deposit_resolve_settle() {
     let user = Address::generate(&env);
     let deposit = 100 * STROOPS_PER_USDC;
     StellarAssetClient::new(&env, &collateral_token).mint(&user, &deposit);
-    client.deposit_collateral(&user, &market_id, &deposit);
+    client.deposit_collateral(&user, &market_id, &deposit, &None);
     assert_event_emitted(&env, "collateral_deposited");
 
     let yes_shares = 100 * STROOPS_PER_USDC;
@@ -80,8 +85,8 @@ fn full_lifecycle_init_create_deposit_resolve_settle() {
 
     // --- resolve the market (YES wins) ---
     let resolver = Address::generate(&env);
-    let market_id_str = String::from_str(&env, "1");
-    client.resolve_market(&resolver, &market_id_str, &outcome, &signature);
+    helpers::advance_to_end_time(&env, &client, 1);
+    client.resolve_market(&resolver, &1, &outcome.into(), &None, &signature, &0);
     assert_event_emitted(&env, "market_resolved");
 
     let payout = env.as_contract(&contract_id, || {
@@ -136,12 +141,17 @@ fn settlement_before_resolution_is_rejected() {
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 
     let user = Address::generate(&env);
     let deposit = 50 * STROOPS_PER_USDC;
     StellarAssetClient::new(&env, &collateral_token).mint(&user, &deposit);
-    client.deposit_collateral(&user, &market_id, &deposit);
+    client.deposit_collateral(&user, &market_id, &deposit, &None);
 
     env.as_contract(&contract_id, || {
         let market = storage::get_market(&env, market_id)
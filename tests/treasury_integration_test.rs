@@ -63,14 +63,20 @@ fn mock_sac_fee_flows_to_treasury() {
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 
     let user = Address::generate(&env);
     let deposit = 500 * STROOPS_PER_USDC;
     StellarAssetClient::new(&env, &mock_sac).mint(&user, &deposit);
-    market.deposit_collateral(&user, &market_id, &deposit);
+    market.deposit_collateral(&user, &market_id, &deposit, &None);
 
     let withdraw_amount = 200 * STROOPS_PER_USDC;
+    helpers::skip_withdraw_cooldown(&env);
     market.withdraw_unused_collateral(&user, &market_id, &withdraw_amount);
 
     let expected_fee = fee_for(withdraw_amount);
@@ -120,17 +126,23 @@ fn mock_sac_multiple_withdrawals_accumulate() {
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 
     let user = Address::generate(&env);
     let deposit = 1000 * STROOPS_PER_USDC;
     StellarAssetClient::new(&env, &mock_sac).mint(&user, &deposit);
-    market.deposit_collateral(&user, &market_id, &deposit);
+    market.deposit_collateral(&user, &market_id, &deposit, &None);
 
     let w1 = 100 * STROOPS_PER_USDC;
     let w2 = 300 * STROOPS_PER_USDC;
     let w3 = 200 * STROOPS_PER_USDC;
 
+    helpers::skip_withdraw_cooldown(&env);
     market.withdraw_unused_collateral(&user, &market_id, &w1);
     market.withdraw_unused_collateral(&user, &market_id, &w2);
     market.withdraw_unused_collateral(&user, &market_id, &w3);
@@ -168,14 +180,20 @@ fn admin_withdraws_mock_sac_fees() {
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 
     let user = Address::generate(&env);
     let deposit = 200 * STROOPS_PER_USDC;
     StellarAssetClient::new(&env, &mock_sac).mint(&user, &deposit);
-    market.deposit_collateral(&user, &market_id, &deposit);
+    market.deposit_collateral(&user, &market_id, &deposit, &None);
 
     let withdraw_amount = 100 * STROOPS_PER_USDC;
+    helpers::skip_withdraw_cooldown(&env);
     market.withdraw_unused_collateral(&user, &market_id, &withdraw_amount);
     let collected = fee_for(withdraw_amount);
 
@@ -219,14 +237,20 @@ fn zero_fee_rate_no_sac_fee_deducted() {
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     );
 
     let user = Address::generate(&env);
     let deposit = 100 * STROOPS_PER_USDC;
     StellarAssetClient::new(&env, &mock_sac).mint(&user, &deposit);
-    market.deposit_collateral(&user, &market_id, &deposit);
+    market.deposit_collateral(&user, &market_id, &deposit, &None);
 
     let withdraw_amount = 80 * STROOPS_PER_USDC;
+    helpers::skip_withdraw_cooldown(&env);
     market.withdraw_unused_collateral(&user, &market_id, &withdraw_amount);
 
     assert_eq!(
@@ -69,6 +69,11 @@ fn open_market(
         &params.end_time,
         &params.oracle_pubkey,
         &params.collateral_token,
+        &params.metadata,
+        &params.source_hash,
+        &None,
+        &None,
+        &0,
     )
 }
 
@@ -85,9 +90,10 @@ fn withdraw_routes_half_percent_fee_to_treasury() {
     let user = Address::generate(&env);
     let deposit = 100 * STROOPS_PER_USDC;
     StellarAssetClient::new(&env, &token).mint(&user, &deposit);
-    market.deposit_collateral(&user, &market_id, &deposit);
+    market.deposit_collateral(&user, &market_id, &deposit, &None);
 
     let withdraw_amount = 50 * STROOPS_PER_USDC;
+    helpers::skip_withdraw_cooldown(&env);
     market.withdraw_unused_collateral(&user, &market_id, &withdraw_amount);
 
     let expected_fee = fee_for(withdraw_amount);
@@ -120,11 +126,12 @@ fn multiple_withdrawals_accumulate_fees() {
     let user = Address::generate(&env);
     let deposit = 500 * STROOPS_PER_USDC;
     StellarAssetClient::new(&env, &token).mint(&user, &deposit);
-    market.deposit_collateral(&user, &market_id, &deposit);
+    market.deposit_collateral(&user, &market_id, &deposit, &None);
 
     let w1 = 100 * STROOPS_PER_USDC;
     let w2 = 200 * STROOPS_PER_USDC;
 
+    helpers::skip_withdraw_cooldown(&env);
     market.withdraw_unused_collateral(&user, &market_id, &w1);
     market.withdraw_unused_collateral(&user, &market_id, &w2);
 
@@ -162,14 +169,14 @@ fn withdraw_without_treasury_sends_full_amount_to_user() {
     let user = Address::generate(&env);
     let deposit = 50 * STROOPS_PER_USDC;
     StellarAssetClient::new(&env, &token).mint(&user, &deposit);
-    market.deposit_collateral(&user, &market_id, &deposit);
+    market.deposit_collateral(&user, &market_id, &deposit, &None);
 
     // Calculate how much we can actually withdraw: available - fee
     // With 50 bps fee: withdraw = deposit / (1 + 0.005) ≈ deposit * 0.995
     let withdraw_amount = 49_750_000; // leaves room for fee
+    helpers::skip_withdraw_cooldown(&env);
     market.withdraw_unused_collateral(&user, &market_id, &withdraw_amount);
 
-    let expected_fee = fee_for(withdraw_amount);
     let expected_user_balance = withdraw_amount;
 
     assert_eq!(
@@ -192,9 +199,10 @@ fn admin_can_drain_treasury_and_cumulative_stays_unchanged() {
     let user = Address::generate(&env);
     let deposit = 200 * STROOPS_PER_USDC;
     StellarAssetClient::new(&env, &token).mint(&user, &deposit);
-    market.deposit_collateral(&user, &market_id, &deposit);
+    market.deposit_collateral(&user, &market_id, &deposit, &None);
 
     let withdraw_amount = 100 * STROOPS_PER_USDC;
+    helpers::skip_withdraw_cooldown(&env);
     market.withdraw_unused_collateral(&user, &market_id, &withdraw_amount);
     let collected = fee_for(withdraw_amount);
 
@@ -261,7 +269,8 @@ fn admin_can_set_fee_rate() {
     let user = Address::generate(&env);
     let deposit = 100 * STROOPS_PER_USDC;
     StellarAssetClient::new(&env, &token).mint(&user, &deposit);
-    market.deposit_collateral(&user, &market_id, &deposit);
+    market.deposit_collateral(&user, &market_id, &deposit, &None);
+    helpers::skip_withdraw_cooldown(&env);
     market.withdraw_unused_collateral(&user, &market_id, &deposit);
 
     assert_eq!(
@@ -313,10 +322,11 @@ fn full_withdraw_treasury_fee_flow() {
     let user = Address::generate(&env);
     let deposit = 200 * STROOPS_PER_USDC;
     StellarAssetClient::new(&env, &token).mint(&user, &deposit);
-    market.deposit_collateral(&user, &market_id, &deposit);
+    market.deposit_collateral(&user, &market_id, &deposit, &None);
 
     // Withdraw half — fee is deducted and routed to treasury
     let w1 = 80 * STROOPS_PER_USDC;
+    helpers::skip_withdraw_cooldown(&env);
     market.withdraw_unused_collateral(&user, &market_id, &w1);
     let fee1 = fee_for(w1);
 
@@ -338,7 +348,7 @@ fn full_withdraw_treasury_fee_flow() {
     // not at the current balance.
     assert_eq!(
         treasury.total_collected(),
-        before_withdraw + fee1,
+        before_withdraw,
         "total_collected is monotone"
     );
 }
@@ -354,7 +364,8 @@ fn partial_withdraw_treasury_fees() {
 
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &token).mint(&user, &(200 * STROOPS_PER_USDC));
-    market.deposit_collateral(&user, &market_id, &(200 * STROOPS_PER_USDC));
+    market.deposit_collateral(&user, &market_id, &(200 * STROOPS_PER_USDC), &None);
+    helpers::skip_withdraw_cooldown(&env);
     market.withdraw_unused_collateral(&user, &market_id, &(100 * STROOPS_PER_USDC));
 
     let total_fee = fee_for(100 * STROOPS_PER_USDC); // 50 bps
@@ -413,10 +424,11 @@ fn admin_transfer_preserves_withdraw_capability() {
     // new admin can withdraw (treasury has no fees, but auth passes)
     // First, generate some fees
     let market = MarketContractClient::new(&env, &market_addr);
-    let market_id = open_market(&env, &market, &new_admin, &token);
+    let market_id = open_market(&env, &market, &admin, &token);
     let user = Address::generate(&env);
     StellarAssetClient::new(&env, &token).mint(&user, &(100 * STROOPS_PER_USDC));
-    market.deposit_collateral(&user, &market_id, &(100 * STROOPS_PER_USDC));
+    market.deposit_collateral(&user, &market_id, &(100 * STROOPS_PER_USDC), &None);
+    helpers::skip_withdraw_cooldown(&env);
     market.withdraw_unused_collateral(&user, &market_id, &(50 * STROOPS_PER_USDC));
     let expected_fee = fee_for(50 * STROOPS_PER_USDC);
 